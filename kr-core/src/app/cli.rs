@@ -0,0 +1,385 @@
+use std::{collections::BTreeMap, ffi::OsString, future::Future, path::PathBuf, pin::Pin};
+
+use anyhow::anyhow;
+use clap::{Arg, ArgMatches, Command};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    bootstrap::Bootstrap,
+    config::{self, dotenv, Config},
+};
+
+type Handler =
+    Box<dyn FnOnce(Ctx) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send>;
+type Setup = Box<dyn FnOnce(&Ctx) -> Bootstrap + Send>;
+
+const SERVE: &str = "serve";
+const MIGRATE: &str = "migrate";
+const SEED: &str = "seed";
+const CONFIG_CHECK: &str = "config-check";
+const CONFIG_DUMP: &str = "config-dump";
+const CRON_RUN: &str = "cron-run";
+
+/// 命令上下文
+#[derive(Debug, Clone)]
+pub struct Ctx {
+    /// 子命令
+    pub command: String,
+    /// 配置文件路径（`--config`/`-c`，或环境变量 APP_CONFIG）
+    pub config: PathBuf,
+    /// 环境标识（`KR_PROFILE`）
+    pub profile: Option<String>,
+}
+
+/// 命令行入口：统一提供 serve、migrate、seed、config-check、config-dump、cron-run 子命令
+///
+/// [`Cli::main`] 在启动 tokio 运行时之前加载 `.env` 文件（见 [`dotenv::load`]）；
+/// 自行管理运行时时，需在 `main` 中先调用 `dotenv::load()`，再启动运行时并调用 [`Cli::run`]；
+/// 除 config-check、config-dump 外，执行子命令前先运行 bootstrap 启动任务；仅注册已设置的子命令
+///
+/// # Examples
+///
+/// ```
+/// fn main() -> anyhow::Result<()> {
+///     Cli::new("order-svc")
+///         .version(env!("CARGO_PKG_VERSION"))
+///         .bootstrap(|ctx| {
+///             let path = ctx.config.clone();
+///             Bootstrap::new()
+///                 .task("config", &[], Duration::from_secs(5), move || async move { init_config(path) })
+///                 .task("db", &["config"], Duration::from_secs(10), || async { init_db().await })
+///         })
+///         .config::<AppConfig>()
+///         .serve(|_| async { http::serve().await })
+///         .migrate(|_| async { migrate::run().await })
+///         .seed(|_| async { seed::run().await })
+///         .job("daily_report", |_| async { report::daily().await })
+///         .main()
+/// }
+///
+/// // order-svc -c config.prod.toml serve
+/// // order-svc cron-run daily_report
+/// ```
+pub struct Cli {
+    name: String,
+    about: Option<String>,
+    version: Option<String>,
+    setup: Option<Setup>,
+    handlers: BTreeMap<&'static str, Handler>,
+    jobs: BTreeMap<String, Handler>,
+}
+
+impl Cli {
+    pub fn new(name: impl AsRef<str>) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            about: None,
+            version: None,
+            setup: None,
+            handlers: BTreeMap::new(),
+            jobs: BTreeMap::new(),
+        }
+    }
+
+    pub fn about(mut self, about: impl AsRef<str>) -> Self {
+        self.about = Some(about.as_ref().to_string());
+        self
+    }
+
+    pub fn version(mut self, version: impl AsRef<str>) -> Self {
+        self.version = Some(version.as_ref().to_string());
+        self
+    }
+
+    /// 启动任务（config-check 外的子命令执行前运行）
+    pub fn bootstrap<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&Ctx) -> Bootstrap + Send + 'static,
+    {
+        self.setup = Some(Box::new(f));
+        self
+    }
+
+    /// 启动服务
+    pub fn serve<F, Fut>(self, f: F) -> Self
+    where
+        F: FnOnce(Ctx) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.handler(SERVE, f)
+    }
+
+    /// 数据库迁移
+    pub fn migrate<F, Fut>(self, f: F) -> Self
+    where
+        F: FnOnce(Ctx) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.handler(MIGRATE, f)
+    }
+
+    /// 初始化数据
+    pub fn seed<F, Fut>(self, f: F) -> Self
+    where
+        F: FnOnce(Ctx) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.handler(SEED, f)
+    }
+
+    /// 自定义配置检查
+    pub fn config_check<F, Fut>(self, f: F) -> Self
+    where
+        F: FnOnce(Ctx) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.handler(CONFIG_CHECK, f)
+    }
+
+    /// 配置检查：按类型 `T` 解析配置文件（支持 `ENC(...)`），同时注册 config-dump 输出合并后的配置
+    pub fn config<T>(self) -> Self
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.config_check(|ctx| async move {
+            Config::<T>::load(&ctx.config)?;
+            Ok(())
+        })
+        .handler(CONFIG_DUMP, |ctx| async move {
+            println!("{}", config::dump(&ctx.config)?);
+            Ok(())
+        })
+    }
+
+    /// 注册定时任务，可通过 `cron-run <job>` 手动执行
+    pub fn job<F, Fut>(mut self, name: impl AsRef<str>, f: F) -> Self
+    where
+        F: FnOnce(Ctx) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.jobs.insert(
+            name.as_ref().to_string(),
+            Box::new(move |ctx| Box::pin(f(ctx))),
+        );
+        self
+    }
+
+    fn handler<F, Fut>(mut self, name: &'static str, f: F) -> Self
+    where
+        F: FnOnce(Ctx) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.handlers
+            .insert(name, Box::new(move |ctx| Box::pin(f(ctx))));
+        self
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(self.name.clone())
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .arg(
+                Arg::new("config")
+                    .short('c')
+                    .long("config")
+                    .env("APP_CONFIG")
+                    .default_value("config.toml")
+                    .global(true)
+                    .help("配置文件路径"),
+            );
+        if let Some(v) = &self.about {
+            cmd = cmd.about(v.clone());
+        }
+        if let Some(v) = &self.version {
+            cmd = cmd.version(v.clone());
+        }
+
+        for name in self.handlers.keys() {
+            let about = match *name {
+                SERVE => "启动服务",
+                MIGRATE => "数据库迁移",
+                SEED => "初始化数据",
+                CONFIG_DUMP => "输出配置（隐藏敏感值）",
+                _ => "检查配置文件",
+            };
+            cmd = cmd.subcommand(Command::new(*name).about(about));
+        }
+        if !self.jobs.is_empty() {
+            let jobs: Vec<String> = self.jobs.keys().cloned().collect();
+            cmd = cmd.subcommand(
+                Command::new(CRON_RUN).about("手动执行定时任务").arg(
+                    Arg::new("job")
+                        .required(true)
+                        .value_parser(clap::builder::PossibleValuesParser::new(jobs)),
+                ),
+            );
+        }
+        cmd
+    }
+
+    /// 加载 `.env` 文件后启动多线程 tokio 运行时，解析命令行参数并执行
+    ///
+    /// `.env` 通过 `std::env::set_var` 写入进程环境，必须在运行时的工作线程启动前完成
+    pub fn main(self) -> anyhow::Result<()> {
+        dotenv::load()?;
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?
+            .block_on(self.run())
+    }
+
+    /// 解析命令行参数并执行（不加载 `.env` 文件，见 [`Cli::main`]）
+    pub async fn run(self) -> anyhow::Result<()> {
+        self.run_from(std::env::args_os()).await
+    }
+
+    /// 解析指定参数并执行（首个参数为程序名）
+    pub async fn run_from<I, T>(mut self, args: I) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = match self.command().try_get_matches_from(args) {
+            Ok(v) => v,
+            // --help、--version
+            Err(e) if !e.use_stderr() => {
+                e.print()?;
+                return Ok(());
+            }
+            Err(e) => return Err(anyhow!("cli: {}", e)),
+        };
+        let Some((name, sub)) = matches.subcommand() else {
+            return Err(anyhow!("cli: missing subcommand"));
+        };
+
+        let ctx = Ctx {
+            command: name.to_string(),
+            config: config_path(&matches),
+            profile: config::profile(),
+        };
+        let handler = match name {
+            CRON_RUN => {
+                let job = sub.get_one::<String>("job").unwrap();
+                self.jobs
+                    .remove(job)
+                    .ok_or_else(|| anyhow!("cli: unknown job `{}`", job))?
+            }
+            _ => self
+                .handlers
+                .remove(name)
+                .ok_or_else(|| anyhow!("cli: unknown command `{}`", name))?,
+        };
+
+        if name != CONFIG_CHECK && name != CONFIG_DUMP {
+            if let Some(setup) = self.setup.take() {
+                setup(&ctx).run().await?;
+            }
+        }
+
+        tracing::info!(command = ctx.command, config = %ctx.config.display(), "[cli] run");
+        handler(ctx).await
+    }
+}
+
+fn config_path(matches: &ArgMatches) -> PathBuf {
+    matches
+        .get_one::<String>("config")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use serde::Deserialize;
+
+    use crate::{app::cli::Cli, bootstrap::Bootstrap};
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct AppConfig {
+        name: String,
+    }
+
+    fn cli(log: Arc<Mutex<Vec<String>>>) -> Cli {
+        let (l1, l2, l3) = (log.clone(), log.clone(), log.clone());
+        Cli::new("demo")
+            .bootstrap(move |ctx| {
+                let cmd = ctx.command.clone();
+                Bootstrap::new().task("init", &[], Duration::from_secs(1), move || async move {
+                    l1.lock().unwrap().push(format!("bootstrap:{}", cmd));
+                    Ok(())
+                })
+            })
+            .config::<AppConfig>()
+            .serve(move |ctx| async move {
+                l2.lock()
+                    .unwrap()
+                    .push(format!("serve:{}", ctx.config.display()));
+                Ok(())
+            })
+            .job("report", move |_| async move {
+                l3.lock().unwrap().push("job:report".to_string());
+                Ok(())
+            })
+    }
+
+    #[tokio::test]
+    async fn test_cli() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        cli(log.clone())
+            .run_from(["demo", "-c", "app.toml", "serve"])
+            .await
+            .unwrap();
+        cli(log.clone())
+            .run_from(["demo", "cron-run", "report"])
+            .await
+            .unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "bootstrap:serve",
+                "serve:app.toml",
+                "bootstrap:cron-run",
+                "job:report"
+            ]
+        );
+
+        // 未知任务、未注册的子命令
+        assert!(cli(log.clone())
+            .run_from(["demo", "cron-run", "other"])
+            .await
+            .is_err());
+        assert!(cli(log.clone())
+            .run_from(["demo", "migrate"])
+            .await
+            .is_err());
+
+        // 配置检查不运行 bootstrap
+        let path = std::env::temp_dir().join(format!("kr-cli-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "name = \"demo\"\n").unwrap();
+        let n = log.lock().unwrap().len();
+        cli(log.clone())
+            .run_from(["demo", "config-check", "--config", path.to_str().unwrap()])
+            .await
+            .unwrap();
+        assert_eq!(log.lock().unwrap().len(), n);
+
+        std::fs::write(&path, "name = 1\n").unwrap();
+        assert!(cli(log.clone())
+            .run_from(["demo", "config-check", "-c", path.to_str().unwrap()])
+            .await
+            .is_err());
+        cli(log.clone())
+            .run_from(["demo", "config-dump", "-c", path.to_str().unwrap()])
+            .await
+            .unwrap();
+        assert_eq!(log.lock().unwrap().len(), n);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,242 @@
+pub mod preflight;
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use tokio::task::JoinSet;
+
+pub use preflight::{preflight, Check, Report};
+
+type TaskFn = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send>;
+
+struct Task {
+    name: String,
+    deps: Vec<String>,
+    timeout: Duration,
+    func: TaskFn,
+}
+
+/// 启动任务编排（按依赖关系执行，无依赖关系的任务并发执行）
+///
+/// # Examples
+///
+/// ```
+/// let report = Bootstrap::new()
+///     .task("config", &[], Duration::from_secs(5), || async { Ok(()) })
+///     .task("db", &["config"], Duration::from_secs(10), || async { Ok(()) })
+///     .task("redis", &["config"], Duration::from_secs(10), || async { Ok(()) })
+///     .task("migrate", &["db"], Duration::from_secs(60), || async { Ok(()) })
+///     .task("warmup", &["migrate", "redis"], Duration::from_secs(30), || async { Ok(()) })
+///     .run()
+///     .await?;
+///
+/// for (name, cost) in report {
+///     println!("{} => {:?}", name, cost);
+/// }
+/// ```
+#[derive(Default)]
+pub struct Bootstrap {
+    tasks: Vec<Task>,
+}
+
+impl Bootstrap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册任务
+    pub fn task<F, Fut>(mut self, name: &str, deps: &[&str], timeout: Duration, f: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.tasks.push(Task {
+            name: name.to_string(),
+            deps: deps.iter().map(|v| v.to_string()).collect(),
+            timeout,
+            func: Box::new(move || Box::pin(f())),
+        });
+        self
+    }
+
+    /// 执行所有任务，返回各任务的耗时（按完成顺序）；
+    /// 任一任务失败或超时，立即取消其余任务并返回错误
+    pub async fn run(self) -> anyhow::Result<Vec<(String, Duration)>> {
+        let index: HashMap<String, usize> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.name.clone(), i))
+            .collect();
+        if index.len() != self.tasks.len() {
+            return Err(anyhow::anyhow!("bootstrap: duplicate task name"));
+        }
+
+        // 入度 & 后继
+        let mut pending = vec![0usize; self.tasks.len()];
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); self.tasks.len()];
+        for (i, t) in self.tasks.iter().enumerate() {
+            for d in &t.deps {
+                let j = index.get(d).ok_or_else(|| {
+                    anyhow::anyhow!("bootstrap: task({}) depends on unknown task({})", t.name, d)
+                })?;
+                pending[i] += 1;
+                dependents[*j].push(i);
+            }
+        }
+        check_cycle(&self.tasks, &pending, &dependents)?;
+
+        let mut slots: Vec<Option<Task>> = self.tasks.into_iter().map(Some).collect();
+        let mut set = JoinSet::new();
+        let spawn = |set: &mut JoinSet<_>, i: usize, task: Task| {
+            set.spawn(async move {
+                let start = Instant::now();
+                let ret = match tokio::time::timeout(task.timeout, (task.func)()).await {
+                    Ok(v) => v,
+                    Err(_) => Err(anyhow::anyhow!("timeout after {:?}", task.timeout)),
+                };
+                (i, task.name, start.elapsed(), ret)
+            });
+        };
+
+        for (i, n) in pending.iter().enumerate() {
+            if *n == 0 {
+                let task = slots[i].take().unwrap();
+                spawn(&mut set, i, task);
+            }
+        }
+
+        let mut report = Vec::with_capacity(slots.len());
+        while let Some(joined) = set.join_next().await {
+            let (i, name, cost, ret) = match joined {
+                Ok(v) => v,
+                Err(e) => {
+                    set.abort_all();
+                    return Err(anyhow::anyhow!("bootstrap: task panicked: {}", e));
+                }
+            };
+            if let Err(e) = ret {
+                set.abort_all();
+                tracing::error!(task = name, cost_ms = cost.as_millis(), err = ?e, "[bootstrap] task failed");
+                return Err(e.context(format!("bootstrap: task({}) failed", name)));
+            }
+            tracing::info!(
+                task = name,
+                cost_ms = cost.as_millis(),
+                "[bootstrap] task done"
+            );
+            report.push((name, cost));
+
+            for &j in &dependents[i] {
+                pending[j] -= 1;
+                if pending[j] == 0 {
+                    let task = slots[j].take().unwrap();
+                    spawn(&mut set, j, task);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+// Kahn 算法检测环
+fn check_cycle(tasks: &[Task], pending: &[usize], dependents: &[Vec<usize>]) -> anyhow::Result<()> {
+    let mut pending = pending.to_vec();
+    let mut queue: Vec<usize> = (0..pending.len()).filter(|i| pending[*i] == 0).collect();
+    let mut visited = 0;
+    while let Some(i) = queue.pop() {
+        visited += 1;
+        for &j in &dependents[i] {
+            pending[j] -= 1;
+            if pending[j] == 0 {
+                queue.push(j);
+            }
+        }
+    }
+    if visited != tasks.len() {
+        let names: Vec<&str> = (0..tasks.len())
+            .filter(|i| pending[*i] > 0)
+            .map(|i| tasks[i].name.as_str())
+            .collect();
+        return Err(anyhow::anyhow!(
+            "bootstrap: dependency cycle among {:?}",
+            names
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::Bootstrap;
+
+    #[tokio::test]
+    async fn test_run_in_order() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut boot = Bootstrap::new();
+        for (name, deps) in [
+            ("warmup", vec!["migrate", "redis"]),
+            ("migrate", vec!["db"]),
+            ("db", vec!["config"]),
+            ("redis", vec!["config"]),
+            ("config", vec![]),
+        ] {
+            let order = order.clone();
+            boot = boot.task(name, &deps, Duration::from_secs(1), move || async move {
+                order.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+
+        let report = boot.run().await.unwrap();
+        assert_eq!(report.len(), 5);
+
+        let order = order.lock().unwrap();
+        let pos = |n: &str| order.iter().position(|v| *v == n).unwrap();
+        assert_eq!(pos("config"), 0);
+        assert!(pos("db") < pos("migrate"));
+        assert_eq!(pos("warmup"), 4);
+    }
+
+    #[tokio::test]
+    async fn test_abort() {
+        // 超时
+        let ret = Bootstrap::new()
+            .task("slow", &[], Duration::from_millis(10), || async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(())
+            })
+            .task("next", &["slow"], Duration::from_secs(1), || async {
+                panic!("should not run")
+            })
+            .run()
+            .await;
+        assert!(ret.is_err());
+
+        // 环
+        let ret = Bootstrap::new()
+            .task("a", &["b"], Duration::from_secs(1), || async { Ok(()) })
+            .task("b", &["a"], Duration::from_secs(1), || async { Ok(()) })
+            .run()
+            .await;
+        assert!(ret.is_err());
+
+        // 未知依赖
+        let ret = Bootstrap::new()
+            .task("a", &["none"], Duration::from_secs(1), || async { Ok(()) })
+            .run()
+            .await;
+        assert!(ret.is_err());
+    }
+}
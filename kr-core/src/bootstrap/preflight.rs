@@ -0,0 +1,312 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use sqlx::{
+    migrate::{Migrate, Migrator},
+    Connection, Database, Pool,
+};
+use tokio::task::JoinSet;
+
+use crate::helper::redkit::Redis;
+
+type CheckFn = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send>;
+
+/// 启动前检查项
+pub struct Check {
+    name: String,
+    timeout: Duration,
+    func: CheckFn,
+}
+
+impl Check {
+    /// 自定义检查，默认超时：5s
+    pub fn new<F, Fut>(name: impl AsRef<str>, f: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        Self {
+            name: name.as_ref().to_string(),
+            timeout: Duration::from_secs(5),
+            func: Box::new(move || Box::pin(f())),
+        }
+    }
+
+    /// 数据库连通性（获取连接并 ping）
+    pub fn sql<DB: Database>(name: impl AsRef<str>, pool: &Pool<DB>) -> Self {
+        let pool = pool.clone();
+        Self::new(name, move || async move {
+            let mut conn = pool.acquire().await.map_err(|e| {
+                anyhow::anyhow!("database unreachable, check dsn and network: {}", e)
+            })?;
+            conn.ping()
+                .await
+                .map_err(|e| anyhow::anyhow!("database ping failed: {}", e))
+        })
+    }
+
+    /// Redis 连通性（PING）
+    pub fn redis(name: impl AsRef<str>, redis: &Redis) -> Self {
+        let redis = redis.clone();
+        Self::new(name, move || async move {
+            let ret: anyhow::Result<String> = match &redis {
+                Redis::Single(pool) => {
+                    async {
+                        let mut conn = pool.get().await?;
+                        Ok(redis::cmd("PING").query_async(&mut *conn).await?)
+                    }
+                    .await
+                }
+                Redis::Cluster(pool) => {
+                    async {
+                        let mut conn = pool.get().await?;
+                        Ok(redis::cmd("PING").query_async(&mut *conn).await?)
+                    }
+                    .await
+                }
+            };
+            ret.map(|_| ())
+                .map_err(|e| anyhow::anyhow!("redis unreachable, check dsn and network: {}", e))
+        })
+    }
+
+    /// 数据库迁移均已执行
+    pub fn migrations<DB>(
+        name: impl AsRef<str>,
+        pool: &Pool<DB>,
+        migrator: &'static Migrator,
+    ) -> Self
+    where
+        DB: Database,
+        DB::Connection: Migrate,
+    {
+        let pool = pool.clone();
+        Self::new(name, move || async move {
+            let mut conn = pool.acquire().await?;
+            let applied = conn.list_applied_migrations().await.map_err(|e| {
+                anyhow::anyhow!(
+                    "list applied migrations failed (run `migrate` first?): {}",
+                    e
+                )
+            })?;
+            let applied: HashSet<i64> = applied.iter().map(|v| v.version).collect();
+            let pending: Vec<i64> = migrator
+                .iter()
+                .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+                .map(|m| m.version)
+                .collect();
+            if !pending.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "{} pending migration(s) {:?}, run `migrate` first",
+                    pending.len(),
+                    pending
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    /// 必填配置项：以 `.` 分隔的路径，值不能为 null 或空字符串
+    pub fn config<T: Serialize>(name: impl AsRef<str>, cfg: &T, keys: &[&str]) -> Self {
+        let value = serde_json::to_value(cfg);
+        let keys: Vec<String> = keys.iter().map(|v| v.to_string()).collect();
+        Self::new(name, move || async move {
+            let value = value?;
+            let missing: Vec<&str> = keys
+                .iter()
+                .filter(|k| {
+                    let v = k.split('.').try_fold(&value, |v, seg| v.get(seg));
+                    match v {
+                        None | Some(serde_json::Value::Null) => true,
+                        Some(serde_json::Value::String(s)) => s.is_empty(),
+                        _ => false,
+                    }
+                })
+                .map(|v| v.as_str())
+                .collect();
+            if !missing.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "missing config keys: {}",
+                    missing.join(", ")
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    /// 设置超时
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = d;
+        self
+    }
+}
+
+/// 检查结果
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub cost: Duration,
+    pub error: Option<String>,
+}
+
+/// 检查报告（按注册顺序）
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub results: Vec<CheckResult>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(|v| v.error.is_none())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results.iter().filter(|v| v.error.is_some())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for v in &self.results {
+            match &v.error {
+                None => writeln!(f, "  [ok]   {} ({}ms)", v.name, v.cost.as_millis())?,
+                Some(e) => writeln!(f, "  [FAIL] {} ({}ms): {}", v.name, v.cost.as_millis(), e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 启动前检查依赖（并发执行，各项独立超时），任一失败时返回包含完整报告的错误
+///
+/// # Examples
+///
+/// ```
+/// static MIGRATOR: Migrator = sqlx::migrate!();
+///
+/// bootstrap::preflight(vec![
+///     Check::config("config", &*cfg.get(), &["db.dsn", "redis.dsn"]),
+///     Check::sql("mysql", &pool),
+///     Check::redis("redis", &redis).timeout(Duration::from_secs(2)),
+///     Check::migrations("migrations", &pool, &MIGRATOR),
+/// ])
+/// .await?;
+/// ```
+pub async fn preflight(checks: Vec<Check>) -> anyhow::Result<Report> {
+    let mut set = JoinSet::new();
+    for (i, check) in checks.into_iter().enumerate() {
+        set.spawn(async move {
+            let start = Instant::now();
+            let ret = match tokio::time::timeout(check.timeout, (check.func)()).await {
+                Ok(v) => v,
+                Err(_) => Err(anyhow::anyhow!("timeout after {:?}", check.timeout)),
+            };
+            (
+                i,
+                CheckResult {
+                    name: check.name,
+                    cost: start.elapsed(),
+                    error: ret.err().map(|e| format!("{:#}", e)),
+                },
+            )
+        });
+    }
+
+    let mut results = Vec::with_capacity(set.len());
+    while let Some(joined) = set.join_next().await {
+        let (i, v) =
+            joined.map_err(|e| anyhow::anyhow!("bootstrap/preflight: check panicked: {}", e))?;
+        match &v.error {
+            None => tracing::info!(
+                check = v.name,
+                cost_ms = v.cost.as_millis(),
+                "[bootstrap::preflight] ok"
+            ),
+            Some(e) => tracing::error!(
+                check = v.name,
+                cost_ms = v.cost.as_millis(),
+                err = e,
+                "[bootstrap::preflight] failed"
+            ),
+        }
+        results.push((i, v));
+    }
+    results.sort_by_key(|(i, _)| *i);
+
+    let report = Report {
+        results: results.into_iter().map(|(_, v)| v).collect(),
+    };
+    if !report.is_ok() {
+        return Err(anyhow::anyhow!(
+            "bootstrap/preflight: {} check(s) failed\n{}",
+            report.failed().count(),
+            report
+        ));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use sqlx::migrate::Migrator;
+
+    use crate::{
+        bootstrap::preflight::{preflight, Check},
+        helper::{self, redkit::Redis},
+        redix, sql,
+    };
+
+    #[tokio::test]
+    async fn test_preflight() {
+        let pool = sql::test::memory_pool(None).await.unwrap();
+        let redis = Redis::Single(redix::open::<redix::Mock>(vec![], None).await.unwrap());
+        let cfg = json!({"db": {"dsn": "sqlite::memory:"}, "redis": {"dsn": ""}});
+
+        let dir = std::env::temp_dir().join(format!("kr_preflight_{}", helper::nonce(8)));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1_demo.sql"), "CREATE TABLE demo (id INTEGER);").unwrap();
+        let migrator: &'static Migrator =
+            Box::leak(Box::new(Migrator::new(dir.as_path()).await.unwrap()));
+
+        let ret = preflight(vec![
+            Check::sql("sqlite", &pool),
+            Check::redis("redis", &redis),
+            Check::config("config", &cfg, &["db.dsn", "redis.dsn", "app.name"]),
+            Check::migrations("migrations", &pool, migrator),
+            Check::new("slow", || async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(())
+            })
+            .timeout(Duration::from_millis(10)),
+        ])
+        .await;
+        let err = format!("{}", ret.unwrap_err());
+        assert!(err.contains("3 check(s) failed"));
+        assert!(err.contains("[ok]   sqlite"));
+        assert!(err.contains("[ok]   redis"));
+        assert!(err.contains("missing config keys: redis.dsn, app.name"));
+        assert!(err.contains("[FAIL] migrations"));
+        assert!(err.contains("[FAIL] slow"));
+
+        migrator.run(&pool).await.unwrap();
+        let report = preflight(vec![
+            Check::config("config", &cfg, &["db.dsn"]),
+            Check::migrations("migrations", &pool, migrator),
+        ])
+        .await
+        .unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.results[0].name, "config");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+struct Entry {
+    value: Arc<[u8]>,
+    expire_at: Option<Instant>,
+    // 最近访问序号，越小越久未访问
+    tick: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    // 访问序号 => key
+    lru: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Inner {
+    fn touch(&mut self, key: &str) -> Option<Arc<[u8]>> {
+        let now = Instant::now();
+        let entry = self.entries.get(key)?;
+        if entry.expire_at.is_some_and(|v| v <= now) {
+            self.remove(key);
+            return None;
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.entries.get_mut(key)?;
+        self.lru.remove(&entry.tick);
+        entry.tick = tick;
+        self.lru.insert(tick, key.to_string());
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: &str, value: Arc<[u8]>, ttl: Option<Duration>, capacity: usize) {
+        self.remove(key);
+
+        // 超过容量：先清理过期的，仍不足时淘汰最久未访问的
+        if self.entries.len() >= capacity {
+            let now = Instant::now();
+            let expired: Vec<String> = self
+                .entries
+                .iter()
+                .filter(|(_, v)| v.expire_at.is_some_and(|t| t <= now))
+                .map(|(k, _)| k.clone())
+                .collect();
+            for k in expired {
+                self.remove(&k);
+            }
+        }
+        while self.entries.len() >= capacity {
+            let Some((_, k)) = self.lru.pop_first() else {
+                break;
+            };
+            self.entries.remove(&k);
+        }
+
+        self.tick += 1;
+        self.lru.insert(self.tick, key.to_string());
+        self.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expire_at: ttl.map(|d| Instant::now() + d),
+                tick: self.tick,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(v) = self.entries.remove(key) {
+            self.lru.remove(&v.tick);
+        }
+    }
+}
+
+/// 进程内缓存（TTL + LRU，线程安全），接口与 [`Redis`](crate::helper::redkit::Redis) 一致，值以 JSON 存储
+///
+/// # Examples
+///
+/// ```
+/// // 最多 10000 个 key
+/// let cache = LocalCache::new(10000);
+///
+/// let user = cache
+///     .get_or_set(
+///         format!("user:{}", id),
+///         || async { mysql::find_one::<model::User>(&pool, stmt).await },
+///         Some(Duration::from_secs(60)),
+///     )
+///     .await?;
+///
+/// // 更新后
+/// cache.delete(format!("user:{}", id));
+/// ```
+#[derive(Clone)]
+pub struct LocalCache {
+    capacity: usize,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl LocalCache {
+    /// `capacity` 为最多缓存的 key 数量
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    pub async fn get_or_set<T, F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<Option<T>>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<T>>>,
+    {
+        let key = key.as_ref();
+        if let Some(v) = self.get(key)? {
+            return Ok(Some(v));
+        }
+
+        // 缓存未命中，调用loader获取数据
+        let data = loader().await?;
+
+        // 数据存在，写入缓存
+        if let Some(v) = &data {
+            self.set(key, v, ttl)?;
+        }
+        Ok(data)
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: impl AsRef<str>) -> anyhow::Result<Option<T>> {
+        let value = self.inner.lock().unwrap().touch(key.as_ref());
+        match value {
+            Some(v) => Ok(Some(serde_json::from_slice(&v)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set<T: Serialize>(
+        &self,
+        key: impl AsRef<str>,
+        value: &T,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        let value: Arc<[u8]> = serde_json::to_vec(value)?.into();
+        self.inner
+            .lock()
+            .unwrap()
+            .insert(key.as_ref(), value, ttl, self.capacity);
+        Ok(())
+    }
+
+    pub fn delete(&self, key: impl AsRef<str>) {
+        self.inner.lock().unwrap().remove(key.as_ref());
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.entries.clear();
+        inner.lru.clear();
+    }
+
+    /// 缓存的 key 数量（含未清理的过期 key）
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use crate::cache::local::LocalCache;
+
+    #[tokio::test]
+    async fn test_local_cache() {
+        let cache = LocalCache::new(2);
+        let calls = AtomicU32::new(0);
+
+        for _ in 0..3 {
+            let v: Option<String> = cache
+                .get_or_set(
+                    "a",
+                    || async {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        Ok(Some("hello".to_string()))
+                    },
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_eq!(v.as_deref(), Some("hello"));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // LRU：访问 a 后写入 c，淘汰 b
+        cache.set("b", &2, None).unwrap();
+        assert_eq!(cache.get::<String>("a").unwrap().as_deref(), Some("hello"));
+        cache.set("c", &3, None).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get::<i32>("b").unwrap(), None);
+        assert_eq!(cache.get::<i32>("c").unwrap(), Some(3));
+
+        // TTL
+        cache.set("d", &4, Some(Duration::from_millis(20))).unwrap();
+        assert_eq!(cache.get::<i32>("d").unwrap(), Some(4));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cache.get::<i32>("d").unwrap(), None);
+
+        cache.delete("c");
+        assert_eq!(cache.get::<i32>("c").unwrap(), None);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
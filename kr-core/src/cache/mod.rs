@@ -0,0 +1,142 @@
+pub mod local;
+
+use std::{future::Future, time::Duration};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::helper::redkit::{Redis, RedisCache};
+
+pub use local::LocalCache;
+
+/// 缓存抽象：业务代码面向该 trait 编写，可在进程内缓存与 Redis 之间切换
+///
+/// # Examples
+///
+/// ```
+/// async fn find_user(cache: &impl Cache, pool: &MySqlPool, id: i64) -> anyhow::Result<Option<model::User>> {
+///     cache
+///         .get_or_set(
+///             &format!("user:{}", id),
+///             || async { mysql::find_one::<model::User>(pool, stmt).await },
+///             Some(Duration::from_secs(60)),
+///         )
+///         .await
+/// }
+///
+/// find_user(&LocalCache::new(10000), &pool, 1).await?;
+/// find_user(&Redis::Single(redis), &pool, 1).await?;
+/// find_user(&RedisCache::new(Redis::Single(redis)).compression(compression), &pool, 1).await?;
+/// ```
+pub trait Cache: Send + Sync {
+    fn get_or_set<T, F, Fut>(
+        &self,
+        key: &str,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> impl Future<Output = anyhow::Result<Option<T>>> + Send
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<Option<T>>> + Send;
+
+    fn delete(&self, key: &str) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+impl Cache for LocalCache {
+    fn get_or_set<T, F, Fut>(
+        &self,
+        key: &str,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> impl Future<Output = anyhow::Result<Option<T>>> + Send
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<Option<T>>> + Send,
+    {
+        LocalCache::get_or_set(self, key, loader, ttl)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        LocalCache::delete(self, key);
+        Ok(())
+    }
+}
+
+impl Cache for Redis {
+    fn get_or_set<T, F, Fut>(
+        &self,
+        key: &str,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> impl Future<Output = anyhow::Result<Option<T>>> + Send
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<Option<T>>> + Send,
+    {
+        Redis::get_or_set(self, key, loader, ttl)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        Redis::delete(self, key).await
+    }
+}
+
+impl Cache for RedisCache {
+    fn get_or_set<T, F, Fut>(
+        &self,
+        key: &str,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> impl Future<Output = anyhow::Result<Option<T>>> + Send
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<Option<T>>> + Send,
+    {
+        RedisCache::get_or_set(self, key, loader, ttl)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.redis().delete(key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        cache::{Cache, LocalCache},
+        helper::redkit::Redis,
+        redix,
+    };
+
+    async fn load(cache: &impl Cache, key: &str, v: i64) -> Option<i64> {
+        cache
+            .get_or_set(
+                key,
+                || async move { Ok(Some(v)) },
+                Some(Duration::from_secs(60)),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cache() {
+        let redis = Redis::Single(redix::open::<redix::Mock>(vec![], None).await.unwrap());
+        let local = LocalCache::new(100);
+
+        assert_eq!(load(&redis, "test_cache", 1).await, Some(1));
+        assert_eq!(load(&redis, "test_cache", 2).await, Some(1));
+        assert_eq!(load(&local, "test_cache", 1).await, Some(1));
+        assert_eq!(load(&local, "test_cache", 2).await, Some(1));
+
+        redis.delete("test_cache").await.unwrap();
+        Cache::delete(&local, "test_cache").await.unwrap();
+        assert_eq!(load(&redis, "test_cache", 3).await, Some(3));
+        assert_eq!(load(&local, "test_cache", 3).await, Some(3));
+    }
+}
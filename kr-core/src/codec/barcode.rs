@@ -0,0 +1,284 @@
+use std::{fmt::Write, io::Cursor};
+
+use anyhow::anyhow;
+use image::{GrayImage, ImageFormat, Luma};
+
+pub use crate::qrcode::Format;
+
+// Code128 符号（条、空宽度交替），0-102 为数据，103-105 为起始符 A/B/C，106 为终止符
+const PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE_C: u8 = 99;
+const CODE_B: u8 = 100;
+const START_B: u8 = 104;
+const START_C: u8 = 105;
+const STOP: u8 = 106;
+
+/// 条形码参数
+#[derive(Debug, Clone)]
+pub struct Options {
+    format: Format,
+    module: u32,
+    height: u32,
+    margin: u32,
+}
+
+/// 默认：PNG、模块宽 2px、高 80px、静区 10 个模块
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            format: Format::Png,
+            module: 2,
+            height: 80,
+            margin: 10,
+        }
+    }
+}
+
+impl Options {
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 最窄条宽度（像素）
+    pub fn module(mut self, px: u32) -> Self {
+        self.module = px.max(1);
+        self
+    }
+
+    /// 条高度（像素）
+    pub fn height(mut self, px: u32) -> Self {
+        self.height = px.max(1);
+        self
+    }
+
+    /// 左右静区宽度（模块数，标准要求不小于 10）
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+}
+
+/// 生成 Code128 条形码（支持 ASCII 可打印字符，连续数字自动使用 C 码集压缩），返回 PNG 或 SVG 数据
+///
+/// # Examples
+///
+/// ```
+/// // 票号
+/// let png = barcode::code128("TK20240101000123", &Options::default())?;
+/// let svg = barcode::code128("6901234567892", &Options::default().format(Format::Svg))?;
+/// ```
+pub fn code128(content: &str, opts: &Options) -> anyhow::Result<Vec<u8>> {
+    let modules = encode_code128(content)?;
+    match opts.format {
+        Format::Png => png(&modules, opts),
+        Format::Svg => svg(&modules, opts),
+    }
+}
+
+/// Code128 编码，返回各模块是否为条（不含静区），用于自定义绘制
+pub fn encode_code128(content: &str) -> anyhow::Result<Vec<bool>> {
+    if content.is_empty() {
+        return Err(anyhow!("codec/barcode: empty content"));
+    }
+    if let Some(c) = content.chars().find(|c| !(' '..='~').contains(c)) {
+        return Err(anyhow!("codec/barcode: unsupported char {:?}", c));
+    }
+
+    let symbols = symbols(content.as_bytes());
+    let checksum = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (i.max(1) * *v as usize) % 103)
+        .sum::<usize>()
+        % 103;
+
+    let mut modules = Vec::new();
+    for v in symbols.into_iter().chain([checksum as u8, STOP]) {
+        for (i, w) in PATTERNS[v as usize].bytes().enumerate() {
+            // 偶数位为条，奇数位为空
+            modules.extend(std::iter::repeat_n(i % 2 == 0, (w - b'0') as usize));
+        }
+    }
+    Ok(modules)
+}
+
+// 符号序列（含起始符，不含校验符和终止符）：
+// 连续数字位于开头或结尾时 4 位及以上、位于中间时 6 位及以上使用 C 码集，其余使用 B 码集
+fn symbols(data: &[u8]) -> Vec<u8> {
+    let digits = |from: usize| {
+        data[from..]
+            .iter()
+            .take_while(|v| v.is_ascii_digit())
+            .count()
+    };
+
+    let mut out = Vec::with_capacity(data.len() + 2);
+    let mut code_c = false;
+    let mut i = 0;
+    while i < data.len() {
+        let run = digits(i);
+        let use_c = match i {
+            0 => run >= 4,
+            _ => run >= 6 || (run >= 4 && i + run == data.len()),
+        };
+        if use_c {
+            // 奇数个数字时第一个数字使用 B 码集
+            if run % 2 == 1 {
+                if i == 0 {
+                    out.push(START_B);
+                } else if code_c {
+                    out.push(CODE_B);
+                }
+                out.push(data[i] - b' ');
+                code_c = false;
+                i += 1;
+            }
+            if out.is_empty() {
+                out.push(START_C);
+            } else if !code_c {
+                out.push(CODE_C);
+            }
+            code_c = true;
+            let end = i + run / 2 * 2;
+            while i < end {
+                out.push((data[i] - b'0') * 10 + (data[i + 1] - b'0'));
+                i += 2;
+            }
+            continue;
+        }
+
+        if out.is_empty() {
+            out.push(START_B);
+        } else if code_c {
+            out.push(CODE_B);
+        }
+        code_c = false;
+        out.push(data[i] - b' ');
+        i += 1;
+    }
+    out
+}
+
+fn png(modules: &[bool], opts: &Options) -> anyhow::Result<Vec<u8>> {
+    let width = (modules.len() as u32 + 2 * opts.margin) * opts.module;
+    let mut img = GrayImage::from_pixel(width, opts.height, Luma([255]));
+    for (i, bar) in modules.iter().enumerate() {
+        if !bar {
+            continue;
+        }
+        let x = (opts.margin + i as u32) * opts.module;
+        for dx in 0..opts.module {
+            for y in 0..opts.height {
+                img.put_pixel(x + dx, y, Luma([0]));
+            }
+        }
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, ImageFormat::Png)?;
+    Ok(buf.into_inner())
+}
+
+fn svg(modules: &[bool], opts: &Options) -> anyhow::Result<Vec<u8>> {
+    let total = modules.len() as u32 + 2 * opts.margin;
+
+    let mut s = String::new();
+    write!(
+        s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" preserveAspectRatio="none" shape-rendering="crispEdges">"#,
+        total * opts.module,
+        opts.height,
+        total,
+        opts.height
+    )?;
+    s.push_str(r##"<rect width="100%" height="100%" fill="#ffffff"/>"##);
+    s.push_str(r##"<path fill="#000000" d=""##);
+    // 连续的条合并为一个矩形
+    let mut i = 0;
+    while i < modules.len() {
+        if !modules[i] {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < modules.len() && modules[i] {
+            i += 1;
+        }
+        write!(
+            s,
+            "M{},0h{}v{}h-{}z",
+            start as u32 + opts.margin,
+            i - start,
+            opts.height,
+            i - start
+        )?;
+    }
+    s.push_str(r#""/></svg>"#);
+
+    Ok(s.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::barcode::{
+        self, Format, Options, CODE_B, CODE_C, PATTERNS, START_B, START_C,
+    };
+
+    #[test]
+    fn test_symbols() {
+        assert!(PATTERNS[..106]
+            .iter()
+            .all(|v| v.bytes().map(|b| (b - b'0') as u32).sum::<u32>() == 11));
+
+        assert_eq!(barcode::symbols(b"AB"), vec![START_B, 33, 34]);
+        assert_eq!(barcode::symbols(b"123456"), vec![START_C, 12, 34, 56]);
+        assert_eq!(
+            barcode::symbols(b"12345"),
+            vec![START_B, 17, CODE_C, 23, 45]
+        );
+        assert_eq!(barcode::symbols(b"A12"), vec![START_B, 33, 17, 18]);
+        assert_eq!(
+            barcode::symbols(b"TK1234567A"),
+            vec![START_B, 52, 43, 17, CODE_C, 23, 45, 67, CODE_B, 33]
+        );
+        assert_eq!(
+            barcode::symbols(b"AB1234"),
+            vec![START_B, 33, 34, CODE_C, 12, 34]
+        );
+    }
+
+    #[test]
+    fn test_code128() {
+        // 起始符 + 2 个数据符 + 校验符 + 终止符
+        let modules = barcode::encode_code128("AB").unwrap();
+        assert_eq!(modules.len(), 11 * 4 + 13);
+        assert!(modules[0] && *modules.last().unwrap());
+        assert!(barcode::encode_code128("").is_err());
+        assert!(barcode::encode_code128("中文").is_err());
+
+        let png = barcode::code128("TK20240101000123", &Options::default()).unwrap();
+        let img = image::load_from_memory(&png).unwrap();
+        let modules = barcode::encode_code128("TK20240101000123").unwrap();
+        assert_eq!(img.width(), (modules.len() as u32 + 20) * 2);
+        assert_eq!(img.height(), 80);
+
+        let svg = barcode::code128("123456", &Options::default().format(Format::Svg)).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,546 @@
+use anyhow::{anyhow, bail};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// CRC16/MODBUS（多项式 0x8005 反射，初值 0xFFFF）
+pub fn crc16_modbus(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for v in data {
+        crc ^= *v as u16;
+        for _ in 0..8 {
+            crc = if crc & 0x0001 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// CRC16/CCITT-FALSE（多项式 0x1021，初值 0xFFFF）
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for v in data {
+        crc ^= (*v as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// CRC32/IEEE（同 zlib、以太网）
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+    for v in data {
+        crc ^= *v as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// 整数转 BCD 码（高位在前，不足 `len` 字节时左侧补 0）
+///
+/// # Examples
+///
+/// ```
+/// // [0x00, 0x12, 0x34]
+/// let b = binary::to_bcd(1234, 3)?;
+/// ```
+pub fn to_bcd(v: u64, len: usize) -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    let mut n = v;
+    for b in buf.iter_mut().rev() {
+        *b = (((n / 10 % 10) << 4) | (n % 10)) as u8;
+        n /= 100;
+    }
+    if n != 0 {
+        bail!("codec/binary: {} overflows {} bcd bytes", v, len);
+    }
+    Ok(buf)
+}
+
+/// BCD 码转整数（非法半字节返回错误）
+pub fn from_bcd(data: &[u8]) -> anyhow::Result<u64> {
+    if data.len() > 10 {
+        bail!("codec/binary: bcd too long ({} bytes)", data.len());
+    }
+    let mut v: u64 = 0;
+    for b in data {
+        let (hi, lo) = (b >> 4, b & 0x0F);
+        if hi > 9 || lo > 9 {
+            bail!("codec/binary: invalid bcd byte 0x{:02X}", b);
+        }
+        v = v
+            .checked_mul(100)
+            .and_then(|v| v.checked_add((hi * 10 + lo) as u64))
+            .ok_or_else(|| anyhow!("codec/binary: bcd overflows u64"))?;
+    }
+    Ok(v)
+}
+
+/// 浮点数转定点数：`v * 10^scale`，四舍五入
+///
+/// # Examples
+///
+/// ```
+/// // 电压 220.57V，保留两位小数 => 22057
+/// let v = binary::to_fixed(220.57, 2);
+/// ```
+pub fn to_fixed(v: f64, scale: u32) -> i64 {
+    (v * 10f64.powi(scale as i32)).round() as i64
+}
+
+/// 定点数转浮点数：`v / 10^scale`
+pub fn from_fixed(v: i64, scale: u32) -> f64 {
+    v as f64 / 10f64.powi(scale as i32)
+}
+
+/// 顺序读取定长字段（`binary_frame!` 生成的代码使用）
+pub struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// 读取 n 个字节
+    pub fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        if self.remaining() < n {
+            bail!(
+                "codec/binary: need {} bytes at offset {}, got {}",
+                n,
+                self.pos,
+                self.remaining()
+            );
+        }
+        let v = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(v)
+    }
+
+    /// 读取 N 个字节
+    pub fn array<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let mut v = [0u8; N];
+        v.copy_from_slice(self.take(N)?);
+        Ok(v)
+    }
+
+    /// 剩余字节数
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+}
+
+/// 定长帧布局，通常由 `binary_frame!` 生成
+pub trait Frame: Sized {
+    /// 编码后的字节数
+    const SIZE: usize;
+
+    fn encode(&self, buf: &mut Vec<u8>) -> anyhow::Result<()>;
+
+    /// 解码（长度必须等于 `SIZE`）
+    fn decode(data: &[u8]) -> anyhow::Result<Self>;
+
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(Self::SIZE);
+        self.encode(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// 长度字段宽度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LenPrefix {
+    U8,
+    U16,
+    U32,
+}
+
+impl LenPrefix {
+    fn size(&self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::U32 => 4,
+        }
+    }
+
+    fn max(&self) -> usize {
+        match self {
+            Self::U8 => u8::MAX as usize,
+            Self::U16 => u16::MAX as usize,
+            Self::U32 => u32::MAX as usize,
+        }
+    }
+}
+
+/// 校验方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crc {
+    None,
+    Crc16Modbus,
+    Crc16Ccitt,
+    Crc32,
+}
+
+impl Crc {
+    fn size(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Crc16Modbus | Self::Crc16Ccitt => 2,
+            Self::Crc32 => 4,
+        }
+    }
+
+    fn checksum(&self, data: &[u8]) -> u64 {
+        match self {
+            Self::None => 0,
+            Self::Crc16Modbus => crc16_modbus(data) as u64,
+            Self::Crc16Ccitt => crc16_ccitt(data) as u64,
+            Self::Crc32 => crc32(data) as u64,
+        }
+    }
+}
+
+/// 长度前缀帧：`[帧头][长度][数据][校验]`
+///
+/// - 长度为数据部分的字节数
+/// - 校验覆盖长度与数据部分
+/// - 长度与校验默认大端序
+///
+/// # Examples
+///
+/// ```
+/// let codec = binary::FrameCodec::new()
+///     .header(&[0xAA, 0x55])
+///     .len_prefix(binary::LenPrefix::U16)
+///     .crc(binary::Crc::Crc16Modbus)
+///     .max_len(4096);
+///
+/// // TCP 流
+/// while let Some(payload) = codec.read_frame(&mut stream).await? {
+///     let v = Telemetry::decode(&payload)?;
+/// }
+///
+/// // 串口等按块读取的场景
+/// buf.extend_from_slice(&chunk);
+/// while let Some(payload) = codec.decode(&mut buf)? {
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameCodec {
+    header: Vec<u8>,
+    len_prefix: LenPrefix,
+    crc: Crc,
+    max_len: usize,
+    little_endian: bool,
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameCodec {
+    /// 默认：无帧头、2 字节长度、无校验、最大 64KB
+    pub fn new() -> Self {
+        Self {
+            header: Vec::new(),
+            len_prefix: LenPrefix::U16,
+            crc: Crc::None,
+            max_len: u16::MAX as usize,
+            little_endian: false,
+        }
+    }
+
+    /// 帧头（魔数）
+    pub fn header(mut self, header: &[u8]) -> Self {
+        self.header = header.to_vec();
+        self
+    }
+
+    pub fn len_prefix(mut self, v: LenPrefix) -> Self {
+        self.len_prefix = v;
+        self
+    }
+
+    pub fn crc(mut self, v: Crc) -> Self {
+        self.crc = v;
+        self
+    }
+
+    /// 数据部分最大字节数，超过时返回错误
+    pub fn max_len(mut self, n: usize) -> Self {
+        self.max_len = n;
+        self
+    }
+
+    /// 长度与校验使用小端序
+    pub fn little_endian(mut self) -> Self {
+        self.little_endian = true;
+        self
+    }
+
+    /// 编码一帧
+    pub fn encode(&self, payload: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.check_len(payload.len())?;
+        let mut buf = Vec::with_capacity(
+            self.header.len() + self.len_prefix.size() + payload.len() + self.crc.size(),
+        );
+        buf.extend_from_slice(&self.header);
+        let start = buf.len();
+        self.put_uint(&mut buf, payload.len() as u64, self.len_prefix.size());
+        buf.extend_from_slice(payload);
+        let sum = self.crc.checksum(&buf[start..]);
+        self.put_uint(&mut buf, sum, self.crc.size());
+        Ok(buf)
+    }
+
+    /// 从缓冲区解码一帧，数据不完整时返回 `None`
+    ///
+    /// 帧头之前的数据会被丢弃；长度超限或校验失败时丢弃一个字节并返回错误，可继续调用以重新同步
+    pub fn decode(&self, buf: &mut Vec<u8>) -> anyhow::Result<Option<Vec<u8>>> {
+        if !self.header.is_empty() {
+            match buf
+                .windows(self.header.len())
+                .position(|v| v == self.header.as_slice())
+            {
+                Some(0) => {}
+                Some(pos) => {
+                    tracing::warn!(skipped = pos, "[codec::binary] garbage before frame header");
+                    buf.drain(..pos);
+                }
+                None => {
+                    // 保留可能是帧头前缀的尾部字节
+                    let keep = self.header.len() - 1;
+                    if buf.len() > keep {
+                        buf.drain(..buf.len() - keep);
+                    }
+                    return Ok(None);
+                }
+            }
+        }
+
+        let start = self.header.len();
+        let len_size = self.len_prefix.size();
+        if buf.len() < start + len_size {
+            return Ok(None);
+        }
+        let len = self.get_uint(&buf[start..start + len_size]) as usize;
+        if let Err(e) = self.check_len(len) {
+            buf.drain(..1);
+            return Err(e);
+        }
+
+        let end = start + len_size + len;
+        if buf.len() < end + self.crc.size() {
+            return Ok(None);
+        }
+        if let Err(e) = self.verify(&buf[start..end], &buf[end..end + self.crc.size()]) {
+            buf.drain(..1);
+            return Err(e);
+        }
+
+        let payload = buf[start + len_size..end].to_vec();
+        buf.drain(..end + self.crc.size());
+        Ok(Some(payload))
+    }
+
+    /// 从流中读取一帧，在帧边界处读到 EOF 时返回 `None`
+    pub async fn read_frame<R>(&self, r: &mut R) -> anyhow::Result<Option<Vec<u8>>>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut head = vec![0u8; self.header.len() + self.len_prefix.size()];
+        // 区分正常关闭与读到半帧
+        let n = r.read(&mut head).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        r.read_exact(&mut head[n..]).await?;
+
+        let (header, len) = head.split_at(self.header.len());
+        if header != self.header.as_slice() {
+            bail!("codec/binary: invalid frame header {:02X?}", header);
+        }
+        let len = self.get_uint(len) as usize;
+        self.check_len(len)?;
+
+        let mut body = vec![0u8; len + self.crc.size()];
+        r.read_exact(&mut body).await?;
+        let mut checked = head[self.header.len()..].to_vec();
+        checked.extend_from_slice(&body[..len]);
+        self.verify(&checked, &body[len..])?;
+
+        body.truncate(len);
+        Ok(Some(body))
+    }
+
+    /// 向流中写入一帧
+    pub async fn write_frame<W>(&self, w: &mut W, payload: &[u8]) -> anyhow::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let buf = self.encode(payload)?;
+        w.write_all(&buf).await?;
+        w.flush().await?;
+        Ok(())
+    }
+
+    fn check_len(&self, len: usize) -> anyhow::Result<()> {
+        let max = self.max_len.min(self.len_prefix.max());
+        if len > max {
+            bail!("codec/binary: frame too large ({} > {})", len, max);
+        }
+        Ok(())
+    }
+
+    // data 为长度 + 数据部分，sum 为帧中的校验值
+    fn verify(&self, data: &[u8], sum: &[u8]) -> anyhow::Result<()> {
+        if self.crc == Crc::None {
+            return Ok(());
+        }
+        let expect = self.crc.checksum(data);
+        let actual = self.get_uint(sum);
+        if expect != actual {
+            bail!(
+                "codec/binary: crc mismatch (expect 0x{:X}, got 0x{:X})",
+                expect,
+                actual
+            );
+        }
+        Ok(())
+    }
+
+    fn put_uint(&self, buf: &mut Vec<u8>, v: u64, size: usize) {
+        if self.little_endian {
+            buf.extend_from_slice(&v.to_le_bytes()[..size]);
+        } else {
+            buf.extend_from_slice(&v.to_be_bytes()[8 - size..]);
+        }
+    }
+
+    fn get_uint(&self, data: &[u8]) -> u64 {
+        let mut b = [0u8; 8];
+        if self.little_endian {
+            b[..data.len()].copy_from_slice(data);
+            u64::from_le_bytes(b)
+        } else {
+            b[8 - data.len()..].copy_from_slice(data);
+            u64::from_be_bytes(b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::binary::{self, Crc, FrameCodec, LenPrefix};
+
+    #[test]
+    fn test_crc() {
+        let data = b"123456789";
+        assert_eq!(binary::crc16_modbus(data), 0x4B37);
+        assert_eq!(binary::crc16_ccitt(data), 0x29B1);
+        assert_eq!(binary::crc32(data), 0xCBF4_3926);
+        assert_eq!(binary::crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_bcd() {
+        assert_eq!(binary::to_bcd(1234, 3).unwrap(), vec![0x00, 0x12, 0x34]);
+        assert_eq!(binary::to_bcd(0, 1).unwrap(), vec![0x00]);
+        assert!(binary::to_bcd(12345, 2).is_err());
+        assert_eq!(binary::from_bcd(&[0x00, 0x12, 0x34]).unwrap(), 1234);
+        assert_eq!(
+            binary::from_bcd(&[0x99; 9]).unwrap(),
+            999_999_999_999_999_999
+        );
+        assert!(binary::from_bcd(&[0x1A]).is_err());
+        assert!(binary::from_bcd(&[0x99; 10]).is_err());
+    }
+
+    #[test]
+    fn test_fixed() {
+        assert_eq!(binary::to_fixed(220.57, 2), 22057);
+        assert_eq!(binary::to_fixed(-1.005, 1), -10);
+        assert_eq!(binary::from_fixed(22057, 2), 220.57);
+        assert_eq!(binary::from_fixed(-15, 0), -15.0);
+    }
+
+    #[test]
+    fn test_decode() {
+        let codec = FrameCodec::new()
+            .header(&[0xAA, 0x55])
+            .len_prefix(LenPrefix::U8)
+            .crc(Crc::Crc16Modbus)
+            .max_len(16);
+
+        let frame = codec.encode(b"hello").unwrap();
+        assert_eq!(frame.len(), 2 + 1 + 5 + 2);
+        assert_eq!(&frame[..3], &[0xAA, 0x55, 5]);
+        assert!(codec.encode(&[0u8; 17]).is_err());
+
+        // 前置垃圾数据 + 半帧
+        let mut buf = vec![0x01, 0x02];
+        buf.extend_from_slice(&frame[..6]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(&frame[6..]);
+        buf.extend_from_slice(&codec.encode(b"world").unwrap());
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), b"hello");
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), b"world");
+        assert!(buf.is_empty());
+
+        // 校验失败后重新同步
+        let mut bad = codec.encode(b"bad").unwrap();
+        *bad.last_mut().unwrap() ^= 0xFF;
+        let mut buf = bad;
+        buf.extend_from_slice(&frame);
+        assert!(codec.decode(&mut buf).is_err());
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_read_write_frame() {
+        let codec = FrameCodec::new()
+            .len_prefix(LenPrefix::U32)
+            .crc(Crc::Crc32)
+            .little_endian();
+
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let writer = codec.clone();
+        tokio::spawn(async move {
+            writer.write_frame(&mut client, b"ping").await.unwrap();
+            writer.write_frame(&mut client, &[7u8; 100]).await.unwrap();
+        });
+
+        assert_eq!(
+            codec.read_frame(&mut server).await.unwrap().unwrap(),
+            b"ping"
+        );
+        assert_eq!(
+            codec.read_frame(&mut server).await.unwrap().unwrap(),
+            vec![7u8; 100]
+        );
+        assert_eq!(codec.read_frame(&mut server).await.unwrap(), None);
+
+        let oversized = FrameCodec::new().max_len(2);
+        let mut data: &[u8] = &[0x00, 0x03, 1, 2, 3];
+        assert!(oversized.read_frame(&mut data).await.is_err());
+    }
+}
@@ -0,0 +1,6 @@
+#[cfg(feature = "qrcode")]
+pub mod barcode;
+pub mod binary;
+#[cfg(feature = "qrcode")]
+pub mod qr;
+pub mod xml;
@@ -0,0 +1,2 @@
+// 二维码生成（同 `crate::qrcode`），条形码见 `codec::barcode`
+pub use crate::qrcode::{generate, EcLevel, Format, Options};
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use quick_xml::{events::Event, Reader};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// XML 响应的 Content-Type
+pub const CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// 序列化为 XML（`root` 为根节点名）
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Serialize)]
+/// struct Reply {
+///     return_code: String,
+///     return_msg: String,
+/// }
+///
+/// // <xml><return_code>SUCCESS</return_code><return_msg>OK</return_msg></xml>
+/// let s = xml::to_string("xml", &reply)?;
+/// ```
+pub fn to_string<T: Serialize>(root: &str, value: &T) -> anyhow::Result<String> {
+    Ok(quick_xml::se::to_string_with_root(root, value)?)
+}
+
+/// 反序列化 XML（支持 CDATA）
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Deserialize)]
+/// struct Notify {
+///     appid: String,
+///     total_fee: i64,
+/// }
+///
+/// let v: Notify = xml::from_str(body)?;
+/// ```
+pub fn from_str<T: DeserializeOwned>(s: &str) -> anyhow::Result<T> {
+    Ok(quick_xml::de::from_str(s)?)
+}
+
+/// 扁平 map 转 XML，值以 CDATA 包裹，按 key 排序（微信支付等回调格式）
+///
+/// # Examples
+///
+/// ```
+/// let mut m = HashMap::new();
+/// m.insert("return_code".to_string(), "SUCCESS".to_string());
+///
+/// // <xml><return_code><![CDATA[SUCCESS]]></return_code></xml>
+/// let s = xml::map_to_xml(&m);
+/// ```
+pub fn map_to_xml(m: &HashMap<String, String>) -> String {
+    let mut keys: Vec<&String> = m.keys().collect();
+    keys.sort();
+
+    let mut s = String::from("<xml>");
+    for k in keys {
+        s.push_str(&format!("<{}>{}</{}>", k, cdata(&m[k]), k));
+    }
+    s.push_str("</xml>");
+    s
+}
+
+/// 解析扁平 XML 为 map（仅取根节点下的一级子节点，支持 CDATA）
+///
+/// # Examples
+///
+/// ```
+/// let m = xml::xml_to_map("<xml><appid><![CDATA[wx123]]></appid><total_fee>1</total_fee></xml>")?;
+/// assert_eq!(m["appid"], "wx123");
+/// ```
+pub fn xml_to_map(s: &str) -> anyhow::Result<HashMap<String, String>> {
+    let mut reader = Reader::from_str(s);
+    reader.config_mut().trim_text(true);
+
+    let mut m = HashMap::new();
+    let mut depth = 0;
+    let mut field: Option<String> = None;
+    let mut value = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                depth += 1;
+                if depth == 2 {
+                    field = Some(String::from_utf8(e.name().as_ref().to_vec())?);
+                    value.clear();
+                }
+            }
+            Event::Empty(e) if depth == 1 => {
+                m.insert(
+                    String::from_utf8(e.name().as_ref().to_vec())?,
+                    String::new(),
+                );
+            }
+            Event::Text(e) if depth == 2 => value.push_str(&e.unescape()?),
+            Event::CData(e) if depth == 2 => value.push_str(std::str::from_utf8(&e)?),
+            Event::End(_) => {
+                if depth == 2 {
+                    if let Some(k) = field.take() {
+                        m.insert(k, std::mem::take(&mut value));
+                    }
+                }
+                depth -= 1;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(anyhow!("codec/xml: unexpected eof"));
+    }
+    Ok(m)
+}
+
+/// 以 CDATA 包裹文本（内容中的 `]]>` 会被拆分）
+pub fn cdata(s: &str) -> String {
+    format!("<![CDATA[{}]]>", s.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::codec::xml;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Notify {
+        appid: String,
+        total_fee: i64,
+        attach: Option<String>,
+    }
+
+    #[test]
+    fn test_serde() {
+        let v = Notify {
+            appid: "wx<123>".to_string(),
+            total_fee: 100,
+            attach: None,
+        };
+        let s = xml::to_string("xml", &v).unwrap();
+        assert_eq!(
+            s,
+            "<xml><appid>wx&lt;123&gt;</appid><total_fee>100</total_fee><attach/></xml>"
+        );
+
+        let v: Notify = xml::from_str(
+            "<xml><appid><![CDATA[wx123]]></appid><total_fee>1</total_fee><attach><![CDATA[a&b]]></attach></xml>",
+        )
+        .unwrap();
+        assert_eq!(
+            v,
+            Notify {
+                appid: "wx123".to_string(),
+                total_fee: 1,
+                attach: Some("a&b".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        let mut m = HashMap::new();
+        m.insert("return_code".to_string(), "SUCCESS".to_string());
+        m.insert("return_msg".to_string(), "a]]>b".to_string());
+        let s = xml::map_to_xml(&m);
+        assert_eq!(
+            s,
+            "<xml><return_code><![CDATA[SUCCESS]]></return_code><return_msg><![CDATA[a]]]]><![CDATA[>b]]></return_msg></xml>"
+        );
+        assert_eq!(xml::xml_to_map(&s).unwrap(), m);
+
+        let m = xml::xml_to_map(
+            "<xml>\n  <appid>wx&amp;1</appid>\n  <detail><a>1</a></detail>\n  <sign/>\n</xml>",
+        )
+        .unwrap();
+        assert_eq!(m["appid"], "wx&1");
+        assert_eq!(m["sign"], "");
+        assert!(!m.contains_key("a"));
+
+        assert!(xml::xml_to_map("<xml><appid>1</appid>").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use std::{collections::HashMap, fmt};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// 错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Code {
+    pub code: i32,
+    pub name: &'static str,
+    pub msg: &'static str,
+    /// 定义所在模块
+    pub module: &'static str,
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.msg)
+    }
+}
+
+impl std::error::Error for Code {}
+
+inventory::collect!(Code);
+
+/// 定义错误码（自动注册到全局错误码表）
+///
+/// # Examples
+///
+/// ```
+/// kr::codes! {
+///     /// 用户不存在
+///     USER_NOT_FOUND = (10001, "用户不存在"),
+///     USER_DISABLED = (10002, "用户已禁用"),
+/// }
+///
+/// return Err(USER_NOT_FOUND.into());
+/// ```
+#[macro_export]
+macro_rules! codes {
+    ($($(#[$meta:meta])* $name:ident = ($code:expr, $msg:expr)),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            pub const $name: $crate::codes::Code = $crate::codes::Code {
+                code: $code,
+                name: stringify!($name),
+                msg: $msg,
+                module: module_path!(),
+            };
+
+            $crate::codes::inventory::submit! { $name }
+        )*
+    };
+}
+
+crate::codes! {
+    /// 服务繁忙（并发已满、排队过长）
+    BUSY = (503, "服务繁忙，请稍后重试"),
+    /// 调用额度已用完（`quota`）
+    QUOTA_EXCEEDED = (429, "调用额度已用完"),
+}
+
+/// 所有已注册的错误码（按 code 排序）
+pub fn all() -> Vec<&'static Code> {
+    let mut list: Vec<&'static Code> = inventory::iter::<Code>.into_iter().collect();
+    list.sort_by_key(|v| (v.code, v.module, v.name));
+    list
+}
+
+/// 查找错误码
+pub fn get(code: i32) -> Option<&'static Code> {
+    inventory::iter::<Code>.into_iter().find(|v| v.code == code)
+}
+
+/// 导出错误码表：(code, name, msg)，用于生成文档或客户端错误表
+///
+/// # Examples
+///
+/// ```
+/// for (code, name, msg) in codes::dump() {
+///     println!("| {} | {} | {} |", code, name, msg);
+/// }
+/// ```
+pub fn dump() -> Vec<(i32, &'static str, &'static str)> {
+    all().into_iter().map(|v| (v.code, v.name, v.msg)).collect()
+}
+
+/// 检测重复的错误码（建议在启动时调用）
+///
+/// # Examples
+///
+/// ```
+/// codes::check()?;
+/// ```
+pub fn check() -> anyhow::Result<()> {
+    check_list(&all())
+}
+
+fn check_list(list: &[&Code]) -> anyhow::Result<()> {
+    let mut seen: HashMap<i32, Vec<&Code>> = HashMap::new();
+    for v in list.iter().copied() {
+        seen.entry(v.code).or_default().push(v);
+    }
+
+    let mut dups: Vec<String> = seen
+        .into_iter()
+        .filter(|(_, list)| list.len() > 1)
+        .map(|(code, list)| {
+            let defs: Vec<String> = list
+                .iter()
+                .map(|v| format!("{}::{}", v.module, v.name))
+                .collect();
+            format!("{} => [{}]", code, defs.join(", "))
+        })
+        .collect();
+    if dups.is_empty() {
+        return Ok(());
+    }
+    dups.sort();
+    Err(anyhow::anyhow!(
+        "codes: duplicate codes: {}",
+        dups.join("; ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codes::{self, Code};
+
+    mod user {
+        crate::codes! {
+            /// 用户不存在
+            USER_NOT_FOUND = (91001, "用户不存在"),
+            USER_DISABLED = (91002, "用户已禁用"),
+        }
+    }
+
+    #[test]
+    fn test_codes() {
+        assert_eq!(user::USER_NOT_FOUND.code, 91001);
+        assert_eq!(codes::get(91002).unwrap().name, "USER_DISABLED");
+
+        let dump = codes::dump();
+        assert!(dump.contains(&(91002, "USER_DISABLED", "用户已禁用")));
+
+        // 全局错误码表无重复
+        codes::check().unwrap();
+
+        let e: anyhow::Error = user::USER_NOT_FOUND.into();
+        assert_eq!(e.to_string(), "[91001] 用户不存在");
+    }
+
+    #[test]
+    fn test_check_duplicate() {
+        // 不注册到全局表，避免影响 codes::check()
+        const ORDER_NOT_FOUND: Code = Code {
+            code: 91001,
+            name: "ORDER_NOT_FOUND",
+            msg: "订单不存在",
+            module: "app::order",
+        };
+
+        let list = [
+            &user::USER_NOT_FOUND,
+            &user::USER_DISABLED,
+            &ORDER_NOT_FOUND,
+        ];
+        let err = codes::check_list(&list).unwrap_err().to_string();
+        assert!(err.contains("91001"));
+        assert!(err.contains("app::order::ORDER_NOT_FOUND"));
+        assert!(!err.contains("91002"));
+
+        codes::check_list(&list[..2]).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+
+/// 环境标识（如：dev、test、prod）
+pub const PROFILE_ENV: &str = "KR_PROFILE";
+
+/// 当前环境标识（环境变量 `KR_PROFILE`，可在 `.env` 中设置）
+pub fn profile() -> Option<String> {
+    crate::env::get::<String>(PROFILE_ENV).ok().flatten()
+}
+
+/// 加载当前目录下的 `.env` 文件，返回已加载的文件
+///
+/// 优先级（高 -> 低），已存在的变量不会被覆盖：
+///
+/// 1. 进程环境变量
+/// 2. `.env.{profile}.local`
+/// 3. `.env.local`
+/// 4. `.env.{profile}`
+/// 5. `.env`
+///
+/// `profile` 取自进程环境变量 `KR_PROFILE`，未设置时取 `.env.local`、`.env` 中的 `KR_PROFILE`；
+/// `*.local` 文件用于本机覆盖，不应提交到仓库
+///
+/// 变量通过 `std::env::set_var` 写入进程环境，非线程安全：须在启动 tokio 运行时及其他线程之前调用
+/// （[`Cli::main`](crate::app::cli::Cli::main) 已按此顺序处理）
+///
+/// # Examples
+///
+/// ```
+/// // KR_PROFILE=prod
+/// fn main() -> anyhow::Result<()> {
+///     config::dotenv::load()?;
+///     // 加载 config.toml 并以 config.prod.toml 覆盖
+///     let cfg = Config::<AppConfig>::load("config.toml")?;
+///     tokio::runtime::Runtime::new()?.block_on(run(cfg))
+/// }
+/// ```
+pub fn load() -> anyhow::Result<Vec<PathBuf>> {
+    load_from(".")
+}
+
+/// 加载指定目录下的 `.env` 文件，规则同 [`load`]
+pub fn load_from(dir: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let profile = match profile() {
+        Some(v) => Some(v),
+        None => {
+            let mut v = None;
+            for name in [".env.local", ".env"] {
+                v = read(&dir.join(name))?.and_then(|vars| {
+                    vars.into_iter()
+                        .find(|(k, v)| k == PROFILE_ENV && !v.trim().is_empty())
+                        .map(|(_, v)| v.trim().to_string())
+                });
+                if v.is_some() {
+                    break;
+                }
+            }
+            v
+        }
+    };
+    load_profile(dir, profile.as_deref())
+}
+
+fn load_profile(dir: &Path, profile: Option<&str>) -> anyhow::Result<Vec<PathBuf>> {
+    let mut names = Vec::with_capacity(4);
+    if let Some(p) = profile {
+        names.push(format!(".env.{}.local", p));
+    }
+    names.push(".env.local".to_string());
+    if let Some(p) = profile {
+        names.push(format!(".env.{}", p));
+    }
+    names.push(".env".to_string());
+
+    // 按优先级从高到低加载，已存在的变量不覆盖
+    let mut loaded = Vec::new();
+    for name in names {
+        let path = dir.join(name);
+        let Some(vars) = read(&path)? else {
+            continue;
+        };
+        for (k, v) in vars {
+            if std::env::var_os(&k).is_none() {
+                std::env::set_var(k, v);
+            }
+        }
+        loaded.push(path);
+    }
+    if !loaded.is_empty() {
+        tracing::info!(profile = profile, files = ?loaded, "[config::dotenv] env loaded");
+    }
+    Ok(loaded)
+}
+
+fn read(path: &Path) -> anyhow::Result<Option<Vec<(String, String)>>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let vars = dotenvy::from_path_iter(path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| anyhow!("config/dotenv: parse `{}` failed: {}", path.display(), e))?;
+    Ok(Some(vars))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::dotenv;
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("kr-dotenv-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(".env"),
+            "KR_DOTENV_A=base\nKR_DOTENV_B=base\nKR_DOTENV_C=base\nKR_DOTENV_D=base\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".env.test"),
+            "KR_DOTENV_B=profile\nKR_DOTENV_C=profile\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join(".env.local"), "KR_DOTENV_C=local\n").unwrap();
+        std::fs::write(dir.join(".env.prod"), "KR_DOTENV_A=prod\n").unwrap();
+        std::env::set_var("KR_DOTENV_D", "process");
+
+        let loaded = dotenv::load_profile(&dir, Some("test")).unwrap();
+        assert_eq!(
+            loaded,
+            vec![
+                dir.join(".env.local"),
+                dir.join(".env.test"),
+                dir.join(".env")
+            ]
+        );
+        assert_eq!(std::env::var("KR_DOTENV_A").unwrap(), "base");
+        assert_eq!(std::env::var("KR_DOTENV_B").unwrap(), "profile");
+        assert_eq!(std::env::var("KR_DOTENV_C").unwrap(), "local");
+        assert_eq!(std::env::var("KR_DOTENV_D").unwrap(), "process");
+
+        std::fs::write(dir.join(".env.local"), "KR_DOTENV_E='unterminated\n").unwrap();
+        assert!(dotenv::load_profile(&dir, None).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,358 @@
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+
+use rand::Rng;
+use redis::AsyncCommands;
+
+use crate::{
+    helper::redkit::{key, Redis},
+    idgen,
+    redix::script,
+};
+
+// 记录批次ID的字段
+const BATCH_FIELD: &str = "__batch";
+
+/// 将计数 hash 切换为待刷写状态并返回其内容；上次刷写未完成时直接返回上次的内容（重放）
+pub const ROTATE: &str = r#"
+if redis.call('EXISTS', KEYS[2]) == 0 then
+    if redis.call('EXISTS', KEYS[1]) == 0 then
+        return {}
+    end
+    redis.call('RENAME', KEYS[1], KEYS[2])
+    redis.call('HSET', KEYS[2], '__batch', ARGV[1])
+end
+return redis.call('HGETALL', KEYS[2])
+"#;
+
+inventory::submit! { crate::redix::script::Builtin { name: "counterkit:rotate", body: ROTATE } }
+
+/// 一次待刷写的计数增量
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// 批次ID：重放时保持不变，可用于落库幂等
+    pub id: String,
+    /// id => 增量
+    pub deltas: HashMap<String, i64>,
+}
+
+/// 增量落库
+pub trait Sink: Send + Sync {
+    fn flush(&self, batch: Batch) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+/// 基于闭包的落库
+///
+/// # Examples
+///
+/// ```
+/// let sink = |batch: Batch| {
+///     let pool = pool.clone();
+///     async move {
+///         let mut tx = pool.begin().await?;
+///         // 批次已处理（重放）则跳过
+///         let stmt = Query::select()
+///             .from(table::CounterBatch::Table)
+///             .and_where(Expr::col(table::CounterBatch::Id).eq(&batch.id))
+///             .to_owned();
+///         if mysql::count(&mut *tx, stmt).await? > 0 {
+///             return Ok(());
+///         }
+///         let stmt = Query::insert()
+///             .into_table(table::CounterBatch::Table)
+///             .columns([table::CounterBatch::Id])
+///             .values_panic([batch.id.into()])
+///             .to_owned();
+///         mysql::create(&mut *tx, stmt).await?;
+///         for (id, delta) in batch.deltas {
+///             let stmt = Query::update()
+///                 .table(table::Post::Table)
+///                 .value(table::Post::Views, Expr::col(table::Post::Views).add(delta))
+///                 .and_where(Expr::col(table::Post::Id).eq(id))
+///                 .to_owned();
+///             mysql::update(&mut *tx, stmt).await?;
+///         }
+///         tx.commit().await?;
+///         Ok(())
+///     }
+/// };
+/// ```
+impl<F, Fut> Sink for F
+where
+    F: Fn(Batch) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<()>> + Send,
+{
+    fn flush(&self, batch: Batch) -> impl Future<Output = anyhow::Result<()>> + Send {
+        self(batch)
+    }
+}
+
+/// 分布式计数器：计数写入 Redis 分片 hash，定期将增量刷写到 DB
+///
+/// - 每次计数随机写入一个分片，分散热点
+/// - 刷写时先将分片原子切换为待刷写状态，落库成功后删除；落库失败或进程崩溃时，下次刷写以相同批次ID重放
+///
+/// # Examples
+///
+/// ```
+/// let counter = Arc::new(Counter::new(Redis::Single(pool), "post:views", None));
+///
+/// counter.incr("10086", 1).await?;
+///
+/// // 每10秒刷写一次
+/// counter.spawn(sink, Duration::from_secs(10));
+///
+/// // 退出前手动刷写
+/// counter.flush(&sink).await?;
+/// ```
+pub struct Counter {
+    redis: Redis,
+    name: String,
+    shards: u32,
+}
+
+impl Counter {
+    /// `shards` 为分片数量，默认：16
+    pub fn new(redis: Redis, name: impl AsRef<str>, shards: Option<u32>) -> Self {
+        Self {
+            redis,
+            name: name.as_ref().to_string(),
+            shards: shards.unwrap_or(16).max(1),
+        }
+    }
+
+    // 使用 hash tag 保证集群模式下两个 key 位于同一 slot
+    fn keys(&self, shard: u32) -> (String, String) {
+        let key = key::with_prefix(format!("kr:counter:{{{}:{}}}", self.name, shard));
+        let flushing = format!("{}:flushing", key);
+        (key, flushing)
+    }
+
+    /// 计数
+    pub async fn incr(&self, id: impl AsRef<str>, delta: i64) -> anyhow::Result<()> {
+        let shard = rand::thread_rng().gen_range(0..self.shards);
+        let (key, _) = self.keys(shard);
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool.get().await?.hincr(key, id.as_ref(), delta).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool.get().await?.hincr(key, id.as_ref(), delta).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 尚未落库的增量（含刷写中的）
+    pub async fn pending(&self, id: impl AsRef<str>) -> anyhow::Result<i64> {
+        let mut total = 0;
+        for shard in 0..self.shards {
+            let (key, flushing) = self.keys(shard);
+            let (a, b): (Option<i64>, Option<i64>) = match &self.redis {
+                Redis::Single(pool) => {
+                    let mut conn = pool.get().await?;
+                    (
+                        conn.hget(&key, id.as_ref()).await?,
+                        conn.hget(&flushing, id.as_ref()).await?,
+                    )
+                }
+                Redis::Cluster(pool) => {
+                    let mut conn = pool.get().await?;
+                    (
+                        conn.hget(&key, id.as_ref()).await?,
+                        conn.hget(&flushing, id.as_ref()).await?,
+                    )
+                }
+            };
+            total += a.unwrap_or(0) + b.unwrap_or(0);
+        }
+        Ok(total)
+    }
+
+    /// 刷写所有分片，返回落库的 id 数量
+    ///
+    /// 某个分片失败时继续刷写其余分片，最后汇总返回错误（失败分片的数据保留，下次刷写时重放）
+    pub async fn flush<S: Sink>(&self, sink: &S) -> anyhow::Result<usize> {
+        let mut count = 0;
+        let mut errs = Vec::new();
+        for shard in 0..self.shards {
+            match self.flush_shard(shard, sink).await {
+                Ok(n) => count += n,
+                Err(e) => errs.push(format!("shard {}: {:#}", shard, e)),
+            }
+        }
+        if !errs.is_empty() {
+            return Err(anyhow::anyhow!(
+                "counterkit: flush failed for {}/{} shards: {}",
+                errs.len(),
+                self.shards,
+                errs.join("; ")
+            ));
+        }
+        Ok(count)
+    }
+
+    async fn flush_shard<S: Sink>(&self, shard: u32, sink: &S) -> anyhow::Result<usize> {
+        let (key, flushing) = self.keys(shard);
+        let batch_id = idgen::uuid_v7().to_string();
+
+        let mut data: HashMap<String, String> = script::global()
+            .call("counterkit:rotate")?
+            .key(&key)
+            .key(&flushing)
+            .arg(&batch_id)
+            .invoke(&self.redis)
+            .await?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        let batch = Batch {
+            id: data.remove(BATCH_FIELD).unwrap_or(batch_id),
+            deltas: data
+                .into_iter()
+                .filter_map(|(k, v)| v.parse::<i64>().ok().map(|n| (k, n)))
+                .filter(|(_, n)| *n != 0)
+                .collect(),
+        };
+        let count = batch.deltas.len();
+        if count > 0 {
+            sink.flush(batch).await?;
+        }
+
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool.get().await?.del(&flushing).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool.get().await?.del(&flushing).await?;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 后台定期刷写，Counter 释放后退出
+    pub fn spawn<S: Sink + 'static>(
+        self: &Arc<Self>,
+        sink: S,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let counter = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(counter) = counter.upgrade() else {
+                    return;
+                };
+                if let Err(e) = counter.flush(&sink).await {
+                    tracing::error!(err = ?e, name = counter.name, "[counterkit] flush failed");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use anyhow::anyhow;
+    use redis::AsyncCommands;
+
+    use crate::{
+        counterkit::{Batch, Counter},
+        helper::redkit::Redis,
+        redix,
+    };
+
+    #[tokio::test]
+    async fn test_counter() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let counter = Counter::new(Redis::Single(pool), "views", Some(4));
+
+        for _ in 0..10 {
+            counter.incr("a", 1).await.unwrap();
+        }
+        counter.incr("b", 5).await.unwrap();
+        assert_eq!(counter.pending("a").await.unwrap(), 10);
+
+        let db: Arc<Mutex<HashMap<String, i64>>> = Arc::default();
+        let batches: Arc<Mutex<Vec<String>>> = Arc::default();
+        let fail = Arc::new(Mutex::new(true));
+
+        let sink = |batch: Batch| {
+            let (db, batches, fail) = (db.clone(), batches.clone(), fail.clone());
+            async move {
+                if *fail.lock().unwrap() {
+                    return Err(anyhow!("db down"));
+                }
+                let mut batches = batches.lock().unwrap();
+                // 幂等：同一批次只应用一次
+                if batches.contains(&batch.id) {
+                    return Ok(());
+                }
+                batches.push(batch.id);
+                let mut db = db.lock().unwrap();
+                for (k, v) in batch.deltas {
+                    *db.entry(k).or_default() += v;
+                }
+                Ok(())
+            }
+        };
+
+        // 落库失败，数据保留待重放
+        assert!(counter.flush(&sink).await.is_err());
+        counter.incr("a", 1).await.unwrap();
+        assert_eq!(counter.pending("a").await.unwrap(), 11);
+
+        *fail.lock().unwrap() = false;
+        counter.flush(&sink).await.unwrap();
+        counter.flush(&sink).await.unwrap();
+        assert_eq!(counter.pending("a").await.unwrap(), 0);
+
+        let db = db.lock().unwrap();
+        assert_eq!(db.get("a"), Some(&11));
+        assert_eq!(db.get("b"), Some(&5));
+    }
+
+    #[tokio::test]
+    async fn test_counter_partial_failure() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let counter = Counter::new(Redis::Single(pool.clone()), "likes", Some(4));
+
+        // 每个分片各写入一次
+        for shard in 0..4 {
+            let (key, _) = counter.keys(shard);
+            let _: () = pool.get().await.unwrap().hincr(key, "x", 1).await.unwrap();
+        }
+
+        // 仅首个批次失败
+        let calls = Arc::new(Mutex::new(0));
+        let sink = |_: Batch| {
+            let calls = calls.clone();
+            async move {
+                let mut calls = calls.lock().unwrap();
+                *calls += 1;
+                if *calls == 1 {
+                    return Err(anyhow!("db down"));
+                }
+                Ok(())
+            }
+        };
+
+        let err = counter.flush(&sink).await.unwrap_err();
+        assert!(err.to_string().contains("1/4 shards"));
+        // 其余分片继续刷写
+        assert_eq!(*calls.lock().unwrap(), 4);
+        assert_eq!(counter.pending("x").await.unwrap(), 1);
+
+        assert_eq!(counter.flush(&sink).await.unwrap(), 1);
+        assert_eq!(counter.pending("x").await.unwrap(), 0);
+    }
+}
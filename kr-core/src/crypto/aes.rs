@@ -1,5 +1,9 @@
 use anyhow::{anyhow, Result};
 use openssl::symm::{decrypt_aead, encrypt_aead, Cipher, Crypter, Mode};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// 流式加解密默认分块大小：64KB
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
 
 /// AES-CBC pkcs#7
 pub struct CBC<K, I> {
@@ -210,6 +214,156 @@ where
         Ok(out)
     }
 
+    /// 流式加密（分块 AES-GCM，每块附带 tag，适用于大文件）
+    ///
+    /// - 输出格式：[ciphertext(chunk_size) | tag(16)]... [ciphertext(<=chunk_size) | tag(16)]
+    /// - 分块 nonce：完整 nonce（>= 12 字节）末 5 字节依次异或 块序号(4字节) | 末块标记(1字节)，可检测分块截断、重排
+    /// - chunk_size: 默认=64KB，解密时须一致
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let gcm = GCM::new(key, nonce);
+    ///
+    /// let mut reader = tokio::fs::File::open("plain.bin").await?;
+    /// let mut writer = tokio::fs::File::create("cipher.bin").await?;
+    /// let n = gcm.encrypt_stream(&mut reader, &mut writer, "aad", None).await?;
+    /// ```
+    pub async fn encrypt_stream<R, W>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        aad: impl AsRef<[u8]>,
+        chunk_size: Option<usize>,
+    ) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let t = self.cipher()?;
+        let chunk_size = chunk_size.unwrap_or(STREAM_CHUNK_SIZE);
+        if chunk_size == 0 {
+            return Err(anyhow!("crypto/aes: invalid chunk size"));
+        }
+
+        // 多读1字节，用于判断是否为末块
+        let mut buf = vec![0; chunk_size + 1];
+        let mut len = read_full(reader, &mut buf).await?;
+        let mut counter = 0u32;
+        let mut total = 0u64;
+        loop {
+            let last = len <= chunk_size;
+            let n = len.min(chunk_size);
+
+            let mut tag = vec![0; 16];
+            let nonce = self.chunk_nonce(counter, last)?;
+            let out = encrypt_aead(
+                t,
+                self.key.as_ref(),
+                Some(&nonce),
+                aad.as_ref(),
+                &buf[..n],
+                &mut tag,
+            )?;
+            writer.write_all(&out).await?;
+            writer.write_all(&tag).await?;
+            total += (out.len() + tag.len()) as u64;
+
+            if last {
+                break;
+            }
+            buf.copy_within(n..len, 0);
+            len = len - n + read_full(reader, &mut buf[len - n..]).await?;
+            counter = counter
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("crypto/aes: too many chunks"))?;
+        }
+        writer.flush().await?;
+
+        Ok(total)
+    }
+
+    /// 流式解密（与 `encrypt_stream` 对应）
+    ///
+    /// 每块 tag 校验通过后才写出该块明文；返回错误时 writer 中可能已有之前校验通过的块，调用方须丢弃
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let gcm = GCM::new(key, nonce);
+    ///
+    /// let mut reader = tokio::fs::File::open("cipher.bin").await?;
+    /// let mut writer = tokio::fs::File::create("plain.bin").await?;
+    /// let n = gcm.decrypt_stream(&mut reader, &mut writer, "aad", None).await?;
+    /// ```
+    pub async fn decrypt_stream<R, W>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        aad: impl AsRef<[u8]>,
+        chunk_size: Option<usize>,
+    ) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let t = self.cipher()?;
+        let chunk_size = chunk_size.unwrap_or(STREAM_CHUNK_SIZE);
+        if chunk_size == 0 {
+            return Err(anyhow!("crypto/aes: invalid chunk size"));
+        }
+        let block = chunk_size + 16;
+
+        let mut buf = vec![0; block + 1];
+        let mut len = read_full(reader, &mut buf).await?;
+        let mut counter = 0u32;
+        let mut total = 0u64;
+        loop {
+            let last = len <= block;
+            let n = len.min(block);
+            if n < 16 {
+                return Err(anyhow!("crypto/aes: truncated stream"));
+            }
+
+            let (data, tag) = buf[..n].split_at(n - 16);
+            let nonce = self.chunk_nonce(counter, last)?;
+            // decrypt_aead 校验 tag 失败时不返回明文
+            let out = decrypt_aead(t, self.key.as_ref(), Some(&nonce), aad.as_ref(), data, tag)?;
+            writer.write_all(&out).await?;
+            total += out.len() as u64;
+
+            if last {
+                break;
+            }
+            buf.copy_within(n..len, 0);
+            len = len - n + read_full(reader, &mut buf[len - n..]).await?;
+            counter = counter
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("crypto/aes: too many chunks"))?;
+        }
+        writer.flush().await?;
+
+        Ok(total)
+    }
+
+    // 保留完整 nonce，仅将块序号和末块标记异或到末尾，不同块的 nonce 互不相同
+    fn chunk_nonce(&self, counter: u32, last: bool) -> Result<Vec<u8>> {
+        let mut nonce = self.nonce.as_ref().to_vec();
+        if nonce.len() < 12 {
+            return Err(anyhow!(
+                "crypto/aes: nonce too short for stream (>= 12 bytes)"
+            ));
+        }
+        let tail = nonce.len() - 5;
+        for (b, v) in nonce[tail..]
+            .iter_mut()
+            .zip(counter.to_be_bytes().into_iter().chain([last as u8]))
+        {
+            *b ^= v;
+        }
+        Ok(nonce)
+    }
+
     fn cipher(&self) -> Result<Cipher> {
         let cipher = match self.key.as_ref().len() {
             16 => Cipher::aes_128_gcm(),
@@ -221,6 +375,130 @@ where
     }
 }
 
+/// AES-CTR
+pub struct CTR<K, I> {
+    key: K,
+    iv: I,
+}
+
+impl<K, I> CTR<K, I>
+where
+    K: AsRef<[u8]>,
+    I: AsRef<[u8]>,
+{
+    pub fn new(key: K, iv: I) -> Self {
+        Self { key, iv }
+    }
+
+    /// # Example
+    ///
+    /// ```
+    /// let ctr = CTR::new(key, iv);
+    /// let cipher = ctr.encrypt("plaintext").unwrap();
+    /// ```
+    pub fn encrypt(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+        self.apply(Mode::Encrypt, data.as_ref())
+    }
+
+    /// # Example
+    ///
+    /// ```
+    /// let ctr = CTR::new(key, iv);
+    /// let plain = ctr.decrypt("ciphertext").unwrap();
+    /// ```
+    pub fn decrypt(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+        self.apply(Mode::Decrypt, data.as_ref())
+    }
+
+    /// 流式加密（无完整性校验，如需校验请使用 `GCM::encrypt_stream`）
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ctr = CTR::new(key, iv);
+    ///
+    /// let mut reader = tokio::fs::File::open("plain.bin").await?;
+    /// let mut writer = tokio::fs::File::create("cipher.bin").await?;
+    /// let n = ctr.encrypt_stream(&mut reader, &mut writer).await?;
+    /// ```
+    pub async fn encrypt_stream<R, W>(&self, reader: &mut R, writer: &mut W) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        self.apply_stream(Mode::Encrypt, reader, writer).await
+    }
+
+    /// 流式解密（无完整性校验，密文被篡改时输出错误明文而不报错）
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ctr = CTR::new(key, iv);
+    ///
+    /// let mut reader = tokio::fs::File::open("cipher.bin").await?;
+    /// let mut writer = tokio::fs::File::create("plain.bin").await?;
+    /// let n = ctr.decrypt_stream(&mut reader, &mut writer).await?;
+    /// ```
+    pub async fn decrypt_stream<R, W>(&self, reader: &mut R, writer: &mut W) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        self.apply_stream(Mode::Decrypt, reader, writer).await
+    }
+
+    fn apply(&self, mode: Mode, data: &[u8]) -> Result<Vec<u8>> {
+        let t = self.cipher()?;
+        let mut c = Crypter::new(t, mode, self.key.as_ref(), Some(self.iv.as_ref()))?;
+
+        let mut out = vec![0; data.len() + t.block_size()];
+        let mut count = c.update(data, &mut out)?;
+        count += c.finalize(&mut out[count..])?;
+        out.truncate(count);
+
+        Ok(out)
+    }
+
+    async fn apply_stream<R, W>(&self, mode: Mode, reader: &mut R, writer: &mut W) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let t = self.cipher()?;
+        let mut c = Crypter::new(t, mode, self.key.as_ref(), Some(self.iv.as_ref()))?;
+
+        let mut buf = vec![0; STREAM_CHUNK_SIZE];
+        let mut out = vec![0; STREAM_CHUNK_SIZE + t.block_size()];
+        let mut total = 0u64;
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            let count = c.update(&buf[..n], &mut out)?;
+            writer.write_all(&out[..count]).await?;
+            total += count as u64;
+        }
+        let count = c.finalize(&mut out)?;
+        writer.write_all(&out[..count]).await?;
+        writer.flush().await?;
+        total += count as u64;
+
+        Ok(total)
+    }
+
+    fn cipher(&self) -> Result<Cipher> {
+        let cipher = match self.key.as_ref().len() {
+            16 => Cipher::aes_128_ctr(),
+            24 => Cipher::aes_192_ctr(),
+            32 => Cipher::aes_256_ctr(),
+            _ => return Err(anyhow!("crypto/aes: invalid key size")),
+        };
+        Ok(cipher)
+    }
+}
+
 fn pkcs7_padding(data: &[u8], block_size: usize) -> Vec<u8> {
     let mut padding = block_size - data.len() % block_size;
     if padding == 0 {
@@ -238,11 +516,24 @@ fn pkcs7_unpadding(data: &[u8]) -> Vec<u8> {
     data[..len - padding].to_vec()
 }
 
+// 尽量读满 buf，返回实际读取的字节数（< buf.len() 表示 EOF）
+async fn read_full<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        let m = reader.read(&mut buf[n..]).await?;
+        if m == 0 {
+            break;
+        }
+        n += m;
+    }
+    Ok(n)
+}
+
 #[cfg(test)]
 mod tests {
     use base64::{prelude::BASE64_STANDARD, Engine};
 
-    use crate::crypto::aes::{CBC, ECB, GCM};
+    use crate::crypto::aes::{CBC, CTR, ECB, GCM};
 
     #[test]
     fn aes_cbc() {
@@ -311,4 +602,92 @@ mod tests {
         let plain = gcm.decrypt(&cipher2, "IIInsomnia", &tag2).unwrap();
         assert_eq!(plain, b"ILoveRust");
     }
+
+    #[test]
+    fn aes_ctr() {
+        let key = "AES256Key-32Characters1234567890";
+        let ctr = CTR::new(key, &key[..16]);
+
+        let cipher = ctr.encrypt("ILoveRust").unwrap();
+        assert_eq!(cipher.len(), 9);
+
+        let plain = ctr.decrypt(&cipher).unwrap();
+        assert_eq!(plain, b"ILoveRust");
+    }
+
+    #[tokio::test]
+    async fn aes_stream() {
+        let key = "AES256Key-32Characters1234567890";
+        let data: Vec<u8> = (0..1000u32).map(|v| v as u8).collect();
+
+        // CTR：与一次性加密结果一致
+        let ctr = CTR::new(key, &key[..16]);
+        let mut cipher = Vec::new();
+        ctr.encrypt_stream(&mut &data[..], &mut cipher)
+            .await
+            .unwrap();
+        assert_eq!(cipher, ctr.encrypt(&data).unwrap());
+        let mut plain = Vec::new();
+        ctr.decrypt_stream(&mut &cipher[..], &mut plain)
+            .await
+            .unwrap();
+        assert_eq!(plain, data);
+
+        // GCM：分块边界 & 空数据
+        let gcm = GCM::new(key, &key[..12]);
+        for len in [0, 1, 99, 100, 101, 1000] {
+            let mut cipher = Vec::new();
+            let n = gcm
+                .encrypt_stream(&mut &data[..len], &mut cipher, "aad", Some(100))
+                .await
+                .unwrap();
+            assert_eq!(n as usize, cipher.len());
+
+            let mut plain = Vec::new();
+            gcm.decrypt_stream(&mut &cipher[..], &mut plain, "aad", Some(100))
+                .await
+                .unwrap();
+            assert_eq!(plain, &data[..len]);
+        }
+
+        // 截断末块
+        let mut cipher = Vec::new();
+        gcm.encrypt_stream(&mut &data[..], &mut cipher, "aad", Some(100))
+            .await
+            .unwrap();
+        let mut plain = Vec::new();
+        assert!(gcm
+            .decrypt_stream(&mut &cipher[..116 * 9], &mut plain, "aad", Some(100))
+            .await
+            .is_err());
+
+        // 篡改第 3 块：仅写出前 2 块已校验的明文
+        let mut tampered = cipher.clone();
+        tampered[116 * 2] ^= 1;
+        let mut plain = Vec::new();
+        assert!(gcm
+            .decrypt_stream(&mut &tampered[..], &mut plain, "aad", Some(100))
+            .await
+            .is_err());
+        assert_eq!(plain, &data[..200]);
+
+        // nonce 不同字节（含前 7 字节之外）均影响密文
+        let nonce2 = {
+            let mut v = key.as_bytes()[..12].to_vec();
+            v[8] ^= 1;
+            v
+        };
+        let mut cipher2 = Vec::new();
+        GCM::new(key, &nonce2)
+            .encrypt_stream(&mut &data[..], &mut cipher2, "aad", Some(100))
+            .await
+            .unwrap();
+        assert_ne!(cipher2, cipher);
+
+        // nonce 过短
+        assert!(GCM::new(key, &key[..8])
+            .encrypt_stream(&mut &data[..], &mut Vec::new(), "aad", Some(100))
+            .await
+            .is_err());
+    }
 }
@@ -0,0 +1,101 @@
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use rand::RngCore;
+
+use crate::crypto::aes::GCM;
+
+pub type Result<T> = anyhow::Result<T>;
+
+pub type KeyProvider = fn() -> Result<Vec<u8>>;
+
+static KEY_PROVIDER: OnceLock<KeyProvider> = OnceLock::new();
+static KEY: OnceLock<Vec<u8>> = OnceLock::new();
+
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+
+/// 设置字段加密密钥（AES-128/192/256），密钥在首次加解密时加载并缓存
+///
+/// # Examples
+///
+/// ```
+/// field::set_key_provider(|| {
+///     let key = std::env::var("FIELD_KEY")?;
+///     Ok(BASE64_STANDARD.decode(key)?)
+/// })
+/// ```
+pub fn set_key_provider(f: KeyProvider) {
+    let _ = KEY_PROVIDER.set(f);
+}
+
+fn key() -> Result<&'static [u8]> {
+    if let Some(v) = KEY.get() {
+        return Ok(v);
+    }
+    let f = KEY_PROVIDER
+        .get()
+        .ok_or_else(|| anyhow!("crypto/field: key provider not set"))?;
+    let key = f()?;
+    Ok(KEY.get_or_init(|| key))
+}
+
+/// 字段加密（AES-GCM），输出 base64(nonce | ciphertext | tag)
+///
+/// # Example
+///
+/// ```
+/// let cipher = field::encrypt("13800138000")?;
+/// ```
+pub fn encrypt(plain: impl AsRef<str>) -> Result<String> {
+    let key = key()?;
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let (cipher, tag) = GCM::new(key, &nonce).encrypt(plain.as_ref(), "", Some(TAG_SIZE))?;
+
+    let mut out = Vec::with_capacity(NONCE_SIZE + cipher.len() + TAG_SIZE);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&cipher);
+    out.extend_from_slice(&tag);
+    Ok(BASE64_STANDARD.encode(out))
+}
+
+/// 字段解密
+///
+/// # Example
+///
+/// ```
+/// let plain = field::decrypt(cipher)?;
+/// ```
+pub fn decrypt(cipher: impl AsRef<str>) -> Result<String> {
+    let key = key()?;
+
+    let data = BASE64_STANDARD.decode(cipher.as_ref())?;
+    if data.len() < NONCE_SIZE + TAG_SIZE {
+        return Err(anyhow!("crypto/field: invalid ciphertext"));
+    }
+    let (nonce, rest) = data.split_at(NONCE_SIZE);
+    let (cipher, tag) = rest.split_at(rest.len() - TAG_SIZE);
+
+    let plain = GCM::new(key, nonce).decrypt(cipher, "", tag)?;
+    Ok(String::from_utf8(plain)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::field;
+
+    #[test]
+    fn field_crypt() {
+        field::set_key_provider(|| Ok(b"AES256Key-32Characters1234567890".to_vec()));
+
+        let cipher = field::encrypt("13800138000").unwrap();
+        assert_ne!(cipher, field::encrypt("13800138000").unwrap());
+        assert_eq!(field::decrypt(&cipher).unwrap(), "13800138000");
+
+        assert!(field::decrypt("aGVsbG8=").is_err());
+    }
+}
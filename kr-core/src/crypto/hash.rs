@@ -140,13 +140,54 @@ pub fn hmac<D: Digest + BlockSizeUser, T: HashOutput>(
     T::from_bytes(h.finalize().into_bytes().into_iter().collect::<Vec<u8>>())
 }
 
+/// 计算MurmurHash3 (x86_32)，非加密哈希，适用于分桶等场景
+///
+/// # Example
+///
+/// ```
+/// let h = murmur3_32(b"shenghui", 0);
+/// ```
+pub fn murmur3_32(data: impl AsRef<[u8]>, seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let data = data.as_ref();
+    let mut h = seed;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in chunks.by_ref() {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, b) in tail.iter().enumerate() {
+            k |= (*b as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+    }
+
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^= h >> 16;
+    h
+}
+
 #[cfg(test)]
 mod tests {
     use md5::Md5;
     use sha1::Sha1;
     use sha2::{Sha224, Sha256, Sha384, Sha512, Sha512_224, Sha512_256};
 
-    use crate::crypto::hash::{hash, hmac, hmac_sha1, hmac_sha256, md5, sha1, sha256};
+    use crate::crypto::hash::{hash, hmac, hmac_sha1, hmac_sha256, md5, murmur3_32, sha1, sha256};
 
     #[test]
     fn digest_hash() {
@@ -235,4 +276,16 @@ mod tests {
             "6ea90a066be004ca5ac384d79605d8a2403cc8a9b14ffc988822bf85be12b038"
         );
     }
+
+    #[test]
+    fn murmur3() {
+        assert_eq!(murmur3_32("", 0), 0);
+        assert_eq!(murmur3_32("", 1), 0x514e28b7);
+        assert_eq!(murmur3_32("test", 0), 0xba6bd213);
+        assert_eq!(murmur3_32("Hello, world!", 1234), 0xfaf6cdb3);
+        assert_eq!(
+            murmur3_32("The quick brown fox jumps over the lazy dog", 0),
+            0x2e4ff723
+        );
+    }
 }
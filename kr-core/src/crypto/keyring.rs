@@ -0,0 +1,237 @@
+use std::{collections::BTreeMap, path::Path, sync::RwLock};
+
+use anyhow::{anyhow, bail};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use rand::RngCore;
+
+use crate::crypto::aes::GCM;
+
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 5; // version(1) | kid(4)
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const DEK_SIZE: usize = 32;
+const WRAPPED_SIZE: usize = NONCE_SIZE + DEK_SIZE + TAG_SIZE;
+
+/// 多版本密钥环（信封加密）
+///
+/// 每次加密随机生成数据密钥(DEK)加密数据，再由最新版本的主密钥加密 DEK；
+/// 密文头部携带主密钥版本(kid)，轮换后旧版本密文仍可解密。
+///
+/// 密文格式：version(1) | kid(4) | wrapped_dek(nonce | dek | tag) | nonce | ciphertext | tag
+///
+/// # Examples
+///
+/// ```
+/// let ring = Keyring::from_env("APP_KEY")?; // APP_KEY_1=base64, APP_KEY_2=base64 ...
+/// let cipher = ring.encrypt("hello")?;
+/// let plain = ring.decrypt(&cipher)?;
+///
+/// // 轮换：新增版本，新数据使用新密钥加密
+/// ring.rotate(new_key)?;
+/// let cipher = ring.reencrypt(&cipher)?;
+/// ```
+#[derive(Debug, Default)]
+pub struct Keyring {
+    keys: RwLock<BTreeMap<u32, Vec<u8>>>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从环境变量加载：<PREFIX>_<kid>=base64(key)
+    pub fn from_env(prefix: &str) -> anyhow::Result<Self> {
+        let ring = Self::new();
+        let prefix = format!("{}_", prefix);
+        for (k, v) in std::env::vars() {
+            if let Some(kid) = k.strip_prefix(&prefix) {
+                let kid: u32 = match kid.parse() {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                ring.add(kid, BASE64_STANDARD.decode(v.trim())?)?;
+            }
+        }
+        Ok(ring)
+    }
+
+    /// 从 JSON 文件加载：{"1": "base64(key)", "2": "base64(key)"}
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let data = std::fs::read(path)?;
+        let map: BTreeMap<u32, String> = serde_json::from_slice(&data)?;
+
+        let ring = Self::new();
+        for (kid, v) in map {
+            ring.add(kid, BASE64_STANDARD.decode(v.trim())?)?;
+        }
+        Ok(ring)
+    }
+
+    /// 从回调加载（如：调用 KMS 解密主密钥）
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = Keyring::from_fn(|| {
+    ///     let keys = kms_client.decrypt_keys()?;
+    ///     Ok(keys.into_iter().map(|k| (k.version, k.plaintext)).collect())
+    /// })?;
+    /// ```
+    pub fn from_fn<F>(f: F) -> anyhow::Result<Self>
+    where
+        F: FnOnce() -> anyhow::Result<Vec<(u32, Vec<u8>)>>,
+    {
+        let ring = Self::new();
+        for (kid, key) in f()? {
+            ring.add(kid, key)?;
+        }
+        Ok(ring)
+    }
+
+    /// 添加指定版本的主密钥（AES-128/192/256）
+    pub fn add(&self, kid: u32, key: Vec<u8>) -> anyhow::Result<()> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            bail!(
+                "crypto/keyring: invalid key size {} (kid={})",
+                key.len(),
+                kid
+            );
+        }
+        self.keys.write().unwrap().insert(kid, key);
+        Ok(())
+    }
+
+    /// 轮换：以 最新版本+1 添加主密钥，返回新的 kid
+    pub fn rotate(&self, key: Vec<u8>) -> anyhow::Result<u32> {
+        let kid = self.primary().map_or(1, |v| v + 1);
+        self.add(kid, key)?;
+        Ok(kid)
+    }
+
+    /// 当前用于加密的版本（最新版本）
+    pub fn primary(&self) -> Option<u32> {
+        self.keys.read().unwrap().keys().next_back().copied()
+    }
+
+    /// 使用最新版本主密钥加密
+    pub fn encrypt(&self, plain: impl AsRef<[u8]>) -> anyhow::Result<Vec<u8>> {
+        let (kid, key) = {
+            let keys = self.keys.read().unwrap();
+            let (kid, key) = keys
+                .iter()
+                .next_back()
+                .ok_or_else(|| anyhow!("crypto/keyring: empty keyring"))?;
+            (*kid, key.clone())
+        };
+
+        let mut header = [0u8; HEADER_SIZE];
+        header[0] = VERSION;
+        header[1..].copy_from_slice(&kid.to_be_bytes());
+
+        let mut dek = [0u8; DEK_SIZE];
+        rand::thread_rng().fill_bytes(&mut dek);
+
+        let plain = plain.as_ref();
+        let mut out =
+            Vec::with_capacity(HEADER_SIZE + WRAPPED_SIZE + NONCE_SIZE + plain.len() + TAG_SIZE);
+        out.extend_from_slice(&header);
+        seal(&key, &dek, &header, &mut out)?;
+        seal(&dek, plain, &header, &mut out)?;
+        Ok(out)
+    }
+
+    /// 解密（根据密文头部的 kid 选择主密钥）
+    pub fn decrypt(&self, cipher: impl AsRef<[u8]>) -> anyhow::Result<Vec<u8>> {
+        let data = cipher.as_ref();
+        let kid = Self::kid(data)?;
+        if data.len() < HEADER_SIZE + WRAPPED_SIZE + NONCE_SIZE + TAG_SIZE {
+            bail!("crypto/keyring: invalid ciphertext");
+        }
+
+        let key = self
+            .keys
+            .read()
+            .unwrap()
+            .get(&kid)
+            .cloned()
+            .ok_or_else(|| anyhow!("crypto/keyring: unknown kid {}", kid))?;
+
+        let (header, rest) = data.split_at(HEADER_SIZE);
+        let (wrapped, body) = rest.split_at(WRAPPED_SIZE);
+        let dek = open(&key, wrapped, header)?;
+        open(&dek, body, header)
+    }
+
+    /// 使用最新版本重新加密（已是最新版本则原样返回）
+    pub fn reencrypt(&self, cipher: impl AsRef<[u8]>) -> anyhow::Result<Vec<u8>> {
+        let data = cipher.as_ref();
+        if Some(Self::kid(data)?) == self.primary() {
+            return Ok(data.to_vec());
+        }
+        self.encrypt(self.decrypt(data)?)
+    }
+
+    /// 读取密文的主密钥版本
+    pub fn kid(cipher: &[u8]) -> anyhow::Result<u32> {
+        if cipher.len() < HEADER_SIZE || cipher[0] != VERSION {
+            bail!("crypto/keyring: invalid ciphertext header");
+        }
+        Ok(u32::from_be_bytes(cipher[1..HEADER_SIZE].try_into()?))
+    }
+}
+
+// nonce | ciphertext | tag
+fn seal(key: &[u8], plain: &[u8], aad: &[u8], out: &mut Vec<u8>) -> anyhow::Result<()> {
+    let mut nonce = [0u8; NONCE_SIZE];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let (cipher, tag) = GCM::new(key, nonce).encrypt(plain, aad, Some(TAG_SIZE))?;
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&cipher);
+    out.extend_from_slice(&tag);
+    Ok(())
+}
+
+fn open(key: &[u8], data: &[u8], aad: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let (nonce, rest) = data.split_at(NONCE_SIZE);
+    let (cipher, tag) = rest.split_at(rest.len() - TAG_SIZE);
+    GCM::new(key, nonce).decrypt(cipher, aad, tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::keyring::Keyring;
+
+    #[test]
+    fn keyring_rotate() {
+        let ring = Keyring::new();
+        assert!(ring.encrypt("hello").is_err());
+        assert!(ring.add(1, b"short".to_vec()).is_err());
+
+        ring.add(1, b"AES128Key-16Char".to_vec()).unwrap();
+        let c1 = ring.encrypt("hello").unwrap();
+        assert_eq!(Keyring::kid(&c1).unwrap(), 1);
+        assert_eq!(ring.decrypt(&c1).unwrap(), b"hello");
+
+        assert_eq!(
+            ring.rotate(b"AES256Key-32Characters1234567890".to_vec())
+                .unwrap(),
+            2
+        );
+        let c2 = ring.encrypt("hello").unwrap();
+        assert_eq!(Keyring::kid(&c2).unwrap(), 2);
+        assert_eq!(ring.decrypt(&c1).unwrap(), b"hello");
+        assert_eq!(ring.decrypt(&c2).unwrap(), b"hello");
+
+        let c3 = ring.reencrypt(&c1).unwrap();
+        assert_eq!(Keyring::kid(&c3).unwrap(), 2);
+        assert_eq!(ring.decrypt(&c3).unwrap(), b"hello");
+
+        // 篡改头部
+        let mut bad = c2.clone();
+        bad[4] = 1;
+        assert!(ring.decrypt(&bad).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 pub mod aes;
+pub mod field;
 pub mod hash;
+pub mod keyring;
+pub mod platform;
 
 pub trait HashOutput {
     type Output;
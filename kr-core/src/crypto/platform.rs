@@ -0,0 +1,385 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::RwLock,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private, Public},
+    sign::{Signer, Verifier},
+    x509::X509,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::crypto::aes::{CBC, GCM};
+
+/// 微信支付 v3 回调通知
+#[derive(Debug)]
+pub struct WechatNotify<T> {
+    pub id: String,
+    pub create_time: String,
+    pub event_type: String,
+    pub resource_type: String,
+    pub summary: String,
+    /// 解密后的资源数据
+    pub resource: T,
+}
+
+/// 微信支付 v3 加密资源（回调 resource、平台证书 encrypt_certificate）
+#[derive(Debug, Deserialize)]
+pub struct WechatResource {
+    pub algorithm: String,
+    pub ciphertext: String,
+    #[serde(default)]
+    pub associated_data: String,
+    pub nonce: String,
+}
+
+impl WechatResource {
+    /// AEAD_AES_256_GCM 解密（ciphertext 末尾16字节为 tag）
+    pub fn decrypt(&self, api_v3_key: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+        if self.algorithm != "AEAD_AES_256_GCM" {
+            bail!("crypto/platform: unsupported algorithm {}", self.algorithm);
+        }
+        let data = BASE64_STANDARD.decode(&self.ciphertext)?;
+        if data.len() < 16 {
+            bail!("crypto/platform: invalid ciphertext");
+        }
+        let (cipher, tag) = data.split_at(data.len() - 16);
+        GCM::new(api_v3_key.as_ref(), self.nonce.as_bytes()).decrypt(
+            cipher,
+            self.associated_data.as_bytes(),
+            tag,
+        )
+    }
+}
+
+/// 解密微信支付 v3 回调通知（验签请使用 `WechatCerts::verify`）
+///
+/// # Examples
+///
+/// ```
+/// // 先验签
+/// certs.verify(&serial, &timestamp, &nonce, &body, &signature)?;
+///
+/// #[derive(Deserialize)]
+/// struct Transaction {
+///     out_trade_no: String,
+///     trade_state: String,
+/// }
+/// let notify = platform::decrypt_wechat_notify::<Transaction>(api_v3_key, &body)?;
+/// ```
+pub fn decrypt_wechat_notify<T: DeserializeOwned>(
+    api_v3_key: impl AsRef<[u8]>,
+    body: impl AsRef<[u8]>,
+) -> Result<WechatNotify<T>> {
+    #[derive(Deserialize)]
+    struct Raw {
+        id: String,
+        create_time: String,
+        event_type: String,
+        resource_type: String,
+        #[serde(default)]
+        summary: String,
+        resource: WechatResource,
+    }
+
+    let raw: Raw = serde_json::from_slice(body.as_ref())?;
+    let plain = raw.resource.decrypt(api_v3_key)?;
+
+    Ok(WechatNotify {
+        id: raw.id,
+        create_time: raw.create_time,
+        event_type: raw.event_type,
+        resource_type: raw.resource_type,
+        summary: raw.summary,
+        resource: serde_json::from_slice(&plain)?,
+    })
+}
+
+/// 微信支付平台证书（按证书序列号管理，支持证书轮换期间多证书并存）
+///
+/// # Examples
+///
+/// ```
+/// let certs = WechatCerts::new();
+///
+/// // 下载平台证书(/v3/certificates)后解密加载
+/// for item in resp.data {
+///     certs.add_encrypted(api_v3_key, &item.encrypt_certificate)?;
+/// }
+///
+/// // 回调验签（header: Wechatpay-Serial, Wechatpay-Timestamp, Wechatpay-Nonce, Wechatpay-Signature）
+/// certs.verify(&serial, &timestamp, &nonce, &body, &signature)?;
+/// ```
+pub struct WechatCerts {
+    keys: RwLock<HashMap<String, PKey<Public>>>,
+    tolerance: Duration,
+}
+
+impl Default for WechatCerts {
+    fn default() -> Self {
+        Self {
+            keys: RwLock::new(HashMap::new()),
+            tolerance: Duration::from_secs(300),
+        }
+    }
+}
+
+impl WechatCerts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 验签允许的时间偏差（防重放），默认：5分钟
+    pub fn tolerance(mut self, d: Duration) -> Self {
+        self.tolerance = d;
+        self
+    }
+
+    /// 加载 PEM 格式的平台证书，返回证书序列号
+    pub fn add_pem(&self, pem: impl AsRef<[u8]>) -> Result<String> {
+        let cert = X509::from_pem(pem.as_ref())?;
+        let serial = cert.serial_number().to_bn()?.to_hex_str()?.to_uppercase();
+        self.keys
+            .write()
+            .unwrap()
+            .insert(serial.clone(), cert.public_key()?);
+        Ok(serial)
+    }
+
+    /// 解密并加载平台证书（/v3/certificates 返回的 encrypt_certificate）
+    pub fn add_encrypted(
+        &self,
+        api_v3_key: impl AsRef<[u8]>,
+        resource: &WechatResource,
+    ) -> Result<String> {
+        let pem = resource.decrypt(api_v3_key)?;
+        self.add_pem(pem)
+    }
+
+    /// 加载微信支付公钥（公钥模式，serial 为公钥ID）
+    pub fn add_public_key(&self, serial: &str, pem: impl AsRef<[u8]>) -> Result<()> {
+        let key = PKey::public_key_from_pem(pem.as_ref())?;
+        self.keys.write().unwrap().insert(serial.to_string(), key);
+        Ok(())
+    }
+
+    /// 验证签名（SHA256withRSA），timestamp 与当前时间偏差超过 `tolerance` 视为过期
+    pub fn verify(
+        &self,
+        serial: &str,
+        timestamp: &str,
+        nonce: &str,
+        body: &str,
+        signature: &str,
+    ) -> Result<()> {
+        let ts: i64 = timestamp
+            .parse()
+            .map_err(|_| anyhow!("crypto/platform: invalid wechatpay timestamp"))?;
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        if (now - ts).unsigned_abs() > self.tolerance.as_secs() {
+            bail!("crypto/platform: wechatpay signature expired");
+        }
+
+        let keys = self.keys.read().unwrap();
+        let key = keys
+            .get(serial)
+            .ok_or_else(|| anyhow!("crypto/platform: unknown wechatpay serial {}", serial))?;
+
+        let msg = format!("{}\n{}\n{}\n", timestamp, nonce, body);
+        if !rsa2_verify(key, msg.as_bytes(), signature)? {
+            bail!("crypto/platform: invalid wechatpay signature");
+        }
+        Ok(())
+    }
+}
+
+/// 支付宝 RSA2 签名（参数按 key 排序，忽略 sign 及空值）
+///
+/// # Examples
+///
+/// ```
+/// let sign = platform::alipay_sign(&params, private_key)?;
+/// ```
+pub fn alipay_sign(params: &HashMap<String, String>, private_key: &str) -> Result<String> {
+    let key = private_key_from_str(private_key)?;
+    let content = alipay_sign_content(params, false);
+
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(content.as_bytes())?;
+    Ok(BASE64_STANDARD.encode(signer.sign_to_vec()?))
+}
+
+/// 支付宝异步通知验签（RSA2，参数按 key 排序，忽略 sign、sign_type 及空值）
+///
+/// # Examples
+///
+/// ```
+/// // public_key: 支付宝公钥（PEM 或 base64）
+/// if !platform::verify_alipay_sign(&params, alipay_public_key)? {
+///     return Err("invalid sign")
+/// }
+/// ```
+pub fn verify_alipay_sign(params: &HashMap<String, String>, public_key: &str) -> Result<bool> {
+    let sign = params
+        .get("sign")
+        .ok_or_else(|| anyhow!("crypto/platform: missing sign"))?;
+    let key = public_key_from_str(public_key)?;
+    let content = alipay_sign_content(params, true);
+    rsa2_verify(&key, content.as_bytes(), sign)
+}
+
+/// 支付宝内容解密（AES-CBC，IV 全0，key 为 base64）
+///
+/// # Examples
+///
+/// ```
+/// let plain = platform::decrypt_alipay_content(aes_key, &response)?;
+/// ```
+pub fn decrypt_alipay_content(aes_key: &str, content: &str) -> Result<String> {
+    let key = BASE64_STANDARD.decode(aes_key.trim())?;
+    let data = BASE64_STANDARD.decode(content.trim())?;
+    let plain = CBC::new(key, [0u8; 16]).decrypt(data)?;
+    Ok(String::from_utf8(plain)?)
+}
+
+fn alipay_sign_content(params: &HashMap<String, String>, skip_sign_type: bool) -> String {
+    params
+        .iter()
+        .filter(|(k, v)| {
+            !v.is_empty() && k.as_str() != "sign" && !(skip_sign_type && k.as_str() == "sign_type")
+        })
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn rsa2_verify(key: &PKey<Public>, data: &[u8], sign: &str) -> Result<bool> {
+    let sign = BASE64_STANDARD.decode(sign.trim())?;
+    let mut verifier = Verifier::new(MessageDigest::sha256(), key)?;
+    verifier.update(data)?;
+    Ok(verifier.verify(&sign)?)
+}
+
+// 兼容无 PEM 头的 base64 密钥
+fn private_key_from_str(s: &str) -> Result<PKey<Private>> {
+    let s = s.trim();
+    if s.starts_with("-----") {
+        return Ok(PKey::private_key_from_pem(s.as_bytes())?);
+    }
+    let der = BASE64_STANDARD.decode(s)?;
+    Ok(PKey::private_key_from_pkcs8(&der)
+        .or_else(|_| openssl::rsa::Rsa::private_key_from_der(&der).and_then(PKey::from_rsa))?)
+}
+
+fn public_key_from_str(s: &str) -> Result<PKey<Public>> {
+    let s = s.trim();
+    if s.starts_with("-----") {
+        return Ok(PKey::public_key_from_pem(s.as_bytes())?);
+    }
+    Ok(PKey::public_key_from_der(&BASE64_STANDARD.decode(s)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use openssl::{hash::MessageDigest, pkey::PKey, rsa::Rsa, sign::Signer};
+    use serde::Deserialize;
+
+    use crate::crypto::{
+        aes::GCM,
+        platform::{self, WechatCerts},
+    };
+
+    #[test]
+    fn test_wechat_notify() {
+        let key = "AES256Key-32Characters1234567890";
+        let (cipher, tag) = GCM::new(key, "abcdefghijkl")
+            .encrypt(r#"{"out_trade_no":"T001"}"#, "transaction", None)
+            .unwrap();
+        let body = format!(
+            r#"{{"id":"1","create_time":"2024-01-01T00:00:00+08:00","event_type":"TRANSACTION.SUCCESS","resource_type":"encrypt-resource","summary":"ok","resource":{{"algorithm":"AEAD_AES_256_GCM","ciphertext":"{}","associated_data":"transaction","nonce":"abcdefghijkl","original_type":"transaction"}}}}"#,
+            BASE64_STANDARD.encode([cipher, tag].concat())
+        );
+
+        #[derive(Deserialize)]
+        struct Transaction {
+            out_trade_no: String,
+        }
+        let notify = platform::decrypt_wechat_notify::<Transaction>(key, &body).unwrap();
+        assert_eq!(notify.event_type, "TRANSACTION.SUCCESS");
+        assert_eq!(notify.resource.out_trade_no, "T001");
+
+        assert!(
+            platform::decrypt_wechat_notify::<Transaction>(&key.replace('A', "B"), &body).is_err()
+        );
+    }
+
+    #[test]
+    fn test_wechat_verify() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let certs = WechatCerts::new();
+        certs
+            .add_public_key("PUB_KEY_ID_1", key.public_key_to_pem().unwrap())
+            .unwrap();
+
+        let body = r#"{"id":"1"}"#;
+        let sign = |ts: &str| {
+            let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+            signer
+                .update(format!("{}\n{}\n{}\n", ts, "nonce", body).as_bytes())
+                .unwrap();
+            BASE64_STANDARD.encode(signer.sign_to_vec().unwrap())
+        };
+
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let ts = now.to_string();
+        certs
+            .verify("PUB_KEY_ID_1", &ts, "nonce", body, &sign(&ts))
+            .unwrap();
+        assert!(certs
+            .verify("PUB_KEY_ID_1", &ts, "nonce", "{}", &sign(&ts))
+            .is_err());
+        assert!(certs
+            .verify("PUB_KEY_ID_2", &ts, "nonce", body, &sign(&ts))
+            .is_err());
+
+        // 签名正确但已过期
+        let ts = (now - 301).to_string();
+        assert!(certs
+            .verify("PUB_KEY_ID_1", &ts, "nonce", body, &sign(&ts))
+            .is_err());
+        assert!(certs
+            .verify("PUB_KEY_ID_1", "abc", "nonce", body, &sign("abc"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_alipay_rsa2() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let private_pem = String::from_utf8(key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        let public_b64 = BASE64_STANDARD.encode(key.public_key_to_der().unwrap());
+
+        let mut params: HashMap<String, String> =
+            [("app_id", "2021"), ("total_amount", "1.00"), ("memo", "")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+        let sign = platform::alipay_sign(&params, &private_pem).unwrap();
+
+        params.insert("sign".into(), sign);
+        params.insert("sign_type".into(), "RSA2".into());
+        assert!(platform::verify_alipay_sign(&params, &public_b64).unwrap());
+
+        params.insert("total_amount".into(), "100.00".into());
+        assert!(!platform::verify_alipay_sign(&params, &public_b64).unwrap());
+    }
+}
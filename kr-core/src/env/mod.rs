@@ -0,0 +1,379 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::config::dotenv::PROFILE_ENV;
+
+/// 可从环境变量解析的类型
+pub trait FromEnv: Sized {
+    fn from_env(s: &str) -> anyhow::Result<Self>;
+}
+
+macro_rules! from_str_impl {
+    ($($t:ty),*) => {
+        $(
+            impl FromEnv for $t {
+                fn from_env(s: &str) -> anyhow::Result<Self> {
+                    Ok(<$t>::from_str(s)?)
+                }
+            }
+        )*
+    };
+}
+
+from_str_impl!(String, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl FromEnv for bool {
+    fn from_env(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "0" | "false" | "no" | "off" => Ok(false),
+            _ => Err(anyhow!("invalid bool `{}`", s)),
+        }
+    }
+}
+
+impl FromEnv for Duration {
+    fn from_env(s: &str) -> anyhow::Result<Self> {
+        parse_duration(s)
+    }
+}
+
+/// 逗号分隔的列表
+impl<T: FromEnv> FromEnv for Vec<T> {
+    fn from_env(s: &str) -> anyhow::Result<Self> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(T::from_env)
+            .collect()
+    }
+}
+
+/// 读取环境变量，未设置或为空时返回 None，解析失败时返回错误
+///
+/// # Examples
+///
+/// ```
+/// let port = env::get::<u16>("PORT")?.unwrap_or(8000);
+/// let timeout = env::get::<Duration>("HTTP_TIMEOUT")?; // 15s、1m30s、500ms
+/// let body_limit = env::get::<ByteSize>("BODY_LIMIT")?; // 64MB
+/// let hosts = env::get::<Vec<String>>("ALLOWED_HOSTS")?; // a.com,b.com
+/// ```
+pub fn get<T: FromEnv>(name: &str) -> anyhow::Result<Option<T>> {
+    let Ok(v) = std::env::var(name) else {
+        return Ok(None);
+    };
+    let v = v.trim();
+    if v.is_empty() {
+        return Ok(None);
+    }
+    T::from_env(v)
+        .map(Some)
+        .map_err(|e| anyhow!("env: parse `{}` failed: {}", name, e))
+}
+
+/// 读取环境变量，未设置时返回默认值
+pub fn get_or<T: FromEnv>(name: &str, default: T) -> anyhow::Result<T> {
+    Ok(get(name)?.unwrap_or(default))
+}
+
+/// 读取必填的环境变量
+pub fn require<T: FromEnv>(name: &str) -> anyhow::Result<T> {
+    get(name)?.ok_or_else(|| anyhow!("env: `{}` is required", name))
+}
+
+/// 生产环境必填，其它环境未设置时使用默认值
+///
+/// # Examples
+///
+/// ```
+/// let key = env::require_in_prod::<Secret<String>>("JWT_SECRET", Secret::new("dev-secret".into()))?;
+/// ```
+pub fn require_in_prod<T: FromEnv>(name: &str, default: T) -> anyhow::Result<T> {
+    match get(name)? {
+        Some(v) => Ok(v),
+        None if is_production() => Err(anyhow!("env: `{}` is required in production", name)),
+        None => Ok(default),
+    }
+}
+
+/// 是否为生产环境（`KR_PROFILE` 或 `APP_ENV` 为 prod/production）
+pub fn is_production() -> bool {
+    [PROFILE_ENV, "APP_ENV"].iter().any(|k| {
+        matches!(
+            std::env::var(k)
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase()
+                .as_str(),
+            "prod" | "production"
+        )
+    })
+}
+
+/// 解析时长：`500ms`、`15s`、`10m`、`2h`、`1d`，可组合如 `1h30m`；纯数字为秒
+pub fn parse_duration(s: &str) -> anyhow::Result<Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(anyhow!("empty duration"));
+    }
+    if let Ok(n) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(n));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let pos = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(|| anyhow!("missing unit in duration `{}`", s))?;
+        let n: f64 = rest[..pos]
+            .parse()
+            .map_err(|_| anyhow!("invalid duration `{}`", s))?;
+        rest = &rest[pos..];
+        let end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..end] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            v => return Err(anyhow!("unknown unit `{}` in duration `{}`", v, s)),
+        };
+        total = Duration::try_from_secs_f64(n * unit)
+            .ok()
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(|| anyhow!("duration `{}` out of range", s))?;
+        rest = &rest[end..];
+    }
+    Ok(total)
+}
+
+/// 解析字节数：`512`、`64KB`、`64MB`、`1.5GB`（1K = 1024，大小写不敏感，`KiB` 等同 `KB`）
+pub fn parse_bytes(s: &str) -> anyhow::Result<u64> {
+    let s = s.trim();
+    let pos = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let n: f64 = s[..pos]
+        .parse()
+        .map_err(|_| anyhow!("invalid byte size `{}`", s))?;
+    let unit: u64 = match s[pos..].trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        v => return Err(anyhow!("unknown unit `{}` in byte size `{}`", v, s)),
+    };
+    Ok((n * unit as f64) as u64)
+}
+
+/// 字节数，可从 `64MB` 等字符串解析（环境变量、配置文件）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_bytes(s).map(ByteSize)
+    }
+}
+
+impl FromEnv for ByteSize {
+    fn from_env(s: &str) -> anyhow::Result<Self> {
+        s.parse()
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Num(u64),
+            Str(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Num(v) => Ok(ByteSize(v)),
+            Raw::Str(v) => v.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+/// 敏感值：Debug、Display、序列化时输出 `***`，通过 `expose` 读取
+///
+/// # Examples
+///
+/// ```
+/// let key = env::require::<Secret<String>>("API_KEY")?;
+/// tracing::info!(key = ?key); // ***
+/// client.auth(key.expose());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(v: T) -> Self {
+        Self(v)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl<T: FromEnv> FromEnv for Secret<T> {
+    fn from_env(s: &str) -> anyhow::Result<Self> {
+        T::from_env(s).map(Secret)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Secret)
+    }
+}
+
+impl<T> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("***")
+    }
+}
+
+/// 配置文件中的时长字段（`"15s"` 或秒数）
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Deserialize)]
+/// struct Http {
+///     #[serde(deserialize_with = "env::de_duration")]
+///     timeout: Duration,
+/// }
+/// ```
+pub fn de_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Num(u64),
+        Str(String),
+    }
+    match Raw::deserialize(deserializer)? {
+        Raw::Num(v) => Ok(Duration::from_secs(v)),
+        Raw::Str(v) => parse_duration(&v).map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde::Deserialize;
+
+    use crate::env::{self, ByteSize, Secret};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(env::parse_duration("15s").unwrap(), Duration::from_secs(15));
+        assert_eq!(env::parse_duration("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(
+            env::parse_duration("1h30m").unwrap(),
+            Duration::from_secs(5400)
+        );
+        assert_eq!(
+            env::parse_duration("500ms").unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            env::parse_duration("1.5s").unwrap(),
+            Duration::from_millis(1500)
+        );
+        assert!(env::parse_duration("15x").is_err());
+        assert!(env::parse_duration("s").is_err());
+        assert!(env::parse_duration("").is_err());
+        assert!(env::parse_duration(" ").is_err());
+        assert!(env::parse_duration("99999999999999999999d").is_err());
+
+        assert_eq!(env::parse_bytes("512").unwrap(), 512);
+        assert_eq!(env::parse_bytes("64MB").unwrap(), 64 << 20);
+        assert_eq!(env::parse_bytes("64 kib").unwrap(), 64 << 10);
+        assert_eq!(env::parse_bytes("1.5G").unwrap(), 3 << 29);
+        assert!(env::parse_bytes("10XB").is_err());
+    }
+
+    #[test]
+    fn test_get() {
+        std::env::set_var("KR_ENV_PORT", "8080");
+        std::env::set_var("KR_ENV_DEBUG", "on");
+        std::env::set_var("KR_ENV_HOSTS", "a.com, b.com,");
+        std::env::set_var("KR_ENV_EMPTY", " ");
+        std::env::set_var("KR_ENV_SECRET", "s3cret");
+
+        assert_eq!(env::get::<u16>("KR_ENV_PORT").unwrap(), Some(8080));
+        assert!(env::get::<u8>("KR_ENV_PORT").is_err());
+        assert!(env::get::<bool>("KR_ENV_DEBUG").unwrap().unwrap());
+        assert_eq!(
+            env::get::<Vec<String>>("KR_ENV_HOSTS").unwrap().unwrap(),
+            vec!["a.com", "b.com"]
+        );
+        assert_eq!(env::get::<String>("KR_ENV_EMPTY").unwrap(), None);
+        assert_eq!(env::get_or("KR_ENV_NONE", 3).unwrap(), 3);
+        assert!(env::require::<String>("KR_ENV_NONE").is_err());
+
+        let secret = env::require::<Secret<String>>("KR_ENV_SECRET").unwrap();
+        assert_eq!(secret.expose(), "s3cret");
+        assert_eq!(format!("{:?} {}", secret, secret), "*** ***");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"***\"");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        #[derive(Deserialize)]
+        struct Cfg {
+            #[serde(deserialize_with = "env::de_duration")]
+            timeout: Duration,
+            limit: ByteSize,
+            token: Secret<String>,
+        }
+
+        let cfg: Cfg =
+            serde_json::from_str(r#"{"timeout": "1m", "limit": "2KB", "token": "abc"}"#).unwrap();
+        assert_eq!(cfg.timeout, Duration::from_secs(60));
+        assert_eq!(cfg.limit, ByteSize(2048));
+        assert_eq!(cfg.token.expose(), "abc");
+    }
+}
@@ -0,0 +1,388 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use redis::{
+    streams::{
+        StreamAutoClaimOptions, StreamAutoClaimReply, StreamId, StreamMaxlen, StreamReadOptions,
+        StreamReadReply,
+    },
+    AsyncCommands,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{sync::broadcast, task::JoinHandle};
+
+use crate::helper::redkit::{key, Redis};
+
+/// 事件定义
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Serialize, Deserialize)]
+/// struct OrderPaid {
+///     order_id: i64,
+/// }
+///
+/// impl Event for OrderPaid {
+///     const NAME: &'static str = "order.paid";
+/// }
+/// ```
+pub trait Event: Serialize + DeserializeOwned + Send + 'static {
+    const NAME: &'static str;
+}
+
+/// Redis Streams 配置
+#[derive(Clone)]
+pub struct Stream {
+    redis: Redis,
+    group: String,
+    consumer: String,
+    maxlen: usize,
+    claim_idle: Duration,
+}
+
+impl Stream {
+    /// 流的最大长度（近似裁剪），默认：100000
+    pub fn maxlen(mut self, n: usize) -> Self {
+        self.maxlen = n;
+        self
+    }
+
+    /// 未确认消息超过该时长后由其它消费者重新认领，默认：60s
+    pub fn claim_idle(mut self, d: Duration) -> Self {
+        self.claim_idle = d;
+        self
+    }
+
+    /// 消费者名称，默认：随机 uuid
+    pub fn consumer(mut self, name: &str) -> Self {
+        self.consumer = name.to_string();
+        self
+    }
+}
+
+/// 事件总线
+///
+/// - Memory：基于 tokio broadcast，进程内使用（如：测试）
+/// - Redis：基于 Redis Streams + 消费组，至少一次投递（处理成功后 ACK，失败或崩溃的消息会被重新认领）
+///
+/// # Examples
+///
+/// ```
+/// let bus = Bus::redis(redis.clone(), "order-service");
+///
+/// // 在启动编排中注册事件处理
+/// Bootstrap::new()
+///     .task("events", &["redis"], Duration::from_secs(5), {
+///         let bus = bus.clone();
+///         move || async move {
+///             bus.subscribe::<OrderPaid, _, _>(|e| async move {
+///                 send_notify(e.order_id).await
+///             });
+///             Ok(())
+///         }
+///     })
+///     .run()
+///     .await?;
+///
+/// bus.publish(&OrderPaid { order_id: 1 }).await?;
+/// ```
+#[derive(Clone)]
+pub enum Bus {
+    Memory(broadcast::Sender<(&'static str, Arc<String>)>),
+    Redis(Stream),
+}
+
+impl Bus {
+    /// 进程内事件总线
+    pub fn memory(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Bus::Memory(tx)
+    }
+
+    /// 基于 Redis Streams 的事件总线（同一 group 内的消费者竞争消费）
+    pub fn redis(redis: Redis, group: &str) -> Self {
+        Bus::Redis(Stream {
+            redis,
+            group: group.to_string(),
+            consumer: uuid::Uuid::new_v4().to_string(),
+            maxlen: 100000,
+            claim_idle: Duration::from_secs(60),
+        })
+    }
+
+    /// 发布事件
+    pub async fn publish<E: Event>(&self, event: &E) -> anyhow::Result<()> {
+        let data = serde_json::to_string(event)?;
+        match self {
+            Bus::Memory(tx) => {
+                // 无订阅者时忽略
+                let _ = tx.send((E::NAME, Arc::new(data)));
+            }
+            Bus::Redis(s) => {
+                let key = stream_key(E::NAME);
+                let maxlen = StreamMaxlen::Approx(s.maxlen);
+                let _: String = match &s.redis {
+                    Redis::Single(pool) => {
+                        pool.get()
+                            .await?
+                            .xadd_maxlen(key, maxlen, "*", &[("data", data)])
+                            .await?
+                    }
+                    Redis::Cluster(pool) => {
+                        pool.get()
+                            .await?
+                            .xadd_maxlen(key, maxlen, "*", &[("data", data)])
+                            .await?
+                    }
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// 订阅事件（后台任务，返回其句柄）
+    pub fn subscribe<E, F, Fut>(&self, handler: F) -> JoinHandle<()>
+    where
+        E: Event,
+        F: Fn(E) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        match self {
+            Bus::Memory(tx) => {
+                let rx = tx.subscribe();
+                tokio::spawn(consume_memory(rx, handler))
+            }
+            Bus::Redis(s) => tokio::spawn(consume_stream(s.clone(), handler)),
+        }
+    }
+}
+
+fn stream_key(name: &str) -> String {
+    key::with_prefix(format!("kr:events:{}", name))
+}
+
+async fn consume_memory<E, F, Fut>(
+    mut rx: broadcast::Receiver<(&'static str, Arc<String>)>,
+    handler: F,
+) where
+    E: Event,
+    F: Fn(E) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    loop {
+        let (name, data) = match rx.recv().await {
+            Ok(v) => v,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!(event = E::NAME, skipped = n, "[events::subscribe] lagged");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if name != E::NAME {
+            continue;
+        }
+        let event: E = match serde_json::from_str(&data) {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::error!(event = E::NAME, err = ?e, "[events::subscribe] decode failed");
+                continue;
+            }
+        };
+        if let Err(e) = handler(event).await {
+            tracing::error!(event = E::NAME, err = ?e, "[events::subscribe] handle failed");
+        }
+    }
+}
+
+async fn consume_stream<E, F, Fut>(s: Stream, handler: F)
+where
+    E: Event,
+    F: Fn(E) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let key = stream_key(E::NAME);
+    let mut ready = false;
+    loop {
+        if !ready {
+            match create_group(&s, &key).await {
+                Ok(_) => ready = true,
+                Err(e) => {
+                    tracing::error!(event = E::NAME, err = ?e, "[events::subscribe] create group failed");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            }
+        }
+
+        match fetch(&s, &key).await {
+            Ok(entries) => {
+                for entry in entries {
+                    handle_entry(&s, &key, entry, &handler).await;
+                }
+            }
+            Err(e) => {
+                tracing::error!(event = E::NAME, err = ?e, "[events::subscribe] read failed");
+                // 流被删除时需重建消费组
+                ready = false;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+// 从流的起始位置创建消费组：首次订阅前（或流被删除重建后）已发布的消息也会被投递
+async fn create_group(s: &Stream, key: &str) -> anyhow::Result<()> {
+    let ret: redis::RedisResult<()> = match &s.redis {
+        Redis::Single(pool) => {
+            pool.get()
+                .await?
+                .xgroup_create_mkstream(key, &s.group, "0")
+                .await
+        }
+        Redis::Cluster(pool) => {
+            pool.get()
+                .await?
+                .xgroup_create_mkstream(key, &s.group, "0")
+                .await
+        }
+    };
+    match ret {
+        Err(e) if e.code() != Some("BUSYGROUP") => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+// 优先认领超时未确认的消息，否则阻塞读取新消息
+async fn fetch(s: &Stream, key: &str) -> anyhow::Result<Vec<StreamId>> {
+    let idle = s.claim_idle.as_millis() as usize;
+    let claim_opts = StreamAutoClaimOptions::default().count(100);
+    let claimed: StreamAutoClaimReply = match &s.redis {
+        Redis::Single(pool) => {
+            pool.get()
+                .await?
+                .xautoclaim_options(key, &s.group, &s.consumer, idle, "0-0", claim_opts)
+                .await?
+        }
+        Redis::Cluster(pool) => {
+            pool.get()
+                .await?
+                .xautoclaim_options(key, &s.group, &s.consumer, idle, "0-0", claim_opts)
+                .await?
+        }
+    };
+    if !claimed.claimed.is_empty() {
+        return Ok(claimed.claimed);
+    }
+
+    let read_opts = StreamReadOptions::default()
+        .group(&s.group, &s.consumer)
+        .count(100)
+        .block(5000);
+    let reply: Option<StreamReadReply> = match &s.redis {
+        Redis::Single(pool) => {
+            pool.get()
+                .await?
+                .xread_options(&[key], &[">"], &read_opts)
+                .await?
+        }
+        Redis::Cluster(pool) => {
+            pool.get()
+                .await?
+                .xread_options(&[key], &[">"], &read_opts)
+                .await?
+        }
+    };
+    Ok(reply
+        .map(|r| r.keys.into_iter().flat_map(|k| k.ids).collect())
+        .unwrap_or_default())
+}
+
+async fn handle_entry<E, F, Fut>(s: &Stream, key: &str, entry: StreamId, handler: &F)
+where
+    E: Event,
+    F: Fn(E) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let event = entry
+        .get::<String>("data")
+        .ok_or_else(|| anyhow::anyhow!("missing field: data"))
+        .and_then(|v| serde_json::from_str::<E>(&v).map_err(anyhow::Error::from));
+    match event {
+        Ok(v) => {
+            if let Err(e) = handler(v).await {
+                // 不 ACK，等待重新认领
+                tracing::error!(event = E::NAME, id = entry.id, err = ?e, "[events::subscribe] handle failed");
+                return;
+            }
+        }
+        // 无法解析的消息直接 ACK，避免反复投递
+        Err(e) => {
+            tracing::error!(event = E::NAME, id = entry.id, err = ?e, "[events::subscribe] decode failed")
+        }
+    }
+
+    if let Err(e) = ack(s, key, &entry.id).await {
+        tracing::error!(event = E::NAME, id = entry.id, err = ?e, "[events::subscribe] ack failed");
+    }
+}
+
+async fn ack(s: &Stream, key: &str, id: &str) -> anyhow::Result<()> {
+    let _: usize = match &s.redis {
+        Redis::Single(pool) => pool.get().await?.xack(key, &s.group, &[id]).await?,
+        Redis::Cluster(pool) => pool.get().await?.xack(key, &s.group, &[id]).await?,
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+    use tokio::sync::mpsc;
+
+    use crate::events::{Bus, Event};
+
+    #[derive(Serialize, Deserialize)]
+    struct Ping {
+        n: i32,
+    }
+
+    impl Event for Ping {
+        const NAME: &'static str = "ping";
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Pong {
+        n: i32,
+    }
+
+    impl Event for Pong {
+        const NAME: &'static str = "pong";
+    }
+
+    #[tokio::test]
+    async fn test_memory_bus() {
+        let bus = Bus::memory(16);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        bus.subscribe::<Ping, _, _>(move |e| {
+            let tx = tx.clone();
+            async move {
+                tx.send(e.n)?;
+                Ok(())
+            }
+        });
+        tokio::task::yield_now().await;
+
+        bus.publish(&Pong { n: 0 }).await.unwrap();
+        bus.publish(&Ping { n: 1 }).await.unwrap();
+        bus.publish(&Ping { n: 2 }).await.unwrap();
+
+        let recv = tokio::time::timeout(Duration::from_secs(1), async {
+            vec![rx.recv().await.unwrap(), rx.recv().await.unwrap()]
+        })
+        .await
+        .unwrap();
+        assert_eq!(recv, vec![1, 2]);
+    }
+}
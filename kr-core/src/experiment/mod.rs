@@ -0,0 +1,199 @@
+use std::{sync::OnceLock, time::Duration};
+
+use crate::{
+    crypto::hash::murmur3_32,
+    helper::redkit::{key, Redis},
+};
+
+/// 实验（按权重分桶）
+///
+/// # Examples
+///
+/// ```
+/// let exp = Experiment::new("checkout_btn", "2024Q3", vec![("control", 50), ("red", 50)]);
+///
+/// // 无状态分桶：同一 unit_id 始终命中同一变体
+/// let v = exp.bucket("10086");
+///
+/// // 持久化分桶：首次分配后写入Redis，后续调整权重不影响已分配用户
+/// let v = Assigner::new(Redis::Single(pool), None).assign(&exp, "10086").await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Experiment {
+    name: String,
+    salt: String,
+    variants: Vec<(String, u32)>,
+}
+
+impl Experiment {
+    pub fn new(
+        name: impl AsRef<str>,
+        salt: impl AsRef<str>,
+        variants: Vec<(impl AsRef<str>, u32)>,
+    ) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            salt: salt.as_ref().to_string(),
+            variants: variants
+                .into_iter()
+                .map(|(v, w)| (v.as_ref().to_string(), w))
+                .collect(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 计算 unit_id（用户ID/设备ID）所属变体，权重均为0时返回 None
+    pub fn bucket(&self, unit_id: impl AsRef<str>) -> Option<&str> {
+        let total: u64 = self.variants.iter().map(|(_, w)| *w as u64).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let h = murmur3_32(
+            format!("{}:{}:{}", self.name, self.salt, unit_id.as_ref()),
+            0,
+        );
+        let mut n = h as u64 % total;
+        for (v, w) in &self.variants {
+            if n < *w as u64 {
+                return Some(v);
+            }
+            n -= *w as u64;
+        }
+        None
+    }
+}
+
+/// 曝光日志：experiment, unit_id, variant
+pub type ExposureLogger = fn(experiment: &str, unit_id: &str, variant: &str);
+
+static EXPOSURE_LOGGER: OnceLock<ExposureLogger> = OnceLock::new();
+
+/// 设置曝光日志
+///
+/// # Examples
+///
+/// ```
+/// experiment::set_exposure_logger(|exp, unit_id, variant| {
+///     tracing::info!(experiment = exp, unit_id = unit_id, variant = variant, "exposure");
+/// })
+/// ```
+pub fn set_exposure_logger(f: ExposureLogger) {
+    let _ = EXPOSURE_LOGGER.set(f);
+}
+
+/// 记录曝光
+pub fn expose(exp: &Experiment, unit_id: &str, variant: &str) {
+    if let Some(logger) = EXPOSURE_LOGGER.get() {
+        logger(&exp.name, unit_id, variant)
+    }
+}
+
+/// 基于Redis持久化的分桶（每个 unit_id 一个 key：`[<前缀>:]kr:exp:<name>:<unit_id>`，各自过期）
+pub struct Assigner {
+    redis: Redis,
+    ttl: Option<Duration>,
+}
+
+impl Assigner {
+    /// `ttl` 为每条分配记录的过期时间（自首次分配起），None 表示不过期
+    pub fn new(redis: Redis, ttl: Option<Duration>) -> Self {
+        Self { redis, ttl }
+    }
+
+    /// 获取（或分配）变体，并记录曝光
+    pub async fn assign(
+        &self,
+        exp: &Experiment,
+        unit_id: impl AsRef<str>,
+    ) -> anyhow::Result<Option<String>> {
+        let unit_id = unit_id.as_ref();
+
+        let key = Self::key(exp, unit_id);
+        let variant: Option<String> = self
+            .redis
+            .get_or_set(
+                &key,
+                || async { Ok(exp.bucket(unit_id).map(|v| v.to_string())) },
+                self.ttl,
+            )
+            .await?;
+
+        if let Some(v) = &variant {
+            expose(exp, unit_id, v);
+        }
+        Ok(variant)
+    }
+
+    fn key(exp: &Experiment, unit_id: &str) -> String {
+        key::with_prefix(format!(
+            "kr:exp:{}:{}",
+            key::sanitize(&exp.name),
+            key::sanitize(unit_id)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use redis::AsyncCommands;
+
+    use super::{Assigner, Experiment};
+    use crate::{helper::redkit::Redis, redix};
+
+    #[test]
+    fn test_bucket() {
+        let exp = Experiment::new("btn", "salt", vec![("a", 20), ("b", 80)]);
+        assert_eq!(exp.bucket("10086"), exp.bucket("10086"));
+
+        let a = (0..10000)
+            .filter(|i| exp.bucket(i.to_string()) == Some("a"))
+            .count();
+        assert!((1500..2500).contains(&a));
+
+        // 不同 salt 重新分桶
+        let exp2 = Experiment::new("btn", "salt2", vec![("a", 20), ("b", 80)]);
+        let diff = (0..1000)
+            .filter(|i| exp.bucket(i.to_string()) != exp2.bucket(i.to_string()))
+            .count();
+        assert!(diff > 0);
+
+        let none = Experiment::new("btn", "salt", vec![("a", 0)]);
+        assert_eq!(none.bucket("1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_assigner() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let assigner = Assigner::new(Redis::Single(pool.clone()), Some(Duration::from_secs(60)));
+
+        let exp = Experiment::new("test_assigner", "salt", vec![("a", 100), ("b", 0)]);
+        assert_eq!(
+            assigner.assign(&exp, "1").await.unwrap().as_deref(),
+            Some("a")
+        );
+
+        // 已分配的不受权重调整影响
+        let exp = Experiment::new("test_assigner", "salt", vec![("a", 0), ("b", 100)]);
+        assert_eq!(
+            assigner.assign(&exp, "1").await.unwrap().as_deref(),
+            Some("a")
+        );
+        assert_eq!(
+            assigner.assign(&exp, "2").await.unwrap().as_deref(),
+            Some("b")
+        );
+
+        // 每条分配记录各自过期
+        let mut conn = pool.get().await.unwrap();
+        for id in ["1", "2"] {
+            let ttl: i64 = conn.ttl(Assigner::key(&exp, id)).await.unwrap();
+            assert!(ttl > 0 && ttl <= 60);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+pub mod store;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::hash;
+
+pub use store::{RedisStore, Store};
+
+/// 功能开关
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Flag {
+    /// 全量开/关
+    Bool(bool),
+    /// 按用户灰度的百分比 (0-100)
+    Percent(u8),
+    /// 按权重分配的变体：[(变体名, 权重)]
+    Variant(Vec<(String, u32)>),
+}
+
+impl Flag {
+    /// 用户是否命中开关（Variant 类型始终为 true）
+    pub fn enabled(&self, name: &str, user_id: &str) -> bool {
+        match self {
+            Flag::Bool(v) => *v,
+            Flag::Percent(p) => bucket(name, user_id, 100) < *p as u64,
+            Flag::Variant(_) => true,
+        }
+    }
+
+    /// 用户命中的变体（非 Variant 类型返回 None）
+    pub fn variant(&self, name: &str, user_id: &str) -> Option<String> {
+        let Flag::Variant(list) = self else {
+            return None;
+        };
+        let total: u64 = list.iter().map(|(_, w)| *w as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut n = bucket(name, user_id, total);
+        for (v, w) in list {
+            if n < *w as u64 {
+                return Some(v.clone());
+            }
+            n -= *w as u64;
+        }
+        None
+    }
+}
+
+// 同一开关、同一用户始终落在同一桶
+// 与 experiment 分桶使用同一哈希（murmur3）
+fn bucket(name: &str, user_id: &str, n: u64) -> u64 {
+    hash::murmur3_32(format!("{}:{}", name, user_id), 0) as u64 % n
+}
+
+/// 功能开关（本地缓存 + 远端存储）
+///
+/// # Examples
+///
+/// ```
+/// let store = RedisStore::new(Redis::Single(pool), "kr:flags", "kr:flags:changed");
+/// let flags = Arc::new(
+///     Flags::new(store, Some(Duration::from_secs(5)))
+///         .define("new_checkout", Flag::Percent(10)),
+/// );
+///
+/// // 订阅变更通知，及时失效本地缓存
+/// flags.subscribe(redis::Client::open("redis://127.0.0.1:6379")?, "kr:flags:changed");
+///
+/// if flags.enabled("new_checkout", "10086").await? {
+///     // ...
+/// }
+/// ```
+pub struct Flags<S> {
+    store: S,
+    ttl: Duration,
+    defaults: HashMap<String, Flag>,
+    cache: RwLock<HashMap<String, (Option<Flag>, Instant)>>,
+}
+
+impl<S: Store> Flags<S> {
+    /// `ttl` 为本地缓存时长，默认: 5s
+    pub fn new(store: S, ttl: Option<Duration>) -> Self {
+        Self {
+            store,
+            ttl: ttl.unwrap_or(Duration::from_secs(5)),
+            defaults: HashMap::new(),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 定义开关默认值（存储中无覆盖值时使用）
+    pub fn define(mut self, name: impl AsRef<str>, flag: Flag) -> Self {
+        self.defaults.insert(name.as_ref().to_string(), flag);
+        self
+    }
+
+    /// 获取开关（未定义返回 None）
+    pub async fn get(&self, name: &str) -> anyhow::Result<Option<Flag>> {
+        if let Some((v, at)) = self.cache.read().unwrap().get(name) {
+            if at.elapsed() < self.ttl {
+                return Ok(v.clone().or_else(|| self.defaults.get(name).cloned()));
+            }
+        }
+
+        let v = self.store.load(name).await?;
+        self.cache
+            .write()
+            .unwrap()
+            .insert(name.to_string(), (v.clone(), Instant::now()));
+
+        Ok(v.or_else(|| self.defaults.get(name).cloned()))
+    }
+
+    /// 用户是否命中开关（未定义视为关闭）
+    pub async fn enabled(&self, name: &str, user_id: impl AsRef<str>) -> anyhow::Result<bool> {
+        let flag = self.get(name).await?;
+        Ok(flag.is_some_and(|v| v.enabled(name, user_id.as_ref())))
+    }
+
+    /// 用户命中的变体
+    pub async fn variant(
+        &self,
+        name: &str,
+        user_id: impl AsRef<str>,
+    ) -> anyhow::Result<Option<String>> {
+        let flag = self.get(name).await?;
+        Ok(flag.and_then(|v| v.variant(name, user_id.as_ref())))
+    }
+
+    /// 失效本地缓存（`None` 表示全部）
+    pub fn invalidate(&self, name: Option<&str>) {
+        let mut cache = self.cache.write().unwrap();
+        match name {
+            Some(v) => {
+                cache.remove(v);
+            }
+            None => cache.clear(),
+        }
+    }
+}
+
+impl<S: Store + 'static> Flags<S> {
+    /// 订阅Redis变更通知（消息内容为开关名，`*` 表示全部），断线自动重连
+    pub fn subscribe(
+        self: &Arc<Self>,
+        client: redis::Client,
+        channel: impl AsRef<str>,
+    ) -> tokio::task::JoinHandle<()> {
+        let flags = Arc::downgrade(self);
+        let channel = channel.as_ref().to_string();
+
+        tokio::spawn(async move {
+            loop {
+                let ret: anyhow::Result<()> = async {
+                    let mut pubsub = client.get_async_pubsub().await?;
+                    pubsub.subscribe(&channel).await?;
+
+                    let mut stream = pubsub.on_message();
+                    while let Some(msg) = stream.next().await {
+                        let Some(flags) = flags.upgrade() else {
+                            return Ok(());
+                        };
+                        let name: String = msg.get_payload()?;
+                        if name == "*" {
+                            flags.invalidate(None);
+                        } else {
+                            flags.invalidate(Some(&name));
+                        }
+                    }
+                    Ok(())
+                }
+                .await;
+
+                if flags.strong_count() == 0 {
+                    return;
+                }
+                if let Err(e) = ret {
+                    tracing::error!(err = ?e, channel = channel, "[flags] subscribe failed");
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_flag() {
+        assert!(Flag::Bool(true).enabled("a", "1"));
+        assert!(!Flag::Percent(0).enabled("a", "1"));
+        assert!(Flag::Percent(100).enabled("a", "1"));
+
+        let hit = (0..10000)
+            .filter(|i| Flag::Percent(30).enabled("a", &i.to_string()))
+            .count();
+        assert!((2500..3500).contains(&hit));
+
+        let v = Flag::Variant(vec![("a".to_string(), 1), ("b".to_string(), 1)]);
+        assert_eq!(v.variant("x", "1"), v.variant("x", "1"));
+        assert!(v.variant("x", "1").is_some());
+        assert!(Flag::Bool(true).variant("x", "1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_flags() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let store = move |name: String| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                match name.as_str() {
+                    "on" => Ok(Some(Flag::Bool(true))),
+                    _ => Ok(None),
+                }
+            }
+        };
+
+        let flags = Flags::new(store, None).define("dft", Flag::Percent(100));
+        assert!(flags.enabled("on", "1").await.unwrap());
+        assert!(flags.enabled("on", "2").await.unwrap());
+        assert!(flags.enabled("dft", "1").await.unwrap());
+        assert!(!flags.enabled("none", "1").await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        flags.invalidate(Some("on"));
+        assert!(flags.enabled("on", "1").await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
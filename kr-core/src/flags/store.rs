@@ -0,0 +1,108 @@
+use std::future::Future;
+
+use redis::AsyncCommands;
+
+use crate::helper::redkit::Redis;
+
+use super::Flag;
+
+/// 开关存储
+pub trait Store: Send + Sync {
+    /// 读取开关（不存在返回 None）
+    fn load(&self, name: &str) -> impl Future<Output = anyhow::Result<Option<Flag>>> + Send;
+}
+
+/// 基于闭包的存储，便于对接 DB
+///
+/// # Examples
+///
+/// ```
+/// let store = |name: String| {
+///     let pool = pool.clone();
+///     async move {
+///         let stmt = Query::select()
+///             .from(table::Flag::Table)
+///             .column(table::Flag::Value)
+///             .and_where(Expr::col(table::Flag::Name).eq(name))
+///             .to_owned();
+///         let row = mysql::find_one::<model::Flag>(&pool, stmt).await?;
+///         Ok(row.map(|v| serde_json::from_str(&v.value)).transpose()?)
+///     }
+/// };
+/// let flags = Flags::new(store, None);
+/// ```
+impl<F, Fut> Store for F
+where
+    F: Fn(String) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<Option<Flag>>> + Send,
+{
+    fn load(&self, name: &str) -> impl Future<Output = anyhow::Result<Option<Flag>>> + Send {
+        self(name.to_string())
+    }
+}
+
+/// 基于Redis Hash的存储（field=开关名，value=JSON）
+pub struct RedisStore {
+    redis: Redis,
+    key: String,
+    channel: String,
+}
+
+impl RedisStore {
+    /// `channel` 用于发布变更通知，配合 `Flags::subscribe` 使本地缓存失效
+    pub fn new(redis: Redis, key: impl AsRef<str>, channel: impl AsRef<str>) -> Self {
+        Self {
+            redis,
+            key: key.as_ref().to_string(),
+            channel: channel.as_ref().to_string(),
+        }
+    }
+
+    /// 设置开关并发布变更通知
+    pub async fn set(&self, name: &str, flag: &Flag) -> anyhow::Result<()> {
+        let v = serde_json::to_string(flag)?;
+        match &self.redis {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+                let _: () = conn.hset(&self.key, name, v).await?;
+                let _: () = conn.publish(&self.channel, name).await?;
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+                let _: () = conn.hset(&self.key, name, v).await?;
+                let _: () = conn.publish(&self.channel, name).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 删除开关并发布变更通知
+    pub async fn remove(&self, name: &str) -> anyhow::Result<()> {
+        match &self.redis {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+                let _: () = conn.hdel(&self.key, name).await?;
+                let _: () = conn.publish(&self.channel, name).await?;
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+                let _: () = conn.hdel(&self.key, name).await?;
+                let _: () = conn.publish(&self.channel, name).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Store for RedisStore {
+    async fn load(&self, name: &str) -> anyhow::Result<Option<Flag>> {
+        let v: Option<String> = match &self.redis {
+            Redis::Single(pool) => pool.get().await?.hget(&self.key, name).await?,
+            Redis::Cluster(pool) => pool.get().await?.hget(&self.key, name).await?,
+        };
+        match v {
+            Some(s) => Ok(Some(serde_json::from_str(&s)?)),
+            None => Ok(None),
+        }
+    }
+}
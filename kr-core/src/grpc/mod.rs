@@ -0,0 +1,529 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use serde_json::Value;
+use tonic::{
+    metadata::MetadataValue,
+    service::Interceptor,
+    transport::{Channel, Endpoint},
+    Request, Response, Status,
+};
+
+use crate::{codes::Code, crypto::hash, helper::http::Retry, idgen, sql};
+
+/// 请求ID的 metadata key
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 业务错误码的 metadata key（见 `status`）
+pub const ERROR_CODE_HEADER: &str = "x-error-code";
+
+/// 请求ID（由 `request_id` 拦截器写入请求扩展）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// 服务端拦截器：透传请求中的 `x-request-id`，缺失或非法时生成 UUIDv7，并写入请求扩展 `RequestId`
+///
+/// # Examples
+///
+/// ```
+/// let svc = GreeterServer::with_interceptor(MyGreeter::default(), grpc::request_id);
+///
+/// // handler 中读取
+/// let id = req.extensions().get::<grpc::RequestId>();
+/// ```
+pub fn request_id(mut req: Request<()>) -> Result<Request<()>, Status> {
+    let id = req
+        .metadata()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| idgen::uuid_v7().to_string());
+
+    if let Ok(v) = MetadataValue::try_from(id.as_str()) {
+        req.metadata_mut().insert(REQUEST_ID_HEADER, v);
+    }
+    req.extensions_mut().insert(RequestId(id));
+    Ok(req)
+}
+
+/// 客户端拦截器：为下游请求设置 `x-request-id`（已设置时保留）
+///
+/// # Examples
+///
+/// ```
+/// let id = req.extensions().get::<grpc::RequestId>().map(|v| v.0.clone()).unwrap_or_default();
+/// let mut cli = UserClient::with_interceptor(client.channel(), grpc::propagate_request_id(id));
+/// ```
+pub fn propagate_request_id(
+    id: impl Into<String>,
+) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone {
+    let id = id.into();
+    move |mut req: Request<()>| {
+        if !id.is_empty() && !req.metadata().contains_key(REQUEST_ID_HEADER) {
+            let v = MetadataValue::try_from(id.as_str())
+                .map_err(|_| Status::invalid_argument("grpc: invalid request id"))?;
+            req.metadata_mut().insert(REQUEST_ID_HEADER, v);
+        }
+        Ok(req)
+    }
+}
+
+/// JWT 载荷（由 `JwtAuth` 拦截器写入请求扩展）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Claims(pub Value);
+
+impl Claims {
+    /// 读取字段
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    /// `sub` 字段
+    pub fn subject(&self) -> Option<&str> {
+        self.0.get("sub").and_then(|v| v.as_str())
+    }
+}
+
+/// 服务端拦截器：校验 `authorization: Bearer <token>`（HS256），校验 `exp`/`nbf`，
+/// 通过后将载荷写入请求扩展 `Claims`，失败时返回 `Unauthenticated`
+///
+/// # Examples
+///
+/// ```
+/// let auth = grpc::JwtAuth::hs256(secret).leeway(Duration::from_secs(30));
+/// let svc = GreeterServer::with_interceptor(MyGreeter::default(), auth);
+///
+/// // handler 中读取
+/// let uid = req.extensions().get::<grpc::Claims>().and_then(|v| v.subject());
+/// ```
+#[derive(Clone)]
+pub struct JwtAuth {
+    secret: Arc<Vec<u8>>,
+    leeway: Duration,
+}
+
+impl JwtAuth {
+    pub fn hs256(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret: Arc::new(secret.as_ref().to_vec()),
+            leeway: Duration::ZERO,
+        }
+    }
+
+    /// 校验 `exp`/`nbf` 时允许的时钟偏差，默认：0
+    pub fn leeway(mut self, d: Duration) -> Self {
+        self.leeway = d;
+        self
+    }
+
+    /// 校验 token，返回载荷
+    pub fn verify(&self, token: &str) -> anyhow::Result<Claims> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(sig), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(anyhow!("grpc: malformed token"));
+        };
+
+        let head: Value = serde_json::from_slice(&BASE64_URL_SAFE_NO_PAD.decode(header)?)?;
+        if head.get("alg").and_then(|v| v.as_str()) != Some("HS256") {
+            return Err(anyhow!("grpc: unsupported token algorithm"));
+        }
+
+        let sig = BASE64_URL_SAFE_NO_PAD.decode(sig)?;
+        // 签名内容为 `header.payload`
+        let signed = &token[..header.len() + payload.len() + 1];
+        let expected = hash::hmac_sha256::<Vec<u8>>(self.secret.as_slice(), signed);
+        // memcmp::eq 要求长度一致
+        if sig.len() != expected.len() || !openssl::memcmp::eq(&sig, &expected) {
+            return Err(anyhow!("grpc: invalid token signature"));
+        }
+
+        let claims: Value = serde_json::from_slice(&BASE64_URL_SAFE_NO_PAD.decode(payload)?)?;
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let leeway = self.leeway.as_secs() as i64;
+        if let Some(exp) = claims.get("exp").and_then(|v| v.as_i64()) {
+            if now > exp + leeway {
+                return Err(anyhow!("grpc: token expired"));
+            }
+        }
+        if let Some(nbf) = claims.get("nbf").and_then(|v| v.as_i64()) {
+            if now + leeway < nbf {
+                return Err(anyhow!("grpc: token not yet valid"));
+            }
+        }
+        Ok(Claims(claims))
+    }
+}
+
+impl Interceptor for JwtAuth {
+    fn call(&mut self, mut req: Request<()>) -> Result<Request<()>, Status> {
+        let token = req
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
+
+        let claims = self.verify(token.trim()).map_err(|e| {
+            tracing::debug!(err = %e, "[grpc::JwtAuth] verify failed");
+            Status::unauthenticated("invalid token")
+        })?;
+        req.extensions_mut().insert(claims);
+        Ok(req)
+    }
+}
+
+impl From<Code> for Status {
+    /// HTTP 风格的错误码映射为对应的 gRPC 状态码，其余业务错误码为 `Unknown`；
+    /// 错误码同时写入 metadata `x-error-code`
+    fn from(v: Code) -> Self {
+        let code = match v.code {
+            400 => tonic::Code::InvalidArgument,
+            401 => tonic::Code::Unauthenticated,
+            403 => tonic::Code::PermissionDenied,
+            404 => tonic::Code::NotFound,
+            409 => tonic::Code::AlreadyExists,
+            412 => tonic::Code::FailedPrecondition,
+            429 => tonic::Code::ResourceExhausted,
+            499 => tonic::Code::Cancelled,
+            501 => tonic::Code::Unimplemented,
+            503 => tonic::Code::Unavailable,
+            504 => tonic::Code::DeadlineExceeded,
+            500..=599 => tonic::Code::Internal,
+            _ => tonic::Code::Unknown,
+        };
+        let mut status = Status::new(code, v.msg);
+        status
+            .metadata_mut()
+            .insert(ERROR_CODE_HEADER, MetadataValue::from(v.code));
+        status
+    }
+}
+
+/// 将 handler 错误转换为 gRPC 状态
+///
+/// - `Status` 原样返回
+/// - `codes::Code` 按错误码映射（见 `impl From<Code> for Status`）
+/// - `sql::Timeout` 为 `DeadlineExceeded`
+/// - 其余错误记录日志并返回 `Internal`（不向调用方暴露错误详情）
+///
+/// # Examples
+///
+/// ```
+/// async fn get_user(&self, req: Request<GetUserReq>) -> Result<Response<User>, Status> {
+///     let user = repo::find(req.get_ref().id).await.map_err(grpc::status)?;
+///     Ok(Response::new(user.into()))
+/// }
+/// ```
+pub fn status(err: anyhow::Error) -> Status {
+    let err = match err.downcast::<Status>() {
+        Ok(v) => return v,
+        Err(e) => e,
+    };
+    if let Some(code) = err.downcast_ref::<Code>() {
+        return (*code).into();
+    }
+    if sql::is_timeout(&err) {
+        return Status::deadline_exceeded("timeout");
+    }
+    tracing::error!(err = ?err, "[grpc] internal error");
+    Status::internal("internal error")
+}
+
+/// gRPC 客户端构建
+pub struct ClientBuilder {
+    endpoint: String,
+    connect_timeout: Duration,
+    timeout: Duration,
+    keepalive: Option<Duration>,
+    retry: Retry,
+}
+
+impl ClientBuilder {
+    /// 建立连接超时，默认：3s
+    pub fn connect_timeout(mut self, d: Duration) -> Self {
+        self.connect_timeout = d;
+        self
+    }
+
+    /// 单次请求超时，默认：10s
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = d;
+        self
+    }
+
+    /// TCP keepalive，默认：60s
+    pub fn keepalive(mut self, d: Option<Duration>) -> Self {
+        self.keepalive = d;
+        self
+    }
+
+    /// 最大尝试次数与首次重试间隔，默认：3 次、100ms
+    pub fn retry(mut self, attempts: u32, backoff: Duration) -> Self {
+        self.retry = Retry {
+            attempts: attempts.max(1),
+            backoff,
+        };
+        self
+    }
+
+    fn endpoint(&self) -> anyhow::Result<Endpoint> {
+        let endpoint = Endpoint::from_shared(self.endpoint.clone())
+            .map_err(|e| anyhow!("grpc: invalid endpoint `{}`: {}", self.endpoint, e))?;
+        Ok(endpoint
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.timeout)
+            .tcp_keepalive(self.keepalive))
+    }
+
+    /// 立即建立连接
+    pub async fn connect(self) -> anyhow::Result<Client> {
+        let channel = self.endpoint()?.connect().await?;
+        Ok(Client {
+            channel,
+            retry: self.retry,
+        })
+    }
+
+    /// 延迟到首次请求时建立连接
+    pub fn connect_lazy(self) -> anyhow::Result<Client> {
+        let channel = self.endpoint()?.connect_lazy();
+        Ok(Client {
+            channel,
+            retry: self.retry,
+        })
+    }
+}
+
+/// gRPC 客户端：超时、`Unavailable`/`ResourceExhausted` 时自动重试（指数退避 + 随机抖动）
+///
+/// # Examples
+///
+/// ```
+/// let client = grpc::Client::builder("http://127.0.0.1:50051")
+///     .timeout(Duration::from_secs(3))
+///     .connect_lazy()?;
+///
+/// let resp = client
+///     .call(|ch| {
+///         let req = HelloRequest { name: "kr".into() };
+///         async move { GreeterClient::new(ch).say_hello(req).await }
+///     })
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    channel: Channel,
+    retry: Retry,
+}
+
+impl Client {
+    pub fn builder(endpoint: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            endpoint: endpoint.into(),
+            connect_timeout: Duration::from_secs(3),
+            timeout: Duration::from_secs(10),
+            keepalive: Some(Duration::from_secs(60)),
+            retry: Retry {
+                attempts: 3,
+                backoff: Duration::from_millis(100),
+            },
+        }
+    }
+
+    /// 底层 Channel（可廉价克隆），用于构建生成的客户端
+    pub fn channel(&self) -> Channel {
+        self.channel.clone()
+    }
+
+    /// 发起调用，可重试的错误按重试策略重试（f 每次尝试时调用，须可重复构建请求）
+    pub async fn call<T, F, Fut>(&self, mut f: F) -> Result<Response<T>, Status>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        self.retry
+            .run(
+                "grpc",
+                || {
+                    let fut = f(self.channel.clone());
+                    async move { fut.await.map_err(anyhow::Error::from) }
+                },
+                |ret| match ret {
+                    Err(e) => e.downcast_ref::<Status>().is_some_and(is_retryable),
+                    Ok(_) => false,
+                },
+            )
+            .await
+            .map_err(status)
+    }
+}
+
+/// 是否为可重试的状态（服务不可用、限流）
+pub fn is_retryable(s: &Status) -> bool {
+    matches!(
+        s.code(),
+        tonic::Code::Unavailable | tonic::Code::ResourceExhausted
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+    use serde_json::json;
+    use tonic::{service::Interceptor, Request, Response, Status};
+
+    use crate::{codes, crypto::hash, grpc, sql};
+
+    fn token(secret: &str, claims: serde_json::Value) -> String {
+        let header = BASE64_URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+        let payload = BASE64_URL_SAFE_NO_PAD.encode(claims.to_string());
+        let data = format!("{}.{}", header, payload);
+        let sig = hash::hmac_sha256::<Vec<u8>>(secret, &data);
+        format!("{}.{}", data, BASE64_URL_SAFE_NO_PAD.encode(sig))
+    }
+
+    fn bearer(token: &str) -> Request<()> {
+        let mut req = Request::new(());
+        req.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        req
+    }
+
+    #[test]
+    fn test_request_id() {
+        // 缺失时生成
+        let req = grpc::request_id(Request::new(())).unwrap();
+        let id = req.extensions().get::<grpc::RequestId>().unwrap().0.clone();
+        assert_eq!(id.len(), 36);
+        assert_eq!(
+            req.metadata().get(grpc::REQUEST_ID_HEADER).unwrap(),
+            id.as_str()
+        );
+
+        // 透传
+        let mut req = Request::new(());
+        req.metadata_mut()
+            .insert(grpc::REQUEST_ID_HEADER, "abc".parse().unwrap());
+        let req = grpc::request_id(req).unwrap();
+        assert_eq!(
+            req.extensions().get::<grpc::RequestId>(),
+            Some(&grpc::RequestId("abc".into()))
+        );
+
+        // 客户端设置
+        let mut propagate = grpc::propagate_request_id("abc");
+        let req = propagate(Request::new(())).unwrap();
+        assert_eq!(req.metadata().get(grpc::REQUEST_ID_HEADER).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_jwt_auth() {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let mut auth = grpc::JwtAuth::hs256("secret").leeway(Duration::from_secs(5));
+
+        let req = auth
+            .call(bearer(&token(
+                "secret",
+                json!({"sub": "10086", "exp": now + 60}),
+            )))
+            .unwrap();
+        let claims = req.extensions().get::<grpc::Claims>().unwrap();
+        assert_eq!(claims.subject(), Some("10086"));
+
+        // 在 leeway 内
+        assert!(auth
+            .call(bearer(&token("secret", json!({"exp": now - 2}))))
+            .is_ok());
+
+        for t in [
+            token("secret", json!({"exp": now - 60})),
+            token("secret", json!({"nbf": now + 60})),
+            token("other", json!({"sub": "10086"})),
+            "a.b".to_string(),
+        ] {
+            let err = auth.call(bearer(&t)).unwrap_err();
+            assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        }
+        let err = auth.call(Request::new(())).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        // 篡改载荷
+        let t = token("secret", json!({"sub": "1"}));
+        let mut parts: Vec<&str> = t.split('.').collect();
+        let payload = BASE64_URL_SAFE_NO_PAD.encode(r#"{"sub":"2"}"#);
+        parts[1] = &payload;
+        assert!(auth.verify(&parts.join(".")).is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        let s: Status = codes::BUSY.into();
+        assert_eq!(s.code(), tonic::Code::Unavailable);
+        assert_eq!(s.metadata().get(grpc::ERROR_CODE_HEADER).unwrap(), "503");
+
+        let s = grpc::status(codes::QUOTA_EXCEEDED.into());
+        assert_eq!(s.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(s.message(), codes::QUOTA_EXCEEDED.msg);
+
+        let s = grpc::status(Status::not_found("user").into());
+        assert_eq!(s.code(), tonic::Code::NotFound);
+
+        let s = grpc::status(sql::Timeout(Duration::from_secs(1)).into());
+        assert_eq!(s.code(), tonic::Code::DeadlineExceeded);
+
+        // 不暴露内部错误详情
+        let s = grpc::status(anyhow::anyhow!("db password wrong"));
+        assert_eq!(s.code(), tonic::Code::Internal);
+        assert_eq!(s.message(), "internal error");
+    }
+
+    #[tokio::test]
+    async fn test_client_retry() {
+        let client = grpc::Client::builder("http://127.0.0.1:1")
+            .retry(3, Duration::from_millis(1))
+            .connect_lazy()
+            .unwrap();
+
+        // 首次不可用，第二次成功
+        let n = AtomicU32::new(0);
+        let resp = client
+            .call(|_| {
+                let attempt = n.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    if attempt == 1 {
+                        return Err(Status::unavailable("down"));
+                    }
+                    Ok(Response::new(attempt))
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(resp.into_inner(), 2);
+
+        // 不可重试的错误原样返回
+        let n = AtomicU32::new(0);
+        let err = client
+            .call(|_| {
+                n.fetch_add(1, Ordering::SeqCst);
+                async { Err::<Response<()>, _>(Status::invalid_argument("bad")) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert_eq!(n.load(Ordering::SeqCst), 1);
+
+        assert!(grpc::Client::builder("not a uri").connect_lazy().is_err());
+    }
+}
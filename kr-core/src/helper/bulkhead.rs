@@ -0,0 +1,206 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::codes;
+
+/// 隔离舱状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkheadState {
+    /// 执行中
+    pub in_flight: usize,
+    /// 排队中
+    pub waiting: usize,
+    /// 累计被拒绝次数
+    pub rejected: u64,
+}
+
+/// 隔离舱：限制并发及排队长度，超出时快速失败（`codes::BUSY`），避免压垮下游（如 DB 连接池）
+///
+/// # Examples
+///
+/// ```
+/// // 最多 20 个并发，最多 100 个排队，排队最长 1s
+/// let bulkhead = Bulkhead::new("db", 20, 100).timeout(Duration::from_secs(1));
+///
+/// let user = bulkhead
+///     .run(async { mysql::find_one::<model::User>(&pool, stmt).await })
+///     .await?;
+///
+/// let state = bulkhead.state();
+/// ```
+pub struct Bulkhead {
+    name: String,
+    concurrency: usize,
+    max_queue: usize,
+    timeout: Option<Duration>,
+    semaphore: Arc<Semaphore>,
+    waiting: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+impl Bulkhead {
+    /// `concurrency` 为最大并发数，`max_queue` 为最大排队数
+    pub fn new(name: impl AsRef<str>, concurrency: usize, max_queue: usize) -> Self {
+        let concurrency = concurrency.max(1);
+        Self {
+            name: name.as_ref().to_string(),
+            concurrency,
+            max_queue,
+            timeout: None,
+            semaphore: Arc::new(Semaphore::new(concurrency)),
+            waiting: AtomicUsize::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// 排队等待的超时时间，默认：不超时
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 获取执行许可（释放许可即结束执行）
+    pub async fn acquire(&self) -> anyhow::Result<OwnedSemaphorePermit> {
+        // 有排队者时不插队
+        if self.waiting.load(Ordering::SeqCst) == 0 {
+            if let Ok(permit) = self.semaphore.clone().try_acquire_owned() {
+                return Ok(permit);
+            }
+        }
+
+        // 排队
+        let queued = self
+            .waiting
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.max_queue).then_some(n + 1)
+            })
+            .is_ok();
+        if !queued {
+            return Err(self.reject("queue full"));
+        }
+        // 调用方取消（drop）等待时同样减少排队数
+        let guard = Waiting(&self.waiting);
+
+        let acquire = self.semaphore.clone().acquire_owned();
+        let ret = match self.timeout {
+            Some(d) => tokio::time::timeout(d, acquire).await.ok(),
+            None => Some(acquire.await),
+        };
+        drop(guard);
+
+        match ret {
+            Some(permit) => Ok(permit?),
+            None => Err(self.reject("queue timeout")),
+        }
+    }
+
+    /// 在隔离舱内执行
+    pub async fn run<F, T>(&self, f: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        let _permit = self.acquire().await?;
+        f.await
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> BulkheadState {
+        BulkheadState {
+            in_flight: self.concurrency - self.semaphore.available_permits(),
+            waiting: self.waiting.load(Ordering::SeqCst),
+            rejected: self.rejected.load(Ordering::SeqCst),
+        }
+    }
+
+    fn reject(&self, reason: &str) -> anyhow::Error {
+        self.rejected.fetch_add(1, Ordering::SeqCst);
+        tracing::warn!(
+            name = self.name,
+            reason = reason,
+            waiting = self.waiting.load(Ordering::SeqCst),
+            "[bulkhead] rejected"
+        );
+        codes::BUSY.into()
+    }
+}
+
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        codes,
+        helper::bulkhead::{Bulkhead, BulkheadState},
+    };
+
+    #[tokio::test]
+    async fn test_bulkhead() {
+        let bulkhead = Arc::new(Bulkhead::new("test", 2, 1));
+
+        let p1 = bulkhead.acquire().await.unwrap();
+        let _p2 = bulkhead.acquire().await.unwrap();
+
+        // 排队
+        let b = bulkhead.clone();
+        let queued = tokio::spawn(async move { b.run(async { Ok(1) }).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(
+            bulkhead.state(),
+            BulkheadState {
+                in_flight: 2,
+                waiting: 1,
+                rejected: 0
+            }
+        );
+
+        // 队列已满
+        let err = bulkhead.run(async { Ok(2) }).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<codes::Code>(), Some(&codes::BUSY));
+
+        drop(p1);
+        assert_eq!(queued.await.unwrap().unwrap(), 1);
+        assert_eq!(bulkhead.state().waiting, 0);
+        assert_eq!(bulkhead.state().rejected, 1);
+
+        // 排队超时
+        let bulkhead = Bulkhead::new("test", 1, 1).timeout(Duration::from_millis(10));
+        let _p = bulkhead.acquire().await.unwrap();
+        assert!(bulkhead.acquire().await.is_err());
+        assert_eq!(bulkhead.state().waiting, 0);
+
+        // 排队中被取消
+        let bulkhead = Arc::new(Bulkhead::new("test", 1, 2));
+        let p = bulkhead.acquire().await.unwrap();
+        let ret = tokio::time::timeout(Duration::from_millis(10), bulkhead.acquire()).await;
+        assert!(ret.is_err());
+        assert_eq!(bulkhead.state().waiting, 0);
+
+        // 有排队者时，新请求不插队
+        let b = bulkhead.clone();
+        let queued = tokio::spawn(async move { b.acquire().await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(bulkhead.state().waiting, 1);
+        drop(p);
+        let p = bulkhead.acquire().await.unwrap();
+        // 许可先交给了排队者
+        assert!(queued.is_finished());
+        queued.await.unwrap().unwrap();
+        drop(p);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+
+// key => 最新调用的序号
+static DEBOUNCE: OnceLock<DashMap<String, u64>> = OnceLock::new();
+// key => (上次执行时间, 节流间隔)
+static THROTTLE: OnceLock<DashMap<String, (Instant, Duration)>> = OnceLock::new();
+
+// 超过该数量时清理过期的节流记录
+const THROTTLE_PURGE_SIZE: usize = 10000;
+
+/// 防抖：等待 `window`，期间同一 key 无新调用时返回 true（执行），否则返回 false（已合并到后续调用）
+///
+/// 一连串密集调用中仅最后一次返回 true
+///
+/// # Examples
+///
+/// ```
+/// // 数据变更风暴中只失效一次缓存
+/// if helper::debounce(format!("user:{}", id), Duration::from_millis(200)).await {
+///     repo.invalidate(&id).await?;
+/// }
+/// ```
+pub async fn debounce(key: impl AsRef<str>, window: Duration) -> bool {
+    let map = DEBOUNCE.get_or_init(DashMap::new);
+    let key = key.as_ref();
+
+    let seq = {
+        let mut v = map.entry(key.to_string()).or_insert(0);
+        *v += 1;
+        *v
+    };
+
+    // 调用返回或被取消时清理自身记录（已有后续调用时保留）
+    let _guard = Cleanup { map, key, seq };
+
+    tokio::time::sleep(window).await;
+
+    // 仅最后一次调用执行
+    map.get(key).is_some_and(|v| *v == seq)
+}
+
+struct Cleanup<'a> {
+    map: &'a DashMap<String, u64>,
+    key: &'a str,
+    seq: u64,
+}
+
+impl Drop for Cleanup<'_> {
+    fn drop(&mut self) {
+        self.map.remove_if(self.key, |_, v| *v == self.seq);
+    }
+}
+
+/// 节流：同一 key 在 `rate` 时间内最多执行一次，返回 true 表示执行，false 表示被合并
+///
+/// # Examples
+///
+/// ```
+/// if helper::throttle("refresh:config", Duration::from_secs(1)) {
+///     reload().await?;
+/// }
+/// ```
+pub fn throttle(key: impl AsRef<str>, rate: Duration) -> bool {
+    let map = THROTTLE.get_or_init(DashMap::new);
+    let now = Instant::now();
+
+    // 按各记录自身的间隔判断是否过期
+    if map.len() > THROTTLE_PURGE_SIZE {
+        map.retain(|_, (t, r)| now.duration_since(*t) < *r);
+    }
+
+    let mut executed = false;
+    map.entry(key.as_ref().to_string())
+        .and_modify(|v| {
+            if now.duration_since(v.0) >= rate {
+                *v = (now, rate);
+                executed = true;
+            }
+        })
+        .or_insert_with(|| {
+            executed = true;
+            (now, rate)
+        });
+    executed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::helper::{
+        debounce,
+        debounce::{DEBOUNCE, THROTTLE_PURGE_SIZE},
+        throttle,
+    };
+
+    #[tokio::test]
+    async fn test_debounce() {
+        let window = Duration::from_millis(50);
+        let calls = (0..5).map(|i| async move {
+            tokio::time::sleep(Duration::from_millis(i * 5)).await;
+            debounce("test:debounce", window).await
+        });
+        let ret = futures::future::join_all(calls).await;
+        assert_eq!(ret, vec![false, false, false, false, true]);
+
+        // 窗口结束后重新计算
+        assert!(debounce("test:debounce", window).await);
+    }
+
+    #[tokio::test]
+    async fn test_debounce_cancel() {
+        let ret = tokio::time::timeout(
+            Duration::from_millis(10),
+            debounce("test:debounce:cancel", Duration::from_secs(1)),
+        )
+        .await;
+        assert!(ret.is_err());
+
+        // 被取消的调用不残留记录
+        let map = DEBOUNCE.get().unwrap();
+        assert!(!map.contains_key("test:debounce:cancel"));
+    }
+
+    #[test]
+    fn test_throttle() {
+        let rate = Duration::from_millis(50);
+        assert!(throttle("test:throttle", rate));
+        assert!(!throttle("test:throttle", rate));
+        assert!(throttle("test:throttle:other", rate));
+
+        std::thread::sleep(rate);
+        assert!(throttle("test:throttle", rate));
+    }
+
+    #[test]
+    fn test_throttle_purge() {
+        let long = Duration::from_secs(3600);
+        for i in 0..=THROTTLE_PURGE_SIZE {
+            throttle(format!("test:throttle:purge:{}", i), long);
+        }
+
+        // 短间隔的调用触发清理，不应清除仍在自身间隔内的记录
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(throttle("test:throttle:purge", Duration::from_millis(10)));
+        assert!(!throttle("test:throttle:purge:0", long));
+    }
+}
@@ -0,0 +1,53 @@
+// HTTP 客户端构建与重试（search、olap 共用，重试亦用于 grpc）
+
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+
+#[cfg(any(feature = "search", feature = "olap"))]
+pub(crate) fn build_client(name: &str, timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .unwrap_or_else(|e| panic!("{}: build http client: {}", name, e))
+}
+
+/// 重试策略：最多尝试 attempts 次，重试间隔从 backoff 开始指数增长，并加上 `[0, backoff]` 的随机抖动
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Retry {
+    pub attempts: u32,
+    pub backoff: Duration,
+}
+
+impl Retry {
+    /// 执行 f，`retryable` 判断结果是否需要重试；达到最大次数后返回最后一次的结果
+    pub(crate) async fn run<T, F, Fut>(
+        &self,
+        name: &str,
+        mut f: F,
+        retryable: impl Fn(&anyhow::Result<T>) -> bool,
+    ) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+        loop {
+            let ret = f().await;
+            if attempt >= self.attempts || !retryable(&ret) {
+                return ret;
+            }
+
+            tracing::warn!(
+                client = name,
+                attempt = attempt,
+                "[http] request failed, retrying"
+            );
+            let jitter = rand::thread_rng().gen_range(0..=backoff.as_millis() as u64);
+            tokio::time::sleep(backoff + Duration::from_millis(jitter)).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
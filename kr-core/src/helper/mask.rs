@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{OnceLock, RwLock},
+};
+
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+tokio::task_local! {
+    static ROLES: Vec<String>;
+}
+
+static RULES: OnceLock<RwLock<HashMap<&'static str, Vec<Rule>>>> = OnceLock::new();
+
+fn rules() -> &'static RwLock<HashMap<&'static str, Vec<Rule>>> {
+    RULES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 脱敏方式
+#[derive(Debug, Clone)]
+pub enum Strategy {
+    /// 移除字段
+    Hide,
+    /// 替换为 `***`
+    Redact,
+    /// 保留前 n 位和后 m 位，其余替换为 `*`
+    Partial(usize, usize),
+    /// 邮箱：保留首字符及域名
+    Email,
+}
+
+impl Strategy {
+    /// 对字符串脱敏
+    pub fn apply(&self, s: &str) -> String {
+        match self {
+            Strategy::Hide | Strategy::Redact => "***".to_string(),
+            Strategy::Partial(head, tail) => {
+                let chars: Vec<char> = s.chars().collect();
+                if chars.len() <= head + tail {
+                    return "*".repeat(chars.len());
+                }
+                let mut out: String = chars[..*head].iter().collect();
+                out.push_str(&"*".repeat(chars.len() - head - tail));
+                out.extend(&chars[chars.len() - tail..]);
+                out
+            }
+            Strategy::Email => match s.split_once('@') {
+                Some((name, domain)) => {
+                    let first: String = name.chars().take(1).collect();
+                    format!("{}***@{}", first, domain)
+                }
+                None => Strategy::Redact.apply(s),
+            },
+        }
+    }
+}
+
+/// 字段脱敏规则
+#[derive(Debug, Clone)]
+pub struct Rule {
+    path: Vec<String>,
+    strategy: Strategy,
+    allow: Vec<String>,
+}
+
+impl Rule {
+    /// `field` 为序列化后的字段名，嵌套字段以 `.` 分隔（数组会逐个元素应用）
+    pub fn new(field: impl AsRef<str>, strategy: Strategy) -> Self {
+        Self {
+            path: field.as_ref().split('.').map(|v| v.to_string()).collect(),
+            strategy,
+            allow: Vec::new(),
+        }
+    }
+
+    /// 拥有任一角色时不脱敏
+    pub fn allow<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.allow
+            .extend(roles.into_iter().map(|v| v.as_ref().to_string()));
+        self
+    }
+
+    fn skip(&self, roles: &[String]) -> bool {
+        self.allow.iter().any(|v| roles.contains(v))
+    }
+}
+
+/// 注册类型的脱敏规则
+///
+/// # Examples
+///
+/// ```
+/// mask::register::<UserDTO>(vec![
+///     Rule::new("phone", Strategy::Partial(3, 4)).allow(["admin"]),
+///     Rule::new("email", Strategy::Email).allow(["admin"]),
+///     Rule::new("profile.id_card", Strategy::Hide),
+/// ]);
+/// ```
+pub fn register<T: ?Sized>(list: Vec<Rule>) {
+    rules()
+        .write()
+        .unwrap()
+        .insert(std::any::type_name::<T>(), list);
+}
+
+/// 在指定角色下执行（通常在鉴权中间件中设置）
+///
+/// # Examples
+///
+/// ```
+/// let ret = mask::scope(vec!["admin".to_string()], async {
+///     // ...
+/// })
+/// .await;
+/// ```
+pub async fn scope<F: Future>(roles: Vec<String>, f: F) -> F::Output {
+    ROLES.scope(roles, f).await
+}
+
+/// 当前角色
+pub fn current() -> Vec<String> {
+    ROLES.try_with(|v| v.clone()).unwrap_or_default()
+}
+
+/// 序列化时按当前角色应用脱敏规则
+///
+/// # Examples
+///
+/// ```
+/// // 使用 UserDTO 的规则
+/// let v = Masked::new(user);
+///
+/// // Vec、Option、Box 等标准容器使用元素类型的规则
+/// let v = Masked::new(users);
+///
+/// // 自定义泛型包装中需对内层值脱敏，直接包装整体会序列化报错
+/// let v = Page { list: Masked::new(users), total };
+///
+/// let json = serde_json::to_string(&v)?;
+/// ```
+pub struct Masked<T> {
+    value: T,
+    rules: &'static str,
+}
+
+impl<T> Masked<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            rules: std::any::type_name::<T>(),
+        }
+    }
+
+    /// 使用类型 `R` 的规则（`Vec`、`Option` 等标准容器无需指定，会自动使用元素类型的规则）
+    pub fn with<R: ?Sized>(value: T) -> Self {
+        Self {
+            value,
+            rules: std::any::type_name::<R>(),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Serialize> Serialize for Masked<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let list = match lookup(self.rules) {
+            Ok(Some(v)) => v,
+            Ok(None) => return self.value.serialize(serializer),
+            Err(e) => {
+                tracing::error!(error = ?e, "[mask] rules not resolved");
+                return Err(serde::ser::Error::custom(e));
+            }
+        };
+
+        let mut v = serde_json::to_value(&self.value).map_err(serde::ser::Error::custom)?;
+        let roles = current();
+        for rule in list.iter().filter(|r| !r.skip(&roles)) {
+            apply(&mut v, &rule.path, &rule.strategy);
+        }
+        v.serialize(serializer)
+    }
+}
+
+// 可直接穿透的容器类型
+const CONTAINERS: [&str; 7] = [
+    "alloc::vec::Vec<",
+    "alloc::collections::vec_deque::VecDeque<",
+    "core::option::Option<",
+    "alloc::boxed::Box<",
+    "alloc::sync::Arc<",
+    "alloc::rc::Rc<",
+    "core::cell::RefCell<",
+];
+
+/// 去掉引用、切片、数组及常见容器，得到元素类型名
+fn unwrap_type(mut name: &str) -> &str {
+    loop {
+        if let Some(v) = name.strip_prefix("&mut ") {
+            name = v;
+        } else if let Some(v) = name.strip_prefix('&') {
+            name = v;
+        } else if let Some(v) = name.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            // [T] 或 [T; N]
+            name = v.rsplit_once("; ").map_or(v, |(t, _)| t);
+        } else if let Some(v) = CONTAINERS
+            .iter()
+            .find_map(|p| name.strip_prefix(p).and_then(|v| v.strip_suffix('>')))
+        {
+            name = v;
+        } else {
+            return name;
+        }
+    }
+}
+
+/// 查找类型的脱敏规则；类型中包含已注册类型但无法确定规则时（如自定义泛型包装）返回错误，不输出明文
+fn lookup(name: &str) -> anyhow::Result<Option<Vec<Rule>>> {
+    let rules = rules().read().unwrap();
+    let inner = unwrap_type(name);
+    if let Some(v) = rules.get(inner) {
+        return Ok(Some(v.clone()));
+    }
+    if let Some(k) = rules.keys().find(|k| contains_type(inner, k)) {
+        anyhow::bail!(
+            "mask: `{}` contains `{}`, wrap the inner value with `Masked` instead",
+            name,
+            k
+        );
+    }
+    Ok(None)
+}
+
+fn contains_type(name: &str, ty: &str) -> bool {
+    name.match_indices(ty).any(|(i, _)| {
+        let ident = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+        !name[..i].ends_with(ident) && !name[i + ty.len()..].starts_with(ident)
+    })
+}
+
+fn apply(v: &mut Value, path: &[String], strategy: &Strategy) {
+    match v {
+        Value::Array(list) => {
+            for item in list {
+                apply(item, path, strategy);
+            }
+        }
+        Value::Object(m) => {
+            let Some((key, rest)) = path.split_first() else {
+                return;
+            };
+            if !rest.is_empty() {
+                if let Some(child) = m.get_mut(key) {
+                    apply(child, rest, strategy);
+                }
+                return;
+            }
+            if let Strategy::Hide = strategy {
+                m.remove(key);
+                return;
+            }
+            if let Some(field) = m.get_mut(key) {
+                let masked = match &*field {
+                    Value::Null => Value::Null,
+                    Value::String(s) => Value::String(strategy.apply(s)),
+                    other => Value::String(strategy.apply(&other.to_string())),
+                };
+                *field = masked;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde_json::json;
+
+    use crate::helper::mask::{self, Masked, Rule, Strategy};
+
+    #[derive(Serialize)]
+    struct Profile {
+        id_card: String,
+    }
+
+    #[derive(Serialize)]
+    struct UserDTO {
+        name: String,
+        phone: String,
+        email: Option<String>,
+        profile: Profile,
+    }
+
+    fn user() -> UserDTO {
+        UserDTO {
+            name: "kr".to_string(),
+            phone: "13800138000".to_string(),
+            email: Some("kr@example.com".to_string()),
+            profile: Profile {
+                id_card: "110101199001011234".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_masked() {
+        mask::register::<UserDTO>(vec![
+            Rule::new("phone", Strategy::Partial(3, 4)).allow(["admin"]),
+            Rule::new("email", Strategy::Email).allow(["admin"]),
+            Rule::new("profile.id_card", Strategy::Hide),
+        ]);
+
+        let v = serde_json::to_value(Masked::new(user())).unwrap();
+        assert_eq!(
+            v,
+            json!({
+                "name": "kr",
+                "phone": "138****8000",
+                "email": "k***@example.com",
+                "profile": {},
+            })
+        );
+
+        let v = mask::scope(vec!["admin".to_string()], async {
+            serde_json::to_value(Masked::with::<UserDTO>(vec![user()])).unwrap()
+        })
+        .await;
+        assert_eq!(
+            v,
+            json!([{
+                "name": "kr",
+                "phone": "13800138000",
+                "email": "kr@example.com",
+                "profile": {},
+            }])
+        );
+
+        // 标准容器使用元素类型的规则
+        let v = serde_json::to_value(Masked::new(Some(vec![Box::new(user())]))).unwrap();
+        assert_eq!(v[0]["phone"], "138****8000");
+        let list = [user()];
+        let v = serde_json::to_value(Masked::new(&list[..])).unwrap();
+        assert_eq!(v[0]["phone"], "138****8000");
+
+        // 无法确定规则的包装类型报错，不输出明文
+        #[derive(Serialize)]
+        struct Page<T> {
+            list: Vec<T>,
+        }
+        let page = Page { list: vec![user()] };
+        assert!(serde_json::to_value(Masked::new(&page)).is_err());
+        let page = Page {
+            list: vec![Masked::new(user())],
+        };
+        let v = serde_json::to_value(&page).unwrap();
+        assert_eq!(v["list"][0]["phone"], "138****8000");
+
+        // 未注册规则的类型原样输出
+        let v = serde_json::to_value(Masked::new(Profile {
+            id_card: "1".to_string(),
+        }))
+        .unwrap();
+        assert_eq!(v, json!({ "id_card": "1" }));
+    }
+}
@@ -1,8 +1,33 @@
+pub mod bulkhead;
+pub mod debounce;
+#[cfg(any(feature = "search", feature = "olap", feature = "grpc"))]
+pub(crate) mod http;
+pub mod mask;
+pub mod page;
+pub mod pool;
+pub mod random;
 pub mod redkit;
+pub mod repo;
+pub mod reserve;
+pub mod signurl;
+pub mod taskgroup;
 pub mod zoned;
 
+pub use bulkhead::{Bulkhead, BulkheadState};
+pub use debounce::{debounce, throttle};
+pub use mask::Masked;
+pub use page::{ListData, PageData};
+pub use pool::{PoolStats, Stats};
+pub use random::{choose_weighted, rand_range, sample, token_bytes, token_hex, token_urlsafe};
+pub use redkit::stats::CacheStats;
+pub use repo::{CachedRepo, Entity};
+pub use reserve::{reserve_unique, Reservation};
+pub use signurl::SignUrl;
+pub use taskgroup::TaskGroup;
+
 use rand::distributions::{Alphanumeric, DistString};
 
+/// 随机字母数字串（密钥、token 等场景使用 [`token_urlsafe`]）
 pub fn nonce(size: usize) -> String {
     let mut rng = rand::thread_rng();
     Alphanumeric.sample_string(&mut rng, size)
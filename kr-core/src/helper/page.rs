@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+/// 游标分页数据
+///
+/// # Examples
+///
+/// ```
+/// // 多查一条用于判断是否还有更多
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(Expr::col(table::Demo::Id).lt(cursor))
+///     .order_by(table::Demo::Id, Order::Desc)
+///     .limit(limit as u64 + 1)
+///     .to_owned();
+/// let rows = mysql::find_all::<model::Demo>(&pool, stmt).await?;
+///
+/// // {"list": [...], "next_cursor": "10086", "has_more": true}
+/// let data = ListData::from_limit(rows, limit, |v| v.id.to_string());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListData<T> {
+    pub list: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl<T> ListData<T> {
+    pub fn new(list: Vec<T>, next_cursor: Option<String>) -> Self {
+        Self {
+            has_more: next_cursor.is_some(),
+            list,
+            next_cursor,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self::new(Vec::new(), None)
+    }
+
+    /// 基于 limit+1 条查询结果构建：超出 limit 时截断，并以最后一条生成下一页游标
+    pub fn from_limit<F>(mut list: Vec<T>, limit: usize, cursor: F) -> Self
+    where
+        F: FnOnce(&T) -> String,
+    {
+        if list.len() <= limit {
+            return Self::new(list, None);
+        }
+        list.truncate(limit);
+        let next = list.last().map(cursor);
+        Self::new(list, next)
+    }
+
+    pub fn map<U, F>(self, f: F) -> ListData<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        ListData {
+            list: self.list.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+}
+
+impl<T> Default for ListData<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// 页码分页数据
+///
+/// # Examples
+///
+/// ```
+/// let ret = mysql::paginate::<model::Demo>(&pool, stmt, page, size).await?;
+///
+/// // {"list": [...], "total": 100, "page": 1, "size": 20, "has_more": true}
+/// let data = PageData::from_paginate(ret, page, size);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageData<T> {
+    pub list: Vec<T>,
+    pub total: i64,
+    pub page: i32,
+    pub size: i32,
+    pub has_more: bool,
+}
+
+impl<T> PageData<T> {
+    /// page、size 的默认值与 `paginate` 一致（page <= 0 => 1，size <= 0 => 20）
+    pub fn new(list: Vec<T>, total: i64, page: i32, size: i32) -> Self {
+        let page = if page <= 0 { 1 } else { page };
+        let size = if size <= 0 { 20 } else { size };
+        Self {
+            has_more: (page as i64) * (size as i64) < total,
+            list,
+            total,
+            page,
+            size,
+        }
+    }
+
+    /// 基于 `paginate` 的返回值构建
+    pub fn from_paginate(ret: (Vec<T>, i64), page: i32, size: i32) -> Self {
+        Self::new(ret.0, ret.1, page, size)
+    }
+
+    pub fn map<U, F>(self, f: F) -> PageData<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        PageData {
+            list: self.list.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            size: self.size,
+            has_more: self.has_more,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::helper::page::{ListData, PageData};
+
+    #[test]
+    fn test_list_data() {
+        let data = ListData::from_limit(vec![5, 4, 3], 2, |v| v.to_string());
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            json!({"list": [5, 4], "next_cursor": "4", "has_more": true})
+        );
+
+        let data = ListData::from_limit(vec![2, 1], 2, |v| v.to_string()).map(|v| v * 10);
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            json!({"list": [20, 10], "next_cursor": null, "has_more": false})
+        );
+    }
+
+    #[test]
+    fn test_page_data() {
+        let data = PageData::from_paginate((vec![1, 2], 3), 1, 2);
+        assert!(data.has_more);
+
+        let data = PageData::new(vec![3], 3, 2, 2);
+        assert!(!data.has_more);
+
+        let data = PageData::<i32>::new(vec![], 0, 0, 0);
+        assert_eq!((data.page, data.size), (1, 20));
+    }
+}
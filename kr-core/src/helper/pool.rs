@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use bb8::ManageConnection;
+use sqlx::{Database, Pool};
+
+/// 连接池统计快照
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// 连接总数
+    pub size: u32,
+    /// 空闲连接数
+    pub idle: u32,
+    /// 使用中的连接数
+    pub in_use: u32,
+    /// 正在等待获取连接的请求数（sqlx、r2d2 不支持，恒为 0）
+    pub pending: u64,
+    /// 累计等待获取连接的次数（sqlx、r2d2 不支持，恒为 0）
+    pub wait_count: u64,
+    /// 累计等待获取连接的时长（sqlx、r2d2 不支持，恒为 0）
+    pub wait_duration: Duration,
+}
+
+impl PoolStats {
+    /// 使用率（使用中 / 最大连接数）
+    pub fn usage(&self, max_size: u32) -> f64 {
+        if max_size == 0 {
+            return 0.0;
+        }
+        self.in_use as f64 / max_size as f64
+    }
+}
+
+/// 获取连接池统计（sqlx、bb8、r2d2，含 `redix` 的 Redis 连接池）
+///
+/// # Examples
+///
+/// ```
+/// let stats = db.stats();
+/// if stats.usage(20) > 0.9 {
+///     tracing::warn!(?stats, "db pool saturated");
+/// }
+///
+/// let stats = redis_pool.stats();
+/// ```
+pub trait Stats {
+    fn stats(&self) -> PoolStats;
+}
+
+impl<DB: Database> Stats for Pool<DB> {
+    fn stats(&self) -> PoolStats {
+        let size = self.size();
+        let idle = self.num_idle() as u32;
+        PoolStats {
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            ..Default::default()
+        }
+    }
+}
+
+impl<M: ManageConnection> Stats for bb8::Pool<M> {
+    fn stats(&self) -> PoolStats {
+        let v = self.state();
+        let s = &v.statistics;
+        PoolStats {
+            size: v.connections,
+            idle: v.idle_connections,
+            in_use: v.connections.saturating_sub(v.idle_connections),
+            pending: s
+                .get_started
+                .saturating_sub(s.get_direct + s.get_waited + s.get_timed_out),
+            wait_count: s.get_waited,
+            wait_duration: s.get_wait_time,
+        }
+    }
+}
+
+impl<M: r2d2::ManageConnection> Stats for r2d2::Pool<M> {
+    fn stats(&self) -> PoolStats {
+        let v = self.state();
+        PoolStats {
+            size: v.connections,
+            idle: v.idle_connections,
+            in_use: v.connections.saturating_sub(v.idle_connections),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{helper::pool::Stats, redix, sql};
+
+    #[tokio::test]
+    async fn test_stats() {
+        let pool = sql::test::memory_pool(None).await.unwrap();
+        let conn = pool.acquire().await.unwrap();
+        let stats = pool.stats();
+        assert_eq!(stats.in_use, 1);
+        assert_eq!(stats.size, stats.idle + 1);
+        drop(conn);
+
+        let params = redix::Params {
+            max_size: Some(1),
+            ..Default::default()
+        };
+        let pool = redix::open::<redix::Mock>(vec![], Some(params))
+            .await
+            .unwrap();
+        let conn = pool.get().await.unwrap();
+        let stats = pool.stats();
+        assert_eq!((stats.size, stats.idle, stats.in_use), (1, 0, 1));
+        assert_eq!(stats.usage(1), 1.0);
+
+        // 等待获取连接
+        let p = pool.clone();
+        let waiter = tokio::spawn(async move {
+            let _conn = p.get().await.unwrap();
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(pool.stats().pending, 1);
+        drop(conn);
+        waiter.await.unwrap();
+        let after = pool.stats();
+        assert_eq!(after.wait_count, stats.wait_count + 1);
+        assert!(after.wait_duration > stats.wait_duration);
+    }
+}
@@ -0,0 +1,124 @@
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use rand::{
+    distributions::uniform::{SampleRange, SampleUniform},
+    rngs::OsRng,
+    seq::{IteratorRandom, SliceRandom},
+    Rng, RngCore,
+};
+
+/// 范围内的随机数（范围为空时返回 None）
+///
+/// # Examples
+///
+/// ```
+/// let n = helper::rand_range(1..=6).unwrap();
+/// let f = helper::rand_range(0.0..1.0).unwrap();
+/// ```
+pub fn rand_range<T, R>(range: R) -> Option<T>
+where
+    T: SampleUniform,
+    R: SampleRange<T>,
+{
+    if range.is_empty() {
+        return None;
+    }
+    Some(rand::thread_rng().gen_range(range))
+}
+
+/// 安全随机字节（OsRng）
+pub fn token_bytes(n: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; n];
+    OsRng.fill_bytes(&mut buf);
+    buf
+}
+
+/// 安全随机 token：n 个随机字节的 URL-safe base64（无填充），适用于密钥、重置链接等
+///
+/// # Examples
+///
+/// ```
+/// // 43 个字符
+/// let token = helper::token_urlsafe(32);
+/// ```
+pub fn token_urlsafe(n: usize) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(token_bytes(n))
+}
+
+/// 安全随机 token：n 个随机字节的十六进制表示
+pub fn token_hex(n: usize) -> String {
+    token_bytes(n)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 按权重随机选择一个元素（权重全为 0 或列表为空时返回 None）
+///
+/// # Examples
+///
+/// ```
+/// let servers = vec![("a", 5), ("b", 3), ("c", 2)];
+/// let (name, _) = helper::choose_weighted(&servers, |v| v.1).unwrap();
+/// ```
+pub fn choose_weighted<T, F>(items: &[T], weight: F) -> Option<&T>
+where
+    F: Fn(&T) -> u32,
+{
+    items.choose_weighted(&mut rand::thread_rng(), weight).ok()
+}
+
+/// 蓄水池抽样：从任意长度的迭代器中等概率随机抽取 k 个元素（不足 k 个时全部返回，顺序随机）
+///
+/// # Examples
+///
+/// ```
+/// let lines = helper::sample(reader.lines().map_while(Result::ok), 100);
+/// ```
+pub fn sample<T, I>(iter: I, k: usize) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+{
+    let mut rng = rand::thread_rng();
+    let mut list = iter.into_iter().choose_multiple(&mut rng, k);
+    list.shuffle(&mut rng);
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::helper::random;
+
+    #[test]
+    fn test_random() {
+        for _ in 0..100 {
+            let n = random::rand_range(1..=6).unwrap();
+            assert!((1..=6).contains(&n));
+        }
+        assert_eq!(random::rand_range(5..5), None);
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 6..=1;
+        assert_eq!(random::rand_range(empty), None);
+        assert_eq!(random::rand_range(3..=3), Some(3));
+
+        let token = random::token_urlsafe(32);
+        assert_eq!(token.len(), 43);
+        assert!(token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(random::token_hex(16).len(), 32);
+        assert_ne!(random::token_urlsafe(16), random::token_urlsafe(16));
+
+        let items = vec![("a", 0), ("b", 1), ("c", 0)];
+        for _ in 0..20 {
+            assert_eq!(random::choose_weighted(&items, |v| v.1).unwrap().0, "b");
+        }
+        assert!(random::choose_weighted(&[("a", 0)], |v| v.1).is_none());
+
+        let list = random::sample(0..1000, 10);
+        assert_eq!(list.len(), 10);
+        assert_eq!(list.iter().collect::<HashSet<_>>().len(), 10);
+        assert_eq!(random::sample(0..3, 10).len(), 3);
+    }
+}
@@ -1,9 +1,22 @@
-use std::{collections::HashMap, future::Future, time::Duration};
+pub mod atomic;
+pub mod codec;
+pub mod failopen;
+pub mod key;
+pub mod stats;
+pub mod swr;
 
+use std::{collections::HashMap, future::Future, sync::OnceLock, time::Duration};
+
+use rand::Rng;
 use redis::{AsyncCommands, RedisResult};
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::redix;
+use crate::redix::{self, script};
+
+pub use atomic::Writes;
+pub use codec::{Algorithm, Compression};
+pub use failopen::FailOpen;
+pub use swr::SwrTtl;
 
 pub const HSET: &str = r#"
 redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
@@ -12,6 +25,41 @@ if redis.call('TTL', KEYS[1]) == -1 then
 end
 "#;
 
+inventory::submit! { crate::redix::script::Builtin { name: "redkit:hset", body: HSET } }
+
+static TTL_JITTER: OnceLock<u8> = OnceLock::new();
+
+/// 设置缓存 TTL 的随机抖动百分比（±N%，取值 0-100），避免批量写入的缓存同时过期
+///
+/// 作用于 `get_or_set`、`hget_or_set` 的写入
+///
+/// # Examples
+///
+/// ```
+/// // 600s => 540s ~ 660s
+/// redkit::set_ttl_jitter(10);
+/// ```
+pub fn set_ttl_jitter(percent: u8) {
+    let _ = TTL_JITTER.set(percent.min(100));
+}
+
+/// 对 TTL 应用随机抖动（未设置时原样返回，最小1秒）
+pub fn jitter_ttl(ttl: Duration) -> Duration {
+    let percent = TTL_JITTER.get().copied().unwrap_or(0);
+    if percent == 0 {
+        return ttl;
+    }
+
+    let secs = ttl.as_secs() as i64;
+    let delta = secs * percent as i64 / 100;
+    if delta == 0 {
+        return ttl;
+    }
+    let v = secs + rand::thread_rng().gen_range(-delta..=delta);
+    Duration::from_secs(v.max(1) as u64)
+}
+
+#[derive(Clone)]
 pub enum Redis {
     Single(redix::SinglePool),
     Cluster(redix::ClusterPool),
@@ -28,31 +76,475 @@ impl Redis {
         T: Serialize + DeserializeOwned + Send + 'static,
         F: FnOnce() -> Fut,
         Fut: Future<Output = anyhow::Result<Option<T>>>,
+    {
+        RedisCache::new(self.clone())
+            .get_or_set(key, loader, ttl)
+            .await
+    }
+
+    pub async fn hget_or_set<T, F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        field: impl AsRef<str>,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<Option<T>>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<T>>>,
+    {
+        RedisCache::new(self.clone())
+            .hget_or_set(key, field, loader, ttl)
+            .await
+    }
+
+    pub async fn mget_map<K, T>(&self, keys: &[K]) -> anyhow::Result<HashMap<String, T>>
+    where
+        K: AsRef<str> + Sync,
+        T: Serialize + DeserializeOwned,
+    {
+        match self {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+
+                let normalized: Vec<_> = keys.iter().map(|k| key::normalize(k.as_ref())).collect();
+                let key_vec: Vec<&str> = normalized.iter().map(|k| &**k).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn.mget(key_vec).await?;
+
+                let mut map = HashMap::with_capacity(keys.len());
+                for (k, v) in keys.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            serde_json::from_slice(&codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                let normalized: Vec<_> = keys.iter().map(|k| key::normalize(k.as_ref())).collect();
+                let key_vec: Vec<&str> = normalized.iter().map(|k| &**k).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn.mget(key_vec).await?;
+
+                let mut map = HashMap::with_capacity(keys.len());
+                for (k, v) in keys.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            serde_json::from_slice(&codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+        }
+    }
+
+    pub async fn mget_str_map<K>(&self, keys: &[K]) -> anyhow::Result<HashMap<String, String>>
+    where
+        K: AsRef<str> + Sync,
+    {
+        match self {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+
+                let normalized: Vec<_> = keys.iter().map(|k| key::normalize(k.as_ref())).collect();
+                let key_vec: Vec<&str> = normalized.iter().map(|k| &**k).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn.mget(key_vec).await?;
+
+                let mut map = HashMap::with_capacity(keys.len());
+                for (k, v) in keys.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            String::from_utf8(codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                let normalized: Vec<_> = keys.iter().map(|k| key::normalize(k.as_ref())).collect();
+                let key_vec: Vec<&str> = normalized.iter().map(|k| &**k).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn.mget(key_vec).await?;
+
+                let mut map = HashMap::with_capacity(keys.len());
+                for (k, v) in keys.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            String::from_utf8(codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+        }
+    }
+
+    pub async fn hgetall<T>(&self, key: impl AsRef<str>) -> anyhow::Result<HashMap<String, T>>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        match self {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+
+                let raw: HashMap<String, Vec<u8>> =
+                    conn.hgetall(&*key::normalize(key.as_ref())).await?;
+
+                let mut map = HashMap::with_capacity(raw.len());
+                for (k, v) in raw {
+                    let parsed = serde_json::from_slice(&codec::decode(v)?)?;
+                    map.insert(k, parsed);
+                }
+                Ok(map)
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                let raw: HashMap<String, Vec<u8>> =
+                    conn.hgetall(&*key::normalize(key.as_ref())).await?;
+
+                let mut map = HashMap::with_capacity(raw.len());
+                for (k, v) in raw {
+                    let parsed = serde_json::from_slice(&codec::decode(v)?)?;
+                    map.insert(k, parsed);
+                }
+                Ok(map)
+            }
+        }
+    }
+
+    pub async fn hmget_map<K, T>(&self, key: K, fields: &[K]) -> anyhow::Result<HashMap<String, T>>
+    where
+        K: AsRef<str> + Sync,
+        T: Serialize + DeserializeOwned,
+    {
+        match self {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+
+                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn
+                    .hmget(&*key::normalize(key.as_ref()), field_vec)
+                    .await?;
+
+                let mut map = HashMap::with_capacity(fields.len());
+                for (k, v) in fields.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            serde_json::from_slice(&codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn
+                    .hmget(&*key::normalize(key.as_ref()), field_vec)
+                    .await?;
+
+                let mut map = HashMap::with_capacity(fields.len());
+                for (k, v) in fields.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            serde_json::from_slice(&codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+        }
+    }
+
+    pub async fn hmget_str_map<K>(
+        &self,
+        key: K,
+        fields: &[K],
+    ) -> anyhow::Result<HashMap<String, String>>
+    where
+        K: AsRef<str> + Sync,
     {
         match self {
             Redis::Single(pool) => {
                 let mut conn = pool.get().await?;
 
-                let key = key.as_ref();
+                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn
+                    .hmget(&*key::normalize(key.as_ref()), field_vec)
+                    .await?;
+
+                let mut map = HashMap::with_capacity(fields.len());
+                for (k, v) in fields.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            String::from_utf8(codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
+                let raw: Vec<Option<Vec<u8>>> = conn
+                    .hmget(&*key::normalize(key.as_ref()), field_vec)
+                    .await?;
+
+                let mut map = HashMap::with_capacity(fields.len());
+                for (k, v) in fields.iter().zip(raw) {
+                    if let Some(s) = v {
+                        map.insert(
+                            k.as_ref().to_string(),
+                            String::from_utf8(codec::decode(s)?)?,
+                        );
+                    }
+                }
+                Ok(map)
+            }
+        }
+    }
+
+    /// 读取二进制值（自动解压）
+    pub async fn get_bytes(&self, key: impl AsRef<str>) -> anyhow::Result<Option<Vec<u8>>> {
+        match self {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+
+                let ret: Option<Vec<u8>> = conn.get(&*key::normalize(key.as_ref())).await?;
+                ret.map(codec::decode).transpose()
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                let ret: Option<Vec<u8>> = conn.get(&*key::normalize(key.as_ref())).await?;
+                ret.map(codec::decode).transpose()
+            }
+        }
+    }
+
+    /// 删除缓存（key 按 `key::normalize` 规范化，与读写方法一致）
+    pub async fn delete(&self, key: impl AsRef<str>) -> anyhow::Result<()> {
+        let key = key::normalize(key.as_ref());
+        match self {
+            Redis::Single(pool) => {
+                let _: () = pool.get().await?.del(&*key).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool.get().await?.del(&*key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 写入二进制值，见 `RedisCache::set_bytes`
+    pub async fn set_bytes(
+        &self,
+        key: impl AsRef<str>,
+        value: impl Into<Vec<u8>>,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        RedisCache::new(self.clone())
+            .set_bytes(key, value, ttl)
+            .await
+    }
+
+    /// 原子写入多个 key，见 `RedisCache::write_atomic`
+    pub async fn write_atomic(&self, writes: Writes) -> anyhow::Result<()> {
+        RedisCache::new(self.clone()).write_atomic(writes).await
+    }
+
+    /// 同 `get_or_set`，值为原始二进制，见 `RedisCache::get_or_set_bytes`
+    pub async fn get_or_set_bytes<F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<Option<Vec<u8>>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<Vec<u8>>>>,
+    {
+        RedisCache::new(self.clone())
+            .get_or_set_bytes(key, loader, ttl)
+            .await
+    }
+
+    /// stale-while-revalidate，见 `RedisCache::get_or_set_swr`
+    pub async fn get_or_set_swr<T, F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        loader: F,
+        ttl: SwrTtl,
+    ) -> anyhow::Result<Option<T>>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<Option<T>>> + Send + 'static,
+    {
+        RedisCache::new(self.clone())
+            .get_or_set_swr(key, loader, ttl)
+            .await
+    }
+}
+
+/// 带实例级配置的 Redis 缓存（值压缩、fail-open 熔断），未配置时与 `Redis` 的同名方法一致
+///
+/// 读取时按值头部自动解压，与是否配置压缩无关；批量读取等方法见 `redis()`
+///
+/// # Examples
+///
+/// ```
+/// // 超过 4KB 的值使用 zstd 压缩
+/// // Redis 不可用时直接调用 loader，连续失败 5 次后熔断 10 秒（熔断状态按实例独立）
+/// let cache = RedisCache::new(Redis::Single(pool))
+///     .compression(Compression::new(Algorithm::Zstd(3), 4096))
+///     .fail_open(FailOpen {
+///         trip_after: Some(5),
+///         cooldown: Some(Duration::from_secs(10)),
+///     });
+///
+/// let user = cache.get_or_set("user:1", || async { find_user(1).await }, Some(Duration::from_secs(600))).await?;
+/// cache.set_bytes("thumb:1", png, Some(Duration::from_secs(3600))).await?;
+/// let m: HashMap<String, User> = cache.redis().mget_map(&keys).await?;
+/// ```
+#[derive(Clone)]
+pub struct RedisCache {
+    redis: Redis,
+    compression: Option<Compression>,
+    fail_open: Option<failopen::Guard>,
+}
+
+impl From<Redis> for RedisCache {
+    fn from(redis: Redis) -> Self {
+        Self::new(redis)
+    }
+}
+
+impl RedisCache {
+    pub fn new(redis: Redis) -> Self {
+        Self {
+            redis,
+            compression: None,
+            fail_open: None,
+        }
+    }
+
+    /// 值压缩
+    pub fn compression(mut self, c: Compression) -> Self {
+        self.compression = Some(c);
+        self
+    }
+
+    /// 开启 fail-open（熔断器在该实例及其克隆之间共享）
+    pub fn fail_open(mut self, opts: FailOpen) -> Self {
+        self.fail_open = Some(failopen::Guard::new(opts));
+        self
+    }
+
+    pub fn redis(&self) -> &Redis {
+        &self.redis
+    }
+
+    /// 是否处于熔断状态
+    pub fn is_tripped(&self) -> bool {
+        self.fail_open.as_ref().is_some_and(|v| v.is_tripped())
+    }
+
+    // 读取失败：开启 fail-open 时记录日志并返回 Ok（由调用方直接调用 loader），否则返回错误
+    fn read_failed(&self, err: anyhow::Error, key: &str) -> anyhow::Result<()> {
+        match &self.fail_open {
+            Some(v) => {
+                v.read_failed(err, key);
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
+    fn read_succeeded(&self) {
+        if let Some(v) = &self.fail_open {
+            v.read_succeeded();
+        }
+    }
+
+    // JSON 值编码（按实例的压缩配置）
+    pub(crate) fn encode_json(&self, data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        codec::encode_json(data, self.compression.as_ref())
+    }
+
+    pub async fn get_or_set<T, F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<Option<T>>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<T>>>,
+    {
+        match &self.redis {
+            Redis::Single(pool) => {
+                let key = key::normalize(key.as_ref());
+                let key: &str = &key;
+
+                // 熔断中，直接调用loader
+                if self.is_tripped() {
+                    return loader().await;
+                }
+                let mut conn = match pool.get().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
 
                 // 从缓存读取
-                let ret_get: Option<String> = conn.get(key).await?;
+                let ret_get: Option<Vec<u8>> = match conn.get(key).await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
+                self.read_succeeded();
                 if let Some(v) = ret_get {
-                    let parsed = serde_json::from_str(&v)?;
+                    stats::hit(key);
+                    let parsed = serde_json::from_slice(&codec::decode(v)?)?;
                     return Ok(parsed);
                 }
 
                 // 缓存未命中，调用loader获取数据
-                let data = loader().await?;
+                let data = stats::load(key, loader()).await?;
 
                 // 数据存在，写入缓存
                 if let Some(v) = &data {
                     let json_str = serde_json::to_string(&v)?;
+                    let value = self.encode_json(json_str.as_bytes().to_vec())?;
                     let set_ret: RedisResult<()> = match ttl {
-                        Some(d) => conn.set_ex(key, &json_str, d.as_secs()).await,
-                        None => conn.set(key, &json_str).await,
+                        Some(d) => conn.set_ex(key, &value, jitter_ttl(d).as_secs()).await,
+                        None => conn.set(key, &value).await,
                     };
                     if let Err(e) = set_ret {
+                        stats::error(key);
                         tracing::error!(error = ?e, key = key, data = json_str, "[cache::get_or_set] set data failed")
                     }
                 }
@@ -60,28 +552,51 @@ impl Redis {
                 Ok(data)
             }
             Redis::Cluster(pool) => {
-                let mut conn = pool.get().await?;
+                let key = key::normalize(key.as_ref());
+                let key: &str = &key;
 
-                let key = key.as_ref();
+                // 熔断中，直接调用loader
+                if self.is_tripped() {
+                    return loader().await;
+                }
+                let mut conn = match pool.get().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
 
                 // 从缓存读取
-                let ret_get: Option<String> = conn.get(key).await?;
+                let ret_get: Option<Vec<u8>> = match conn.get(key).await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
+                self.read_succeeded();
                 if let Some(v) = ret_get {
-                    let parsed = serde_json::from_str(&v)?;
+                    stats::hit(key);
+                    let parsed = serde_json::from_slice(&codec::decode(v)?)?;
                     return Ok(parsed);
                 }
 
                 // 缓存未命中，调用loader获取数据
-                let data = loader().await?;
+                let data = stats::load(key, loader()).await?;
 
                 // 数据存在，写入缓存
                 if let Some(v) = &data {
                     let json_str = serde_json::to_string(&v)?;
+                    let value = self.encode_json(json_str.as_bytes().to_vec())?;
                     let set_ret: RedisResult<()> = match ttl {
-                        Some(d) => conn.set_ex(key, &json_str, d.as_secs()).await,
-                        None => conn.set(key, &json_str).await,
+                        Some(d) => conn.set_ex(key, &value, jitter_ttl(d).as_secs()).await,
+                        None => conn.set(key, &value).await,
                     };
                     if let Err(e) = set_ret {
+                        stats::error(key);
                         tracing::error!(error = ?e, key = key, data = json_str, "[cache::get_or_set] set data failed")
                     }
                 }
@@ -103,39 +618,63 @@ impl Redis {
         F: FnOnce() -> Fut,
         Fut: Future<Output = anyhow::Result<Option<T>>>,
     {
-        match self {
+        match &self.redis {
             Redis::Single(pool) => {
-                let mut conn = pool.get().await?;
-
-                let key = key.as_ref();
+                let key = key::normalize(key.as_ref());
+                let key: &str = &key;
                 let field = field.as_ref();
 
+                // 熔断中，直接调用loader
+                if self.is_tripped() {
+                    return loader().await;
+                }
+                let mut conn = match pool.get().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
+
                 // 从缓存读取
-                let ret_get: Option<String> = conn.hget(key, field).await?;
+                let ret_get: Option<Vec<u8>> = match conn.hget(key, field).await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
+                self.read_succeeded();
                 if let Some(v) = ret_get {
-                    let parsed = serde_json::from_str(&v)?;
+                    stats::hit(key);
+                    let parsed = serde_json::from_slice(&codec::decode(v)?)?;
                     return Ok(parsed);
                 }
 
                 // 缓存未命中，调用loader获取数据
-                let data = loader().await?;
+                let data = stats::load(key, loader()).await?;
 
                 // 数据存在，写入缓存
                 if let Some(v) = &data {
                     let json_str = serde_json::to_string(&v)?;
+                    let value = self.encode_json(json_str.as_bytes().to_vec())?;
                     let set_ret: RedisResult<()> = match ttl {
                         Some(d) => {
-                            redis::Script::new(HSET)
+                            script::global()
+                                .get("redkit:hset")?
                                 .key(key)
                                 .arg(field)
-                                .arg(&json_str)
-                                .arg(d.as_secs() as i64)
+                                .arg(&value)
+                                .arg(jitter_ttl(d).as_secs() as i64)
                                 .invoke_async(&mut *conn)
                                 .await
                         }
-                        None => conn.hset(key, field, &json_str).await,
+                        None => conn.hset(key, field, &value).await,
                     };
                     if let Err(e) = set_ret {
+                        stats::error(key);
                         tracing::error!(error = ?e, key = key, data = json_str, "[cache::hget_or_hset] set data failed")
                     }
                 }
@@ -143,37 +682,61 @@ impl Redis {
                 Ok(data)
             }
             Redis::Cluster(pool) => {
-                let mut conn = pool.get().await?;
-
-                let key = key.as_ref();
+                let key = key::normalize(key.as_ref());
+                let key: &str = &key;
                 let field = field.as_ref();
 
+                // 熔断中，直接调用loader
+                if self.is_tripped() {
+                    return loader().await;
+                }
+                let mut conn = match pool.get().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
+
                 // 从缓存读取
-                let ret_get: Option<String> = conn.hget(key, field).await?;
+                let ret_get: Option<Vec<u8>> = match conn.hget(key, field).await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        stats::error(key);
+                        self.read_failed(e.into(), key)?;
+                        return loader().await;
+                    }
+                };
+                self.read_succeeded();
                 if let Some(v) = ret_get {
-                    let parsed = serde_json::from_str(&v)?;
+                    stats::hit(key);
+                    let parsed = serde_json::from_slice(&codec::decode(v)?)?;
                     return Ok(parsed);
                 }
 
                 // 缓存未命中，调用loader获取数据
-                let data = loader().await?;
+                let data = stats::load(key, loader()).await?;
 
                 // 数据存在，写入缓存
                 if let Some(v) = &data {
                     let json_str = serde_json::to_string(&v)?;
+                    let value = self.encode_json(json_str.as_bytes().to_vec())?;
                     let set_ret: RedisResult<()> = match ttl {
                         Some(d) => {
-                            redis::Script::new(HSET)
+                            script::global()
+                                .get("redkit:hset")?
                                 .key(key)
                                 .arg(field)
-                                .arg(&json_str)
-                                .arg(d.as_secs() as i64)
+                                .arg(&value)
+                                .arg(jitter_ttl(d).as_secs() as i64)
                                 .invoke_async(&mut *conn)
                                 .await
                         }
-                        None => conn.hset(key, field, &json_str).await,
+                        None => conn.hset(key, field, &value).await,
                     };
                     if let Err(e) = set_ret {
+                        stats::error(key);
                         tracing::error!(error = ?e, key = key, data = json_str, "[cache::hget_or_hset] set data failed")
                     }
                 }
@@ -183,187 +746,208 @@ impl Redis {
         }
     }
 
-    pub async fn mget_map<K, T>(&self, keys: &[K]) -> anyhow::Result<HashMap<String, T>>
-    where
-        K: AsRef<str> + Sync,
-        T: Serialize + DeserializeOwned,
-    {
-        match self {
+    /// 写入二进制值（总是加头部，超过压缩阈值时压缩）
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// redis.set_bytes("thumb:1", png, Some(Duration::from_secs(3600))).await?;
+    /// let v = redis.get_bytes("thumb:1").await?;
+    /// ```
+    pub async fn set_bytes(
+        &self,
+        key: impl AsRef<str>,
+        value: impl Into<Vec<u8>>,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        let value = codec::encode_bytes(value.into(), self.compression.as_ref())?;
+        let key = key::normalize(key.as_ref());
+        let key: &str = &key;
+        match &self.redis {
             Redis::Single(pool) => {
                 let mut conn = pool.get().await?;
 
-                let key_vec: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.mget(key_vec).await?;
-
-                let mut map = HashMap::with_capacity(keys.len());
-                for (k, v) in keys.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), serde_json::from_str(&s)?);
-                    }
+                match ttl {
+                    Some(d) => conn.set_ex(key, value, d.as_secs().max(1)).await?,
+                    None => conn.set(key, value).await?,
                 }
-                Ok(map)
+                Ok(())
             }
             Redis::Cluster(pool) => {
                 let mut conn = pool.get().await?;
 
-                let key_vec: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.mget(key_vec).await?;
-
-                let mut map = HashMap::with_capacity(keys.len());
-                for (k, v) in keys.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), serde_json::from_str(&s)?);
-                    }
+                match ttl {
+                    Some(d) => conn.set_ex(key, value, d.as_secs().max(1)).await?,
+                    None => conn.set(key, value).await?,
                 }
-                Ok(map)
+                Ok(())
             }
         }
     }
 
-    pub async fn mget_str_map<K>(&self, keys: &[K]) -> anyhow::Result<HashMap<String, String>>
-    where
-        K: AsRef<str> + Sync,
-    {
-        match self {
+    /// 原子写入多个 key（缓存值及其索引、标签 key），见 `Writes`
+    pub async fn write_atomic(&self, writes: Writes) -> anyhow::Result<()> {
+        if writes.is_empty() {
+            return Ok(());
+        }
+        match &self.redis {
             Redis::Single(pool) => {
                 let mut conn = pool.get().await?;
 
-                let key_vec: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.mget(key_vec).await?;
-
-                let mut map = HashMap::with_capacity(keys.len());
-                for (k, v) in keys.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), s);
+                let ret: RedisResult<()> = writes
+                    .pipeline(self.compression.as_ref())?
+                    .query_async(&mut *conn)
+                    .await;
+                let Err(e) = ret else {
+                    return Ok(());
+                };
+
+                // EXEC 中的命令出错时不会回滚，删除已写入的缓存值
+                for key in writes.values() {
+                    let ret: RedisResult<()> = conn.del(key).await;
+                    if let Err(e) = ret {
+                        tracing::error!(error = ?e, key = key, "[cache::write_atomic] rollback failed");
                     }
                 }
-                Ok(map)
+                Err(e.into())
             }
             Redis::Cluster(pool) => {
                 let mut conn = pool.get().await?;
 
-                let key_vec: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.mget(key_vec).await?;
-
-                let mut map = HashMap::with_capacity(keys.len());
-                for (k, v) in keys.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), s);
-                    }
-                }
-                Ok(map)
+                atomic::write_slots(&mut *conn, &writes, self.compression.as_ref()).await
             }
         }
     }
 
-    pub async fn hgetall<T>(&self, key: impl AsRef<str>) -> anyhow::Result<HashMap<String, T>>
+    /// 同 `get_or_set`，值为原始二进制（不经过 JSON）
+    pub async fn get_or_set_bytes<F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        loader: F,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<Option<Vec<u8>>>
     where
-        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<Vec<u8>>>>,
     {
-        match self {
-            Redis::Single(pool) => {
-                let mut conn = pool.get().await?;
-
-                let raw: HashMap<String, String> = conn.hgetall(key.as_ref()).await?;
+        let key = key.as_ref();
 
-                let mut map = HashMap::with_capacity(raw.len());
-                for (k, v) in raw {
-                    let parsed = serde_json::from_str(&v)?;
-                    map.insert(k, parsed);
-                }
-                Ok(map)
+        // 熔断中，直接调用loader
+        if self.is_tripped() {
+            return loader().await;
+        }
+        let cached = match self.redis.get_bytes(key).await {
+            Ok(v) => v,
+            Err(e) => {
+                stats::error(key);
+                self.read_failed(e, key)?;
+                return loader().await;
             }
-            Redis::Cluster(pool) => {
-                let mut conn = pool.get().await?;
-
-                let raw: HashMap<String, String> = conn.hgetall(key.as_ref()).await?;
+        };
+        self.read_succeeded();
+        if let Some(v) = cached {
+            stats::hit(key);
+            return Ok(Some(v));
+        }
 
-                let mut map = HashMap::with_capacity(raw.len());
-                for (k, v) in raw {
-                    let parsed = serde_json::from_str(&v)?;
-                    map.insert(k, parsed);
-                }
-                Ok(map)
+        let data = stats::load(key, loader()).await?;
+        if let Some(v) = &data {
+            if let Err(e) = self.set_bytes(key, v.clone(), ttl.map(jitter_ttl)).await {
+                stats::error(key);
+                tracing::error!(error = ?e, key = key, size = v.len(), "[cache::get_or_set_bytes] set data failed")
             }
         }
+        Ok(data)
     }
 
-    pub async fn hmget_map<K, T>(&self, key: K, fields: &[K]) -> anyhow::Result<HashMap<String, T>>
+    /// stale-while-revalidate：软过期后仍立即返回缓存值，并在后台刷新（同一 key 进程内只刷新一次），
+    /// 硬过期后同步调用 loader；适用于 loader 较慢的热点数据
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let pool = pool.clone();
+    /// let ret = redis
+    ///     .get_or_set_swr(
+    ///         "rank:top100",
+    ///         move || async move { load_rank(&pool).await },
+    ///         SwrTtl::new(Duration::from_secs(60), Duration::from_secs(3600)),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn get_or_set_swr<T, F, Fut>(
+        &self,
+        key: impl AsRef<str>,
+        loader: F,
+        ttl: SwrTtl,
+    ) -> anyhow::Result<Option<T>>
     where
-        K: AsRef<str> + Sync,
-        T: Serialize + DeserializeOwned,
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<Option<T>>> + Send + 'static,
     {
-        match self {
-            Redis::Single(pool) => {
-                let mut conn = pool.get().await?;
+        let key = key.as_ref();
 
-                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.hmget(key.as_ref(), field_vec).await?;
-
-                let mut map = HashMap::with_capacity(fields.len());
-                for (k, v) in fields.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), serde_json::from_str(&s)?);
-                    }
+        // 熔断中，直接调用loader
+        if self.is_tripped() {
+            return loader().await;
+        }
+        let ret_get = match self.redis.get_bytes(key).await {
+            Ok(v) => v,
+            Err(e) => {
+                stats::error(key);
+                self.read_failed(e, key)?;
+                return loader().await;
+            }
+        };
+        self.read_succeeded();
+
+        if let Some(v) = ret_get {
+            stats::hit(key);
+            let envelope: swr::Envelope<T> = serde_json::from_slice(&v)?;
+            if envelope.is_stale() {
+                if let Some(refresh) = swr::Refresh::try_start(key) {
+                    let redis = self.clone();
+                    let key = key.to_string();
+                    tokio::spawn(async move {
+                        let _refresh = refresh;
+                        if let Err(e) = redis.refresh_swr(&key, loader, ttl).await {
+                            tracing::error!(error = ?e, key = key, "[cache::get_or_set_swr] refresh failed")
+                        }
+                    });
                 }
-                Ok(map)
             }
-            Redis::Cluster(pool) => {
-                let mut conn = pool.get().await?;
-
-                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.hmget(key.as_ref(), field_vec).await?;
+            return Ok(Some(envelope.value));
+        }
 
-                let mut map = HashMap::with_capacity(fields.len());
-                for (k, v) in fields.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), serde_json::from_str(&s)?);
-                    }
-                }
-                Ok(map)
+        // 缓存未命中，调用loader获取数据
+        let data = stats::load(key, loader()).await?;
+        if let Some(v) = &data {
+            if let Err(e) = self.set_swr(key, v, ttl).await {
+                stats::error(key);
+                tracing::error!(error = ?e, key = key, "[cache::get_or_set_swr] set data failed")
             }
         }
+        Ok(data)
     }
 
-    pub async fn hmget_str_map<K>(
-        &self,
-        key: K,
-        fields: &[K],
-    ) -> anyhow::Result<HashMap<String, String>>
+    async fn refresh_swr<T, F, Fut>(&self, key: &str, loader: F, ttl: SwrTtl) -> anyhow::Result<()>
     where
-        K: AsRef<str> + Sync,
+        T: Serialize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<T>>>,
     {
-        match self {
-            Redis::Single(pool) => {
-                let mut conn = pool.get().await?;
-
-                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.hmget(key.as_ref(), field_vec).await?;
-
-                let mut map = HashMap::with_capacity(fields.len());
-                for (k, v) in fields.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), s);
-                    }
-                }
-                Ok(map)
-            }
-            Redis::Cluster(pool) => {
-                let mut conn = pool.get().await?;
-
-                let field_vec: Vec<&str> = fields.iter().map(|k| k.as_ref()).collect();
-                let raw: Vec<Option<String>> = conn.hmget(key.as_ref(), field_vec).await?;
-
-                let mut map = HashMap::with_capacity(fields.len());
-                for (k, v) in fields.iter().zip(raw.into_iter()) {
-                    if let Some(s) = v {
-                        map.insert(k.as_ref().to_string(), s);
-                    }
-                }
-                Ok(map)
-            }
+        match loader().await? {
+            Some(v) => self.set_swr(key, &v, ttl).await,
+            // 数据已不存在，删除缓存
+            None => self.redis.delete(key).await,
         }
     }
+
+    async fn set_swr<T: Serialize>(&self, key: &str, value: &T, ttl: SwrTtl) -> anyhow::Result<()> {
+        let data = serde_json::to_vec(&swr::Envelope::new(value, ttl.soft))?;
+        self.set_bytes(key, data, Some(ttl.hard)).await
+    }
 }
 
 #[cfg(test)]
@@ -384,9 +968,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_or_set() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let ret = Redis::Single(pool.clone())
             .get_or_set(
@@ -411,10 +993,51 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_hget_or_set() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
+    async fn test_get_or_set_swr() {
+        let redis = Redis::Single(redix::open::<redix::Mock>(vec![], None).await.unwrap());
+        // 软过期基于墙上时间（跨进程存储于 Redis），无法使用 tokio 的暂停时间
+        let ttl = SwrTtl::new(Duration::from_millis(200), Duration::from_secs(60));
+
+        let ret: Option<i64> = redis
+            .get_or_set_swr("test_swr", || async { Ok(Some(1)) }, ttl)
+            .await
+            .unwrap();
+        assert_eq!(ret, Some(1));
+        let ret: Option<i64> = redis
+            .get_or_set_swr("test_swr", || async { Ok(Some(2)) }, ttl)
+            .await
+            .unwrap();
+        assert_eq!(ret, Some(1));
+
+        // 软过期：返回旧值，后台刷新
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let ret: Option<i64> = redis
+            .get_or_set_swr("test_swr", || async { Ok(Some(3)) }, ttl)
             .await
             .unwrap();
+        assert_eq!(ret, Some(1));
+
+        // 等待后台刷新完成（同一 key 刷新中时不会重复刷新）
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let ret: Option<i64> = redis
+                    .get_or_set_swr("test_swr", || async { Ok(Some(4)) }, ttl)
+                    .await
+                    .unwrap();
+                if ret == Some(3) {
+                    break;
+                }
+                assert_eq!(ret, Some(1));
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("background refresh did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_hget_or_set() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let ret = Redis::Single(pool.clone())
             .hget_or_set(
@@ -441,9 +1064,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_mget_map() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let _: RedisResult<()> = pool
             .get()
@@ -472,9 +1093,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_mget_str_map() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let _: RedisResult<()> = pool
             .get()
@@ -503,9 +1122,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_hgetall() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let _: RedisResult<()> = pool
             .get()
@@ -529,9 +1146,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_hmget_map() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let _: RedisResult<()> = pool
             .get()
@@ -558,9 +1173,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_hmget_str_map() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         let _: RedisResult<()> = pool
             .get()
@@ -584,4 +1197,225 @@ mod tests {
 
         let _: RedisResult<()> = pool.get().await.unwrap().del("test").await;
     }
+
+    #[tokio::test]
+    async fn test_bytes() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let redis = Redis::Single(pool.clone());
+
+        let data: Vec<u8> = (0..=255u8).collect();
+        redis.set_bytes("bytes", data.clone(), None).await.unwrap();
+        assert_eq!(redis.get_bytes("bytes").await.unwrap(), Some(data.clone()));
+        assert_eq!(redis.get_bytes("none").await.unwrap(), None);
+
+        let ret = redis
+            .get_or_set_bytes("bytes", || async { Ok(Some(vec![1])) }, None)
+            .await
+            .unwrap();
+        assert_eq!(ret, Some(data));
+
+        // 压缩值
+        let blob = "hello world ".repeat(100).into_bytes();
+        let v = Compression::new(Algorithm::Lz4, 64)
+            .encode(blob.clone())
+            .unwrap();
+        let _: RedisResult<()> = pool.get().await.unwrap().set("blob", v).await;
+        assert_eq!(redis.get_bytes("blob").await.unwrap(), Some(blob));
+
+        let json = serde_json::to_vec(&Demo {
+            id: 1,
+            name: "x".repeat(200),
+        })
+        .unwrap();
+        let v = Compression::new(Algorithm::Zstd(3), 64)
+            .encode(json)
+            .unwrap();
+        let _: RedisResult<()> = pool.get().await.unwrap().set("demo", v).await;
+        let ret = redis
+            .get_or_set::<Demo, _, _>("demo", || async { Ok(None) }, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ret.name.len(), 200);
+
+        let _: RedisResult<()> = pool
+            .get()
+            .await
+            .unwrap()
+            .del(&["bytes", "blob", "demo"])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_compression() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let plain = Redis::Single(pool.clone());
+        let cache =
+            RedisCache::new(plain.clone()).compression(Compression::new(Algorithm::Lz4, 64));
+        let demo = || async {
+            Ok(Some(Demo {
+                id: 1,
+                name: "x".repeat(200),
+            }))
+        };
+
+        // 仅配置了压缩的实例写入压缩值，读取与配置无关
+        cache.get_or_set("compress:a", demo, None).await.unwrap();
+        plain.get_or_set("compress:b", demo, None).await.unwrap();
+        let mut conn = pool.get().await.unwrap();
+        let a: Vec<u8> = conn.get("compress:a").await.unwrap();
+        let b: Vec<u8> = conn.get("compress:b").await.unwrap();
+        assert!(codec::is_compressed(&a));
+        assert!(!codec::is_compressed(&b));
+        let ret: Option<Demo> = plain
+            .get_or_set("compress:a", || async { Ok(None) }, None)
+            .await
+            .unwrap();
+        assert_eq!(ret.unwrap().name.len(), 200);
+
+        // 以头部字节开头的原始二进制值
+        let raw = vec![0xFF, b'K', b'R', 1, 0, 0];
+        cache
+            .set_bytes("compress:raw", raw.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(plain.get_bytes("compress:raw").await.unwrap(), Some(raw));
+
+        let w = Writes::new()
+            .set(
+                "compress:c",
+                &Demo {
+                    id: 2,
+                    name: "y".repeat(200),
+                },
+                None,
+            )
+            .unwrap();
+        cache.write_atomic(w).await.unwrap();
+        let c: Vec<u8> = conn.get("compress:c").await.unwrap();
+        assert!(codec::is_compressed(&c));
+    }
+
+    #[tokio::test]
+    async fn test_fail_open() {
+        let pool = redix::open::<redix::Single>(
+            vec!["redis://127.0.0.1:1".to_string()],
+            Some(redix::Params {
+                conn_timeout: Some(Duration::from_millis(100)),
+                lazy: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        let redis = Redis::Single(pool);
+        let loader = || async {
+            Ok(Some(Demo {
+                id: 1,
+                name: "loader".to_string(),
+            }))
+        };
+
+        assert!(redis.get_or_set("demo", loader, None).await.is_err());
+
+        let cache = RedisCache::new(redis.clone()).fail_open(FailOpen {
+            trip_after: Some(2),
+            cooldown: Some(Duration::from_secs(60)),
+        });
+        let ret = cache.get_or_set("demo", loader, None).await.unwrap();
+        assert_eq!(ret.unwrap().name, "loader");
+        assert!(!cache.is_tripped());
+        let ret = cache.hget_or_set("demo", "1", loader, None).await.unwrap();
+        assert_eq!(ret.unwrap().id, 1);
+        assert!(cache.is_tripped());
+
+        // 熔断状态按实例独立，未开启 fail-open 的实例不受影响
+        assert!(redis.get_or_set("demo", loader, None).await.is_err());
+        assert!(!RedisCache::new(redis)
+            .fail_open(FailOpen::default())
+            .is_tripped());
+    }
+
+    #[tokio::test]
+    async fn test_fail_open_bytes() {
+        let pool = redix::open::<redix::Single>(
+            vec!["redis://127.0.0.1:1".to_string()],
+            Some(redix::Params {
+                conn_timeout: Some(Duration::from_millis(100)),
+                lazy: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        let loader = || async { Ok(Some(b"loader".to_vec())) };
+
+        let cache = RedisCache::new(Redis::Single(pool));
+        assert!(cache.get_or_set_bytes("bytes", loader, None).await.is_err());
+
+        let cache = cache.fail_open(FailOpen {
+            trip_after: Some(1),
+            cooldown: Some(Duration::from_secs(60)),
+        });
+        let ret = cache.get_or_set_bytes("bytes", loader, None).await.unwrap();
+        assert_eq!(ret.as_deref(), Some(&b"loader"[..]));
+        assert!(cache.is_tripped());
+
+        // 熔断中，直接调用loader
+        let ret = cache.get_or_set_bytes("bytes", loader, None).await.unwrap();
+        assert_eq!(ret.as_deref(), Some(&b"loader"[..]));
+    }
+
+    #[tokio::test]
+    async fn test_write_atomic() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let redis = Redis::Single(pool.clone());
+        let demo = Demo {
+            id: 1,
+            name: "atomic".to_string(),
+        };
+
+        let w = Writes::new()
+            .set("demo:{1}", &demo, Some(Duration::from_secs(60)))
+            .unwrap()
+            .sadd("demo:{1}:tags", "vip")
+            .sadd("tag:vip", 1)
+            .zadd("demo:recent", 1, 100);
+        redis.write_atomic(w).await.unwrap();
+
+        let ret: Option<Demo> = redis
+            .get_or_set("demo:{1}", || async { Ok(None) }, None)
+            .await
+            .unwrap();
+        assert_eq!(ret.unwrap().name, "atomic");
+        let mut conn = pool.get().await.unwrap();
+        let tags: Vec<String> = conn.smembers("demo:{1}:tags").await.unwrap();
+        assert_eq!(tags, vec!["vip"]);
+        let ids: Vec<i64> = conn.smembers("tag:vip").await.unwrap();
+        assert_eq!(ids, vec![1]);
+        let n: i64 = conn.zcard("demo:recent").await.unwrap();
+        assert_eq!(n, 1);
+
+        // 出错时删除已写入的缓存值
+        let _: () = conn.set("tag:svip", "x").await.unwrap();
+        let w = Writes::new()
+            .set("demo:{2}", &demo, None)
+            .unwrap()
+            .sadd("tag:svip", 2);
+        assert!(redis.write_atomic(w).await.is_err());
+        let exists: bool = conn.exists("demo:{2}").await.unwrap();
+        assert!(!exists);
+
+        redis.write_atomic(Writes::new()).await.unwrap();
+    }
+
+    #[test]
+    fn test_jitter_ttl() {
+        set_ttl_jitter(10);
+        for _ in 0..100 {
+            let d = jitter_ttl(Duration::from_secs(600)).as_secs();
+            assert!((540..=660).contains(&d));
+        }
+        assert_eq!(jitter_ttl(Duration::from_secs(5)), Duration::from_secs(5));
+    }
 }
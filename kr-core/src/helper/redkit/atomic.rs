@@ -0,0 +1,373 @@
+use std::{borrow::Cow, time::Duration};
+
+use redis::{aio::ConnectionLike, Cmd, RedisResult, ToRedisArgs, Value};
+use serde::Serialize;
+
+use super::{codec, key, Compression};
+
+/// 多 key 原子写入：主缓存 key 与其索引、标签 key 一起写入
+///
+/// - 单节点：所有命令在一个 MULTI/EXEC 事务中执行
+/// - 集群：按 slot 分组，每组一个 MULTI/EXEC 事务；跨多个 slot 时写入前对涉及的 key 做快照（DUMP），
+///   部分分组失败时将已执行分组的 key 还原（RESTORE，原本不存在的删除），各类写入均可补偿，
+///   避免索引指向过期数据。主 key 与索引 key 使用相同的 hash tag 时仍为单个事务，无需快照
+/// - 事务中的命令出错（如 WRONGTYPE）时 Redis 不会回滚，同样删除已写入的缓存值
+///
+/// # Examples
+///
+/// ```
+/// let id = user.id;
+/// let w = Writes::new()
+///     .set(Key::new("user").tag(id).build(), &user, Some(Duration::from_secs(600)))?
+///     .sadd(Key::new("user").tag(id).part("tags").build(), "vip")
+///     .sadd(Key::new("tag").part("vip").build(), id)
+///     .zadd(Key::new("user").part("recent").build(), id, user.updated_at);
+/// redis.write_atomic(w).await?;
+/// ```
+#[derive(Default)]
+pub struct Writes {
+    ops: Vec<Op>,
+}
+
+pub(crate) struct Op {
+    pub key: String,
+    pub cmd: Cmd,
+    // 缓存值（JSON, TTL 秒数）：写入时按实例的压缩配置编码，失败时需删除
+    pub value: Option<(Vec<u8>, Option<u64>)>,
+}
+
+impl Op {
+    fn build(&self, c: Option<&Compression>) -> anyhow::Result<Cmd> {
+        let Some((json, ttl)) = &self.value else {
+            return Ok(self.cmd.clone());
+        };
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(&self.key).arg(codec::encode_json(json.clone(), c)?);
+        if let Some(secs) = ttl {
+            cmd.arg("EX").arg(secs);
+        }
+        Ok(cmd)
+    }
+}
+
+impl Writes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(mut self, key: String, cmd: Cmd) -> Self {
+        self.ops.push(Op {
+            key,
+            cmd,
+            value: None,
+        });
+        self
+    }
+
+    /// 写入缓存值（与 `get_or_set` 相同的 JSON 编码）
+    pub fn set<T: Serialize>(
+        mut self,
+        key: impl Into<String>,
+        value: &T,
+        ttl: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        let key = normalized(key);
+        let value = serde_json::to_vec(value)?;
+        let ttl = ttl.map(|d| super::jitter_ttl(d).as_secs().max(1));
+        self.ops.push(Op {
+            key,
+            cmd: Cmd::new(),
+            value: Some((value, ttl)),
+        });
+        Ok(self)
+    }
+
+    pub fn del(self, key: impl Into<String>) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("DEL");
+        cmd.arg(&key);
+        self.push(key, cmd)
+    }
+
+    pub fn expire(self, key: impl Into<String>, ttl: Duration) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("EXPIRE");
+        cmd.arg(&key).arg(ttl.as_secs().max(1));
+        self.push(key, cmd)
+    }
+
+    pub fn hset(
+        self,
+        key: impl Into<String>,
+        field: impl ToRedisArgs,
+        value: impl ToRedisArgs,
+    ) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("HSET");
+        cmd.arg(&key).arg(field).arg(value);
+        self.push(key, cmd)
+    }
+
+    pub fn hdel(self, key: impl Into<String>, field: impl ToRedisArgs) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("HDEL");
+        cmd.arg(&key).arg(field);
+        self.push(key, cmd)
+    }
+
+    pub fn sadd(self, key: impl Into<String>, member: impl ToRedisArgs) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("SADD");
+        cmd.arg(&key).arg(member);
+        self.push(key, cmd)
+    }
+
+    pub fn srem(self, key: impl Into<String>, member: impl ToRedisArgs) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("SREM");
+        cmd.arg(&key).arg(member);
+        self.push(key, cmd)
+    }
+
+    pub fn zadd(
+        self,
+        key: impl Into<String>,
+        member: impl ToRedisArgs,
+        score: impl ToRedisArgs,
+    ) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("ZADD");
+        cmd.arg(&key).arg(score).arg(member);
+        self.push(key, cmd)
+    }
+
+    pub fn zrem(self, key: impl Into<String>, member: impl ToRedisArgs) -> Self {
+        let key = normalized(key);
+        let mut cmd = redis::cmd("ZREM");
+        cmd.arg(&key).arg(member);
+        self.push(key, cmd)
+    }
+
+    /// 缓存值的 key
+    pub(crate) fn values(&self) -> Vec<&str> {
+        self.ops
+            .iter()
+            .filter(|v| v.value.is_some())
+            .map(|v| v.key.as_str())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// 所有命令放入一个事务
+    pub(crate) fn pipeline(&self, c: Option<&Compression>) -> anyhow::Result<redis::Pipeline> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for op in &self.ops {
+            pipe.add_command(op.build(c)?).ignore();
+        }
+        Ok(pipe)
+    }
+
+    /// 按 slot 分组（保持首次出现的顺序及组内命令顺序），每组一个事务
+    pub(crate) fn slot_groups(
+        &self,
+        c: Option<&Compression>,
+    ) -> anyhow::Result<Vec<(u16, redis::Pipeline, Vec<&str>)>> {
+        let mut groups: Vec<(u16, redis::Pipeline, Vec<&str>)> = Vec::new();
+        for op in &self.ops {
+            let slot = key::slot(&op.key);
+            let idx = match groups.iter().position(|(s, ..)| *s == slot) {
+                Some(i) => i,
+                None => {
+                    let mut pipe = redis::pipe();
+                    pipe.atomic();
+                    groups.push((slot, pipe, Vec::new()));
+                    groups.len() - 1
+                }
+            };
+            let (_, pipe, values) = &mut groups[idx];
+            pipe.add_command(op.build(c)?).ignore();
+            if op.value.is_some() {
+                values.push(op.key.as_str());
+            }
+        }
+        Ok(groups)
+    }
+}
+
+// 集群跨 slot 写入：写入前快照涉及的 key，部分分组失败时还原已执行分组（含失败分组）的 key
+pub(crate) async fn write_slots<C: ConnectionLike + Send>(
+    conn: &mut C,
+    writes: &Writes,
+    c: Option<&Compression>,
+) -> anyhow::Result<()> {
+    let groups = writes.slot_groups(c)?;
+
+    // 单个 slot：一个事务，出错时删除已写入的缓存值（同单节点）
+    if let [(_, pipe, values)] = groups.as_slice() {
+        let ret: RedisResult<()> = pipe.query_async(conn).await;
+        let Err(e) = ret else {
+            return Ok(());
+        };
+        for key in values {
+            let ret: RedisResult<()> = redis::cmd("DEL").arg(key).query_async(conn).await;
+            if let Err(e) = ret {
+                tracing::error!(error = ?e, key = key, "[cache::write_atomic] rollback failed");
+            }
+        }
+        return Err(e.into());
+    }
+
+    let snapshots = snapshot(conn, writes).await?;
+    for (i, (slot, pipe, _)) in groups.iter().enumerate() {
+        let ret: RedisResult<()> = pipe.query_async(conn).await;
+        let Err(e) = ret else {
+            continue;
+        };
+        tracing::error!(error = ?e, slot = slot, "[cache::write_atomic] write slot failed");
+
+        // EXEC 中的命令出错时不会回滚，失败分组同样需要还原
+        let slots: Vec<u16> = groups[..=i].iter().map(|(s, ..)| *s).collect();
+        for (key, snap) in &snapshots {
+            if slots.contains(&key::slot(key)) {
+                restore(conn, key, snap).await;
+            }
+        }
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+// key => (DUMP, PTTL)
+type Snapshot = (Option<Vec<u8>>, i64);
+
+async fn snapshot<'a, C: ConnectionLike + Send>(
+    conn: &mut C,
+    writes: &'a Writes,
+) -> anyhow::Result<Vec<(&'a str, Snapshot)>> {
+    let mut keys: Vec<&str> = Vec::new();
+    for op in &writes.ops {
+        if !keys.contains(&op.key.as_str()) {
+            keys.push(&op.key);
+        }
+    }
+
+    let mut pipe = redis::pipe();
+    for key in &keys {
+        pipe.cmd("DUMP").arg(key).cmd("PTTL").arg(key);
+    }
+    let ret: Vec<Value> = pipe.query_async(conn).await?;
+    let mut out = Vec::with_capacity(keys.len());
+    for (key, v) in keys.into_iter().zip(ret.chunks(2)) {
+        let dump: Option<Vec<u8>> = redis::from_redis_value(&v[0])?;
+        let pttl: i64 = redis::from_redis_value(&v[1])?;
+        out.push((key, (dump, pttl)));
+    }
+    Ok(out)
+}
+
+async fn restore<C: ConnectionLike + Send>(conn: &mut C, key: &str, snap: &Snapshot) {
+    let cmd = match snap {
+        (Some(dump), pttl) => {
+            let mut cmd = redis::cmd("RESTORE");
+            cmd.arg(key).arg((*pttl).max(0)).arg(dump).arg("REPLACE");
+            cmd
+        }
+        (None, _) => {
+            let mut cmd = redis::cmd("DEL");
+            cmd.arg(key);
+            cmd
+        }
+    };
+    let ret: RedisResult<()> = cmd.query_async(conn).await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, key = key, "[cache::write_atomic] rollback failed");
+    }
+}
+
+// 按 `key::set_max_len` 规范化
+fn normalized(key: impl Into<String>) -> String {
+    let key = key.into();
+    match key::normalize(&key) {
+        Cow::Borrowed(_) => key,
+        Cow::Owned(v) => v,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::AsyncCommands;
+
+    use crate::{
+        helper::redkit::{
+            atomic::{self, Writes},
+            key,
+        },
+        redix,
+    };
+
+    #[test]
+    fn test_slot_groups() {
+        let w = Writes::new()
+            .set("user:{1}", &1, None)
+            .unwrap()
+            .sadd("user:{1}:tags", "vip")
+            .sadd("tag:vip", 1)
+            .set("user:{2}", &2, None)
+            .unwrap()
+            .zadd("user:{1}:recent", 1, 100);
+        let groups = w.slot_groups(None).unwrap();
+
+        // user:{1} 相关的 key 位于同一分组
+        assert_eq!(groups[0].0, key::slot("1"));
+        assert_eq!(groups[0].1.cmd_iter().count(), 3);
+        assert_eq!(groups[0].2, vec!["user:{1}"]);
+        let n: usize = groups.iter().map(|(_, p, _)| p.cmd_iter().count()).sum();
+        assert_eq!(n, 5);
+        assert_eq!(w.pipeline(None).unwrap().cmd_iter().count(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_write_slots() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let mut conn = pool.get().await.unwrap();
+
+        let _: () = conn.sadd("slots:tags", "old").await.unwrap();
+        let _: () = conn.zadd("slots:rank", "a", 1).await.unwrap();
+        let _: () = conn.hset("slots:meta", "v", "1").await.unwrap();
+        let _: () = conn.set("slots:bad", "x").await.unwrap();
+
+        // 跨 slot，最后一个分组出错（WRONGTYPE），已执行的分组全部还原
+        let w = Writes::new()
+            .set("slots:value", &1, None)
+            .unwrap()
+            .srem("slots:tags", "old")
+            .sadd("slots:tags", "new")
+            .zrem("slots:rank", "a")
+            .hdel("slots:meta", "v")
+            .sadd("slots:bad", 1);
+        assert!(w.slot_groups(None).unwrap().len() > 1);
+        assert!(atomic::write_slots(&mut *conn, &w, None).await.is_err());
+
+        let exists: bool = conn.exists("slots:value").await.unwrap();
+        assert!(!exists);
+        let tags: Vec<String> = conn.smembers("slots:tags").await.unwrap();
+        assert_eq!(tags, vec!["old"]);
+        let score: Option<f64> = conn.zscore("slots:rank", "a").await.unwrap();
+        assert_eq!(score, Some(1.0));
+        let v: Option<String> = conn.hget("slots:meta", "v").await.unwrap();
+        assert_eq!(v.as_deref(), Some("1"));
+
+        // 成功时不还原
+        let w = Writes::new()
+            .set("slots:value", &1, None)
+            .unwrap()
+            .sadd("slots:tags", "new");
+        atomic::write_slots(&mut *conn, &w, None).await.unwrap();
+        let n: i64 = conn.scard("slots:tags").await.unwrap();
+        assert_eq!(n, 2);
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::anyhow;
+
+// 值头部：0xFF 不会出现在 UTF-8/JSON 开头，未加头部的 JSON 值可原样读取
+const MAGIC: [u8; 3] = [0xFF, b'K', b'R'];
+const HEADER_SIZE: usize = 4;
+
+// 未压缩的二进制值
+const FLAG_RAW: u8 = 0;
+const FLAG_ZSTD: u8 = 1;
+const FLAG_LZ4: u8 = 2;
+
+/// 压缩算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// 压缩级别 1-22，默认 3
+    Zstd(i32),
+    Lz4,
+}
+
+/// 值压缩：超过阈值的值压缩后写入，读取时按头部标记自动解压
+///
+/// 在 [`RedisCache`](super::RedisCache) 实例上配置
+#[derive(Debug, Clone, Copy)]
+pub struct Compression {
+    pub algorithm: Algorithm,
+    /// 超过该字节数时压缩
+    pub threshold: usize,
+}
+
+impl Compression {
+    pub fn new(algorithm: Algorithm, threshold: usize) -> Self {
+        Self {
+            algorithm,
+            threshold,
+        }
+    }
+
+    /// 超过阈值且压缩后更小时压缩（加头部），否则原样返回；用于 JSON 值
+    pub fn encode(&self, data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        match self.compress(&data)? {
+            Some((flag, body)) => Ok(frame(flag, &body)),
+            None => Ok(data),
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> anyhow::Result<Option<(u8, Vec<u8>)>> {
+        if data.len() < self.threshold {
+            return Ok(None);
+        }
+        let (flag, body) = match self.algorithm {
+            Algorithm::Zstd(level) => (FLAG_ZSTD, zstd::bulk::compress(data, level)?),
+            Algorithm::Lz4 => (FLAG_LZ4, lz4_flex::compress_prepend_size(data)),
+        };
+        if body.len() + HEADER_SIZE >= data.len() {
+            return Ok(None);
+        }
+        Ok(Some((flag, body)))
+    }
+}
+
+fn frame(flag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_SIZE + body.len());
+    out.extend_from_slice(&MAGIC);
+    out.push(flag);
+    out.extend_from_slice(body);
+    out
+}
+
+/// 编码 JSON 值：需要压缩时压缩，否则原样返回（JSON 不以 0xFF 开头，读取时不会误判）
+pub fn encode_json(data: Vec<u8>, c: Option<&Compression>) -> anyhow::Result<Vec<u8>> {
+    match c {
+        Some(c) => c.encode(data),
+        None => Ok(data),
+    }
+}
+
+/// 编码二进制值：总是加头部（未压缩时标记为原始值），以头部字节开头的原始值也能正确读取
+pub fn encode_bytes(data: Vec<u8>, c: Option<&Compression>) -> anyhow::Result<Vec<u8>> {
+    if let Some(c) = c {
+        if let Some((flag, body)) = c.compress(&data)? {
+            return Ok(frame(flag, &body));
+        }
+    }
+    Ok(frame(FLAG_RAW, &data))
+}
+
+/// 按头部标记解码，无头部的值原样返回（与是否设置压缩无关）
+pub fn decode(data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    if data.len() < HEADER_SIZE || data[..MAGIC.len()] != MAGIC {
+        return Ok(data);
+    }
+    let body = &data[HEADER_SIZE..];
+    match data[MAGIC.len()] {
+        FLAG_RAW => Ok(body.to_vec()),
+        FLAG_ZSTD => Ok(zstd::stream::decode_all(body)?),
+        FLAG_LZ4 => lz4_flex::decompress_size_prepended(body)
+            .map_err(|e| anyhow!("redkit/codec: lz4 decompress failed: {}", e)),
+        v => Err(anyhow!("redkit/codec: unknown compression flag {}", v)),
+    }
+}
+
+/// 是否为压缩值
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.len() >= HEADER_SIZE && data[..MAGIC.len()] == MAGIC && data[MAGIC.len()] != FLAG_RAW
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helper::redkit::codec::{self, Algorithm, Compression};
+
+    #[test]
+    fn test_codec() {
+        let data = "hello world ".repeat(100).into_bytes();
+        for algo in [Algorithm::Zstd(3), Algorithm::Lz4] {
+            let c = Compression::new(algo, 64);
+            let v = c.encode(data.clone()).unwrap();
+            assert!(codec::is_compressed(&v));
+            assert!(v.len() < data.len());
+            assert_eq!(codec::decode(v).unwrap(), data);
+
+            // 未超过阈值
+            let v = c.encode(b"short".to_vec()).unwrap();
+            assert_eq!(v, b"short");
+        }
+
+        // 压缩后不更小时不压缩
+        let c = Compression::new(Algorithm::Lz4, 0);
+        let v = c.encode(vec![1, 2, 3]).unwrap();
+        assert_eq!(v, vec![1, 2, 3]);
+
+        assert_eq!(codec::decode(b"{\"a\":1}".to_vec()).unwrap(), b"{\"a\":1}");
+        assert!(codec::decode(vec![0xFF, b'K', b'R', 9, 0]).is_err());
+    }
+
+    #[test]
+    fn test_codec_bytes() {
+        // 以头部字节开头的原始二进制值
+        let raw = vec![0xFF, b'K', b'R', 1, 2, 3];
+        for c in [None, Some(Compression::new(Algorithm::Lz4, 1024))] {
+            let v = codec::encode_bytes(raw.clone(), c.as_ref()).unwrap();
+            assert!(!codec::is_compressed(&v));
+            assert_eq!(codec::decode(v).unwrap(), raw);
+        }
+
+        let data = "hello world ".repeat(100).into_bytes();
+        let c = Compression::new(Algorithm::Zstd(3), 64);
+        let v = codec::encode_bytes(data.clone(), Some(&c)).unwrap();
+        assert!(codec::is_compressed(&v));
+        assert_eq!(codec::decode(v).unwrap(), data);
+
+        assert_eq!(codec::encode_json(b"{}".to_vec(), None).unwrap(), b"{}");
+    }
+}
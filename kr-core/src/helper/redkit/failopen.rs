@@ -0,0 +1,123 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// Redis 不可用时降级：`get_or_set`、`hget_or_set` 读取失败时记录日志并直接调用 loader（跳过写入）
+///
+/// 在 [`RedisCache`](super::RedisCache) 实例上配置，熔断状态按实例独立
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailOpen {
+    /// 连续失败达到该次数时熔断，熔断期间不再访问 Redis，直接调用 loader（None 不熔断）
+    pub trip_after: Option<u32>,
+    /// 熔断持续时间，默认：10s
+    pub cooldown: Option<Duration>,
+}
+
+// 实例的 fail-open 配置与熔断器
+#[derive(Clone)]
+pub(crate) struct Guard {
+    opts: FailOpen,
+    breaker: Arc<Breaker>,
+}
+
+impl Guard {
+    pub(crate) fn new(opts: FailOpen) -> Self {
+        Self {
+            opts,
+            breaker: Arc::new(Breaker::new()),
+        }
+    }
+
+    pub(crate) fn is_tripped(&self) -> bool {
+        self.breaker.is_open()
+    }
+
+    // 读取失败：记录日志，连续失败达到阈值时熔断
+    pub(crate) fn read_failed(&self, err: anyhow::Error, key: &str) {
+        tracing::warn!(error = ?err, key = key, "[redkit::fail_open] redis read failed, call loader directly");
+        if let Some(n) = self.opts.trip_after {
+            let cooldown = self.opts.cooldown.unwrap_or(Duration::from_secs(10));
+            if self.breaker.fail(n, cooldown) {
+                tracing::error!(
+                    failures = n,
+                    cooldown_ms = cooldown.as_millis(),
+                    "[redkit::fail_open] circuit breaker tripped"
+                );
+            }
+        }
+    }
+
+    pub(crate) fn read_succeeded(&self) {
+        self.breaker.succeed();
+    }
+}
+
+// 连续失败计数熔断器
+struct Breaker {
+    failures: AtomicU32,
+    // 熔断结束时间：相对 BASE 的毫秒数
+    open_until: AtomicU64,
+}
+
+impl Breaker {
+    const fn new() -> Self {
+        Self {
+            failures: AtomicU32::new(0),
+            open_until: AtomicU64::new(0),
+        }
+    }
+
+    fn now_ms() -> u64 {
+        static BASE: OnceLock<Instant> = OnceLock::new();
+        BASE.get_or_init(Instant::now).elapsed().as_millis() as u64
+    }
+
+    fn is_open(&self) -> bool {
+        Self::now_ms() < self.open_until.load(Ordering::Relaxed)
+    }
+
+    // 记录失败，返回是否触发熔断
+    fn fail(&self, trip_after: u32, cooldown: Duration) -> bool {
+        let n = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if n < trip_after.max(1) {
+            return false;
+        }
+        self.failures.store(0, Ordering::Relaxed);
+        self.open_until.store(
+            Self::now_ms() + cooldown.as_millis() as u64,
+            Ordering::Relaxed,
+        );
+        true
+    }
+
+    fn succeed(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::helper::redkit::failopen::Breaker;
+
+    #[test]
+    fn test_breaker() {
+        let b = Breaker::new();
+        assert!(!b.fail(3, Duration::from_millis(50)));
+        assert!(!b.fail(3, Duration::from_millis(50)));
+        b.succeed();
+        assert!(!b.fail(3, Duration::from_millis(50)));
+        assert!(!b.fail(3, Duration::from_millis(50)));
+        assert!(!b.is_open());
+        assert!(b.fail(3, Duration::from_millis(50)));
+        assert!(b.is_open());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!b.is_open());
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
+use crate::crypto::hash;
+
+pub(super) static PREFIX: OnceLock<String> = OnceLock::new();
+
+static MAX_LEN: OnceLock<usize> = OnceLock::new();
+
+// 摘要 key => 原始 key 的指纹，用于发现摘要冲突
+static DIGESTS: OnceLock<Mutex<HashMap<String, u32>>> = OnceLock::new();
+static COLLISIONS: AtomicU64 = AtomicU64::new(0);
+
+// 冲突检测最多记录的摘要 key 数量
+const MAX_DIGESTS: usize = 100_000;
+
+/// 设置 key 的全局前缀（通常为应用名），内置模块（saga、queue、counterkit、registry 等）的 key 同样使用该前缀
+///
+/// # Examples
+///
+/// ```
+/// key::set_prefix("shop");
+///
+/// // shop:user:profile:1
+/// let k = Key::new("user").part("profile").part(1).build();
+/// ```
+pub fn set_prefix(prefix: impl AsRef<str>) {
+    let _ = PREFIX.set(sanitize(prefix.as_ref()));
+}
+
+/// 加上全局前缀（未设置时原样返回）
+pub fn with_prefix(key: impl AsRef<str>) -> String {
+    match PREFIX.get() {
+        Some(v) => format!("{}:{}", v, key.as_ref()),
+        None => key.as_ref().to_string(),
+    }
+}
+
+/// 设置 key 的最大长度（字节，最小 64），超过时缓存层（`Redis` 的读写方法、`Writes`）自动转为摘要形式：
+/// `<前缀>:<命名空间>:[{tag}:]sha1:<摘要>`，保留命名空间（统计）与 hash tag（集群 slot）
+///
+/// 用户输入（搜索词、URL 等）作为 key 时，避免超长 key 以及隐私数据出现在 key 中
+///
+/// # Examples
+///
+/// ```
+/// key::set_max_len(200);
+///
+/// // shop:search:sha1:2fd4e1c67a2d28fced849ee1bb76e7391b93eb12
+/// let v = redis.get_or_set(Key::new("search").part(&query).build(), loader, ttl).await?;
+/// ```
+pub fn set_max_len(n: usize) {
+    let _ = MAX_LEN.set(n.max(64));
+}
+
+/// 按 `set_max_len` 规范化 key（未设置或未超长时原样返回）
+pub fn normalize(key: &str) -> Cow<'_, str> {
+    match MAX_LEN.get() {
+        Some(max) if key.len() > *max => {
+            let v = digest_key(key);
+            track_digest(&v, key);
+            Cow::Owned(v)
+        }
+        _ => Cow::Borrowed(key),
+    }
+}
+
+/// 摘要冲突次数（不同的原始 key 得到相同的摘要 key）
+pub fn collisions() -> u64 {
+    COLLISIONS.load(Ordering::Relaxed)
+}
+
+// <前缀>:<命名空间>:[{tag}:]sha1:<摘要>
+fn digest_key(key: &str) -> String {
+    let (prefix, rest) = match PREFIX.get() {
+        Some(p) => match key
+            .strip_prefix(p.as_str())
+            .and_then(|v| v.strip_prefix(':'))
+        {
+            Some(rest) => (Some(p.as_str()), rest),
+            None => (None, key),
+        },
+        None => (None, key),
+    };
+
+    let mut parts: Vec<&str> = Vec::with_capacity(4);
+    parts.extend(prefix);
+    // 命名空间过长时同样视为用户数据
+    match rest.split_once(':') {
+        Some((ns, _)) if ns.len() <= 32 && !ns.contains(['{', '}']) => parts.push(ns),
+        _ => {}
+    }
+    let tag = hash_tag_of(key.as_bytes())
+        .and_then(|v| std::str::from_utf8(v).ok())
+        .map(|v| format!("{{{}}}", v));
+    let digest = format!("sha1:{}", hash::sha1::<String>(key));
+    let mut v = parts.join(":");
+    for p in tag.iter().chain([&digest]) {
+        if !v.is_empty() {
+            v.push(':');
+        }
+        v.push_str(p);
+    }
+    v
+}
+
+fn track_digest(digest: &str, key: &str) {
+    let fingerprint = hash::murmur3_32(key, 0);
+    let mut digests = DIGESTS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap();
+    match digests.get(digest) {
+        Some(v) if *v != fingerprint => {
+            COLLISIONS.fetch_add(1, Ordering::Relaxed);
+            // 不记录原始 key，避免隐私数据进入日志
+            tracing::error!(key = digest, "[redkit::key] digest collision");
+        }
+        Some(_) => {}
+        None if digests.len() < MAX_DIGESTS => {
+            digests.insert(digest.to_string(), fingerprint);
+        }
+        None => {}
+    }
+}
+
+/// 分隔符 `:` 及 hash tag 字符 `{`、`}` 替换为 `_`，避免 key 冲突与误用 slot
+pub fn sanitize(v: impl Display) -> String {
+    v.to_string().replace([':', '{', '}'], "_")
+}
+
+/// hash tag：集群模式下 tag 相同的 key 位于同一 slot
+pub fn hash_tag(v: impl Display) -> String {
+    format!("{{{}}}", sanitize(v))
+}
+
+/// 集群模式下 key 所在的 slot（CRC16 % 16384，含 hash tag 时仅计算 tag 部分）
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(key::slot("order:{10086}:items"), key::slot("order:{10086}:amount"));
+/// ```
+pub fn slot(key: impl AsRef<[u8]>) -> u16 {
+    let key = key.as_ref();
+    crc16(hash_tag_of(key).unwrap_or(key)) % 16384
+}
+
+// 第一个 `{` 之后到其后第一个 `}` 之间的非空内容
+fn hash_tag_of(key: &[u8]) -> Option<&[u8]> {
+    let start = key.iter().position(|v| *v == b'{')?;
+    match key[start + 1..].iter().position(|v| *v == b'}') {
+        Some(len) if len > 0 => Some(&key[start + 1..start + 1 + len]),
+        _ => None,
+    }
+}
+
+// CRC16-XMODEM
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for v in data {
+        crc ^= (*v as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// 命名空间 key 构造器：`<prefix>:<module>:<part>...`
+///
+/// # Examples
+///
+/// ```
+/// // order:{10086}:items
+/// let k = Key::new("order").tag(10086).part("items").build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Key {
+    parts: Vec<String>,
+    tagged: bool,
+}
+
+impl Key {
+    pub fn new(module: impl Display) -> Self {
+        Self {
+            parts: vec![sanitize(module)],
+            tagged: false,
+        }
+    }
+
+    pub fn part(mut self, v: impl Display) -> Self {
+        self.parts.push(sanitize(v));
+        self
+    }
+
+    /// 作为 hash tag 的部分（仅第一个生效，重复调用按普通部分处理）
+    pub fn tag(mut self, v: impl Display) -> Self {
+        if self.tagged {
+            return self.part(v);
+        }
+        self.tagged = true;
+        self.parts.push(hash_tag(v));
+        self
+    }
+
+    /// 以 SHA1 摘要作为部分（用户输入等不宜直接出现在 key 中的数据）
+    pub fn hashed(mut self, v: impl AsRef<[u8]>) -> Self {
+        self.parts.push(hash::sha1::<String>(v));
+        self
+    }
+
+    pub fn build(self) -> String {
+        with_prefix(self.parts.join(":"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helper::redkit::key::{self, Key};
+
+    #[test]
+    fn test_key() {
+        assert_eq!(
+            Key::new("user").part("profile").part(1).build(),
+            "user:profile:1"
+        );
+        assert_eq!(
+            Key::new("order").tag(10086).part("items").tag(2).build(),
+            "order:{10086}:items:2"
+        );
+        // 不允许注入分隔符和 hash tag
+        assert_eq!(Key::new("user").part("1:admin").build(), "user:1_admin");
+        assert_eq!(key::hash_tag("a{b}"), "{a_b_}");
+        assert_eq!(
+            Key::new("search").hashed("abc").build(),
+            "search:a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+    }
+
+    #[test]
+    fn test_digest_key() {
+        let long = "x".repeat(300);
+        let k = key::digest_key(&format!("search:{}", long));
+        assert!(k.starts_with("search:sha1:"));
+        assert_eq!(k.len(), "search:sha1:".len() + 40);
+
+        // 保留 hash tag，slot 不变
+        let raw = format!("order:{{10086}}:q:{}", long);
+        let k = key::digest_key(&raw);
+        assert!(k.starts_with("order:{10086}:sha1:"));
+        assert_eq!(key::slot(&k), key::slot(&raw));
+
+        // 命名空间过长时不保留
+        assert!(key::digest_key(&long).starts_with("sha1:"));
+
+        key::track_digest("t:sha1:1", "a");
+        key::track_digest("t:sha1:1", "a");
+        assert_eq!(key::collisions(), 0);
+        key::track_digest("t:sha1:1", "b");
+        assert_eq!(key::collisions(), 1);
+    }
+
+    #[test]
+    fn test_slot() {
+        assert_eq!(key::slot("123456789"), 0x31C3);
+        assert_eq!(key::slot("foo"), 12182);
+        assert_eq!(
+            key::slot("{user1000}.following"),
+            key::slot("{user1000}.followers")
+        );
+        assert_ne!(key::slot("foo{}{bar}"), key::slot("bar"));
+        assert_eq!(key::slot("foo{{bar}}zap"), key::slot("{bar"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use super::key::PREFIX;
+
+// 命名空间数量上限，超过后计入 `OTHER`
+const MAX_NAMESPACES: usize = 1000;
+
+/// 超过命名空间数量上限后的统计归属
+pub const OTHER: &str = "_other";
+
+static STATS: OnceLock<RwLock<HashMap<String, Arc<Counters>>>> = OnceLock::new();
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    errors: AtomicU64,
+    load_nanos: AtomicU64,
+}
+
+/// 缓存统计快照（`get_or_set`、`hget_or_set`、`get_or_set_bytes`、`get_or_set_swr`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数（即调用 loader 的次数）
+    pub misses: u64,
+    /// Redis 读写失败次数
+    pub errors: u64,
+    /// loader 累计耗时
+    pub load_time: Duration,
+}
+
+impl CacheStats {
+    /// 命中率
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+
+    /// loader 平均耗时
+    pub fn avg_load_time(&self) -> Duration {
+        if self.misses == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos((self.load_time.as_nanos() / self.misses as u128) as u64)
+    }
+}
+
+/// 按命名空间的统计快照
+///
+/// 命名空间为去掉全局前缀后 key 的第一段（`shop:user:profile:1` => `user`）
+///
+/// # Examples
+///
+/// ```
+/// for (ns, v) in redkit::stats::snapshot() {
+///     tracing::info!(ns = ns, hits = v.hits, misses = v.misses, hit_rate = v.hit_rate(), "cache stats");
+/// }
+/// ```
+pub fn snapshot() -> HashMap<String, CacheStats> {
+    stats()
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| {
+            let s = CacheStats {
+                hits: v.hits.load(Ordering::Relaxed),
+                misses: v.misses.load(Ordering::Relaxed),
+                errors: v.errors.load(Ordering::Relaxed),
+                load_time: Duration::from_nanos(v.load_nanos.load(Ordering::Relaxed)),
+            };
+            (k.clone(), s)
+        })
+        .collect()
+}
+
+/// 清空统计
+pub fn reset() {
+    stats().write().unwrap().clear();
+}
+
+pub(crate) fn hit(key: &str) {
+    counters(key).hits.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn error(key: &str) {
+    counters(key).errors.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 未命中：调用 loader 并记录耗时
+pub(crate) async fn load<T, Fut>(key: &str, fut: Fut) -> anyhow::Result<T>
+where
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let start = Instant::now();
+    let ret = fut.await;
+    let c = counters(key);
+    c.misses.fetch_add(1, Ordering::Relaxed);
+    c.load_nanos
+        .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    ret
+}
+
+fn stats() -> &'static RwLock<HashMap<String, Arc<Counters>>> {
+    STATS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+fn counters(key: &str) -> Arc<Counters> {
+    let ns = namespace(key);
+    if let Some(v) = stats().read().unwrap().get(ns) {
+        return v.clone();
+    }
+
+    let mut map = stats().write().unwrap();
+    let ns = if map.len() >= MAX_NAMESPACES && !map.contains_key(ns) {
+        OTHER
+    } else {
+        ns
+    };
+    map.entry(ns.to_string()).or_default().clone()
+}
+
+fn namespace(key: &str) -> &str {
+    let key = match PREFIX.get() {
+        Some(p) => key
+            .strip_prefix(p.as_str())
+            .and_then(|v| v.strip_prefix(':'))
+            .unwrap_or(key),
+        None => key,
+    };
+    key.split(':').next().unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::helper::redkit::stats;
+
+    #[tokio::test]
+    async fn test_stats() {
+        assert_eq!(stats::namespace("stats_ns:profile:1"), "stats_ns");
+        assert_eq!(stats::namespace("plain"), "plain");
+
+        stats::hit("stats_ns:1");
+        stats::hit("stats_ns:2");
+        stats::error("stats_ns:2");
+        let v: i32 = stats::load("stats_ns:3", async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            Ok(1)
+        })
+        .await
+        .unwrap();
+        assert_eq!(v, 1);
+
+        let s = stats::snapshot()["stats_ns"];
+        assert_eq!((s.hits, s.misses, s.errors), (2, 1, 1));
+        assert!(s.load_time >= Duration::from_millis(5));
+        assert!(s.avg_load_time() >= Duration::from_millis(5));
+        assert!((s.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats::CacheStats::default().hit_rate(), 0.0);
+
+        // 未命中次数超过 u32 范围
+        let s = stats::CacheStats {
+            misses: u32::MAX as u64 + 1,
+            load_time: Duration::from_secs(u32::MAX as u64 + 1),
+            ..Default::default()
+        };
+        assert_eq!(s.avg_load_time(), Duration::from_secs(1));
+    }
+}
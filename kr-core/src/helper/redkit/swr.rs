@@ -0,0 +1,91 @@
+use std::{
+    collections::HashSet,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+// 正在后台刷新的 key（进程内去重）
+static INFLIGHT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn inflight() -> &'static Mutex<HashSet<String>> {
+    INFLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// SWR 缓存的 TTL
+#[derive(Debug, Clone, Copy)]
+pub struct SwrTtl {
+    /// 软过期：超过后仍返回缓存值，同时在后台刷新
+    pub soft: Duration,
+    /// 硬过期：Redis key 的过期时间，超过后同步调用 loader（不小于 soft）
+    pub hard: Duration,
+}
+
+impl SwrTtl {
+    pub fn new(soft: Duration, hard: Duration) -> Self {
+        Self {
+            soft,
+            hard: hard.max(soft),
+        }
+    }
+}
+
+/// 缓存值：数据 + 软过期时间（Unix 毫秒）
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Envelope<T> {
+    #[serde(rename = "v")]
+    pub value: T,
+    #[serde(rename = "s")]
+    pub stale_at: i64,
+}
+
+impl<T> Envelope<T> {
+    pub fn new(value: T, soft: Duration) -> Self {
+        Self {
+            value,
+            stale_at: now_ms() + soft.as_millis() as i64,
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        now_ms() >= self.stale_at
+    }
+}
+
+fn now_ms() -> i64 {
+    (time::OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+/// 后台刷新占位，离开作用域时释放
+pub(crate) struct Refresh(String);
+
+impl Refresh {
+    /// 同一 key 已在刷新中时返回 None
+    pub fn try_start(key: &str) -> Option<Self> {
+        if inflight().lock().unwrap().insert(key.to_string()) {
+            return Some(Self(key.to_string()));
+        }
+        None
+    }
+}
+
+impl Drop for Refresh {
+    fn drop(&mut self) {
+        inflight().lock().unwrap().remove(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helper::redkit::swr::Refresh;
+
+    #[test]
+    fn test_refresh() {
+        let r = Refresh::try_start("test_refresh");
+        assert!(r.is_some());
+        assert!(Refresh::try_start("test_refresh").is_none());
+        drop(r);
+        assert!(Refresh::try_start("test_refresh").is_some());
+    }
+}
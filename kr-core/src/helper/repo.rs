@@ -0,0 +1,312 @@
+use std::{
+    collections::HashMap, fmt::Display, future::Future, hash::Hash, marker::PhantomData,
+    time::Duration,
+};
+
+use redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::helper::redkit::{jitter_ttl, key, Redis, RedisCache};
+
+/// 可按主键缓存的实体
+///
+/// # Examples
+///
+/// ```
+/// impl Entity for model::User {
+///     type Id = i64;
+///     const NAME: &'static str = "user";
+///
+///     fn id(&self) -> i64 {
+///         self.id
+///     }
+/// }
+/// ```
+pub trait Entity: Serialize + DeserializeOwned + Send + 'static {
+    type Id: Display + Eq + Hash + Clone + Send + Sync;
+
+    /// 缓存命名空间（通常为表名）
+    const NAME: &'static str;
+
+    fn id(&self) -> Self::Id;
+}
+
+/// 按主键读穿透缓存（缓存 key：`[<前缀>:]repo:<NAME>:<id>`，前缀见 `key::set_prefix`）
+///
+/// # Examples
+///
+/// ```
+/// let repo = CachedRepo::<model::User>::new(redis, Some(Duration::from_secs(600)));
+///
+/// // 使用实例级配置（如值压缩）
+/// let repo = CachedRepo::<model::User>::new(
+///     RedisCache::new(redis).compression(Compression::new(Algorithm::Zstd(3), 4096)),
+///     Some(Duration::from_secs(600)),
+/// );
+///
+/// let user = repo
+///     .get(1, || async {
+///         let stmt = Query::select()
+///             .from(table::User::Table)
+///             .expr(Expr::cust("*"))
+///             .and_where(Expr::col(table::User::Id).eq(1))
+///             .to_owned();
+///         mysql::find_one::<model::User>(&pool, stmt).await
+///     })
+///     .await?;
+///
+/// let users = repo
+///     .get_many(&[1, 2, 3], |ids| async move {
+///         let stmt = Query::select()
+///             .from(table::User::Table)
+///             .expr(Expr::cust("*"))
+///             .and_where(Expr::col(table::User::Id).is_in(ids))
+///             .to_owned();
+///         mysql::find_all::<model::User>(&pool, stmt).await
+///     })
+///     .await?;
+///
+/// // 更新/删除后
+/// repo.invalidate(&1).await?;
+/// ```
+pub struct CachedRepo<T: Entity> {
+    redis: RedisCache,
+    ttl: Option<Duration>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Entity> Clone for CachedRepo<T> {
+    fn clone(&self) -> Self {
+        Self {
+            redis: self.redis.clone(),
+            ttl: self.ttl,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Entity> CachedRepo<T> {
+    pub fn new(redis: impl Into<RedisCache>, ttl: Option<Duration>) -> Self {
+        Self {
+            redis: redis.into(),
+            ttl,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 缓存 key
+    pub fn key(id: &T::Id) -> String {
+        key::with_prefix(format!("repo:{}:{}", T::NAME, id))
+    }
+
+    /// 获取单个实体，缓存未命中时调用 loader 并写入缓存
+    pub async fn get<F, Fut>(&self, id: T::Id, loader: F) -> anyhow::Result<Option<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<T>>>,
+    {
+        self.redis
+            .get_or_set(Self::key(&id), loader, self.ttl)
+            .await
+    }
+
+    /// 批量获取（按 ids 顺序返回，不存在的忽略），未命中的 id 一次性交由 loader 查询并回填缓存
+    pub async fn get_many<F, Fut>(&self, ids: &[T::Id], loader: F) -> anyhow::Result<Vec<T>>
+    where
+        F: FnOnce(Vec<T::Id>) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<T>>>,
+    {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<String> = ids.iter().map(Self::key).collect();
+        let mut cached: HashMap<String, T> = self.redis.redis().mget_map(&keys).await?;
+
+        let missing: Vec<T::Id> = ids
+            .iter()
+            .zip(&keys)
+            .filter(|(_, k)| !cached.contains_key(*k))
+            .map(|(id, _)| id.clone())
+            .collect();
+        if !missing.is_empty() {
+            let loaded = loader(missing).await?;
+            if let Err(e) = self.fill(&loaded).await {
+                tracing::error!(error = ?e, name = T::NAME, "[repo::get_many] fill cache failed");
+            }
+            for v in loaded {
+                cached.insert(Self::key(&v.id()), v);
+            }
+        }
+
+        Ok(keys.iter().filter_map(|k| cached.remove(k)).collect())
+    }
+
+    /// 删除缓存
+    pub async fn invalidate(&self, id: &T::Id) -> anyhow::Result<()> {
+        self.redis.redis().delete(Self::key(id)).await
+    }
+
+    async fn fill(&self, list: &[T]) -> anyhow::Result<()> {
+        if list.is_empty() {
+            return Ok(());
+        }
+
+        // 与 get_or_set 一致：key 规范化，值经过 codec 编码
+        let mut items = Vec::with_capacity(list.len());
+        for v in list {
+            let key = key::normalize(&Self::key(&v.id())).into_owned();
+            items.push((key, self.redis.encode_json(serde_json::to_vec(v)?)?));
+        }
+
+        match self.redis.redis() {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+
+                let mut pipe = redis::pipe();
+                for (k, v) in &items {
+                    match self.ttl {
+                        Some(d) => pipe.set_ex(k, v, jitter_ttl(d).as_secs()).ignore(),
+                        None => pipe.set(k, v).ignore(),
+                    };
+                }
+                let _: () = pipe.query_async(&mut *conn).await?;
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+
+                // 各 key 可能位于不同 slot，逐个写入
+                for (k, v) in &items {
+                    let _: () = match self.ttl {
+                        Some(d) => conn.set_ex(k, v, jitter_ttl(d).as_secs()).await?,
+                        None => conn.set(k, v).await?,
+                    };
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        helper::{
+            redkit::{key, Redis},
+            repo::{CachedRepo, Entity},
+        },
+        redix,
+    };
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        id: i64,
+        name: String,
+    }
+
+    impl Entity for User {
+        type Id = i64;
+        const NAME: &'static str = "user";
+
+        fn id(&self) -> i64 {
+            self.id
+        }
+    }
+
+    fn user(id: i64) -> User {
+        User {
+            id,
+            name: format!("u{}", id),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_repo() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let repo = CachedRepo::<User>::new(Redis::Single(pool), Some(Duration::from_secs(60)));
+        let calls = AtomicUsize::new(0);
+
+        let v = repo
+            .get(1, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(Some(user(1)))
+            })
+            .await
+            .unwrap();
+        assert_eq!(v, Some(user(1)));
+
+        // 1 已缓存，仅查询 2、3（3 不存在）
+        let list = repo
+            .get_many(&[3, 2, 1], |ids| async move {
+                assert_eq!(ids, vec![3, 2]);
+                Ok(vec![user(2)])
+            })
+            .await
+            .unwrap();
+        assert_eq!(list, vec![user(2), user(1)]);
+
+        // 全部命中
+        let list = repo
+            .get_many(&[1, 2], |_| async { panic!("should hit cache") })
+            .await
+            .unwrap();
+        assert_eq!(list.len(), 2);
+
+        repo.invalidate(&1).await.unwrap();
+        repo.get(1, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(user(1)))
+        })
+        .await
+        .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct LongName {
+        id: String,
+    }
+
+    impl Entity for LongName {
+        type Id = String;
+        const NAME: &'static str = "long_name";
+
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_repo_long_key() {
+        key::set_max_len(64);
+
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let repo = CachedRepo::<LongName>::new(Redis::Single(pool), None);
+        let id = "x".repeat(100);
+        let calls = AtomicUsize::new(0);
+        let load = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(LongName { id: id.clone() }))
+        };
+
+        // get_many 回填的缓存可被 get 命中，invalidate 后重新加载
+        repo.get_many(std::slice::from_ref(&id), |_| async {
+            Ok(vec![LongName { id: id.clone() }])
+        })
+        .await
+        .unwrap();
+        repo.get(id.clone(), load).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        repo.invalidate(&id).await.unwrap();
+        repo.get(id.clone(), load).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{future::Future, time::Duration};
+
+use redis::{AsyncCommands, ExistenceCheck::NX, SetExpiry::EX};
+use uuid::Uuid;
+
+use crate::{helper::redkit::Redis, redix::script};
+
+/// 唯一值预占（离开作用域自动释放，除非调用 `keep`）
+///
+/// # Examples
+///
+/// ```
+/// let Some(r) = Reservation::acquire(&redis, "reserve:username:foo", Duration::from_secs(30)).await? else {
+///     return Err("username is taken")
+/// };
+/// if user_exists("foo").await? {
+///     return Err("username is taken") // 自动释放
+/// }
+/// create_user("foo").await?;
+/// r.keep(); // 保留至过期，覆盖主从延迟窗口
+/// ```
+pub struct Reservation {
+    redis: Redis,
+    key: String,
+    token: Option<String>,
+}
+
+impl Reservation {
+    /// 预占，已被占用时返回 None
+    pub async fn acquire(
+        redis: &Redis,
+        key: impl AsRef<str>,
+        ttl: Duration,
+    ) -> anyhow::Result<Option<Self>> {
+        let key = key.as_ref();
+        let token = Uuid::new_v4().to_string();
+
+        let opts = redis::SetOptions::default()
+            .conditional_set(NX)
+            .with_expiration(EX(ttl.as_secs().max(1)));
+        let ok: bool = match redis {
+            Redis::Single(pool) => pool.get().await?.set_options(key, &token, opts).await?,
+            Redis::Cluster(pool) => pool.get().await?.set_options(key, &token, opts).await?,
+        };
+        if !ok {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            redis: redis.clone(),
+            key: key.to_string(),
+            token: Some(token),
+        }))
+    }
+
+    /// 手动释放
+    pub async fn release(mut self) -> anyhow::Result<()> {
+        let Some(token) = self.token.take() else {
+            return Ok(());
+        };
+        del(&self.redis, &self.key, &token).await
+    }
+
+    /// 保留预占直至过期（不再自动释放）
+    pub fn keep(mut self) {
+        self.token = None;
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let Some(token) = self.token.take() else {
+            return;
+        };
+
+        // 不在 tokio 运行时内（如运行时关闭过程中）时无法异步释放，等待 TTL 过期
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(
+                key = self.key,
+                "[helper::reserve] no runtime on drop, expires by ttl"
+            );
+            return;
+        };
+
+        let redis = self.redis.clone();
+        let key = self.key.clone();
+
+        handle.spawn(async move {
+            if let Err(e) = del(&redis, &key, &token).await {
+                tracing::error!(err = ?e, "[helper::reserve] drop release(key={}) failed", key);
+            }
+        });
+    }
+}
+
+async fn del(redis: &Redis, key: &str, token: &str) -> anyhow::Result<()> {
+    let _: i64 = script::global()
+        .call("mutex:del")?
+        .key(key)
+        .arg(token)
+        .invoke(redis)
+        .await?;
+    Ok(())
+}
+
+/// 唯一值预占 + DB唯一性校验 + 写入
+///
+/// 1. Redis SET NX 预占 key，失败返回 None
+/// 2. 调用 `exists` 校验DB，已存在则释放预占并返回 None
+/// 3. 调用 `insert` 写入，失败则释放预占并返回错误；成功则保留预占至过期
+///
+/// # Examples
+///
+/// ```
+/// let ret = helper::reserve_unique(
+///     &redis,
+///     format!("reserve:phone:{}", phone),
+///     Duration::from_secs(30),
+///     || async { user_exists_by_phone(&phone).await },
+///     || async { create_user(&phone).await },
+/// )
+/// .await?;
+/// if ret.is_none() {
+///     return Err("phone is already registered")
+/// }
+/// ```
+pub async fn reserve_unique<T, C, CFut, I, IFut>(
+    redis: &Redis,
+    key: impl AsRef<str>,
+    ttl: Duration,
+    exists: C,
+    insert: I,
+) -> anyhow::Result<Option<T>>
+where
+    C: FnOnce() -> CFut,
+    CFut: Future<Output = anyhow::Result<bool>>,
+    I: FnOnce() -> IFut,
+    IFut: Future<Output = anyhow::Result<T>>,
+{
+    let Some(r) = Reservation::acquire(redis, key, ttl).await? else {
+        return Ok(None);
+    };
+
+    let ret = async {
+        if exists().await? {
+            return Ok(None);
+        }
+        insert().await.map(Some)
+    }
+    .await;
+
+    match ret {
+        Ok(Some(v)) => {
+            r.keep();
+            Ok(Some(v))
+        }
+        Ok(None) => {
+            r.release().await?;
+            Ok(None)
+        }
+        Err(e) => {
+            if let Err(err) = r.release().await {
+                tracing::error!(err = ?err, "[helper::reserve_unique] release failed");
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::redix;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reserve_unique() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let redis = Redis::Single(pool);
+
+        let ret = reserve_unique(
+            &redis,
+            "test_reserve_unique",
+            Duration::from_secs(10),
+            || async { Ok(false) },
+            || async { Ok(1) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(ret, Some(1));
+
+        // 预占保留中
+        let ret = Reservation::acquire(&redis, "test_reserve_unique", Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(ret.is_none());
+
+        let _: redis::RedisResult<()> = match &redis {
+            Redis::Single(pool) => pool.get().await.unwrap().del("test_reserve_unique").await,
+            Redis::Cluster(_) => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn test_drop_without_runtime() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let r = rt.block_on(async {
+            let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+            Reservation::acquire(&Redis::Single(pool), "test_drop", Duration::from_secs(10))
+                .await
+                .unwrap()
+                .unwrap()
+        });
+        // 运行时外 drop 不 panic，预占按 TTL 过期
+        drop(r);
+    }
+}
@@ -0,0 +1,177 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::anyhow;
+use redis::{AsyncCommands, ExistenceCheck::NX, SetExpiry::EX};
+
+use crate::{
+    crypto::hash,
+    helper,
+    helper::redkit::{key, Redis},
+};
+
+const EXPIRES: &str = "expires";
+const NONCE: &str = "nonce";
+const SIGN: &str = "sign";
+
+/// URL 签名：参数按 key 排序后与路径一起做 HMAC-SHA256，附带过期时间和随机数；
+/// 设置 Redis 后校验时记录 nonce，同一链接只能使用一次
+///
+/// # Examples
+///
+/// ```
+/// let signer = SignUrl::new("secret").with_redis(Redis::Single(pool));
+///
+/// // /download?expires=1700000600&file=a.pdf&nonce=xxx&sign=xxx
+/// let url = signer.sign("/download", &[("file", "a.pdf")], Duration::from_secs(600));
+///
+/// // 校验通过返回业务参数
+/// let params = signer.verify("/download", query).await?;
+/// ```
+#[derive(Clone)]
+pub struct SignUrl {
+    secret: Vec<u8>,
+    redis: Option<Redis>,
+}
+
+impl SignUrl {
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+            redis: None,
+        }
+    }
+
+    /// 开启防重放
+    pub fn with_redis(mut self, redis: Redis) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+
+    /// 签名，返回 `path?query`
+    pub fn sign<K, V>(&self, path: &str, params: &[(K, V)], ttl: Duration) -> String
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut m: BTreeMap<String, String> = params
+            .iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+            .collect();
+        let expires = time::OffsetDateTime::now_utc().unix_timestamp() + ttl.as_secs() as i64;
+        m.insert(EXPIRES.to_string(), expires.to_string());
+        m.insert(NONCE.to_string(), helper::nonce(16));
+
+        let query = encode(&m);
+        let sign = self.signature(path, &query);
+        format!("{}?{}&{}={}", path, query, SIGN, sign)
+    }
+
+    /// 校验签名、过期时间及 nonce，返回业务参数（不含 expires、nonce、sign）
+    pub async fn verify(
+        &self,
+        path: &str,
+        query: &str,
+    ) -> anyhow::Result<BTreeMap<String, String>> {
+        let mut m: BTreeMap<String, String> = form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+
+        let sign = m
+            .remove(SIGN)
+            .ok_or_else(|| anyhow!("signurl: missing sign"))?;
+        let expected = self.signature(path, &encode(&m));
+        // memcmp::eq 要求长度一致
+        if sign.len() != expected.len()
+            || !openssl::memcmp::eq(sign.as_bytes(), expected.as_bytes())
+        {
+            return Err(anyhow!("signurl: invalid sign"));
+        }
+
+        let expires: i64 = m
+            .remove(EXPIRES)
+            .ok_or_else(|| anyhow!("signurl: missing expires"))?
+            .parse()?;
+        let remain = expires - time::OffsetDateTime::now_utc().unix_timestamp();
+        if remain <= 0 {
+            return Err(anyhow!("signurl: expired"));
+        }
+
+        let nonce = m
+            .remove(NONCE)
+            .ok_or_else(|| anyhow!("signurl: missing nonce"))?;
+        if let Some(redis) = &self.redis {
+            let key = key::with_prefix(format!("kr:signurl:nonce:{}", nonce));
+            let opts = redis::SetOptions::default()
+                .conditional_set(NX)
+                .with_expiration(EX(remain as u64));
+            let ok: bool = match redis {
+                Redis::Single(pool) => pool.get().await?.set_options(key, 1, opts).await?,
+                Redis::Cluster(pool) => pool.get().await?.set_options(key, 1, opts).await?,
+            };
+            if !ok {
+                return Err(anyhow!("signurl: replayed"));
+            }
+        }
+
+        Ok(m)
+    }
+
+    fn signature(&self, path: &str, query: &str) -> String {
+        hash::hmac_sha256::<String>(&self.secret, format!("{}?{}", path, query))
+    }
+}
+
+// 按 key 排序编码
+fn encode(m: &BTreeMap<String, String>) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(m)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        helper::{redkit::Redis, signurl::SignUrl},
+        redix,
+    };
+
+    #[tokio::test]
+    async fn test_signurl() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let signer = SignUrl::new("secret").with_redis(Redis::Single(pool));
+
+        let url = signer.sign(
+            "/download",
+            &[("file", "a b.pdf"), ("uid", "1")],
+            Duration::from_secs(60),
+        );
+        let (path, query) = url.split_once('?').unwrap();
+        assert_eq!(path, "/download");
+        assert!(query.starts_with("expires="));
+        assert!(query.contains("file=a+b.pdf"));
+
+        // 路径不一致
+        assert!(signer.verify("/other", query).await.is_err());
+        // 参数被篡改
+        let tampered = query.replace("uid=1", "uid=2");
+        assert!(signer.verify(path, &tampered).await.is_err());
+        let truncated = &query[..query.len() - 1];
+        assert!(signer.verify(path, truncated).await.is_err());
+
+        let params = signer.verify(path, query).await.unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params["file"], "a b.pdf");
+
+        // 重放
+        let err = signer.verify(path, query).await.unwrap_err();
+        assert_eq!(err.to_string(), "signurl: replayed");
+
+        // 过期
+        let url = signer.sign("/download", &[("file", "a.pdf")], Duration::ZERO);
+        let (path, query) = url.split_once('?').unwrap();
+        let err = signer.verify(path, query).await.unwrap_err();
+        assert_eq!(err.to_string(), "signurl: expired");
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, future::Future, time::Duration};
+
+use tokio::{
+    task::{Id, JoinSet},
+    time::Instant,
+};
+
+/// 结构化并发：并发执行带标签的任务，任一任务失败、panic 或整体超时时取消其余任务并返回错误
+///
+/// # Examples
+///
+/// ```
+/// let mut group = TaskGroup::new().timeout(Duration::from_secs(3));
+/// group.spawn("user", async move { load_user(uid).await });
+/// group.spawn("orders", async move { load_orders(uid).await });
+///
+/// // 按 spawn 顺序返回
+/// let ret = group.wait().await?;
+/// ```
+pub struct TaskGroup<T> {
+    set: JoinSet<anyhow::Result<T>>,
+    // 任务ID => (序号, 标签)
+    labels: HashMap<Id, (usize, String)>,
+    timeout: Option<Duration>,
+}
+
+impl<T: Send + 'static> Default for TaskGroup<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + 'static> TaskGroup<T> {
+    pub fn new() -> Self {
+        Self {
+            set: JoinSet::new(),
+            labels: HashMap::new(),
+            timeout: None,
+        }
+    }
+
+    /// 整体超时（从调用 `wait` 开始计时）
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = Some(d);
+        self
+    }
+
+    pub fn spawn<F>(&mut self, label: impl AsRef<str>, fut: F)
+    where
+        F: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        let handle = self.set.spawn(fut);
+        let index = self.labels.len();
+        self.labels
+            .insert(handle.id(), (index, label.as_ref().to_string()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// 等待所有任务完成，返回按 spawn 顺序排列的结果
+    pub async fn wait(mut self) -> anyhow::Result<Vec<T>> {
+        let deadline = self.timeout.map(|d| Instant::now() + d);
+        let mut results: Vec<Option<T>> = (0..self.labels.len()).map(|_| None).collect();
+
+        loop {
+            let joined = match deadline {
+                Some(v) => match tokio::time::timeout_at(v, self.set.join_next_with_id()).await {
+                    Ok(v) => v,
+                    Err(_) => {
+                        self.set.shutdown().await;
+                        let pending: Vec<&str> = results
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, v)| v.is_none())
+                            .filter_map(|(i, _)| self.label_at(i))
+                            .collect();
+                        tracing::error!(pending = ?pending, "[helper::taskgroup] timeout");
+                        return Err(anyhow::anyhow!(
+                            "helper/taskgroup: timeout after {:?}, pending tasks {:?}",
+                            self.timeout.unwrap_or_default(),
+                            pending
+                        ));
+                    }
+                },
+                None => self.set.join_next_with_id().await,
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+
+            match joined {
+                Ok((id, Ok(v))) => {
+                    let (index, _) = self.labels[&id];
+                    results[index] = Some(v);
+                }
+                Ok((id, Err(e))) => {
+                    self.set.shutdown().await;
+                    let label = &self.labels[&id].1;
+                    tracing::error!(task = label, err = ?e, "[helper::taskgroup] task failed");
+                    return Err(e.context(format!("helper/taskgroup: task({}) failed", label)));
+                }
+                Err(e) => {
+                    self.set.shutdown().await;
+                    let label = self.labels.get(&e.id()).map(|v| v.1.as_str()).unwrap_or("");
+                    tracing::error!(task = label, err = %e, "[helper::taskgroup] task panicked");
+                    return Err(anyhow::anyhow!(
+                        "helper/taskgroup: task({}) panicked: {}",
+                        label,
+                        e
+                    ));
+                }
+            }
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    fn label_at(&self, index: usize) -> Option<&str> {
+        self.labels
+            .values()
+            .find(|(i, _)| *i == index)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::helper::taskgroup::TaskGroup;
+
+    #[tokio::test]
+    async fn test_task_group() {
+        let mut group = TaskGroup::new();
+        group.spawn("a", async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(1)
+        });
+        group.spawn("b", async { Ok(2) });
+        assert_eq!(group.wait().await.unwrap(), vec![1, 2]);
+
+        // 失败时取消其余任务
+        let done = Arc::new(AtomicBool::new(false));
+        let mut group = TaskGroup::new();
+        let flag = done.clone();
+        group.spawn("slow", async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        group.spawn("fail", async { Err(anyhow::anyhow!("oops")) });
+        let err = group.wait().await.unwrap_err();
+        assert!(format!("{:#}", err).contains("task(fail) failed"));
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!done.load(Ordering::SeqCst));
+
+        // panic
+        let mut group = TaskGroup::<()>::new();
+        group.spawn("boom", async { panic!("boom") });
+        let err = group.wait().await.unwrap_err();
+        assert!(err.to_string().contains("task(boom) panicked"));
+
+        // 超时
+        let mut group = TaskGroup::new().timeout(Duration::from_millis(20));
+        group.spawn("fast", async { Ok(()) });
+        group.spawn("slow", async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        });
+        let err = group.wait().await.unwrap_err();
+        assert!(err.to_string().contains("[\"slow\"]"));
+    }
+}
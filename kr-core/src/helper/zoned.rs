@@ -1,4 +1,9 @@
-use jiff::{tz::TimeZone, Timestamp, Zoned};
+use jiff::{
+    civil::{DateTime, Time},
+    fmt::strtime,
+    tz::TimeZone,
+    Timestamp, Zoned,
+};
 use time::OffsetDateTime;
 
 pub const DATE_TIME: &str = "%Y-%m-%d %H:%M:%S";
@@ -14,6 +19,17 @@ pub trait ToZoned {
     fn to_zoned_in_tz(&self, tz: &str) -> anyhow::Result<Zoned>;
 }
 
+/// Trait: 将 jiff::Zoned 转换回其他时间类型（Unix 时间戳见 `UnixTime::from_zoned`）
+///
+/// # Examples
+///
+/// ```
+/// let odt = OffsetDateTime::from_zoned(&z)?;
+/// ```
+pub trait FromZoned: Sized {
+    fn from_zoned(z: &Zoned) -> anyhow::Result<Self>;
+}
+
 // ------------------- time::OffsetDateTime -------------------
 impl ToZoned for OffsetDateTime {
     fn to_system_zoned(&self) -> anyhow::Result<Zoned> {
@@ -27,7 +43,17 @@ impl ToZoned for OffsetDateTime {
     }
 }
 
+impl FromZoned for OffsetDateTime {
+    /// 保留时区偏移
+    fn from_zoned(z: &Zoned) -> anyhow::Result<Self> {
+        let odt = OffsetDateTime::from_unix_timestamp_nanos(z.timestamp().as_nanosecond())?;
+        let offset = time::UtcOffset::from_whole_seconds(z.offset().seconds())?;
+        Ok(odt.to_offset(offset))
+    }
+}
+
 // ------------------- Unix timestamp -------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnixTime {
     Sec(i64),
     Milli(i64),
@@ -57,13 +83,111 @@ impl ToZoned for UnixTime {
     }
 }
 
+/// Unix 时间戳单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnixUnit {
+    Sec,
+    Milli,
+    Micro,
+    Nano,
+}
+
+impl UnixTime {
+    /// 由 jiff::Zoned 转为指定单位的时间戳
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let v = UnixTime::from_zoned(&z, UnixUnit::Milli);
+    /// ```
+    pub fn from_zoned(z: &Zoned, unit: UnixUnit) -> Self {
+        let ts = z.timestamp();
+        match unit {
+            UnixUnit::Sec => UnixTime::Sec(ts.as_second()),
+            UnixUnit::Milli => UnixTime::Milli(ts.as_millisecond()),
+            UnixUnit::Micro => UnixTime::Micro(ts.as_microsecond()),
+            UnixUnit::Nano => UnixTime::Nano(ts.as_nanosecond()),
+        }
+    }
+
+    /// 时间戳数值
+    pub fn value(&self) -> i128 {
+        match self {
+            UnixTime::Sec(v) | UnixTime::Milli(v) | UnixTime::Micro(v) => *v as i128,
+            UnixTime::Nano(v) => *v,
+        }
+    }
+}
+
+/// 解析带时区名的时间字符串（RFC 9557）
+///
+/// # Examples
+///
+/// ```
+/// let z = zoned::parse_zoned("2019-07-12T13:34:56+08:00[Asia/Shanghai]")?;
+/// ```
+pub fn parse_zoned(s: &str) -> anyhow::Result<Zoned> {
+    Ok(s.parse::<Zoned>()?)
+}
+
+/// 按格式解析本地时间并指定时区（仅含日期时为当天零点）
+///
+/// # Examples
+///
+/// ```
+/// let z = zoned::parse_in_tz("2019-07-12 13:34:56", zoned::DATE_TIME, "Asia/Shanghai")?;
+/// let z = zoned::parse_in_tz("2019-07-12", zoned::DATE_ONLY, "Asia/Shanghai")?;
+/// ```
+pub fn parse_in_tz(s: &str, pattern: &str, tz: &str) -> anyhow::Result<Zoned> {
+    Ok(parse_datetime(s, pattern)?.in_tz(tz)?)
+}
+
+/// 按格式解析系统本地时区的时间
+pub fn parse_in_system(s: &str, pattern: &str) -> anyhow::Result<Zoned> {
+    Ok(parse_datetime(s, pattern)?.to_zoned(TimeZone::system())?)
+}
+
+fn parse_datetime(s: &str, pattern: &str) -> anyhow::Result<DateTime> {
+    let tm = strtime::parse(pattern, s)?;
+    match tm.to_datetime() {
+        Ok(v) => Ok(v),
+        Err(_) => Ok(tm.to_date()?.to_datetime(Time::midnight())),
+    }
+}
+
+/// 格式化
+///
+/// # Examples
+///
+/// ```
+/// let s = zoned::format(&z, zoned::DATE_TIME)?;
+/// ```
+pub fn format(z: &Zoned, pattern: &str) -> anyhow::Result<String> {
+    Ok(strtime::format(pattern, z)?)
+}
+
+/// 格式化为 `2019-07-12 13:34:56`
+pub fn format_datetime(z: &Zoned) -> String {
+    z.strftime(DATE_TIME).to_string()
+}
+
+/// 格式化为 `2019-07-12`
+pub fn format_date(z: &Zoned) -> String {
+    z.strftime(DATE_ONLY).to_string()
+}
+
+/// 格式化为 `13:34:56`
+pub fn format_time(z: &Zoned) -> String {
+    z.strftime(TIME_OLNY).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use jiff::fmt::strtime;
     use time::OffsetDateTime;
 
-    use crate::helper::zoned::ToZoned;
-    use crate::helper::zoned::{self, UnixTime};
+    use crate::helper::zoned::{self, UnixTime, UnixUnit};
+    use crate::helper::zoned::{FromZoned, ToZoned};
 
     #[test]
     fn offset_datetime_to_zoned() {
@@ -82,6 +206,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn zoned_round_trip() {
+        let z = zoned::parse_zoned("2019-07-12T13:34:56+08:00[Asia/Shanghai]").unwrap();
+        assert_eq!(z.time_zone().iana_name(), Some("Asia/Shanghai"));
+        assert!(zoned::parse_zoned("2019-07-12 13:34:56").is_err());
+
+        let odt = OffsetDateTime::from_zoned(&z).unwrap();
+        assert_eq!(odt.unix_timestamp(), 1_562_909_696);
+        assert_eq!(odt.offset().whole_hours(), 8);
+        assert_eq!(odt.to_zoned_in_tz("Asia/Shanghai").unwrap(), z);
+
+        let v =
+            zoned::parse_in_tz("2019-07-12 13:34:56", zoned::DATE_TIME, "Asia/Shanghai").unwrap();
+        assert_eq!(v, z);
+        assert_eq!(
+            zoned::format(&v, zoned::DATE_TIME).unwrap(),
+            "2019-07-12 13:34:56"
+        );
+
+        let d = zoned::parse_in_tz("2019-07-12", zoned::DATE_ONLY, "Asia/Shanghai").unwrap();
+        assert_eq!(
+            zoned::format(&d, zoned::DATE_TIME).unwrap(),
+            "2019-07-12 00:00:00"
+        );
+        assert!(zoned::parse_in_tz("2019-07-12", zoned::DATE_TIME, "Asia/Shanghai").is_err());
+
+        for unit in [
+            UnixUnit::Sec,
+            UnixUnit::Milli,
+            UnixUnit::Micro,
+            UnixUnit::Nano,
+        ] {
+            let v = UnixTime::from_zoned(&z, unit);
+            assert_eq!(v.to_zoned_in_tz("Asia/Shanghai").unwrap(), z);
+        }
+        assert_eq!(
+            UnixTime::from_zoned(&z, UnixUnit::Sec),
+            UnixTime::Sec(1_562_909_696)
+        );
+        assert_eq!(
+            UnixTime::from_zoned(&z, UnixUnit::Milli).value(),
+            1_562_909_696_000
+        );
+        assert_eq!(
+            UnixTime::from_zoned(&z, UnixUnit::Nano).value(),
+            1_562_909_696_000_000_000
+        );
+
+        assert_eq!(zoned::format_datetime(&z), "2019-07-12 13:34:56");
+        assert_eq!(zoned::format_date(&z), "2019-07-12");
+        assert_eq!(zoned::format_time(&z), "13:34:56");
+
+        let v = zoned::parse_in_system("2019-07-12 13:34:56", zoned::DATE_TIME).unwrap();
+        assert_eq!(
+            zoned::format(&v, zoned::DATE_TIME).unwrap(),
+            "2019-07-12 13:34:56"
+        );
+    }
+
     #[test]
     fn unix_timestamp_to_zoned() {
         // second
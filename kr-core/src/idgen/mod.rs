@@ -0,0 +1,212 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// UUIDv7（按时间有序，适合作为索引）
+///
+/// # Examples
+///
+/// ```
+/// let id = idgen::uuid_v7();
+/// ```
+pub fn uuid_v7() -> Uuid {
+    Uuid::now_v7()
+}
+
+/// base62 编码
+///
+/// # Examples
+///
+/// ```
+/// let s = idgen::base62_encode(10086); // "2cg"
+/// ```
+pub fn base62_encode(mut n: u128) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+    let mut buf = Vec::with_capacity(22);
+    while n > 0 {
+        buf.push(ALPHABET[(n % 62) as usize]);
+        n /= 62;
+    }
+    buf.reverse();
+    String::from_utf8(buf).unwrap()
+}
+
+/// base62 解码
+///
+/// # Examples
+///
+/// ```
+/// let n = idgen::base62_decode("2cg")?; // 10086
+/// ```
+pub fn base62_decode(s: &str) -> anyhow::Result<u128> {
+    if s.is_empty() {
+        return Err(anyhow!("idgen: empty base62 string"));
+    }
+    let mut n: u128 = 0;
+    for c in s.bytes() {
+        let v = match c {
+            b'0'..=b'9' => c - b'0',
+            b'A'..=b'Z' => c - b'A' + 10,
+            b'a'..=b'z' => c - b'a' + 36,
+            _ => return Err(anyhow!("idgen: invalid base62 char '{}'", c as char)),
+        };
+        n = n
+            .checked_mul(62)
+            .and_then(|n| n.checked_add(v as u128))
+            .ok_or_else(|| anyhow!("idgen: base62 overflow"))?;
+    }
+    Ok(n)
+}
+
+/// 对外展示的短ID：数据库存储为 BIGINT，序列化为 base62 字符串
+///
+/// # Examples
+///
+/// ```
+/// #[derive(sqlx::FromRow, Serialize, Model)]
+/// pub struct Order {
+///     pub id: ShortId, // JSON: "2cg"
+/// }
+///
+/// let id: ShortId = "2cg".parse()?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ShortId(pub i64);
+
+impl fmt::Display for ShortId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&base62_encode(self.0 as u64 as u128))
+    }
+}
+
+impl FromStr for ShortId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let n = base62_decode(s)?;
+        let n = u64::try_from(n).map_err(|_| anyhow!("idgen: short id overflow"))?;
+        Ok(Self(n as i64))
+    }
+}
+
+impl From<i64> for ShortId {
+    fn from(v: i64) -> Self {
+        Self(v)
+    }
+}
+
+impl From<ShortId> for i64 {
+    fn from(v: ShortId) -> Self {
+        v.0
+    }
+}
+
+impl From<ShortId> for sea_query::Value {
+    fn from(v: ShortId) -> Self {
+        sea_query::Value::BigInt(Some(v.0))
+    }
+}
+
+impl Serialize for ShortId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ShortId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl<DB: sqlx::Database> sqlx::Type<DB> for ShortId
+where
+    i64: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <i64 as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <i64 as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: sqlx::Database> sqlx::Encode<'q, DB> for ShortId
+where
+    i64: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <i64 as sqlx::Encode<'q, DB>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r, DB: sqlx::Database> sqlx::Decode<'r, DB> for ShortId
+where
+    i64: sqlx::Decode<'r, DB>,
+{
+    fn decode(
+        value: <DB as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        <i64 as sqlx::Decode<'r, DB>>::decode(value).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::idgen::{self, ShortId};
+
+    #[test]
+    fn test_base62() {
+        assert_eq!(idgen::base62_encode(0), "0");
+        assert_eq!(idgen::base62_encode(61), "z");
+        assert_eq!(idgen::base62_encode(62), "10");
+        assert_eq!(idgen::base62_decode("2cg").unwrap(), 10086);
+        assert_eq!(
+            idgen::base62_decode(&idgen::base62_encode(u128::MAX)).unwrap(),
+            u128::MAX
+        );
+        assert!(idgen::base62_decode("a-b").is_err());
+        assert!(idgen::base62_decode("").is_err());
+    }
+
+    #[test]
+    fn test_uuid_v7() {
+        let a = idgen::uuid_v7();
+        let b = idgen::uuid_v7();
+        assert_eq!(a.get_version_num(), 7);
+        assert!(a < b);
+    }
+
+    #[test]
+    fn test_short_id() {
+        let id = ShortId(10086);
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""2cg""#);
+        assert_eq!(serde_json::from_str::<ShortId>(r#""2cg""#).unwrap(), id);
+        assert_eq!(
+            ShortId(-1).to_string().parse::<ShortId>().unwrap(),
+            ShortId(-1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_short_id_sqlx() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let (v,): (ShortId,) = sqlx::query_as("SELECT ?")
+            .bind(ShortId(10086))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(v, ShortId(10086));
+    }
+}
@@ -0,0 +1,207 @@
+use std::io::Cursor;
+
+use anyhow::anyhow;
+use image::{imageops::FilterType, DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+
+/// 图片校验规则
+#[derive(Debug, Clone)]
+pub struct Limits {
+    /// 文件大小上限（字节）
+    pub max_bytes: usize,
+    /// 宽、高上限（像素）
+    pub max_dimension: u32,
+    /// 允许的格式
+    pub formats: Vec<ImageFormat>,
+}
+
+/// 默认：10MB、8192px、JPEG/PNG/GIF/WebP
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 << 20,
+            max_dimension: 8192,
+            formats: vec![
+                ImageFormat::Jpeg,
+                ImageFormat::Png,
+                ImageFormat::Gif,
+                ImageFormat::WebP,
+            ],
+        }
+    }
+}
+
+/// 已解码的图片（按 EXIF 方向旋转，不保留 EXIF 等元数据）
+///
+/// # Examples
+///
+/// ```
+/// let img = imagekit::load(&bytes, &Limits::default())?;
+///
+/// // 原图去除 EXIF（GPS、设备信息）后保存
+/// let origin = img.strip()?;
+/// // 头像：裁剪为 256x256 的 JPEG
+/// let avatar = img.cover(256, 256).encode(ImageFormat::Jpeg)?;
+/// // 占位图
+/// let hash = img.blurhash(4, 3)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Image {
+    inner: DynamicImage,
+    format: ImageFormat,
+}
+
+/// 校验并解码图片（按内容识别格式，不依赖文件扩展名）
+pub fn load(data: &[u8], limits: &Limits) -> anyhow::Result<Image> {
+    if data.len() > limits.max_bytes {
+        return Err(anyhow!(
+            "imagekit: size {} exceeds limit {}",
+            data.len(),
+            limits.max_bytes
+        ));
+    }
+
+    let format = image::guess_format(data).map_err(|_| anyhow!("imagekit: unknown format"))?;
+    if !limits.formats.contains(&format) {
+        return Err(anyhow!("imagekit: format {:?} not allowed", format));
+    }
+
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    let mut decode_limits = image::Limits::default();
+    decode_limits.max_image_width = Some(limits.max_dimension);
+    decode_limits.max_image_height = Some(limits.max_dimension);
+    reader.limits(decode_limits);
+
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut inner = DynamicImage::from_decoder(decoder)?;
+    inner.apply_orientation(orientation);
+
+    Ok(Image { inner, format })
+}
+
+impl Image {
+    pub fn width(&self) -> u32 {
+        self.inner.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.inner.height()
+    }
+
+    /// 原始格式
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// MIME 类型（如：image/jpeg）
+    pub fn mime(&self) -> &'static str {
+        self.format.to_mime_type()
+    }
+
+    /// 原格式重新编码（去除 EXIF 等元数据，方向已修正）
+    pub fn strip(&self) -> anyhow::Result<Vec<u8>> {
+        self.encode(self.format)
+    }
+
+    /// 等比缩放至 `max_w x max_h` 以内（不放大）
+    pub fn thumbnail(&self, max_w: u32, max_h: u32) -> Image {
+        if self.width() <= max_w && self.height() <= max_h {
+            return self.clone();
+        }
+        Image {
+            inner: self.inner.resize(max_w, max_h, FilterType::Lanczos3),
+            format: self.format,
+        }
+    }
+
+    /// 等比缩放后居中裁剪为 `w x h`（头像、封面）
+    pub fn cover(&self, w: u32, h: u32) -> Image {
+        Image {
+            inner: self.inner.resize_to_fill(w, h, FilterType::Lanczos3),
+            format: self.format,
+        }
+    }
+
+    /// 编码为指定格式（JPEG 不支持透明通道，自动转为 RGB）
+    pub fn encode(&self, format: ImageFormat) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Cursor::new(Vec::new());
+        match format {
+            ImageFormat::Jpeg => {
+                DynamicImage::ImageRgb8(self.inner.to_rgb8()).write_to(&mut buf, format)?
+            }
+            _ => self.inner.write_to(&mut buf, format)?,
+        }
+        Ok(buf.into_inner())
+    }
+
+    /// 生成 BlurHash 占位符，`x`、`y` 为横纵分量数（1-9，常用 4x3）
+    pub fn blurhash(&self, x: u32, y: u32) -> anyhow::Result<String> {
+        // 分量数较少，缩小后计算即可
+        let small = self.inner.thumbnail(32, 32).to_rgba8();
+        blurhash::encode(x, y, small.width(), small.height(), small.as_raw())
+            .map_err(|e| anyhow!("imagekit: blurhash failed: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    use crate::imagekit::{self, Limits};
+
+    fn png(w: u32, h: u32) -> Vec<u8> {
+        let img = RgbImage::from_fn(w, h, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+        });
+        let mut buf = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut buf, ImageFormat::Png)
+            .unwrap();
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_imagekit() {
+        let data = png(400, 200);
+        let img = imagekit::load(&data, &Limits::default()).unwrap();
+        assert_eq!((img.width(), img.height()), (400, 200));
+        assert_eq!(img.mime(), "image/png");
+
+        let thumb = img.thumbnail(100, 100);
+        assert_eq!((thumb.width(), thumb.height()), (100, 50));
+        // 不放大
+        let same = img.thumbnail(1000, 1000);
+        assert_eq!((same.width(), same.height()), (400, 200));
+
+        let avatar = img.cover(64, 64);
+        assert_eq!((avatar.width(), avatar.height()), (64, 64));
+        let jpeg = avatar.encode(ImageFormat::Jpeg).unwrap();
+        assert_eq!(image::guess_format(&jpeg).unwrap(), ImageFormat::Jpeg);
+
+        let stripped = img.strip().unwrap();
+        assert_eq!(image::guess_format(&stripped).unwrap(), ImageFormat::Png);
+
+        let hash = img.blurhash(4, 3).unwrap();
+        assert_eq!(hash.len(), 4 + 2 * 4 * 3);
+
+        // 校验
+        assert!(imagekit::load(b"not an image", &Limits::default()).is_err());
+        let limits = Limits {
+            max_dimension: 300,
+            ..Default::default()
+        };
+        assert!(imagekit::load(&data, &limits).is_err());
+        let limits = Limits {
+            formats: vec![ImageFormat::Jpeg],
+            ..Default::default()
+        };
+        assert!(imagekit::load(&data, &limits).is_err());
+        let limits = Limits {
+            max_bytes: 10,
+            ..Default::default()
+        };
+        assert!(imagekit::load(&data, &limits).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::anyhow;
+use tokio_util::sync::CancellationToken;
+
+// 处理中的文件目录（位于监听目录下）
+const PROCESSING_DIR: &str = ".processing";
+
+/// 待处理的文件
+#[derive(Debug, Clone)]
+pub struct Incoming {
+    /// 文件名
+    pub name: String,
+    /// 处理中的路径（位于 `.processing` 目录）
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl Incoming {
+    /// 以流的方式读取
+    pub async fn open(&self) -> anyhow::Result<tokio::fs::File> {
+        Ok(tokio::fs::File::open(&self.path).await?)
+    }
+}
+
+/// 目录监听：定时扫描目录中的新文件，通过 rename 抢占后交给处理函数，
+/// 成功后移入归档目录，失败后移入失败目录并写入 `<文件名>.error`
+///
+/// - 以 `.` 开头及 `.tmp`、`.part`、`.filepart` 结尾的文件视为未上传完成
+/// - 修改时间距今不足 `stable_for` 的文件视为仍在写入
+/// - rename 在同一文件系统内是原子的，多实例监听同一目录时每个文件只会被处理一次
+/// - 上次崩溃遗留在 `.processing` 中的文件可通过 `recover` 放回监听目录
+///
+/// # Examples
+///
+/// ```
+/// let shutdown = worker::Shutdown::new();
+///
+/// let watcher = Watcher::new("/data/sftp/orders")
+///     .archive_dir("/data/sftp/archive")
+///     .failed_dir("/data/sftp/failed")
+///     .extensions(&["csv"])
+///     .stable_for(Duration::from_secs(10));
+///
+/// watcher.recover().await?;
+/// tokio::spawn(watcher.run(shutdown.clone(), |file: Incoming| async move {
+///     let reader = BufReader::new(file.open().await?);
+///     let mut lines = reader.lines();
+///     while let Some(line) = lines.next_line().await? {
+///         // ...
+///     }
+///     Ok(())
+/// }));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    dir: PathBuf,
+    archive_dir: PathBuf,
+    failed_dir: PathBuf,
+    extensions: Vec<String>,
+    interval: Duration,
+    stable_for: Duration,
+}
+
+impl Watcher {
+    /// 默认：归档目录 `<dir>/archive`、失败目录 `<dir>/failed`、扫描间隔 5s、稳定时间 5s
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref().to_path_buf();
+        Self {
+            archive_dir: dir.join("archive"),
+            failed_dir: dir.join("failed"),
+            dir,
+            extensions: Vec::new(),
+            interval: Duration::from_secs(5),
+            stable_for: Duration::from_secs(5),
+        }
+    }
+
+    pub fn archive_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.archive_dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn failed_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.failed_dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// 只处理指定扩展名的文件（不区分大小写），默认处理所有文件
+    pub fn extensions(mut self, exts: &[&str]) -> Self {
+        self.extensions = exts
+            .iter()
+            .map(|v| v.trim_start_matches('.').to_lowercase())
+            .collect();
+        self
+    }
+
+    /// 扫描间隔
+    pub fn interval(mut self, d: Duration) -> Self {
+        self.interval = d;
+        self
+    }
+
+    /// 文件最后修改后需保持不变的时长
+    pub fn stable_for(mut self, d: Duration) -> Self {
+        self.stable_for = d;
+        self
+    }
+
+    fn processing_dir(&self) -> PathBuf {
+        self.dir.join(PROCESSING_DIR)
+    }
+
+    /// 持续监听直到收到关闭信号（当前文件处理完成后退出）
+    pub async fn run<F, Fut>(self, shutdown: CancellationToken, f: F) -> anyhow::Result<()>
+    where
+        F: Fn(Incoming) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        tracing::info!(dir = ?self.dir, "[io::watch] started");
+        while !shutdown.is_cancelled() {
+            if let Err(e) = self.scan(&f, Some(&shutdown)).await {
+                tracing::error!(dir = ?self.dir, err = ?e, "[io::watch] scan failed");
+            }
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(self.interval) => {}
+            }
+        }
+        tracing::info!(dir = ?self.dir, "[io::watch] stopped");
+        Ok(())
+    }
+
+    /// 扫描一次并处理所有就绪的文件，返回处理数量（含失败）
+    pub async fn scan_once<F, Fut>(&self, f: F) -> anyhow::Result<usize>
+    where
+        F: Fn(Incoming) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        self.scan(&f, None).await
+    }
+
+    /// 将 `.processing` 中遗留的文件放回监听目录（多实例共享目录时会放回其它实例处理中的文件，
+    /// 应在单一实例或所有实例启动前执行）
+    pub async fn recover(&self) -> anyhow::Result<usize> {
+        let mut count = 0;
+        let mut entries = match tokio::fs::read_dir(self.processing_dir()).await {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_file() {
+                continue;
+            }
+            tokio::fs::rename(entry.path(), self.dir.join(entry.file_name())).await?;
+            tracing::warn!(file = ?entry.file_name(), "[io::watch] recovered");
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    async fn scan<F, Fut>(
+        &self,
+        f: &F,
+        shutdown: Option<&CancellationToken>,
+    ) -> anyhow::Result<usize>
+    where
+        F: Fn(Incoming) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        let mut ready = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            // 列出目录后文件可能已被其它实例认领或删除
+            let meta = match entry.metadata().await {
+                Ok(v) => v,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str().map(|v| v.to_string()) else {
+                continue;
+            };
+            if !self.accept(&name) {
+                continue;
+            }
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            if age < self.stable_for {
+                continue;
+            }
+            ready.push((modified, name));
+        }
+        // 按修改时间先后处理
+        ready.sort();
+
+        let mut count = 0;
+        for (_, name) in ready {
+            if shutdown.is_some_and(|v| v.is_cancelled()) {
+                break;
+            }
+            let Some(file) = self.claim(&name).await? else {
+                continue;
+            };
+            let ret = f(file.clone()).await;
+            self.finish(&file, ret).await?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn accept(&self, name: &str) -> bool {
+        if name.starts_with('.') {
+            return false;
+        }
+        let lower = name.to_lowercase();
+        if [".tmp", ".part", ".filepart"]
+            .iter()
+            .any(|v| lower.ends_with(v))
+        {
+            return false;
+        }
+        if self.extensions.is_empty() {
+            return true;
+        }
+        Path::new(&lower)
+            .extension()
+            .and_then(|v| v.to_str())
+            .is_some_and(|ext| self.extensions.iter().any(|v| v == ext))
+    }
+
+    // rename 到 .processing，已被其它实例抢占时返回 None
+    async fn claim(&self, name: &str) -> anyhow::Result<Option<Incoming>> {
+        let dir = self.processing_dir();
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let path = dir.join(name);
+        match tokio::fs::rename(self.dir.join(name), &path).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let size = tokio::fs::metadata(&path).await?.len();
+        Ok(Some(Incoming {
+            name: name.to_string(),
+            path,
+            size,
+        }))
+    }
+
+    async fn finish(&self, file: &Incoming, ret: anyhow::Result<()>) -> anyhow::Result<()> {
+        let (dir, err) = match ret {
+            Ok(_) => (&self.archive_dir, None),
+            Err(e) => (&self.failed_dir, Some(e)),
+        };
+        tokio::fs::create_dir_all(dir).await?;
+
+        let target = unique_path(dir, &file.name).await;
+        tokio::fs::rename(&file.path, &target).await.map_err(|e| {
+            anyhow!(
+                "io/watch: move {:?} to {:?} failed: {}",
+                file.path,
+                target,
+                e
+            )
+        })?;
+
+        match err {
+            None => tracing::info!(file = file.name, size = file.size, "[io::watch] archived"),
+            Some(e) => {
+                let mut path = target.into_os_string();
+                path.push(".error");
+                tokio::fs::write(&path, format!("{:#}\n", e)).await?;
+                tracing::error!(file = file.name, err = ?e, "[io::watch] failed");
+            }
+        }
+        Ok(())
+    }
+}
+
+// 目标目录中已存在同名文件时追加时间戳后缀
+async fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        let ts = time::OffsetDateTime::now_utc().unix_timestamp_nanos();
+        return dir.join(format!("{}.{}", name, ts));
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use tokio::io::AsyncReadExt;
+
+    use crate::{
+        helper,
+        io::watch::{Incoming, Watcher},
+    };
+
+    #[tokio::test]
+    async fn test_watcher() {
+        let dir = std::env::temp_dir().join(format!("kr_watch_{}", helper::nonce(8)));
+        std::fs::create_dir_all(dir.join(".processing")).unwrap();
+        std::fs::write(dir.join("a.csv"), "1,2").unwrap();
+        std::fs::write(dir.join("b.CSV"), "bad").unwrap();
+        std::fs::write(dir.join("c.txt"), "skip").unwrap();
+        std::fs::write(dir.join("d.csv.part"), "uploading").unwrap();
+        // 上次崩溃遗留
+        std::fs::write(dir.join(".processing/e.csv"), "3,4").unwrap();
+
+        let watcher = Watcher::new(&dir)
+            .extensions(&["csv"])
+            .stable_for(Duration::ZERO);
+        assert_eq!(watcher.recover().await.unwrap(), 1);
+
+        let seen: Arc<Mutex<Vec<String>>> = Arc::default();
+        let handler = |file: Incoming| {
+            let seen = seen.clone();
+            async move {
+                let mut s = String::new();
+                file.open().await?.read_to_string(&mut s).await?;
+                seen.lock().unwrap().push(s.clone());
+                if s == "bad" {
+                    return Err(anyhow::anyhow!("invalid line"));
+                }
+                Ok(())
+            }
+        };
+        assert_eq!(watcher.scan_once(handler).await.unwrap(), 3);
+        assert_eq!(watcher.scan_once(handler).await.unwrap(), 0);
+
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, vec!["1,2", "3,4", "bad"]);
+        assert!(dir.join("archive/a.csv").exists());
+        assert!(dir.join("archive/e.csv").exists());
+        assert!(dir.join("failed/b.CSV").exists());
+        let err = std::fs::read_to_string(dir.join("failed/b.CSV.error")).unwrap();
+        assert_eq!(err.trim(), "invalid line");
+        assert!(dir.join("c.txt").exists());
+        assert!(dir.join("d.csv.part").exists());
+
+        // 未稳定的文件暂不处理
+        std::fs::write(dir.join("f.csv"), "new").unwrap();
+        let watcher = watcher.stable_for(Duration::from_secs(60));
+        assert_eq!(watcher.scan_once(handler).await.unwrap(), 0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
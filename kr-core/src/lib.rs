@@ -1,5 +1,44 @@
+pub mod app;
+pub mod bootstrap;
+pub mod cache;
+pub mod codec;
+pub mod codes;
+pub mod config;
+pub mod counterkit;
 pub mod crypto;
+pub mod dsn;
+pub mod env;
+pub mod events;
+pub mod experiment;
+pub mod flags;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod helper;
+pub mod idgen;
+#[cfg(feature = "imagekit")]
+pub mod imagekit;
+pub mod io;
+pub mod money;
 pub mod mutex;
+#[cfg(feature = "olap")]
+pub mod olap;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod pipeline;
+#[cfg(feature = "qrcode")]
+pub mod qrcode;
+pub mod queue;
+pub mod quota;
+pub mod ratelimit;
 pub mod redix;
+pub mod registry;
+pub mod saga;
+#[cfg(feature = "search")]
+pub mod search;
+pub mod shard;
 pub mod sql;
+pub mod sse;
+pub mod template;
+pub mod times;
+pub mod webhook;
+pub mod worker;
@@ -0,0 +1,315 @@
+use anyhow::{anyhow, bail};
+
+// 十进制数最多的小数位数
+const MAX_SCALE: u32 = 18;
+
+/// 舍入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// 四舍五入（0.5 远离 0）
+    HalfUp,
+    /// 银行家舍入：四舍六入五取偶
+    HalfEven,
+    /// 截断（向 0）
+    Down,
+    /// 进位（远离 0）
+    Up,
+    /// 向负无穷
+    Floor,
+    /// 向正无穷
+    Ceil,
+}
+
+/// 解析金额（元）为分，超过两位小数时按 `mode` 舍入；支持千分位 `,`
+///
+/// # Examples
+///
+/// ```
+/// // 10001
+/// let v = money::parse("100.01", Rounding::HalfEven)?;
+/// // 234（2.345 => 2.34）
+/// let v = money::parse("2.345", Rounding::HalfEven)?;
+/// // 123456
+/// let v = money::parse("1,234.56", Rounding::HalfEven)?;
+/// ```
+pub fn parse(s: &str, mode: Rounding) -> anyhow::Result<i64> {
+    let (n, scale) = parse_decimal(s)?;
+    let v = if scale <= 2 {
+        n.checked_mul(10i128.pow(2 - scale))
+            .ok_or_else(|| anyhow!("money: {} overflows", s))?
+    } else {
+        div_round(n, 10i128.pow(scale - 2), mode)
+    };
+    to_i64(v)
+}
+
+/// 分格式化为元：`1,234.56`
+pub fn format(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.unsigned_abs();
+    let int = (abs / 100).to_string();
+
+    let mut out = String::with_capacity(int.len() + int.len() / 3 + 4);
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    format!("{}{}.{:02}", sign, out, abs % 100)
+}
+
+/// 按 `unit` 的整数倍舍入（如 `unit = 100` 时舍入到元）
+///
+/// # Examples
+///
+/// ```
+/// // 1200
+/// let v = money::round(1250, 100, Rounding::HalfEven)?;
+/// ```
+pub fn round(cents: i64, unit: i64, mode: Rounding) -> anyhow::Result<i64> {
+    if unit <= 0 {
+        bail!("money: unit must be positive");
+    }
+    to_i64(div_round(cents as i128, unit as i128, mode) * unit as i128)
+}
+
+/// 按比例计算：`cents * numerator / denominator`，结果按 `mode` 舍入
+pub fn apply_rate(
+    cents: i64,
+    numerator: i64,
+    denominator: i64,
+    mode: Rounding,
+) -> anyhow::Result<i64> {
+    if denominator == 0 {
+        bail!("money: denominator is zero");
+    }
+    let (mut n, mut d) = (cents as i128 * numerator as i128, denominator as i128);
+    if d < 0 {
+        (n, d) = (-n, -d);
+    }
+    to_i64(div_round(n, d, mode))
+}
+
+/// 按百分比计算（百分比为十进制字符串，避免浮点误差），结果按 `mode` 舍入
+///
+/// # Examples
+///
+/// ```
+/// // 手续费 0.6%：10001 => 60.006 => 60
+/// let fee = money::percent(10001, "0.6", Rounding::HalfEven)?;
+///
+/// // 向上取整：61
+/// let fee = money::percent(10001, "0.6", Rounding::Ceil)?;
+/// ```
+pub fn percent(cents: i64, pct: &str, mode: Rounding) -> anyhow::Result<i64> {
+    let (n, scale) = parse_decimal(pct)?;
+    let v = (cents as i128)
+        .checked_mul(n)
+        .ok_or_else(|| anyhow!("money: {} * {}% overflows", cents, pct))?;
+    to_i64(div_round(v, 100 * 10i128.pow(scale), mode))
+}
+
+/// 平均分摊，余数从前往后逐分分配，合计等于 `cents`
+///
+/// # Examples
+///
+/// ```
+/// // 100.01 分 3 份：[3334, 3334, 3333]
+/// let parts = money::split(10001, 3)?;
+/// ```
+pub fn split(cents: i64, n: usize) -> anyhow::Result<Vec<i64>> {
+    allocate(cents, &vec![1; n])
+}
+
+/// 按比例分摊（最大余数法），合计等于 `cents`；余数相同时靠前的优先
+///
+/// # Examples
+///
+/// ```
+/// // 优惠 10 元按商品金额 [30.00, 30.00, 40.00] 分摊：[300, 300, 400]
+/// let parts = money::allocate(1000, &[3000, 3000, 4000])?;
+/// ```
+pub fn allocate(cents: i64, ratios: &[u64]) -> anyhow::Result<Vec<i64>> {
+    let total: u128 = ratios.iter().map(|v| *v as u128).sum();
+    if total == 0 {
+        bail!("money: ratios must not be empty or all zero");
+    }
+
+    let abs = cents.unsigned_abs() as u128;
+    let mut parts: Vec<u128> = Vec::with_capacity(ratios.len());
+    let mut remainders: Vec<(usize, u128)> = Vec::with_capacity(ratios.len());
+    for (i, r) in ratios.iter().enumerate() {
+        let v = abs * *r as u128;
+        parts.push(v / total);
+        remainders.push((i, v % total));
+    }
+
+    // 剩余的分按余数从大到小分配
+    let left = abs - parts.iter().sum::<u128>();
+    remainders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    for (i, _) in remainders.into_iter().take(left as usize) {
+        parts[i] += 1;
+    }
+
+    // 在 i128 中还原符号：i64::MIN 的绝对值超出 i64
+    let sign: i128 = if cents < 0 { -1 } else { 1 };
+    parts
+        .into_iter()
+        .map(|v| to_i64(sign * v as i128))
+        .collect()
+}
+
+// 整数除法，按 mode 舍入（d > 0）
+fn div_round(n: i128, d: i128, mode: Rounding) -> i128 {
+    let (q, r) = (n / d, n % d);
+    if r == 0 {
+        return q;
+    }
+    let twice = r.abs() * 2;
+    let away = match mode {
+        Rounding::HalfUp => twice >= d,
+        Rounding::HalfEven => twice > d || (twice == d && q % 2 != 0),
+        Rounding::Down => false,
+        Rounding::Up => true,
+        Rounding::Floor => n < 0,
+        Rounding::Ceil => n > 0,
+    };
+    if !away {
+        return q;
+    }
+    if n < 0 {
+        q - 1
+    } else {
+        q + 1
+    }
+}
+
+// 十进制字符串 => (整数, 小数位数)：`-1,234.5` => (-12345, 1)
+fn parse_decimal(s: &str) -> anyhow::Result<(i128, u32)> {
+    let err = || anyhow!("money: invalid number {:?}", s);
+    let v = s.trim();
+    let (neg, v) = match v.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, v.strip_prefix('+').unwrap_or(v)),
+    };
+    let (int, frac) = v.split_once('.').unwrap_or((v, ""));
+    let int = int.replace(',', "");
+    if (int.is_empty() && frac.is_empty())
+        || !int.bytes().all(|b| b.is_ascii_digit())
+        || !frac.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(err());
+    }
+    if frac.len() > MAX_SCALE as usize {
+        bail!("money: too many decimal places in {:?}", s);
+    }
+
+    let n: i128 = format!("{}{}", int, frac).parse().map_err(|_| err())?;
+    Ok((if neg { -n } else { n }, frac.len() as u32))
+}
+
+fn to_i64(v: i128) -> anyhow::Result<i64> {
+    i64::try_from(v).map_err(|_| anyhow!("money: {} overflows i64", v))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::money::{self, Rounding};
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(money::parse("100.01", Rounding::HalfEven).unwrap(), 10001);
+        assert_eq!(money::parse("1,234.5", Rounding::HalfEven).unwrap(), 123450);
+        assert_eq!(money::parse("-0.5", Rounding::HalfEven).unwrap(), -50);
+        assert_eq!(money::parse(".5", Rounding::HalfEven).unwrap(), 50);
+        assert_eq!(money::parse("2.345", Rounding::HalfEven).unwrap(), 234);
+        assert_eq!(money::parse("2.355", Rounding::HalfEven).unwrap(), 236);
+        assert_eq!(money::parse("2.345", Rounding::HalfUp).unwrap(), 235);
+        assert_eq!(money::parse("-2.345", Rounding::HalfUp).unwrap(), -235);
+        assert!(money::parse("1e3", Rounding::HalfEven).is_err());
+        assert!(money::parse("", Rounding::HalfEven).is_err());
+        assert!(money::parse("99999999999999999999", Rounding::HalfEven).is_err());
+
+        assert_eq!(money::format(123456789), "1,234,567.89");
+        assert_eq!(money::format(-5), "-0.05");
+    }
+
+    #[test]
+    fn test_rounding() {
+        let cases = [
+            // (分子, 分母) => [HalfUp, HalfEven, Down, Up, Floor, Ceil]
+            ((25, 10), [3, 2, 2, 3, 2, 3]),
+            ((35, 10), [4, 4, 3, 4, 3, 4]),
+            ((-25, 10), [-3, -2, -2, -3, -3, -2]),
+            ((21, 10), [2, 2, 2, 3, 2, 3]),
+            ((-29, 10), [-3, -3, -2, -3, -3, -2]),
+        ];
+        let modes = [
+            Rounding::HalfUp,
+            Rounding::HalfEven,
+            Rounding::Down,
+            Rounding::Up,
+            Rounding::Floor,
+            Rounding::Ceil,
+        ];
+        for ((n, d), expect) in cases {
+            for (mode, v) in modes.iter().zip(expect) {
+                assert_eq!(
+                    money::apply_rate(n, 1, d, *mode).unwrap(),
+                    v,
+                    "{}/{} {:?}",
+                    n,
+                    d,
+                    mode
+                );
+            }
+        }
+
+        assert_eq!(money::round(1250, 100, Rounding::HalfEven).unwrap(), 1200);
+        assert_eq!(money::round(1350, 100, Rounding::HalfEven).unwrap(), 1400);
+        assert!(money::apply_rate(1, 1, 0, Rounding::HalfUp).is_err());
+        assert_eq!(money::apply_rate(10, 1, -4, Rounding::HalfUp).unwrap(), -3);
+
+        assert_eq!(
+            money::percent(10001, "0.6", Rounding::HalfEven).unwrap(),
+            60
+        );
+        assert_eq!(money::percent(10001, "0.6", Rounding::Ceil).unwrap(), 61);
+        assert_eq!(money::percent(250, "1", Rounding::HalfEven).unwrap(), 2);
+        assert_eq!(money::percent(250, "1", Rounding::HalfUp).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_allocate() {
+        assert_eq!(money::split(10001, 3).unwrap(), vec![3334, 3334, 3333]);
+        assert_eq!(money::split(-10001, 3).unwrap(), vec![-3334, -3334, -3333]);
+        assert_eq!(money::split(2, 3).unwrap(), vec![1, 1, 0]);
+        assert!(money::split(100, 0).is_err());
+
+        assert_eq!(
+            money::allocate(1000, &[3000, 3000, 4000]).unwrap(),
+            vec![300, 300, 400]
+        );
+        // 100 按 1:1:1 => 33.33...，余数 1 分给第一个
+        assert_eq!(money::allocate(100, &[1, 1, 1]).unwrap(), vec![34, 33, 33]);
+        // 余数大的优先
+        assert_eq!(money::allocate(100, &[1, 2]).unwrap(), vec![33, 67]);
+        assert_eq!(money::allocate(5, &[0, 1]).unwrap(), vec![0, 5]);
+
+        let parts = money::allocate(99_999, &[7, 13, 29, 51]).unwrap();
+        assert_eq!(parts.iter().sum::<i64>(), 99_999);
+
+        // 边界值
+        assert_eq!(money::allocate(i64::MIN, &[1]).unwrap(), vec![i64::MIN]);
+        assert_eq!(
+            money::split(i64::MIN, 2).unwrap(),
+            vec![i64::MIN / 2, i64::MIN / 2]
+        );
+        assert_eq!(
+            money::allocate(i64::MIN, &[u64::MAX, 1]).unwrap(),
+            vec![i64::MIN, 0]
+        );
+        assert_eq!(money::allocate(i64::MAX, &[1]).unwrap(), vec![i64::MAX]);
+    }
+}
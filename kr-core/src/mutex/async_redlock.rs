@@ -3,7 +3,7 @@ use std::time;
 use tokio::time::sleep;
 use uuid::Uuid;
 
-use crate::redix;
+use crate::redix::{self, script};
 
 /// 基于Redis的异步分布式锁（离开作用域自动释放）
 ///
@@ -84,7 +84,8 @@ impl AsyncRedLock {
         }
 
         let mut conn = self.pool.get().await?;
-        redis::Script::new(super::DEL)
+        script::global()
+            .get("mutex:del")?
             .key(&self.key)
             .arg(&self.token)
             .invoke_async::<()>(&mut *conn)
@@ -134,15 +135,25 @@ impl Drop for AsyncRedLock {
             return;
         }
 
+        // 不在 tokio 运行时内时无法异步释放，等待锁过期
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(
+                key = self.key,
+                "[mutex.async_red_lock] no runtime on drop, expires by ttl"
+            );
+            return;
+        };
+
         let pool = self.pool.clone();
         let key = self.key.clone();
         let token = self.token.clone().unwrap();
 
         // 异步释放锁
-        tokio::spawn(async move {
+        handle.spawn(async move {
             if let Err(e) = async {
                 let mut conn = pool.get().await?;
-                redis::Script::new(super::DEL)
+                script::global()
+                    .get("mutex:del")?
                     .key(&key)
                     .arg(&token)
                     .invoke_async::<()>(&mut *conn)
@@ -180,9 +191,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_async_red_lock() {
-        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:6379".to_string()], None)
-            .await
-            .unwrap();
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
 
         {
             let lock =
@@ -8,3 +8,5 @@ else
 	return 0
 end
 "#;
+
+inventory::submit! { crate::redix::script::Builtin { name: "mutex:del", body: DEL } }
@@ -1,14 +1,16 @@
-use redis::{Commands, ExistenceCheck::NX, SetExpiry::EX};
+use redis::{ExistenceCheck::NX, FromRedisValue, SetExpiry::EX};
 use std::{thread, time};
 use uuid::Uuid;
 
+use crate::redix::{script, BlockingPool};
+
 /// 基于Redis的分布式锁（离开作用域自动释放）
 ///
 /// # Examples
 ///
 /// ```
 /// // 获取锁
-/// let lock = RedLock::new(pool, "key", Duration::from_secs(10)).acquire()?;
+/// let lock = RedLock::new(pool.clone(), "key", Duration::from_secs(10)).acquire()?;
 /// if lock.is_none() {
 ///     return Err("operation is too frequent, please try again later")
 /// }
@@ -22,17 +24,54 @@ use uuid::Uuid;
 /// }
 /// // 手动释放
 /// lock.unwrap().release()?;
+///
+/// // 与异步代码共用连接池（离开作用域时在运行时中异步释放，不阻塞当前线程）
+/// let pool = redix::BlockingPool::new(pool)?;
+/// let lock = RedLock::with_blocking(pool, "key", Duration::from_secs(10)).acquire()?;
 /// ```
 pub struct RedLock {
-    pool: r2d2::Pool<redis::Client>,
+    pool: Pool,
     key: String,
     ttl: time::Duration,
     token: Option<String>,
     prevent: bool,
 }
 
+enum Pool {
+    Sync(r2d2::Pool<redis::Client>),
+    Blocking(BlockingPool),
+}
+
+impl Pool {
+    fn query<T: FromRedisValue>(&self, cmd: &redis::Cmd) -> anyhow::Result<T> {
+        match self {
+            Pool::Sync(pool) => Ok(cmd.query(&mut *pool.get()?)?),
+            Pool::Blocking(pool) => pool.query(cmd),
+        }
+    }
+
+    fn invoke<T: FromRedisValue>(
+        &self,
+        invocation: &redis::ScriptInvocation<'_>,
+    ) -> anyhow::Result<T> {
+        match self {
+            Pool::Sync(pool) => Ok(invocation.invoke(&mut *pool.get()?)?),
+            Pool::Blocking(pool) => pool.invoke(invocation),
+        }
+    }
+}
+
 impl RedLock {
     pub fn new(pool: r2d2::Pool<redis::Client>, key: impl AsRef<str>, ttl: time::Duration) -> Self {
+        Self::with_pool(Pool::Sync(pool), key, ttl)
+    }
+
+    /// 使用异步连接池的同步封装
+    pub fn with_blocking(pool: BlockingPool, key: impl AsRef<str>, ttl: time::Duration) -> Self {
+        Self::with_pool(Pool::Blocking(pool), key, ttl)
+    }
+
+    fn with_pool(pool: Pool, key: impl AsRef<str>, ttl: time::Duration) -> Self {
         RedLock {
             pool,
             key: key.as_ref().to_string(),
@@ -76,11 +115,12 @@ impl RedLock {
             return Ok(());
         }
 
-        let mut conn = self.pool.get()?;
-        redis::Script::new(super::DEL)
-            .key(&self.key)
-            .arg(&self.token)
-            .invoke::<()>(&mut conn)?;
+        self.pool.invoke::<()>(
+            script::global()
+                .get("mutex:del")?
+                .key(&self.key)
+                .arg(&self.token),
+        )?;
         self.token = None;
         Ok(())
     }
@@ -91,14 +131,14 @@ impl RedLock {
     }
 
     fn set_nx(&mut self) -> anyhow::Result<()> {
-        let mut conn = self.pool.get()?;
-
         let token = Uuid::new_v4().to_string();
 
         let opts = redis::SetOptions::default()
             .conditional_set(NX)
             .with_expiration(EX(self.ttl.as_secs().max(1)));
-        let ret_setnx: redis::RedisResult<bool> = conn.set_options(&self.key, &token, opts);
+        let ret_setnx: anyhow::Result<bool> = self
+            .pool
+            .query(redis::cmd("SET").arg(&self.key).arg(&token).arg(opts));
         match ret_setnx {
             Ok(v) => {
                 if v {
@@ -108,7 +148,7 @@ impl RedLock {
             }
             Err(e) => {
                 // 尝试GET一次：避免因redis网络错误导致误加锁
-                let ret_get: Option<String> = conn.get(&self.key)?;
+                let ret_get: Option<String> = self.pool.query(redis::cmd("GET").arg(&self.key))?;
                 let v = ret_get.ok_or(e)?;
                 if v == token {
                     self.token = Some(token);
@@ -126,6 +166,28 @@ impl Drop for RedLock {
             return;
         }
 
+        // 异步连接池：派发至运行时释放，不阻塞当前线程（运行时已关闭时等待锁过期）
+        if let Pool::Blocking(pool) = &self.pool {
+            let (pool, key, token) = (pool.clone(), self.key.clone(), self.token.take());
+            pool.handle().clone().spawn(async move {
+                let ret: anyhow::Result<()> = async {
+                    let mut conn = pool.pool().get().await?;
+                    script::global()
+                        .get("mutex:del")?
+                        .key(&key)
+                        .arg(&token)
+                        .invoke_async::<()>(&mut *conn)
+                        .await?;
+                    Ok(())
+                }
+                .await;
+                if let Err(e) = ret {
+                    tracing::error!(err = ?e, "[mutex.red_lock] drop release(key={}) failed", key);
+                }
+            });
+            return;
+        }
+
         // 释放锁
         if let Err(e) = self.release() {
             tracing::error!(err = ?e, "[mutex.red_lock] drop release(key={}) failed", self.key);
@@ -136,13 +198,45 @@ impl Drop for RedLock {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::redix;
 
-    #[test]
-    fn test_red_lock() {
-        let pool = r2d2::Pool::new(redis::Client::open("redis://127.0.0.1:6379").unwrap()).unwrap();
-        let lock = RedLock::new(pool, "test_red_lock", time::Duration::from_secs(10))
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_red_lock() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let pool = BlockingPool::new(pool).unwrap();
+        let ttl = time::Duration::from_secs(10);
+
+        let lock = RedLock::with_blocking(pool.clone(), "test_red_lock", ttl)
             .acquire()
             .unwrap();
         assert!(lock.is_some());
+
+        // 普通线程中
+        let p = pool.clone();
+        let other =
+            thread::spawn(move || RedLock::with_blocking(p, "test_red_lock", ttl).acquire())
+                .join()
+                .unwrap()
+                .unwrap();
+        assert!(other.is_none());
+
+        // 离开作用域后在运行时中异步释放
+        drop(lock);
+        tokio::time::sleep(time::Duration::from_millis(50)).await;
+        let lock = RedLock::with_blocking(pool, "test_red_lock", ttl)
+            .acquire()
+            .unwrap();
+        assert!(lock.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_red_lock_current_thread() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let pool = BlockingPool::new(pool).unwrap();
+
+        // current_thread 运行时中无法阻塞，返回错误而非 panic
+        let ret = RedLock::with_blocking(pool, "test_red_lock_ct", time::Duration::from_secs(10))
+            .acquire();
+        assert!(ret.is_err());
     }
 }
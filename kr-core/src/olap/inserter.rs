@@ -0,0 +1,325 @@
+use std::{marker::PhantomData, time::Duration};
+
+use anyhow::anyhow;
+use serde::Serialize;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+use super::Client;
+
+enum Msg<T> {
+    Row(T),
+    Flush(oneshot::Sender<anyhow::Result<()>>),
+}
+
+/// 缓冲写入配置：行数达到 `max_rows` 或距上次刷写超过 `period` 时批量写入
+///
+/// 刷写失败（已重试）时记录错误日志并丢弃该批数据，`flush`、`close` 返回错误
+///
+/// # Examples
+///
+/// ```
+/// let inserter = ch
+///     .inserter::<Event>("events")
+///     .max_rows(10_000)
+///     .period(Duration::from_secs(5))
+///     .spawn();
+///
+/// inserter.write(event).await?;
+///
+/// // 优雅关闭：写入剩余数据
+/// inserter.close().await?;
+/// ```
+pub struct Inserter<T> {
+    client: Client,
+    table: String,
+    max_rows: usize,
+    period: Duration,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Inserter<T>
+where
+    T: Serialize + Send + 'static,
+{
+    /// 默认：10000 行或 1s
+    pub fn new(client: Client, table: impl Into<String>) -> Self {
+        Self {
+            client,
+            table: table.into(),
+            max_rows: 10000,
+            period: Duration::from_secs(1),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn max_rows(mut self, n: usize) -> Self {
+        self.max_rows = n.max(1);
+        self
+    }
+
+    pub fn period(mut self, d: Duration) -> Self {
+        self.period = d.max(Duration::from_millis(10));
+        self
+    }
+
+    /// 启动后台刷写任务
+    pub fn spawn(self) -> InsertHandle<T> {
+        // 缓冲区满时 `write` 等待，形成背压
+        let (tx, rx) = mpsc::channel(self.max_rows * 2);
+        let task = tokio::spawn(self.run(rx));
+        InsertHandle { tx, task }
+    }
+
+    async fn run(self, mut rx: mpsc::Receiver<Msg<T>>) -> anyhow::Result<()> {
+        let mut buf: Vec<T> = Vec::with_capacity(self.max_rows);
+        let mut ticker = tokio::time::interval(self.period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker.tick().await;
+
+        loop {
+            tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(Msg::Row(row)) => {
+                        buf.push(row);
+                        if buf.len() >= self.max_rows {
+                            let _ = self.flush(&mut buf).await;
+                            ticker.reset();
+                        }
+                    }
+                    Some(Msg::Flush(done)) => {
+                        let _ = done.send(self.flush(&mut buf).await);
+                        ticker.reset();
+                    }
+                    None => return self.flush(&mut buf).await,
+                },
+                _ = ticker.tick() => {
+                    let _ = self.flush(&mut buf).await;
+                }
+            }
+        }
+    }
+
+    async fn flush(&self, buf: &mut Vec<T>) -> anyhow::Result<()> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        let rows = buf.len();
+        let data = super::encode_rows(buf);
+        buf.clear();
+        let ret = match data {
+            Ok(v) => self.client.insert_rows(&self.table, v).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &ret {
+            tracing::error!(err = ?e, table = self.table, rows = rows, "[olap::inserter] flush failed, rows dropped");
+        }
+        ret
+    }
+}
+
+/// 缓冲写入句柄
+pub struct InsertHandle<T> {
+    tx: mpsc::Sender<Msg<T>>,
+    task: JoinHandle<anyhow::Result<()>>,
+}
+
+impl<T> InsertHandle<T> {
+    /// 写入一行（缓冲区满时等待）
+    pub async fn write(&self, row: T) -> anyhow::Result<()> {
+        self.tx
+            .send(Msg::Row(row))
+            .await
+            .map_err(|_| anyhow!("olap: inserter closed"))
+    }
+
+    /// 立即刷写缓冲区
+    pub async fn flush(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(Msg::Flush(tx))
+            .await
+            .map_err(|_| anyhow!("olap: inserter closed"))?;
+        rx.await?
+    }
+
+    /// 关闭并写入剩余数据
+    pub async fn close(self) -> anyhow::Result<()> {
+        drop(self.tx);
+        self.task.await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use serde::{Deserialize, Serialize};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::olap;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Event {
+        id: i64,
+        name: String,
+    }
+
+    // 返回 (请求行, 请求体)
+    async fn read_request(conn: &mut tokio::net::TcpStream) -> (String, String) {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 4096];
+        loop {
+            let n = conn.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            let s = String::from_utf8_lossy(&buf).to_string();
+            if let Some(pos) = s.find("\r\n\r\n") {
+                let len = s[..pos]
+                    .lines()
+                    .find_map(|v| {
+                        let (k, v) = v.split_once(':')?;
+                        k.eq_ignore_ascii_case("content-length")
+                            .then(|| v.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if buf.len() >= pos + 4 + len {
+                    let line = s.lines().next().unwrap_or_default().to_string();
+                    return (line, s[pos + 4..pos + 4 + len].to_string());
+                }
+            }
+            if n == 0 {
+                return (String::new(), String::new());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut conn, _) = listener.accept().await.unwrap();
+                let (line, body) = read_request(&mut conn).await;
+                let resp = if body.starts_with("SELECT") {
+                    "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}\n".to_string()
+                } else {
+                    String::new()
+                };
+                recorded.lock().unwrap().push((line, body));
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    resp.len(),
+                    resp
+                );
+                conn.write_all(resp.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = olap::Client::new(format!("http://{}", addr)).database("analytics");
+
+        let rows: Vec<Event> = client
+            .select(
+                "SELECT id, name FROM events WHERE id > {id:UInt64};",
+                &[("id", "0")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].name, "b");
+        {
+            let reqs = requests.lock().unwrap();
+            let (line, body) = &reqs[0];
+            assert!(line.contains("database=analytics") && line.contains("param_id=0"));
+            assert_eq!(
+                body,
+                "SELECT id, name FROM events WHERE id > {id:UInt64} FORMAT JSONEachRow"
+            );
+        }
+
+        // 按行数刷写 + 关闭时刷写剩余数据
+        let inserter = client
+            .inserter::<Event>("events")
+            .max_rows(2)
+            .period(Duration::from_secs(60))
+            .spawn();
+        for id in 0..3 {
+            inserter
+                .write(Event {
+                    id,
+                    name: format!("e{}", id),
+                })
+                .await
+                .unwrap();
+        }
+        inserter.close().await.unwrap();
+
+        let reqs = requests.lock().unwrap();
+        let inserts: Vec<_> = reqs[1..]
+            .iter()
+            .map(|(_, body)| body.lines().count())
+            .collect();
+        assert_eq!(inserts, vec![2, 1]);
+        assert!(reqs[1]
+            .0
+            .contains("query=INSERT+INTO+events+FORMAT+JSONEachRow"));
+        assert_eq!(reqs[1].1.lines().next().unwrap(), r#"{"id":0,"name":"e0"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_insert_retry() {
+        // 第一次返回 503，重试时携带相同的去重 token
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut conn, _) = listener.accept().await.unwrap();
+                let (line, _) = read_request(&mut conn).await;
+                let n = {
+                    let mut reqs = recorded.lock().unwrap();
+                    reqs.push(line);
+                    reqs.len()
+                };
+                let status = if n == 1 {
+                    "503 Service Unavailable"
+                } else {
+                    "200 OK"
+                };
+                let resp = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                conn.write_all(resp.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client =
+            olap::Client::new(format!("http://{}", addr)).retry(3, Duration::from_millis(1));
+        let rows = vec![Event {
+            id: 1,
+            name: "a".to_string(),
+        }];
+        client.insert("events", &rows).await.unwrap();
+        client.insert("events", &rows).await.unwrap();
+
+        let reqs = requests.lock().unwrap();
+        assert_eq!(reqs.len(), 3);
+        let token = |line: &str| {
+            line.split(['?', '&', ' '])
+                .find_map(|v| v.strip_prefix("insert_deduplication_token="))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(token(&reqs[0]), token(&reqs[1]));
+        assert_ne!(token(&reqs[1]), token(&reqs[2]));
+    }
+}
@@ -0,0 +1,232 @@
+pub mod inserter;
+
+pub use inserter::{InsertHandle, Inserter};
+
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    helper::http::{self, Retry},
+    sql,
+};
+
+/// ClickHouse 客户端（HTTP 接口，数据格式为 `JSONEachRow`）
+///
+/// - 网络错误、429、502/503/504 时自动重试；写入时每批附带 `insert_deduplication_token`（重试时不变），
+///   需表开启去重（Replicated*MergeTree，或 MergeTree 设置 `non_replicated_deduplication_window`）才能避免重试导致的重复写入
+/// - 语句经由 `sql::set_sql_logger` 设置的日志输出，并可被 `SqlRecorder` 收集
+///
+/// # Examples
+///
+/// ```
+/// let ch = olap::Client::new("http://127.0.0.1:8123")
+///     .database("analytics")
+///     .auth("default", "");
+///
+/// ch.insert("events", &events).await?;
+///
+/// // 参数绑定：{name:Type}
+/// let rows: Vec<Pv> = ch
+///     .select(
+///         "SELECT page, count() AS pv FROM events WHERE day = {day:Date} GROUP BY page",
+///         &[("day", "2024-01-01")],
+///     )
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    endpoint: String,
+    database: Option<String>,
+    auth: Option<(String, String)>,
+    retry: Retry,
+}
+
+impl Client {
+    /// 默认：超时 30s，最多尝试 3 次，首次重试间隔 100ms（指数退避 + 随机抖动）
+    pub fn new(endpoint: impl AsRef<str>) -> Self {
+        Self {
+            http: http::build_client("olap", Duration::from_secs(30)),
+            endpoint: endpoint.as_ref().trim_end_matches('/').to_string(),
+            database: None,
+            auth: None,
+            retry: Retry {
+                attempts: 3,
+                backoff: Duration::from_millis(100),
+            },
+        }
+    }
+
+    /// 默认数据库
+    pub fn database(mut self, db: impl Into<String>) -> Self {
+        self.database = Some(db.into());
+        self
+    }
+
+    pub fn auth(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.auth = Some((user.into(), password.into()));
+        self
+    }
+
+    /// 单次请求超时
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.http = http::build_client("olap", d);
+        self
+    }
+
+    /// 最大尝试次数与首次重试间隔
+    pub fn retry(mut self, attempts: u32, backoff: Duration) -> Self {
+        self.retry = Retry {
+            attempts: attempts.max(1),
+            backoff,
+        };
+        self
+    }
+
+    /// 执行无返回值的语句（DDL 等）
+    pub async fn execute(&self, query: &str) -> anyhow::Result<()> {
+        self.send(query, None, &[]).await?;
+        Ok(())
+    }
+
+    /// 查询，结果按列名反序列化（语句末尾不需要 `FORMAT`）
+    pub async fn select<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        params: &[(&str, &str)],
+    ) -> anyhow::Result<Vec<T>> {
+        let query = format!("{} FORMAT JSONEachRow", query.trim().trim_end_matches(';'));
+        let body = self.send(&query, None, params).await?;
+        body.lines()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| serde_json::from_str(v).map_err(|e| anyhow!("olap: invalid row {}: {}", v, e)))
+            .collect()
+    }
+
+    /// 批量写入（单次请求）
+    pub async fn insert<T: Serialize>(&self, table: &str, rows: &[T]) -> anyhow::Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        self.insert_rows(table, encode_rows(rows)?).await
+    }
+
+    // 已编码的 JSONEachRow 数据；每批附带 `insert_deduplication_token`，重试时相同，
+    // 前一次实际已写入时由 ClickHouse 去重
+    async fn insert_rows(&self, table: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        let query = format!("INSERT INTO {} FORMAT JSONEachRow", table);
+        let token = uuid::Uuid::new_v4().to_string();
+        self.send(&query, Some((data, token)), &[]).await?;
+        Ok(())
+    }
+
+    /// 缓冲写入：按行数或时间间隔批量刷写，见 `Inserter`
+    pub fn inserter<T>(&self, table: impl Into<String>) -> Inserter<T>
+    where
+        T: Serialize + Send + 'static,
+    {
+        Inserter::new(self.clone(), table)
+    }
+
+    // 有数据时语句放在 URL 参数 `query` 中，否则作为请求体
+    async fn send(
+        &self,
+        query: &str,
+        data: Option<(Vec<u8>, String)>,
+        params: &[(&str, &str)],
+    ) -> anyhow::Result<String> {
+        // 写入语句附带行数
+        let trace = match &data {
+            Some((v, _)) => format!(
+                "{} -- {} rows",
+                query,
+                v.iter().filter(|b| **b == b'\n').count()
+            ),
+            None => query.to_string(),
+        };
+        let start = Instant::now();
+        let ret = self.send_with_retry(query, data, params).await;
+        match &ret {
+            Ok(_) => sql::trace_sql(trace, start.elapsed(), None),
+            Err(e) => sql::trace_sql(trace, start.elapsed(), Some(e)),
+        }
+        ret
+    }
+
+    async fn send_with_retry(
+        &self,
+        query: &str,
+        data: Option<(Vec<u8>, String)>,
+        params: &[(&str, &str)],
+    ) -> anyhow::Result<String> {
+        let (status, body) = self
+            .retry
+            .run(
+                "olap",
+                || self.request(query, data.clone(), params),
+                |ret| match ret {
+                    Ok((status, _)) => matches!(
+                        *status,
+                        StatusCode::TOO_MANY_REQUESTS
+                            | StatusCode::BAD_GATEWAY
+                            | StatusCode::SERVICE_UNAVAILABLE
+                            | StatusCode::GATEWAY_TIMEOUT
+                    ),
+                    Err(_) => true,
+                },
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(anyhow!("olap: {} {}", status, body.trim()));
+        }
+        Ok(body)
+    }
+
+    async fn request(
+        &self,
+        query: &str,
+        data: Option<(Vec<u8>, String)>,
+        params: &[(&str, &str)],
+    ) -> anyhow::Result<(StatusCode, String)> {
+        let mut url = reqwest::Url::parse(&self.endpoint)?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(db) = &self.database {
+                pairs.append_pair("database", db);
+            }
+            for (k, v) in params {
+                pairs.append_pair(&format!("param_{}", k), v);
+            }
+            if let Some((_, token)) = &data {
+                pairs.append_pair("query", query);
+                pairs.append_pair("insert_deduplication_token", token);
+            }
+        }
+
+        let mut req = self.http.post(url);
+        if let Some((user, password)) = &self.auth {
+            req = req.basic_auth(user, Some(password));
+        }
+        req = match data {
+            Some((v, _)) => req.body(v),
+            None => req.body(query.to_string()),
+        };
+
+        let resp = req.send().await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+        Ok((status, body))
+    }
+}
+
+fn encode_rows<T: Serialize>(rows: &[T]) -> anyhow::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    for row in rows {
+        serde_json::to_writer(&mut data, row)?;
+        data.push(b'\n');
+    }
+    Ok(data)
+}
@@ -0,0 +1,585 @@
+use std::io::Cursor;
+
+use anyhow::anyhow;
+use printpdf::{BuiltinFont, Line, Mm, PdfDocument, Point};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+// 1pt = 0.3528mm
+const PT: f32 = 0.3528;
+// 行高（字号的倍数）
+const LEADING: f32 = 1.4;
+// 表格单元格内边距（mm）
+const PADDING: f32 = 1.5;
+
+/// 表格列对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// 表格（跨页时在新页重复表头）
+///
+/// # Examples
+///
+/// ```
+/// let table = Table::new(&["商品", "数量", "金额"])
+///     .widths(&[3.0, 1.0, 1.0])
+///     .align(&[Align::Left, Align::Right, Align::Right])
+///     .row(vec!["咖啡".into(), "2".into(), "36.00".into()]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    widths: Vec<f32>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// 列宽比例，默认等宽
+    pub fn widths(mut self, widths: &[f32]) -> Self {
+        self.widths = widths.to_vec();
+        self
+    }
+
+    /// 列对齐方式，默认左对齐
+    pub fn align(mut self, aligns: &[Align]) -> Self {
+        self.aligns = aligns.to_vec();
+        self
+    }
+
+    pub fn row(mut self, row: Vec<String>) -> Self {
+        self.rows.push(row);
+        self
+    }
+
+    pub fn rows(mut self, rows: impl IntoIterator<Item = Vec<String>>) -> Self {
+        self.rows.extend(rows);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Block {
+    Heading(String),
+    Text(String),
+    Table(Table),
+    Space(f32),
+    Rule,
+}
+
+/// PDF 文档（回执、报表导出），内容按顺序排版，超出页面自动分页
+///
+/// - 内置字体仅支持拉丁字符，中文等需通过 `font` 嵌入 TTF/OTF 字体（完整嵌入，注意字体大小）
+/// - 设置页眉时每页顶部显示页眉、底部显示页码
+///
+/// # Examples
+///
+/// ```
+/// let doc = Document::new("订单回执")
+///     .font(std::fs::read("fonts/NotoSansSC-Regular.otf")?)
+///     .header("kr 商城")
+///     .heading("订单回执")
+///     .text(format!("订单号：{}\n下单时间：{}", order.no, order.created_at))
+///     .rule()
+///     .table(
+///         Table::new(&["商品", "数量", "金额"])
+///             .widths(&[3.0, 1.0, 1.0])
+///             .align(&[Align::Left, Align::Right, Align::Right])
+///             .rows(items.iter().map(|v| vec![v.name.clone(), v.qty.to_string(), v.amount.to_string()])),
+///     )
+///     .space(5.0)
+///     .text(format!("合计：{}", order.total));
+///
+/// // 写入文件或 HTTP 响应
+/// let mut file = tokio::fs::File::create("receipt.pdf").await?;
+/// doc.write_to(&mut file).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Document {
+    title: String,
+    font: Option<Vec<u8>>,
+    width: f32,
+    height: f32,
+    margin: f32,
+    font_size: f32,
+    header: Option<String>,
+    blocks: Vec<Block>,
+}
+
+impl Document {
+    /// 默认：A4、边距 15mm、字号 10pt
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            font: None,
+            width: 210.0,
+            height: 297.0,
+            margin: 15.0,
+            font_size: 10.0,
+            header: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// 嵌入字体（TTF/OTF 数据）
+    pub fn font(mut self, data: Vec<u8>) -> Self {
+        self.font = Some(data);
+        self
+    }
+
+    /// 页面尺寸（mm）
+    pub fn page(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// 页边距（mm）
+    pub fn margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// 正文字号（pt）
+    pub fn font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// 页眉（同时启用页码）
+    pub fn header(mut self, text: impl Into<String>) -> Self {
+        self.header = Some(text.into());
+        self
+    }
+
+    /// 标题（正文字号的 1.6 倍）
+    pub fn heading(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Heading(text.into()));
+        self
+    }
+
+    /// 段落（按换行符分行，超出宽度自动折行）
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Text(text.into()));
+        self
+    }
+
+    pub fn table(mut self, table: Table) -> Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    /// 空白（mm）
+    pub fn space(mut self, height: f32) -> Self {
+        self.blocks.push(Block::Space(height));
+        self
+    }
+
+    /// 分隔线
+    pub fn rule(mut self) -> Self {
+        self.blocks.push(Block::Rule);
+        self
+    }
+
+    /// 渲染为 PDF 数据（在阻塞线程中执行）
+    pub async fn render(self) -> anyhow::Result<Vec<u8>> {
+        tokio::task::spawn_blocking(move || self.render_blocking()).await?
+    }
+
+    /// 渲染并写入（文件、HTTP 响应 body 等），返回写入字节数
+    pub async fn write_to<W>(self, w: &mut W) -> anyhow::Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let data = self.render().await?;
+        w.write_all(&data).await?;
+        w.flush().await?;
+        Ok(data.len() as u64)
+    }
+
+    fn render_blocking(&self) -> anyhow::Result<Vec<u8>> {
+        let metrics = Metrics::new(self.font.as_deref())?;
+        let pages = self.layout(&metrics);
+
+        let (doc, page, layer) =
+            PdfDocument::new(&self.title, Mm(self.width), Mm(self.height), "content");
+        let font = match &self.font {
+            Some(v) => doc.add_external_font(Cursor::new(v))?,
+            None => doc.add_builtin_font(BuiltinFont::Helvetica)?,
+        };
+        for (i, ops) in pages.iter().enumerate() {
+            let (page, layer) = if i == 0 {
+                (page, layer)
+            } else {
+                doc.add_page(Mm(self.width), Mm(self.height), "content")
+            };
+            let layer = doc.get_page(page).get_layer(layer);
+            for op in ops {
+                match op {
+                    Op::Text { x, y, size, text } => {
+                        layer.use_text(text, *size, Mm(*x), Mm(*y), &font);
+                    }
+                    Op::Line { x1, x2, y } => layer.add_line(Line {
+                        points: vec![
+                            (Point::new(Mm(*x1), Mm(*y)), false),
+                            (Point::new(Mm(*x2), Mm(*y)), false),
+                        ],
+                        is_closed: false,
+                    }),
+                }
+            }
+        }
+        Ok(doc.save_to_bytes()?)
+    }
+
+    fn layout(&self, metrics: &Metrics) -> Vec<Vec<Op>> {
+        let mut l = Layout {
+            doc: self,
+            metrics,
+            pages: Vec::new(),
+            y: 0.0,
+        };
+        l.new_page();
+
+        let width = self.width - 2.0 * self.margin;
+        for block in &self.blocks {
+            match block {
+                Block::Heading(text) => {
+                    let size = self.font_size * 1.6;
+                    for line in metrics.wrap(text, size, width) {
+                        l.text(self.margin, size, line);
+                    }
+                    l.y -= size * PT * 0.5;
+                }
+                Block::Text(text) => {
+                    for line in metrics.wrap(text, self.font_size, width) {
+                        l.text(self.margin, self.font_size, line);
+                    }
+                }
+                Block::Table(table) => l.table(table, width),
+                Block::Space(h) => {
+                    if l.y - h < l.bottom() {
+                        l.new_page();
+                    } else {
+                        l.y -= h;
+                    }
+                }
+                Block::Rule => {
+                    l.ensure(2.0);
+                    l.y -= 1.0;
+                    l.line(self.margin, self.width - self.margin);
+                    l.y -= 1.0;
+                }
+            }
+        }
+
+        // 页码
+        let mut pages = l.pages;
+        if self.header.is_some() {
+            let total = pages.len();
+            let size = self.font_size * 0.8;
+            for (i, ops) in pages.iter_mut().enumerate() {
+                let text = format!("{} / {}", i + 1, total);
+                let x = (self.width - metrics.measure(&text, size)) / 2.0;
+                ops.push(Op::Text {
+                    x,
+                    y: self.margin - size * PT,
+                    size,
+                    text,
+                });
+            }
+        }
+        pages
+    }
+}
+
+// 绘制操作（坐标单位 mm，原点为页面左下角）
+#[derive(Debug)]
+enum Op {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        text: String,
+    },
+    Line {
+        x1: f32,
+        x2: f32,
+        y: f32,
+    },
+}
+
+struct Layout<'a> {
+    doc: &'a Document,
+    metrics: &'a Metrics<'a>,
+    pages: Vec<Vec<Op>>,
+    // 当前位置（距页面底部，mm）
+    y: f32,
+}
+
+impl Layout<'_> {
+    fn line_height(&self, size: f32) -> f32 {
+        size * PT * LEADING
+    }
+
+    fn bottom(&self) -> f32 {
+        match self.doc.header {
+            Some(_) => self.doc.margin + self.line_height(self.doc.font_size),
+            None => self.doc.margin,
+        }
+    }
+
+    fn new_page(&mut self) {
+        let mut ops = Vec::new();
+        self.y = self.doc.height - self.doc.margin;
+        if let Some(header) = &self.doc.header {
+            let size = self.doc.font_size * 0.8;
+            let y = self.y - size * PT;
+            ops.push(Op::Text {
+                x: self.doc.margin,
+                y,
+                size,
+                text: header.clone(),
+            });
+            ops.push(Op::Line {
+                x1: self.doc.margin,
+                x2: self.doc.width - self.doc.margin,
+                y: y - 1.5,
+            });
+            self.y = y - 1.5 - self.line_height(size);
+        }
+        self.pages.push(ops);
+    }
+
+    // 剩余高度不足时换页
+    fn ensure(&mut self, h: f32) {
+        if self.y - h < self.bottom() {
+            self.new_page();
+        }
+    }
+
+    fn push(&mut self, op: Op) {
+        self.pages.last_mut().unwrap().push(op);
+    }
+
+    fn text(&mut self, x: f32, size: f32, text: String) {
+        let h = self.line_height(size);
+        self.ensure(h);
+        // 基线位于行内偏下位置
+        let y = self.y - size * PT * 1.1;
+        self.push(Op::Text { x, y, size, text });
+        self.y -= h;
+    }
+
+    fn line(&mut self, x1: f32, x2: f32) {
+        let y = self.y;
+        self.push(Op::Line { x1, x2, y });
+    }
+
+    fn table(&mut self, table: &Table, width: f32) {
+        let cols = table.headers.len();
+        if cols == 0 {
+            return;
+        }
+        let weights: Vec<f32> = (0..cols)
+            .map(|i| table.widths.get(i).copied().unwrap_or(1.0).max(0.0))
+            .collect();
+        let sum: f32 = weights.iter().sum::<f32>().max(f32::EPSILON);
+        let widths: Vec<f32> = weights.iter().map(|v| width * v / sum).collect();
+
+        let (x1, x2) = (self.doc.margin, self.doc.margin + width);
+        let header = self.cells(&table.headers, &widths);
+        self.ensure(header.0 + self.row_height(table.rows.first(), &widths));
+        self.line(x1, x2);
+        self.row(table, &header, &widths);
+        self.line(x1, x2);
+
+        for row in &table.rows {
+            let cells = self.cells(row, &widths);
+            if self.y - cells.0 < self.bottom() {
+                self.new_page();
+                self.line(x1, x2);
+                self.row(table, &header, &widths);
+                self.line(x1, x2);
+            }
+            self.row(table, &cells, &widths);
+        }
+        self.line(x1, x2);
+        self.y -= self.line_height(self.doc.font_size) * 0.5;
+    }
+
+    fn row_height(&self, row: Option<&Vec<String>>, widths: &[f32]) -> f32 {
+        row.map(|v| self.cells(v, widths).0).unwrap_or(0.0)
+    }
+
+    // 单元格折行，返回（行高，各单元格的行）
+    fn cells(&self, row: &[String], widths: &[f32]) -> (f32, Vec<Vec<String>>) {
+        let size = self.doc.font_size;
+        let cells: Vec<Vec<String>> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let text = row.get(i).map(|v| v.as_str()).unwrap_or("");
+                self.metrics.wrap(text, size, w - 2.0 * PADDING)
+            })
+            .collect();
+        let lines = cells.iter().map(|v| v.len()).max().unwrap_or(1).max(1);
+        (lines as f32 * self.line_height(size) + 2.0 * PADDING, cells)
+    }
+
+    fn row(&mut self, table: &Table, cells: &(f32, Vec<Vec<String>>), widths: &[f32]) {
+        let size = self.doc.font_size;
+        let top = self.y - PADDING;
+        let mut x = self.doc.margin;
+        for (i, lines) in cells.1.iter().enumerate() {
+            let w = widths[i];
+            let align = table.aligns.get(i).copied().unwrap_or_default();
+            for (n, line) in lines.iter().enumerate() {
+                let tw = self.metrics.measure(line, size);
+                let tx = match align {
+                    Align::Left => x + PADDING,
+                    Align::Center => x + (w - tw) / 2.0,
+                    Align::Right => x + w - PADDING - tw,
+                };
+                let y = top - n as f32 * self.line_height(size) - size * PT * 1.1;
+                self.push(Op::Text {
+                    x: tx,
+                    y,
+                    size,
+                    text: line.clone(),
+                });
+            }
+            x += w;
+        }
+        self.y -= cells.0;
+    }
+}
+
+// 字形宽度（用于折行与对齐）
+struct Metrics<'a> {
+    face: Option<ttf_parser::Face<'a>>,
+}
+
+impl<'a> Metrics<'a> {
+    fn new(font: Option<&'a [u8]>) -> anyhow::Result<Self> {
+        let face = match font {
+            Some(v) => Some(
+                ttf_parser::Face::parse(v, 0).map_err(|e| anyhow!("pdf: invalid font: {}", e))?,
+            ),
+            None => None,
+        };
+        Ok(Self { face })
+    }
+
+    // 文本宽度（mm）
+    fn measure(&self, text: &str, size: f32) -> f32 {
+        text.chars().map(|c| self.char_width(c, size)).sum()
+    }
+
+    fn char_width(&self, c: char, size: f32) -> f32 {
+        let em = match &self.face {
+            Some(face) => face
+                .glyph_index(c)
+                .and_then(|id| face.glyph_hor_advance(id))
+                .map(|v| v as f32 / face.units_per_em() as f32)
+                .unwrap_or(1.0),
+            // 内置字体按 Helvetica 平均字宽估算
+            None if c.is_ascii() => 0.556,
+            None => 1.0,
+        };
+        em * size * PT
+    }
+
+    // 按宽度折行：优先在空格处断开，中文等按字符断开
+    fn wrap(&self, text: &str, size: f32, width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        for para in text.lines() {
+            let mut line = String::new();
+            let mut w = 0.0;
+            for c in para.chars() {
+                let cw = self.char_width(c, size);
+                if w + cw > width && !line.is_empty() {
+                    let rest = match line.rfind(' ') {
+                        Some(i) if c.is_ascii_alphanumeric() && i > 0 => line.split_off(i + 1),
+                        _ => String::new(),
+                    };
+                    lines.push(line.trim_end().to_string());
+                    w = self.measure(&rest, size);
+                    line = rest;
+                    if c == ' ' {
+                        continue;
+                    }
+                }
+                line.push(c);
+                w += cw;
+            }
+            lines.push(line);
+        }
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::{Align, Document, Metrics, Table};
+
+    #[test]
+    fn test_wrap() {
+        let m = Metrics::new(None).unwrap();
+        // 10pt ASCII 约 1.96mm/字符
+        let lines = m.wrap("hello world foo bar\nnext", 10.0, 25.0);
+        assert_eq!(lines, vec!["hello world", "foo bar", "next"]);
+        let lines = m.wrap("中文按字符断开折行", 10.0, 15.0);
+        assert_eq!(lines, vec!["中文按字", "符断开折", "行"]);
+        assert_eq!(m.wrap("", 10.0, 15.0), vec![""]);
+    }
+
+    #[tokio::test]
+    async fn test_render() {
+        let table = Table::new(&["Item", "Qty", "Amount"])
+            .widths(&[3.0, 1.0, 1.0])
+            .align(&[Align::Left, Align::Right, Align::Right])
+            .rows((0..100).map(|i| vec![format!("item {}", i), "1".into(), "9.90".into()]));
+        let doc = Document::new("receipt")
+            .header("kr")
+            .heading("Receipt")
+            .text("Order: 20240101")
+            .rule()
+            .table(table)
+            .space(5.0)
+            .text("Total: 990.00");
+
+        // 表格跨页，页码
+        let metrics = Metrics::new(None).unwrap();
+        let pages = doc.layout(&metrics);
+        assert!(pages.len() > 1);
+        let ops = format!("{:?}", pages[1]);
+        assert!(ops.contains("\"Item\""));
+        assert!(ops.contains(&format!("\"2 / {}\"", pages.len())));
+
+        let mut buf = Vec::new();
+        let n = doc.write_to(&mut buf).await.unwrap();
+        assert_eq!(n as usize, buf.len());
+        assert!(buf.starts_with(b"%PDF"));
+
+        assert!(Document::new("x")
+            .font(b"bad".to_vec())
+            .render()
+            .await
+            .is_err());
+    }
+}
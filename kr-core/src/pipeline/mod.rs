@@ -0,0 +1,522 @@
+pub mod source;
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use futures::{stream::BoxStream, Stream, StreamExt};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+/// 出错时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// 停止读取并丢弃未处理的数据，返回第一个错误
+    Abort,
+    /// 记录日志并跳过，失败数超过 max 时按 `Abort` 处理
+    Skip { max: u64 },
+}
+
+/// 写入目标（批量）
+pub trait Sink<T>: Send + Sync {
+    fn write(&self, batch: Vec<T>) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+/// 基于闭包的写入目标
+///
+/// # Examples
+///
+/// ```
+/// let sink = |rows: Vec<model::Order>| {
+///     let client = client.clone();
+///     async move {
+///         client.upsert("orders", &rows).await?;
+///         Ok(())
+///     }
+/// };
+/// ```
+impl<T, F, Fut> Sink<T> for F
+where
+    F: Fn(Vec<T>) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<()>> + Send,
+{
+    fn write(&self, batch: Vec<T>) -> impl Future<Output = anyhow::Result<()>> + Send {
+        self(batch)
+    }
+}
+
+/// 处理进度
+#[derive(Debug, Default)]
+pub struct Progress {
+    read: AtomicU64,
+    skipped: AtomicU64,
+    written: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl Progress {
+    /// 从数据源读取的条数
+    pub fn read(&self) -> u64 {
+        self.read.load(Ordering::Relaxed)
+    }
+
+    /// 被处理阶段过滤（返回 `None`）的条数
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// 写入成功的条数
+    pub fn written(&self) -> u64 {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    /// 失败的条数（写入失败时按整批计）
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// 执行结果
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub read: u64,
+    pub skipped: u64,
+    pub written: u64,
+    pub failed: u64,
+    /// 是否因关闭信号提前结束
+    pub cancelled: bool,
+    pub elapsed: Duration,
+}
+
+struct Ctx {
+    name: String,
+    capacity: usize,
+    on_error: OnError,
+    shutdown: CancellationToken,
+    // 停止读取数据源（关闭或中止）
+    stop: CancellationToken,
+    abort: CancellationToken,
+    error: Mutex<Option<anyhow::Error>>,
+    progress: Arc<Progress>,
+    report_every: Option<Duration>,
+}
+
+impl Ctx {
+    fn fail(&self, err: anyhow::Error, n: u64) {
+        let failed = self.progress.failed.fetch_add(n, Ordering::Relaxed) + n;
+        tracing::warn!(pipeline = self.name, err = ?err, failed = failed, "[pipeline] item failed");
+
+        let err = match self.on_error {
+            OnError::Abort => err,
+            OnError::Skip { max } if failed > max => {
+                anyhow!(
+                    "pipeline: too many failures ({} > {}): {}",
+                    failed,
+                    max,
+                    err
+                )
+            }
+            OnError::Skip { .. } => return,
+        };
+        self.error.lock().unwrap().get_or_insert(err);
+        self.abort.cancel();
+        self.stop.cancel();
+    }
+
+    fn aborted(&self) -> bool {
+        self.abort.is_cancelled()
+    }
+}
+
+/// 批处理管道：数据源 → N 个并行处理阶段 → 批量写入，适用于回填、迁移脚本
+///
+/// - 各阶段之间使用有界 channel 连接（背压），阶段内并发处理时不保证顺序
+/// - 收到关闭信号后停止读取数据源，已读取的数据处理并写入后返回（`Report::cancelled` 为 true）
+///
+/// # Examples
+///
+/// ```
+/// let shutdown = worker::Shutdown::new();
+///
+/// let report = Pipeline::new("backfill_order_es")
+///     .capacity(1000)
+///     .on_error(OnError::Skip { max: 100 })
+///     .shutdown(shutdown.clone())
+///     .report_every(Duration::from_secs(10))
+///     .source(source::paged(0, move |last_id| {
+///         let pool = pool.clone();
+///         async move {
+///             let rows = find_orders_after(&pool, last_id, 1000).await?;
+///             let next = rows.last().map(|v| v.id);
+///             Ok((rows, next))
+///         }
+///     }))
+///     .stage(8, |order| async move { Ok(Some(to_document(order).await?)) })
+///     .sink(500, |docs: Vec<OrderDoc>| {
+///         let client = client.clone();
+///         async move {
+///             client.upsert("orders", &docs).await?;
+///             Ok(())
+///         }
+///     })
+///     .await?;
+/// tracing::info!(report = ?report, "backfill done");
+/// ```
+pub struct Pipeline {
+    name: String,
+    capacity: usize,
+    on_error: OnError,
+    shutdown: CancellationToken,
+    report_every: Option<Duration>,
+}
+
+impl Pipeline {
+    /// 默认：channel 容量 1024，出错时中止
+    pub fn new(name: impl AsRef<str>) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            capacity: 1024,
+            on_error: OnError::Abort,
+            shutdown: CancellationToken::new(),
+            report_every: None,
+        }
+    }
+
+    /// 阶段之间 channel 的容量
+    pub fn capacity(mut self, n: usize) -> Self {
+        self.capacity = n.max(1);
+        self
+    }
+
+    pub fn on_error(mut self, policy: OnError) -> Self {
+        self.on_error = policy;
+        self
+    }
+
+    /// 关闭信号
+    pub fn shutdown(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    /// 定期输出进度日志
+    pub fn report_every(mut self, d: Duration) -> Self {
+        self.report_every = Some(d);
+        self
+    }
+
+    /// 数据源（如：sqlx 的 `fetch`、`source::paged`、`source::lines`、`source::redis_scan`）
+    pub fn source<T, E, S>(self, stream: S) -> Flow<T>
+    where
+        T: Send + 'static,
+        E: Into<anyhow::Error>,
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+    {
+        let stop = self.shutdown.child_token();
+        let ctx = Arc::new(Ctx {
+            name: self.name,
+            capacity: self.capacity,
+            on_error: self.on_error,
+            shutdown: self.shutdown,
+            stop: stop.clone(),
+            abort: CancellationToken::new(),
+            error: Mutex::new(None),
+            progress: Arc::new(Progress::default()),
+            report_every: self.report_every,
+        });
+
+        let c = ctx.clone();
+        let stream = stream
+            .take_until(stop.cancelled_owned())
+            .filter_map(move |v| {
+                let v = match v {
+                    Ok(v) => {
+                        c.progress.read.fetch_add(1, Ordering::Relaxed);
+                        Some(v)
+                    }
+                    Err(e) => {
+                        c.fail(e.into(), 1);
+                        None
+                    }
+                };
+                futures::future::ready(v)
+            })
+            .boxed();
+        Flow {
+            ctx,
+            stream,
+            stages: Vec::new(),
+        }
+    }
+}
+
+/// 管道中的数据流，见 `Pipeline`
+pub struct Flow<T> {
+    ctx: Arc<Ctx>,
+    stream: BoxStream<'static, T>,
+    // 各处理阶段的任务，在 sink 结束时等待并检查 panic
+    stages: Vec<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> Flow<T> {
+    pub fn progress(&self) -> Arc<Progress> {
+        self.ctx.progress.clone()
+    }
+
+    /// 处理阶段：concurrency 个并发处理，返回 `None` 时丢弃该条数据
+    pub fn stage<U, F, Fut>(self, concurrency: usize, f: F) -> Flow<U>
+    where
+        U: Send + 'static,
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<Option<U>>> + Send + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel(self.ctx.capacity);
+        let ctx = self.ctx.clone();
+        let input = self.stream;
+        let mut stages = self.stages;
+        stages.push(tokio::spawn(async move {
+            let c = ctx.clone();
+            // 中止后丢弃未处理的数据
+            let mut out = input
+                .filter(move |_| futures::future::ready(!c.aborted()))
+                .map(f)
+                .buffer_unordered(concurrency.max(1));
+            while let Some(ret) = out.next().await {
+                match ret {
+                    Ok(Some(v)) => {
+                        if tx.send(v).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => {
+                        ctx.progress.skipped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => ctx.fail(e, 1),
+                }
+            }
+        }));
+
+        let stream = futures::stream::poll_fn(move |cx| rx.poll_recv(cx)).boxed();
+        Flow {
+            ctx: self.ctx,
+            stream,
+            stages,
+        }
+    }
+
+    /// 按 batch_size 分批写入（单个写入任务），执行直到数据源结束、收到关闭信号或中止
+    pub async fn sink<K: Sink<T>>(self, batch_size: usize, sink: K) -> anyhow::Result<Report> {
+        let start = Instant::now();
+        let ctx = self.ctx;
+        tracing::info!(pipeline = ctx.name, "[pipeline] started");
+
+        let done = CancellationToken::new();
+        if let Some(every) = ctx.report_every {
+            let (ctx, done) = (ctx.clone(), done.clone());
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(every);
+                ticker.tick().await;
+                loop {
+                    tokio::select! {
+                        _ = done.cancelled() => break,
+                        _ = ticker.tick() => {
+                            let p = &ctx.progress;
+                            tracing::info!(
+                                pipeline = ctx.name,
+                                read = p.read(),
+                                skipped = p.skipped(),
+                                written = p.written(),
+                                failed = p.failed(),
+                                "[pipeline] progress"
+                            );
+                        }
+                    }
+                }
+            });
+        }
+
+        let mut batches = self.stream.chunks(batch_size.max(1));
+        while let Some(batch) = batches.next().await {
+            // 中止后继续消费以便上游退出
+            if ctx.aborted() {
+                continue;
+            }
+            let n = batch.len() as u64;
+            match sink.write(batch).await {
+                Ok(_) => {
+                    ctx.progress.written.fetch_add(n, Ordering::Relaxed);
+                }
+                Err(e) => ctx.fail(e, n),
+            }
+        }
+        done.cancel();
+
+        // 阶段 panic 时其输出提前结束，此处将其作为错误返回
+        for handle in self.stages {
+            if let Err(e) = handle.await {
+                let err = match e.try_into_panic() {
+                    Ok(panic) => {
+                        let msg = panic
+                            .downcast_ref::<&str>()
+                            .map(|v| v.to_string())
+                            .or_else(|| panic.downcast_ref::<String>().cloned())
+                            .unwrap_or_default();
+                        anyhow!("pipeline: stage panicked: {}", msg)
+                    }
+                    Err(e) => anyhow!("pipeline: stage cancelled: {}", e),
+                };
+                tracing::error!(pipeline = ctx.name, err = ?err, "[pipeline] stage failed");
+                ctx.error.lock().unwrap().get_or_insert(err);
+                ctx.abort.cancel();
+                ctx.stop.cancel();
+            }
+        }
+
+        let p = &ctx.progress;
+        let report = Report {
+            read: p.read(),
+            skipped: p.skipped(),
+            written: p.written(),
+            failed: p.failed(),
+            cancelled: ctx.shutdown.is_cancelled(),
+            elapsed: start.elapsed(),
+        };
+        tracing::info!(pipeline = ctx.name, report = ?report, "[pipeline] finished");
+
+        let err = ctx.error.lock().unwrap().take();
+        match err {
+            Some(e) => Err(e),
+            None => Ok(report),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use anyhow::anyhow;
+
+    use crate::pipeline::{source, OnError, Pipeline};
+
+    #[tokio::test]
+    async fn test_pipeline() {
+        let out = Arc::new(Mutex::new(Vec::new()));
+        let o = out.clone();
+        let report = Pipeline::new("test")
+            .capacity(4)
+            .source(source::iter(1..=100))
+            .stage(
+                4,
+                |v: i64| async move { Ok((v % 2 == 0).then_some(v * 10)) },
+            )
+            .stage(2, |v: i64| async move { Ok(Some(v.to_string())) })
+            .sink(7, move |batch: Vec<String>| {
+                let o = o.clone();
+                async move {
+                    assert!(batch.len() <= 7);
+                    o.lock().unwrap().extend(batch);
+                    Ok(())
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            (report.read, report.skipped, report.written, report.failed),
+            (100, 50, 50, 0)
+        );
+        assert!(!report.cancelled);
+        let mut out: Vec<i64> = out
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|v| v.parse().unwrap())
+            .collect();
+        out.sort();
+        assert_eq!(out, (1..=50).map(|v| v * 20).collect::<Vec<_>>());
+
+        // 跳过失败，超过上限后中止
+        let ret = Pipeline::new("skip")
+            .on_error(OnError::Skip { max: 2 })
+            .source(source::iter(1..=10))
+            .stage(1, |v: i64| async move {
+                match v {
+                    3 | 5 => Err(anyhow!("bad {}", v)),
+                    _ => Ok(Some(v)),
+                }
+            })
+            .sink(100, |_: Vec<i64>| async { Ok(()) })
+            .await
+            .unwrap();
+        assert_eq!((ret.written, ret.failed), (8, 2));
+
+        let flow = Pipeline::new("abort")
+            .source(source::iter(1..=10))
+            .stage(1, |v: i64| async move { Ok(Some(v)) });
+        let progress = flow.progress();
+        let ret = flow
+            .sink(1, |batch: Vec<i64>| async move {
+                match batch[0] {
+                    4 => Err(anyhow!("sink down")),
+                    _ => Ok(()),
+                }
+            })
+            .await;
+        assert!(ret.unwrap_err().to_string().contains("sink down"));
+        assert_eq!((progress.written(), progress.failed()), (3, 1));
+    }
+
+    #[tokio::test]
+    async fn test_stage_panic() {
+        // 即使策略为跳过，阶段 panic 也返回错误
+        let ret = Pipeline::new("panic")
+            .on_error(OnError::Skip { max: 100 })
+            .source(source::iter(1..=100))
+            .stage(1, |v: i64| async move {
+                if v == 10 {
+                    panic!("boom");
+                }
+                Ok(Some(v))
+            })
+            .stage(2, |v: i64| async move { Ok(Some(v)) })
+            .sink(5, |_: Vec<i64>| async { Ok(()) })
+            .await;
+        let err = ret.unwrap_err().to_string();
+        assert!(err.contains("stage panicked: boom"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        let shutdown = crate::worker::Shutdown::new();
+        let token = shutdown.clone();
+        let report = Pipeline::new("shutdown")
+            .shutdown(shutdown.clone())
+            .source(source::iter(0..))
+            .stage(2, move |v: u64| {
+                let token = token.clone();
+                async move {
+                    if v == 50 {
+                        token.cancel();
+                    }
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    Ok(Some(v))
+                }
+            })
+            .sink(10, |_: Vec<u64>| async { Ok(()) })
+            .await
+            .unwrap();
+        assert!(report.cancelled);
+        assert!(report.read >= 51);
+        // 已读取的数据全部写入
+        assert_eq!(report.written, report.read);
+    }
+}
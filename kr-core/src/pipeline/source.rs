@@ -0,0 +1,193 @@
+use std::{future::Future, path::PathBuf};
+
+use anyhow::anyhow;
+use futures::{stream, Stream, StreamExt};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+
+use crate::helper::redkit::Redis;
+
+/// 内存数据
+pub fn iter<I>(items: I) -> impl Stream<Item = anyhow::Result<I::Item>> + Send + 'static
+where
+    I: IntoIterator + Send + 'static,
+    I::IntoIter: Send,
+{
+    stream::iter(items.into_iter().map(Ok))
+}
+
+/// 分页（游标）读取：f(cursor) 返回（本页数据，下一页游标），下一页游标为 `None` 时结束
+///
+/// # Examples
+///
+/// ```
+/// // 按主键游标分页
+/// let src = source::paged(0, move |last_id| {
+///     let pool = pool.clone();
+///     async move {
+///         let stmt = Query::select()
+///             .columns([table::Order::Id, table::Order::Amount])
+///             .from(table::Order::Table)
+///             .and_where(Expr::col(table::Order::Id).gt(last_id))
+///             .order_by(table::Order::Id, Order::Asc)
+///             .limit(1000)
+///             .to_owned();
+///         let rows = mysql::find_all::<_, model::Order>(&pool, stmt, None).await?;
+///         let next = rows.last().map(|v| v.id);
+///         Ok((rows, next))
+///     }
+/// });
+/// ```
+pub fn paged<C, T, F, Fut>(
+    cursor: C,
+    f: F,
+) -> impl Stream<Item = anyhow::Result<T>> + Send + 'static
+where
+    C: Send + 'static,
+    T: Send + 'static,
+    F: FnMut(C) -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<(Vec<T>, Option<C>)>> + Send,
+{
+    stream::unfold((Some(cursor), f), |(cursor, mut f)| async move {
+        let cursor = cursor?;
+        let (items, next) = match f(cursor).await {
+            Ok((items, next)) => (items.into_iter().map(Ok).collect::<Vec<_>>(), next),
+            // 出错后结束
+            Err(e) => (vec![Err(e)], None),
+        };
+        Some((stream::iter(items), (next, f)))
+    })
+    .flatten()
+}
+
+/// 按行读取文件（不含换行符）
+pub fn lines(
+    path: impl Into<PathBuf>,
+) -> impl Stream<Item = anyhow::Result<String>> + Send + 'static {
+    enum State {
+        Init(PathBuf),
+        Reading(Lines<BufReader<tokio::fs::File>>),
+        Done,
+    }
+
+    stream::unfold(State::Init(path.into()), |state| async move {
+        let mut lines = match state {
+            State::Init(path) => match tokio::fs::File::open(&path).await {
+                Ok(f) => BufReader::new(f).lines(),
+                Err(e) => {
+                    let e = anyhow!("pipeline/source: open {}: {}", path.display(), e);
+                    return Some((Err(e), State::Done));
+                }
+            },
+            State::Reading(lines) => lines,
+            State::Done => return None,
+        };
+        match lines.next_line().await {
+            Ok(Some(line)) => Some((Ok(line), State::Reading(lines))),
+            Ok(None) => None,
+            Err(e) => Some((Err(e.into()), State::Done)),
+        }
+    })
+}
+
+/// SCAN 遍历匹配的 key（可能重复，处理需幂等；仅支持单节点）
+///
+/// # Examples
+///
+/// ```
+/// let src = source::redis_scan(redis.clone(), "shop:session:*", 500);
+/// ```
+pub fn redis_scan(
+    redis: Redis,
+    pattern: impl Into<String>,
+    count: usize,
+) -> impl Stream<Item = anyhow::Result<String>> + Send + 'static {
+    let pattern = pattern.into();
+    stream::unfold(Some(0u64), move |cursor| {
+        let redis = redis.clone();
+        let pattern = pattern.clone();
+        async move {
+            let cursor = cursor?;
+            let ret: anyhow::Result<(u64, Vec<String>)> = async {
+                match &redis {
+                    Redis::Single(pool) => {
+                        let mut conn = pool.get().await?;
+                        Ok(redis::cmd("SCAN")
+                            .arg(cursor)
+                            .arg("MATCH")
+                            .arg(&pattern)
+                            .arg("COUNT")
+                            .arg(count.max(1))
+                            .query_async(&mut *conn)
+                            .await?)
+                    }
+                    Redis::Cluster(_) => {
+                        Err(anyhow!("pipeline/source: scan is not supported on cluster"))
+                    }
+                }
+            }
+            .await;
+            match ret {
+                Ok((next, keys)) => {
+                    let next = (next != 0).then_some(next);
+                    Some((
+                        stream::iter(keys.into_iter().map(Ok).collect::<Vec<_>>()),
+                        next,
+                    ))
+                }
+                Err(e) => Some((stream::iter(vec![Err(e)]), None)),
+            }
+        }
+    })
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use redis::AsyncCommands;
+
+    use crate::{helper, helper::redkit::Redis, pipeline::source, redix};
+
+    #[tokio::test]
+    async fn test_paged() {
+        let items: Vec<i64> = source::paged(0, |last: i64| async move {
+            let rows: Vec<i64> = (last + 1..=(last + 3).min(10)).collect();
+            let next = rows.last().copied().filter(|v| *v < 10);
+            Ok((rows, next))
+        })
+        .map(|v| v.unwrap())
+        .collect()
+        .await;
+        assert_eq!(items, (1..=10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_lines() {
+        let path = std::env::temp_dir().join(format!("kr_lines_{}", helper::nonce(8)));
+        std::fs::write(&path, "a\nb\n\nc").unwrap();
+        let lines: Vec<String> = source::lines(&path).map(|v| v.unwrap()).collect().await;
+        assert_eq!(lines, vec!["a", "b", "", "c"]);
+        std::fs::remove_file(&path).unwrap();
+
+        let ret: Vec<_> = source::lines(&path).collect().await;
+        assert_eq!(ret.len(), 1);
+        assert!(ret[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_redis_scan() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let mut conn = pool.get().await.unwrap();
+        for i in 0..25 {
+            let _: () = conn.set(format!("session:{}", i), 1).await.unwrap();
+        }
+        let _: () = conn.set("user:1", 1).await.unwrap();
+
+        let keys: Vec<String> = source::redis_scan(Redis::Single(pool.clone()), "session:*", 10)
+            .map(|v| v.unwrap())
+            .collect()
+            .await;
+        assert_eq!(keys.len(), 25);
+        assert!(keys.iter().all(|v| v.starts_with("session:")));
+    }
+}
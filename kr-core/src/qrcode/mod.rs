@@ -0,0 +1,259 @@
+use std::{fmt::Write, io::Cursor};
+
+use anyhow::anyhow;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use image::{imageops, DynamicImage, ImageFormat, Rgba, RgbaImage};
+
+pub use ::qrcode::EcLevel;
+use ::qrcode::{Color, QrCode};
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Svg,
+}
+
+/// 二维码参数
+#[derive(Debug, Clone)]
+pub struct Options {
+    format: Format,
+    size: u32,
+    margin: u32,
+    level: EcLevel,
+    logo: Option<Vec<u8>>,
+    logo_ratio: f32,
+}
+
+/// 默认：PNG、256px、边距 4 个模块、纠错级别 M
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            format: Format::Png,
+            size: 256,
+            margin: 4,
+            level: EcLevel::M,
+            logo: None,
+            logo_ratio: 0.2,
+        }
+    }
+}
+
+impl Options {
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 图片边长（像素），内容过多放不下时按实际需要放大
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// 静区宽度（模块数，标准建议不小于 4）
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// 纠错级别
+    pub fn level(mut self, level: EcLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// 中心 logo（JPEG/PNG 等图片数据），`ratio` 为 logo 占边长的比例（最大 0.3）；
+    /// 设置 logo 时纠错级别自动提升为 H
+    pub fn logo(mut self, data: Vec<u8>, ratio: f32) -> Self {
+        self.logo = Some(data);
+        self.logo_ratio = ratio.clamp(0.05, 0.3);
+        self
+    }
+}
+
+/// 生成二维码，返回 PNG 或 SVG 数据
+///
+/// # Examples
+///
+/// ```
+/// // 支付二维码
+/// let png = qrcode::generate(&pay_url, &Options::default().size(300))?;
+///
+/// // 邀请码：带 logo 的 SVG
+/// let svg = qrcode::generate(
+///     &invite_url,
+///     &Options::default()
+///         .format(Format::Svg)
+///         .logo(std::fs::read("logo.png")?, 0.2),
+/// )?;
+/// ```
+pub fn generate(content: impl AsRef<[u8]>, opts: &Options) -> anyhow::Result<Vec<u8>> {
+    let level = if opts.logo.is_some() {
+        EcLevel::H
+    } else {
+        opts.level
+    };
+    let code = QrCode::with_error_correction_level(content, level)
+        .map_err(|e| anyhow!("qrcode: encode failed: {}", e))?;
+    let logo = match &opts.logo {
+        Some(v) => Some(image::load_from_memory(v)?),
+        None => None,
+    };
+
+    match opts.format {
+        Format::Png => png(&code, opts, logo),
+        Format::Svg => svg(&code, opts, logo),
+    }
+}
+
+fn png(code: &QrCode, opts: &Options, logo: Option<DynamicImage>) -> anyhow::Result<Vec<u8>> {
+    let width = code.width() as u32;
+    let total = width + 2 * opts.margin;
+    // 模块按整数像素绘制（边缘清晰），剩余像素均分到两侧
+    let scale = (opts.size / total).max(1);
+    let size = opts.size.max(total * scale);
+    let offset = (size - width * scale) / 2;
+
+    let mut img = RgbaImage::from_pixel(size, size, Rgba([255, 255, 255, 255]));
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != Color::Dark {
+            continue;
+        }
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        for dy in 0..scale {
+            for dx in 0..scale {
+                img.put_pixel(
+                    offset + x * scale + dx,
+                    offset + y * scale + dy,
+                    Rgba([0, 0, 0, 255]),
+                );
+            }
+        }
+    }
+
+    if let Some(logo) = logo {
+        let side = (size as f32 * opts.logo_ratio) as u32;
+        let logo = logo.thumbnail(side, side).to_rgba8();
+        // logo 四周留白，避免与模块粘连
+        let pad = (side / 10).max(2);
+        let bg = RgbaImage::from_pixel(
+            logo.width() + 2 * pad,
+            logo.height() + 2 * pad,
+            Rgba([255, 255, 255, 255]),
+        );
+        let x = (size - bg.width()) / 2;
+        let y = (size - bg.height()) / 2;
+        imageops::overlay(&mut img, &bg, x as i64, y as i64);
+        imageops::overlay(&mut img, &logo, (x + pad) as i64, (y + pad) as i64);
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, ImageFormat::Png)?;
+    Ok(buf.into_inner())
+}
+
+fn svg(code: &QrCode, opts: &Options, logo: Option<DynamicImage>) -> anyhow::Result<Vec<u8>> {
+    let width = code.width() as u32;
+    let total = width + 2 * opts.margin;
+
+    // 坐标以模块为单位，通过 viewBox 缩放到指定尺寸
+    let mut s = String::new();
+    write!(
+        s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {total} {total}" shape-rendering="crispEdges">"#,
+        size = opts.size,
+        total = total
+    )?;
+    s.push_str(r##"<rect width="100%" height="100%" fill="#ffffff"/>"##);
+    s.push_str(r##"<path fill="#000000" d=""##);
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            write!(s, "M{},{}h1v1h-1z", x + opts.margin, y + opts.margin)?;
+        }
+    }
+    s.push_str(r#""/>"#);
+
+    if let Some(logo) = logo {
+        let side = total as f32 * opts.logo_ratio;
+        let pad = side / 10.0;
+        let x = (total as f32 - side) / 2.0;
+
+        let mut buf = Cursor::new(Vec::new());
+        logo.write_to(&mut buf, ImageFormat::Png)?;
+        write!(
+            s,
+            r##"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="#ffffff"/>"##,
+            x - pad,
+            x - pad,
+            side + 2.0 * pad,
+            side + 2.0 * pad
+        )?;
+        write!(
+            s,
+            r#"<image x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" href="data:image/png;base64,{}"/>"#,
+            x,
+            x,
+            side,
+            side,
+            BASE64_STANDARD.encode(buf.into_inner())
+        )?;
+    }
+    s.push_str("</svg>");
+
+    Ok(s.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+
+    use crate::qrcode::{self, EcLevel, Format, Options};
+
+    #[test]
+    fn test_generate() {
+        let png = qrcode::generate("https://example.com/pay?id=1", &Options::default()).unwrap();
+        let img = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(img.dimensions(), (256, 256));
+        // 静区为白色
+        assert_eq!(img.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+
+        // 尺寸不足时放大
+        let png = qrcode::generate("hello", &Options::default().size(10).margin(2)).unwrap();
+        let img = image::load_from_memory(&png).unwrap();
+        assert_eq!(img.width(), 21 + 4);
+
+        let svg = qrcode::generate(
+            "hello",
+            &Options::default().format(Format::Svg).level(EcLevel::L),
+        )
+        .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="0 0 29 29""#));
+        assert!(svg.ends_with("</svg>"));
+
+        // logo
+        let mut buf = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(40, 40, Rgba([255, 0, 0, 255])))
+            .write_to(&mut buf, ImageFormat::Png)
+            .unwrap();
+        let logo = buf.into_inner();
+        let png = qrcode::generate("hello", &Options::default().logo(logo.clone(), 0.2)).unwrap();
+        let img = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(img.get_pixel(128, 128), &Rgba([255, 0, 0, 255]));
+        let svg = qrcode::generate(
+            "hello",
+            &Options::default().format(Format::Svg).logo(logo, 0.2),
+        )
+        .unwrap();
+        assert!(String::from_utf8(svg)
+            .unwrap()
+            .contains("data:image/png;base64,"));
+
+        assert!(qrcode::generate("x", &Options::default().logo(b"bad".to_vec(), 0.2)).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use std::{future::Future, time::Duration};
+
+use redis::AsyncCommands;
+
+use crate::helper::redkit::{key, Redis};
+
+/// 消息去重：以 `SET NX EX` 记录已处理的消息ID，消费者重启后仍可跳过重复消息
+///
+/// 两阶段标记：处理前标记为「处理中」（短 TTL，默认：5min），处理成功后标记为「已完成」（长 TTL），
+/// 处理失败撤销标记；消费者在处理中途崩溃时，「处理中」标记过期后消息可被重新处理
+///
+/// 同一去重器的 key 使用相同的 hash tag，集群模式下批量操作不会跨 slot
+///
+/// # Examples
+///
+/// ```
+/// let dedup = Dedup::new(Redis::Single(pool), "order-events", Some(Duration::from_secs(86400)));
+///
+/// // 单条：重复消息返回 None，处理失败自动撤销标记
+/// dedup.run(&msg.id, || handle(&msg)).await?;
+///
+/// // 手动
+/// if dedup.mark(&msg.id).await? {
+///     match handle(&msg).await {
+///         Ok(_) => dedup.done(&msg.id).await?,
+///         Err(e) => {
+///             dedup.unmark(&msg.id).await?;
+///             return Err(e);
+///         }
+///     }
+/// }
+///
+/// // 批量：一次往返
+/// let ids: Vec<&str> = msgs.iter().map(|v| v.id.as_str()).collect();
+/// let fresh = dedup.mark_many(&ids).await?;
+/// for (msg, ok) in msgs.iter().zip(fresh) {
+///     if ok {
+///         handle(msg).await?;
+///         dedup.done(&msg.id).await?;
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Dedup {
+    redis: Redis,
+    name: String,
+    ttl: Duration,
+    processing_ttl: Duration,
+}
+
+const PROCESSING: &str = "processing";
+const DONE: &str = "done";
+
+impl Dedup {
+    /// `ttl` 为「已完成」记录保留时间，默认：24h
+    pub fn new(redis: Redis, name: impl AsRef<str>, ttl: Option<Duration>) -> Self {
+        Self {
+            redis,
+            name: name.as_ref().to_string(),
+            ttl: ttl.unwrap_or(Duration::from_secs(86400)),
+            processing_ttl: Duration::from_secs(300),
+        }
+    }
+
+    /// 设置「处理中」标记的保留时间，应大于单条消息的最长处理时间，默认：5min
+    pub fn with_processing_ttl(mut self, ttl: Duration) -> Self {
+        self.processing_ttl = ttl;
+        self
+    }
+
+    fn key(&self, id: &str) -> String {
+        key::with_prefix(format!("kr:dedup:{{{}}}:{}", self.name, id))
+    }
+
+    /// 标记为处理中，首次标记返回 true，重复消息返回 false
+    pub async fn mark(&self, id: impl AsRef<str>) -> anyhow::Result<bool> {
+        Ok(self.mark_many(&[id.as_ref()]).await?[0])
+    }
+
+    /// 批量标记为处理中（pipeline 一次往返），按顺序返回是否首次标记
+    pub async fn mark_many<S: AsRef<str>>(&self, ids: &[S]) -> anyhow::Result<Vec<bool>> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut pipe = redis::pipe();
+        for id in ids {
+            pipe.cmd("SET")
+                .arg(self.key(id.as_ref()))
+                .arg(PROCESSING)
+                .arg("NX")
+                .arg("EX")
+                .arg(self.processing_ttl.as_secs().max(1));
+        }
+        let ret: Vec<Option<String>> = match &self.redis {
+            Redis::Single(pool) => pipe.query_async(&mut *pool.get().await?).await?,
+            Redis::Cluster(pool) => pipe.query_async(&mut *pool.get().await?).await?,
+        };
+        Ok(ret.into_iter().map(|v| v.is_some()).collect())
+    }
+
+    /// 标记为已完成（处理成功后）
+    pub async fn done(&self, id: impl AsRef<str>) -> anyhow::Result<()> {
+        let key = self.key(id.as_ref());
+        let ttl = self.ttl.as_secs().max(1);
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool.get().await?.set_ex(&key, DONE, ttl).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool.get().await?.set_ex(&key, DONE, ttl).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 去重执行：重复消息返回 None；成功后标记为已完成，失败则撤销标记并返回错误
+    pub async fn run<T, F, Fut>(&self, id: impl AsRef<str>, f: F) -> anyhow::Result<Option<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let id = id.as_ref();
+        if !self.mark(id).await? {
+            return Ok(None);
+        }
+        match f().await {
+            Ok(v) => {
+                self.done(id).await?;
+                Ok(Some(v))
+            }
+            Err(e) => {
+                if let Err(err) = self.unmark(id).await {
+                    tracing::error!(error = ?err, id = id, "[dedup] unmark failed");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// 是否处理中或已完成
+    pub async fn contains(&self, id: impl AsRef<str>) -> anyhow::Result<bool> {
+        Ok(self.contains_many(&[id.as_ref()]).await?[0])
+    }
+
+    /// 批量检查（MGET 一次往返），按顺序返回是否已处理
+    pub async fn contains_many<S: AsRef<str>>(&self, ids: &[S]) -> anyhow::Result<Vec<bool>> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let keys: Vec<String> = ids.iter().map(|v| self.key(v.as_ref())).collect();
+        let ret: Vec<Option<String>> = match &self.redis {
+            Redis::Single(pool) => pool.get().await?.mget(&keys).await?,
+            Redis::Cluster(pool) => pool.get().await?.mget(&keys).await?,
+        };
+        Ok(ret.into_iter().map(|v| v.is_some()).collect())
+    }
+
+    /// 撤销标记（处理失败需要重试时）
+    pub async fn unmark(&self, id: impl AsRef<str>) -> anyhow::Result<()> {
+        let key = self.key(id.as_ref());
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool.get().await?.del(&key).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool.get().await?.del(&key).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::AsyncCommands;
+
+    use crate::{helper::redkit::Redis, queue::dedup::Dedup, redix};
+
+    #[tokio::test]
+    async fn test_dedup() {
+        let redis = Redis::Single(redix::open::<redix::Mock>(vec![], None).await.unwrap());
+        let dedup = Dedup::new(redis, "test", None);
+
+        assert!(dedup.mark("a").await.unwrap());
+        assert!(!dedup.mark("a").await.unwrap());
+        assert_eq!(
+            dedup.mark_many(&["a", "b", "b", "c"]).await.unwrap(),
+            vec![false, true, false, true]
+        );
+        assert_eq!(
+            dedup.contains_many(&["a", "d"]).await.unwrap(),
+            vec![true, false]
+        );
+
+        dedup.unmark("a").await.unwrap();
+        assert!(!dedup.contains("a").await.unwrap());
+        assert!(dedup.mark("a").await.unwrap());
+        assert!(dedup.mark_many::<&str>(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dedup_run() {
+        let redis = Redis::Single(redix::open::<redix::Mock>(vec![], None).await.unwrap());
+        let dedup = Dedup::new(redis, "test_run", None);
+
+        // 失败撤销标记，可重试
+        let ret: anyhow::Result<Option<()>> = dedup
+            .run("a", || async { Err(anyhow::anyhow!("oops")) })
+            .await;
+        assert!(ret.is_err());
+        assert!(!dedup.contains("a").await.unwrap());
+
+        assert_eq!(dedup.run("a", || async { Ok(1) }).await.unwrap(), Some(1));
+        assert_eq!(dedup.run("a", || async { Ok(2) }).await.unwrap(), None);
+
+        // 已完成的记录使用长 TTL
+        let ttl: i64 = match &dedup.redis {
+            Redis::Single(pool) => pool.get().await.unwrap().ttl(dedup.key("a")).await.unwrap(),
+            Redis::Cluster(_) => unreachable!(),
+        };
+        assert!(ttl > 300);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod dedup;
+pub mod priority;
+
+pub use dedup::Dedup;
+pub use priority::{DeadJob, Job, PriorityQueue, Stats};
@@ -0,0 +1,538 @@
+use std::time::Duration;
+
+use redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    helper::redkit::{key, Redis},
+    idgen,
+    redix::script::{self, Builtin},
+};
+
+/// 写入任务：KEYS[1]=jobs，KEYS[2]=ready；ARGV[1]=id，ARGV[2]=任务数据
+pub const PUSH: &str = r#"
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+return redis.call('LPUSH', KEYS[2], ARGV[1])
+"#;
+
+/// 按优先级从高到低取出一个任务并标记为处理中
+///
+/// KEYS[1]=jobs，KEYS[2]=inflight，KEYS[3]=attempts，KEYS[4..]=各优先级 ready；ARGV[1]=可见性超时截止时间（毫秒）
+pub const POP: &str = r#"
+for i = 4, #KEYS do
+    local id = redis.call('RPOP', KEYS[i])
+    while id do
+        local job = redis.call('HGET', KEYS[1], id)
+        if job then
+            redis.call('ZADD', KEYS[2], ARGV[1], id)
+            local n = redis.call('HINCRBY', KEYS[3], id, 1)
+            return {id, job, n}
+        end
+        id = redis.call('RPOP', KEYS[i])
+    end
+end
+return false
+"#;
+
+/// 确认任务：KEYS[1]=inflight，KEYS[2]=jobs，KEYS[3]=attempts；ARGV[1]=id
+pub const ACK: &str = r#"
+if redis.call('ZREM', KEYS[1], ARGV[1]) == 0 then
+    return 0
+end
+redis.call('HDEL', KEYS[2], ARGV[1])
+redis.call('HDEL', KEYS[3], ARGV[1])
+return 1
+"#;
+
+/// 重新投递或转入死信：KEYS[1]=inflight，KEYS[2]=ready 或 dead，KEYS[3]=jobs，KEYS[4]=attempts；
+/// ARGV[1]=id，ARGV[2]=死信数据（为空时重新投递到队首）
+pub const RELEASE: &str = r#"
+if redis.call('ZREM', KEYS[1], ARGV[1]) == 0 then
+    return 0
+end
+if ARGV[2] == '' then
+    redis.call('RPUSH', KEYS[2], ARGV[1])
+else
+    redis.call('LPUSH', KEYS[2], ARGV[2])
+    redis.call('HDEL', KEYS[3], ARGV[1])
+    redis.call('HDEL', KEYS[4], ARGV[1])
+end
+return 1
+"#;
+
+/// 死信重新入队：KEYS[1]=dead，KEYS[2]=jobs，KEYS[3]=ready；ARGV[1]=死信数据，ARGV[2]=新任务ID，ARGV[3]=任务数据
+///
+/// 仅当死信队尾仍为 ARGV[1] 时移动（期间被其他进程取走则返回 0）
+pub const REDRIVE: &str = r#"
+if redis.call('LINDEX', KEYS[1], -1) ~= ARGV[1] then
+    return 0
+end
+redis.call('RPOP', KEYS[1])
+redis.call('HSET', KEYS[2], ARGV[2], ARGV[3])
+redis.call('LPUSH', KEYS[3], ARGV[2])
+return 1
+"#;
+
+inventory::submit! { Builtin { name: "queue:priority:push", body: PUSH } }
+inventory::submit! { Builtin { name: "queue:priority:pop", body: POP } }
+inventory::submit! { Builtin { name: "queue:priority:ack", body: ACK } }
+inventory::submit! { Builtin { name: "queue:priority:release", body: RELEASE } }
+inventory::submit! { Builtin { name: "queue:priority:redrive", body: REDRIVE } }
+
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    #[serde(rename = "p")]
+    priority: u8,
+    #[serde(rename = "d")]
+    payload: serde_json::Value,
+}
+
+/// 取出的任务
+#[derive(Debug, Clone)]
+pub struct Job<T> {
+    pub id: String,
+    /// 优先级（0 最高）
+    pub priority: u8,
+    pub payload: T,
+    /// 第几次投递（从 1 开始）
+    pub attempts: u32,
+}
+
+/// 死信任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadJob<T> {
+    pub id: String,
+    pub priority: u8,
+    pub payload: T,
+    pub attempts: u32,
+    /// 失败原因
+    pub reason: String,
+    /// 转入死信的时间（Unix秒）
+    pub dead_at: i64,
+}
+
+/// 队列长度
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// 各优先级待处理数量
+    pub ready: Vec<usize>,
+    /// 处理中
+    pub inflight: usize,
+    /// 死信
+    pub dead: usize,
+}
+
+/// 基于 Redis 的优先级队列（每个优先级一个 LIST，处理中的任务记录在 ZSET）
+///
+/// - 高优先级的任务先出队，同优先级先进先出
+/// - 取出后超过可见性超时未确认的任务会被重新投递（至少一次，消费需幂等）
+/// - 投递次数达到上限后转入死信列表
+///
+/// # Examples
+///
+/// ```
+/// let queue = PriorityQueue::new(Redis::Single(pool), "email")
+///     .levels(3)
+///     .visibility(Duration::from_secs(60))
+///     .max_attempts(5);
+///
+/// queue.push(&Email { to: "a@b.com".into() }, 0).await?;
+///
+/// if let Some(job) = queue.pop::<Email>().await? {
+///     match send(&job.payload).await {
+///         Ok(_) => queue.ack(&job.id).await?,
+///         Err(e) => queue.nack(&job.id, e.to_string()).await?,
+///     };
+/// }
+///
+/// // 死信重新入队
+/// queue.redrive(100).await?;
+/// ```
+#[derive(Clone)]
+pub struct PriorityQueue {
+    redis: Redis,
+    name: String,
+    levels: u8,
+    visibility: Duration,
+    max_attempts: u32,
+}
+
+impl PriorityQueue {
+    /// 默认：3 个优先级、可见性超时 30s、最多投递 5 次
+    pub fn new(redis: Redis, name: impl AsRef<str>) -> Self {
+        Self {
+            redis,
+            name: name.as_ref().to_string(),
+            levels: 3,
+            visibility: Duration::from_secs(30),
+            max_attempts: 5,
+        }
+    }
+
+    /// 优先级数量（0 ~ n-1，0 最高）
+    pub fn levels(mut self, n: u8) -> Self {
+        self.levels = n.max(1);
+        self
+    }
+
+    /// 可见性超时：取出后在此时间内未确认则重新投递
+    pub fn visibility(mut self, d: Duration) -> Self {
+        self.visibility = d;
+        self
+    }
+
+    /// 最大投递次数，超过后转入死信
+    pub fn max_attempts(mut self, n: u32) -> Self {
+        self.max_attempts = n.max(1);
+        self
+    }
+
+    // 使用 hash tag 保证集群模式下所有 key 位于同一 slot
+    fn key(&self, suffix: &str) -> String {
+        key::with_prefix(format!("kr:queue:{{{}}}:{}", self.name, suffix))
+    }
+
+    fn ready_key(&self, priority: u8) -> String {
+        self.key(&format!("ready:{}", priority.min(self.levels - 1)))
+    }
+
+    /// 入队，返回任务ID；`priority` 超出范围时按最低优先级处理
+    pub async fn push<T: Serialize>(&self, payload: &T, priority: u8) -> anyhow::Result<String> {
+        let id = idgen::uuid_v7().to_string();
+        let priority = priority.min(self.levels - 1);
+        let data = serde_json::to_string(&Stored {
+            priority,
+            payload: serde_json::to_value(payload)?,
+        })?;
+
+        let _: i64 = script::global()
+            .call("queue:priority:push")?
+            .key(self.key("jobs"))
+            .key(self.ready_key(priority))
+            .arg(&id)
+            .arg(data)
+            .invoke(&self.redis)
+            .await?;
+        Ok(id)
+    }
+
+    /// 取出一个任务（先回收已超时的任务），队列为空时返回 None
+    pub async fn pop<T: DeserializeOwned>(&self) -> anyhow::Result<Option<Job<T>>> {
+        self.reclaim().await?;
+
+        let mut invocation = script::global()
+            .call("queue:priority:pop")?
+            .key(self.key("jobs"))
+            .key(self.key("inflight"))
+            .key(self.key("attempts"));
+        for p in 0..self.levels {
+            invocation = invocation.key(self.ready_key(p));
+        }
+        let ret: Option<(String, String, u32)> = invocation
+            .arg(now_ms() + self.visibility.as_millis() as i64)
+            .invoke(&self.redis)
+            .await?;
+
+        let Some((id, data, attempts)) = ret else {
+            return Ok(None);
+        };
+        let stored: Stored = serde_json::from_str(&data)?;
+        match serde_json::from_value(stored.payload) {
+            Ok(payload) => Ok(Some(Job {
+                id,
+                priority: stored.priority,
+                payload,
+                attempts,
+            })),
+            Err(e) => {
+                // 无法解析的任务直接转入死信，避免反复投递
+                let reason = format!("invalid payload: {}", e);
+                self.nack_with(&id, &reason, true).await?;
+                Err(anyhow::anyhow!("queue/priority: job({}) {}", id, reason))
+            }
+        }
+    }
+
+    /// 确认任务已完成；任务已超时被重新投递时返回 false
+    pub async fn ack(&self, id: impl AsRef<str>) -> anyhow::Result<bool> {
+        let n: i64 = script::global()
+            .call("queue:priority:ack")?
+            .key(self.key("inflight"))
+            .key(self.key("jobs"))
+            .key(self.key("attempts"))
+            .arg(id.as_ref())
+            .invoke(&self.redis)
+            .await?;
+        Ok(n == 1)
+    }
+
+    /// 处理失败：未达到最大投递次数时立即重新投递，否则转入死信
+    pub async fn nack(&self, id: impl AsRef<str>, reason: impl AsRef<str>) -> anyhow::Result<bool> {
+        self.nack_with(id.as_ref(), reason.as_ref(), false).await
+    }
+
+    /// 延长处理中任务的可见性超时（长任务定期调用）
+    pub async fn touch(&self, id: impl AsRef<str>) -> anyhow::Result<bool> {
+        let deadline = now_ms() + self.visibility.as_millis() as i64;
+        let inflight = self.key("inflight");
+        let mut pipe = redis::pipe();
+        pipe.cmd("ZADD")
+            .arg(&inflight)
+            .arg("XX")
+            .arg(deadline)
+            .arg(id.as_ref())
+            .ignore()
+            .zscore(&inflight, id.as_ref());
+        let (score,): (Option<f64>,) = match &self.redis {
+            Redis::Single(pool) => pipe.query_async(&mut *pool.get().await?).await?,
+            Redis::Cluster(pool) => pipe.query_async(&mut *pool.get().await?).await?,
+        };
+        Ok(score.is_some())
+    }
+
+    /// 回收已超时的任务（重新投递或转入死信），返回回收数量
+    pub async fn reclaim(&self) -> anyhow::Result<usize> {
+        let inflight = self.key("inflight");
+        let expired: Vec<String> = match &self.redis {
+            Redis::Single(pool) => {
+                pool.get()
+                    .await?
+                    .zrangebyscore_limit(&inflight, "-inf", now_ms(), 0, 100)
+                    .await?
+            }
+            Redis::Cluster(pool) => {
+                pool.get()
+                    .await?
+                    .zrangebyscore_limit(&inflight, "-inf", now_ms(), 0, 100)
+                    .await?
+            }
+        };
+
+        let mut count = 0;
+        for id in expired {
+            if self.nack_with(&id, "visibility timeout", false).await? {
+                tracing::warn!(queue = self.name, id = id, "[queue::priority] job timeout");
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 查看死信（最近的在前）
+    pub async fn dead<T: DeserializeOwned>(&self, limit: usize) -> anyhow::Result<Vec<DeadJob<T>>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let key = self.key("dead");
+        let list: Vec<String> = match &self.redis {
+            Redis::Single(pool) => {
+                pool.get()
+                    .await?
+                    .lrange(&key, 0, limit as isize - 1)
+                    .await?
+            }
+            Redis::Cluster(pool) => {
+                pool.get()
+                    .await?
+                    .lrange(&key, 0, limit as isize - 1)
+                    .await?
+            }
+        };
+        list.iter().map(|v| Ok(serde_json::from_str(v)?)).collect()
+    }
+
+    /// 将最早的 `limit` 个死信重新入队（投递次数重新计算），返回数量
+    pub async fn redrive(&self, limit: usize) -> anyhow::Result<usize> {
+        let key = self.key("dead");
+        let mut count = 0;
+        while count < limit {
+            let data: Option<String> = match &self.redis {
+                Redis::Single(pool) => pool.get().await?.lindex(&key, -1).await?,
+                Redis::Cluster(pool) => pool.get().await?.lindex(&key, -1).await?,
+            };
+            let Some(data) = data else {
+                break;
+            };
+            let dead: DeadJob<serde_json::Value> = serde_json::from_str(&data)?;
+            let priority = dead.priority.min(self.levels - 1);
+            let job = serde_json::to_string(&Stored {
+                priority,
+                payload: dead.payload,
+            })?;
+
+            // 出队与入队在同一脚本中完成，避免中途失败丢失任务
+            let n: i64 = script::global()
+                .call("queue:priority:redrive")?
+                .key(&key)
+                .key(self.key("jobs"))
+                .key(self.ready_key(priority))
+                .arg(&data)
+                .arg(idgen::uuid_v7().to_string())
+                .arg(job)
+                .invoke(&self.redis)
+                .await?;
+            count += n as usize;
+        }
+        Ok(count)
+    }
+
+    /// 队列长度
+    pub async fn stats(&self) -> anyhow::Result<Stats> {
+        let mut pipe = redis::pipe();
+        for p in 0..self.levels {
+            pipe.llen(self.ready_key(p));
+        }
+        pipe.zcard(self.key("inflight")).llen(self.key("dead"));
+        let mut ret: Vec<usize> = match &self.redis {
+            Redis::Single(pool) => pipe.query_async(&mut *pool.get().await?).await?,
+            Redis::Cluster(pool) => pipe.query_async(&mut *pool.get().await?).await?,
+        };
+
+        let dead = ret.pop().unwrap_or_default();
+        let inflight = ret.pop().unwrap_or_default();
+        Ok(Stats {
+            ready: ret,
+            inflight,
+            dead,
+        })
+    }
+
+    // 重新投递或转入死信（force_dead 或投递次数已达上限）
+    async fn nack_with(&self, id: &str, reason: &str, force_dead: bool) -> anyhow::Result<bool> {
+        let (data, attempts): (Option<String>, Option<u32>) = match &self.redis {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+                (
+                    conn.hget(self.key("jobs"), id).await?,
+                    conn.hget(self.key("attempts"), id).await?,
+                )
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+                (
+                    conn.hget(self.key("jobs"), id).await?,
+                    conn.hget(self.key("attempts"), id).await?,
+                )
+            }
+        };
+        let Some(data) = data else {
+            return Ok(false);
+        };
+        let stored: Stored = serde_json::from_str(&data)?;
+        let attempts = attempts.unwrap_or_default();
+
+        let (target, dead) = if force_dead || attempts >= self.max_attempts {
+            let dead = DeadJob {
+                id: id.to_string(),
+                priority: stored.priority,
+                payload: stored.payload,
+                attempts,
+                reason: reason.to_string(),
+                dead_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+            };
+            (self.key("dead"), serde_json::to_string(&dead)?)
+        } else {
+            (self.ready_key(stored.priority), String::new())
+        };
+
+        let n: i64 = script::global()
+            .call("queue:priority:release")?
+            .key(self.key("inflight"))
+            .key(&target)
+            .key(self.key("jobs"))
+            .key(self.key("attempts"))
+            .arg(id)
+            .arg(&dead)
+            .invoke(&self.redis)
+            .await?;
+        if n == 1 && !dead.is_empty() {
+            tracing::error!(
+                queue = self.name,
+                id = id,
+                attempts = attempts,
+                reason = reason,
+                "[queue::priority] job dead"
+            );
+        }
+        Ok(n == 1)
+    }
+}
+
+fn now_ms() -> i64 {
+    (time::OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        helper::redkit::Redis,
+        queue::priority::{PriorityQueue, Stats},
+        redix,
+    };
+
+    #[tokio::test]
+    async fn test_priority_queue() {
+        let redis = Redis::Single(redix::open::<redix::Mock>(vec![], None).await.unwrap());
+        let queue = PriorityQueue::new(redis, "test")
+            .levels(2)
+            .visibility(Duration::from_millis(20))
+            .max_attempts(2);
+
+        queue.push(&"low-1", 1).await.unwrap();
+        queue.push(&"low-2", 9).await.unwrap();
+        queue.push(&"high", 0).await.unwrap();
+        assert_eq!(
+            queue.stats().await.unwrap(),
+            Stats {
+                ready: vec![1, 2],
+                inflight: 0,
+                dead: 0
+            }
+        );
+
+        // 高优先级先出队，同优先级先进先出
+        let job = queue.pop::<String>().await.unwrap().unwrap();
+        assert_eq!((job.payload.as_str(), job.attempts), ("high", 1));
+        assert!(queue.ack(&job.id).await.unwrap());
+        assert!(!queue.ack(&job.id).await.unwrap());
+
+        let job = queue.pop::<String>().await.unwrap().unwrap();
+        assert_eq!(job.payload, "low-1");
+        assert!(queue.touch(&job.id).await.unwrap());
+
+        // 失败后立即重新投递到队首
+        assert!(queue.nack(&job.id, "oops").await.unwrap());
+        let job = queue.pop::<String>().await.unwrap().unwrap();
+        assert_eq!((job.payload.as_str(), job.attempts), ("low-1", 2));
+
+        // 超时未确认且达到最大投递次数：转入死信
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let next = queue.pop::<String>().await.unwrap().unwrap();
+        assert_eq!(next.payload, "low-2");
+        assert!(!queue.ack(&job.id).await.unwrap());
+
+        let dead = queue.dead::<String>(10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].payload, "low-1");
+        assert_eq!(dead[0].reason, "visibility timeout");
+
+        // 死信重新入队（并发执行时只移动一次）
+        let (a, b) = tokio::join!(queue.redrive(10), queue.redrive(10));
+        assert_eq!(a.unwrap() + b.unwrap(), 1);
+        assert!(queue.ack(&next.id).await.unwrap());
+        let job = queue.pop::<String>().await.unwrap().unwrap();
+        assert_eq!((job.payload.as_str(), job.attempts), ("low-1", 1));
+        assert!(queue.ack(&job.id).await.unwrap());
+        assert!(queue.pop::<String>().await.unwrap().is_none());
+        assert_eq!(
+            queue.stats().await.unwrap(),
+            Stats {
+                ready: vec![0, 0],
+                inflight: 0,
+                dead: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+
+use jiff::{tz::TimeZone, Timestamp, ToSpan, Zoned};
+use redis::AsyncCommands;
+use serde::Serialize;
+
+use crate::{
+    codes,
+    helper::redkit::{key, Redis},
+    redix::script,
+};
+
+/// 扣减额度：KEYS[1]=用量 hash，KEYS[2]=额度覆盖 hash；ARGV[1]=app_id，ARGV[2]=扣减量，
+/// ARGV[3]=默认额度（-1 为不限），ARGV[4]=距周期结束的秒数
+///
+/// 返回 {是否成功, 已用量, 额度}
+pub const CONSUME: &str = r#"
+local limit = tonumber(redis.call('HGET', KEYS[2], ARGV[1]) or ARGV[3])
+local used = tonumber(redis.call('HGET', KEYS[1], ARGV[1]) or '0')
+local n = tonumber(ARGV[2])
+if limit >= 0 and used + n > limit then
+    return {0, used, limit}
+end
+used = redis.call('HINCRBY', KEYS[1], ARGV[1], n)
+if redis.call('TTL', KEYS[1]) == -1 then
+    redis.call('EXPIRE', KEYS[1], ARGV[4])
+end
+return {1, used, limit}
+"#;
+
+inventory::submit! { crate::redix::script::Builtin { name: "quota:consume", body: CONSUME } }
+
+// 额度覆盖中表示不限
+const UNLIMITED: i64 = -1;
+
+/// 额度周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// 自然日
+    Daily,
+    /// 自然月
+    Monthly,
+}
+
+/// 某个调用方在当前周期的用量
+#[derive(Debug, Clone, Serialize)]
+pub struct Usage {
+    pub app_id: String,
+    /// 周期标识（`20241017`、`202410`）
+    pub period: String,
+    /// 已用量（补发额度后可能为负）
+    pub used: i64,
+    /// 额度，`None` 为不限
+    pub limit: Option<u64>,
+    /// 周期结束（额度重置）时间
+    pub reset_at: Timestamp,
+}
+
+impl Usage {
+    /// 剩余额度，`None` 为不限
+    pub fn remaining(&self) -> Option<u64> {
+        self.limit
+            .map(|v| (v as i64).saturating_sub(self.used).max(0) as u64)
+    }
+}
+
+/// 按调用方（app_id）的接口调用额度，存储在 Redis hash 中
+///
+/// - 每个周期一个 hash（field 为 app_id），周期结束时过期
+/// - 超出额度时返回 `codes::QUOTA_EXCEEDED`
+/// - 单个调用方的额度可覆盖默认额度（长期有效），也可在当前周期补发额度
+///
+/// # Examples
+///
+/// ```
+/// let quota = Quota::new(Redis::Single(pool), "open-api", Period::Daily, 10000)
+///     .tz(TimeZone::get("Asia/Shanghai")?);
+///
+/// // 接口调用时扣减，超出额度返回 codes::QUOTA_EXCEEDED
+/// let usage = quota.consume(&app_id, 1).await?;
+/// headers.insert("X-RateLimit-Remaining", usage.remaining().unwrap_or(u64::MAX).into());
+///
+/// // 管理接口
+/// let list = quota.usages().await?;
+/// quota.set_limit("vip-app", Some(100000)).await?;
+/// quota.grant("app-1", 500).await?;
+/// ```
+#[derive(Clone)]
+pub struct Quota {
+    redis: Redis,
+    name: String,
+    period: Period,
+    limit: u64,
+    tz: TimeZone,
+}
+
+impl Quota {
+    /// `limit` 为默认额度，时区默认为系统时区
+    pub fn new(redis: Redis, name: impl AsRef<str>, period: Period, limit: u64) -> Self {
+        Self {
+            redis,
+            name: name.as_ref().to_string(),
+            period,
+            limit,
+            tz: TimeZone::system(),
+        }
+    }
+
+    /// 周期划分所用的时区
+    pub fn tz(mut self, tz: TimeZone) -> Self {
+        self.tz = tz;
+        self
+    }
+
+    /// 扣减额度，超出时返回 `codes::QUOTA_EXCEEDED`（不扣减）
+    pub async fn consume(&self, app_id: impl AsRef<str>, n: u64) -> anyhow::Result<Usage> {
+        let app_id = app_id.as_ref();
+        let (period, reset_at) = self.window(&Timestamp::now().to_zoned(self.tz.clone()))?;
+
+        let (ok, used, limit): (i64, i64, i64) = script::global()
+            .call("quota:consume")?
+            .key(self.usage_key(&period))
+            .key(self.limits_key())
+            .arg(app_id)
+            .arg(n)
+            .arg(i64::try_from(self.limit).unwrap_or(i64::MAX))
+            .arg(ttl(reset_at))
+            .invoke(&self.redis)
+            .await?;
+        if ok == 0 {
+            tracing::warn!(
+                quota = self.name,
+                app_id = app_id,
+                used = used,
+                limit = limit,
+                "[quota::consume] quota exceeded"
+            );
+            return Err(codes::QUOTA_EXCEEDED.into());
+        }
+
+        Ok(Usage {
+            app_id: app_id.to_string(),
+            period,
+            used,
+            limit: to_limit(limit),
+            reset_at,
+        })
+    }
+
+    /// 当前周期的用量
+    pub async fn usage(&self, app_id: impl AsRef<str>) -> anyhow::Result<Usage> {
+        let app_id = app_id.as_ref();
+        let (period, reset_at) = self.window(&Timestamp::now().to_zoned(self.tz.clone()))?;
+        let usage_key = self.usage_key(&period);
+        let (used, limit): (Option<i64>, Option<i64>) = match &self.redis {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+                (
+                    conn.hget(&usage_key, app_id).await?,
+                    conn.hget(self.limits_key(), app_id).await?,
+                )
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+                (
+                    conn.hget(&usage_key, app_id).await?,
+                    conn.hget(self.limits_key(), app_id).await?,
+                )
+            }
+        };
+
+        Ok(Usage {
+            app_id: app_id.to_string(),
+            period,
+            used: used.unwrap_or(0),
+            limit: self.limit_or_default(limit),
+            reset_at,
+        })
+    }
+
+    /// 当前周期有用量或设置了额度覆盖的所有调用方（按 app_id 排序）
+    pub async fn usages(&self) -> anyhow::Result<Vec<Usage>> {
+        let (period, reset_at) = self.window(&Timestamp::now().to_zoned(self.tz.clone()))?;
+        let usage_key = self.usage_key(&period);
+        let (used, limits): (HashMap<String, i64>, HashMap<String, i64>) = match &self.redis {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+                (
+                    conn.hgetall(&usage_key).await?,
+                    conn.hgetall(self.limits_key()).await?,
+                )
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+                (
+                    conn.hgetall(&usage_key).await?,
+                    conn.hgetall(self.limits_key()).await?,
+                )
+            }
+        };
+
+        let mut apps: Vec<&String> = used.keys().chain(limits.keys()).collect();
+        apps.sort();
+        apps.dedup();
+        Ok(apps
+            .into_iter()
+            .map(|app_id| Usage {
+                app_id: app_id.clone(),
+                period: period.clone(),
+                used: used.get(app_id).copied().unwrap_or(0),
+                limit: self.limit_or_default(limits.get(app_id).copied()),
+                reset_at,
+            })
+            .collect())
+    }
+
+    /// 覆盖调用方的额度（长期有效），`None` 为不限
+    pub async fn set_limit(
+        &self,
+        app_id: impl AsRef<str>,
+        limit: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let v = match limit {
+            Some(v) => i64::try_from(v).unwrap_or(i64::MAX),
+            None => UNLIMITED,
+        };
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool
+                    .get()
+                    .await?
+                    .hset(self.limits_key(), app_id.as_ref(), v)
+                    .await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool
+                    .get()
+                    .await?
+                    .hset(self.limits_key(), app_id.as_ref(), v)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 取消额度覆盖，恢复默认额度
+    pub async fn clear_limit(&self, app_id: impl AsRef<str>) -> anyhow::Result<()> {
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool
+                    .get()
+                    .await?
+                    .hdel(self.limits_key(), app_id.as_ref())
+                    .await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool
+                    .get()
+                    .await?
+                    .hdel(self.limits_key(), app_id.as_ref())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 调整当前周期的剩余额度：`n` 为正时补发，为负时扣减（不受额度限制）
+    pub async fn grant(&self, app_id: impl AsRef<str>, n: i64) -> anyhow::Result<Usage> {
+        let app_id = app_id.as_ref();
+        let (period, reset_at) = self.window(&Timestamp::now().to_zoned(self.tz.clone()))?;
+        let delta = n
+            .checked_neg()
+            .ok_or_else(|| anyhow::anyhow!("quota: grant amount {} out of range", n))?;
+        let usage_key = self.usage_key(&period);
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hincr(&usage_key, app_id, delta)
+            .ignore()
+            .expire(&usage_key, ttl(reset_at))
+            .ignore();
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pipe.query_async(&mut *pool.get().await?).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pipe.query_async(&mut *pool.get().await?).await?;
+            }
+        }
+        self.usage(app_id).await
+    }
+
+    /// 清零调用方在当前周期的用量
+    pub async fn reset(&self, app_id: impl AsRef<str>) -> anyhow::Result<()> {
+        let (period, _) = self.window(&Timestamp::now().to_zoned(self.tz.clone()))?;
+        let usage_key = self.usage_key(&period);
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pool.get().await?.hdel(usage_key, app_id.as_ref()).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pool.get().await?.hdel(usage_key, app_id.as_ref()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // (周期标识, 周期结束时间)
+    fn window(&self, now: &Zoned) -> anyhow::Result<(String, Timestamp)> {
+        let (period, end) = match self.period {
+            Period::Daily => (now.strftime("%Y%m%d").to_string(), now.date().tomorrow()?),
+            Period::Monthly => (
+                now.strftime("%Y%m").to_string(),
+                now.date().first_of_month().checked_add(1.month())?,
+            ),
+        };
+        Ok((period, end.to_zoned(self.tz.clone())?.timestamp()))
+    }
+
+    fn limit_or_default(&self, v: Option<i64>) -> Option<u64> {
+        match v {
+            Some(v) => to_limit(v),
+            None => Some(self.limit),
+        }
+    }
+
+    // 使用 hash tag 保证集群模式下用量与额度覆盖位于同一 slot
+    fn usage_key(&self, period: &str) -> String {
+        key::with_prefix(format!("kr:quota:{{{}}}:{}", self.name, period))
+    }
+
+    fn limits_key(&self) -> String {
+        key::with_prefix(format!("kr:quota:{{{}}}:limits", self.name))
+    }
+}
+
+// 距周期结束的秒数
+fn ttl(reset_at: Timestamp) -> i64 {
+    (reset_at.as_second() - Timestamp::now().as_second()).max(1)
+}
+
+fn to_limit(v: i64) -> Option<u64> {
+    (v >= 0).then_some(v as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::{civil::date, tz::TimeZone, Timestamp};
+
+    use crate::{
+        codes,
+        helper::redkit::Redis,
+        quota::{Period, Quota},
+        redix,
+    };
+
+    #[tokio::test]
+    async fn test_window() {
+        let tz = TimeZone::get("Asia/Shanghai").unwrap();
+        let now = date(2024, 2, 28)
+            .at(23, 30, 0, 0)
+            .to_zoned(tz.clone())
+            .unwrap();
+
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let q = Quota::new(Redis::Single(pool), "t", Period::Daily, 1).tz(tz);
+        let (period, end) = q.window(&now).unwrap();
+        assert_eq!(period, "20240228");
+        assert_eq!(end, "2024-02-28T16:00:00Z".parse::<Timestamp>().unwrap());
+
+        let q = Quota {
+            period: Period::Monthly,
+            ..q
+        };
+        let (period, end) = q.window(&now).unwrap();
+        assert_eq!(period, "202402");
+        assert_eq!(end, "2024-02-29T16:00:00Z".parse::<Timestamp>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_quota() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let quota = Quota::new(Redis::Single(pool), "api", Period::Daily, 3);
+
+        let usage = quota.consume("app-1", 2).await.unwrap();
+        assert_eq!((usage.used, usage.remaining()), (2, Some(1)));
+        assert!(usage.reset_at > Timestamp::now());
+
+        // 超出额度不扣减
+        let err = quota.consume("app-1", 2).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<codes::Code>(),
+            Some(&codes::QUOTA_EXCEEDED)
+        );
+        assert_eq!(quota.usage("app-1").await.unwrap().used, 2);
+
+        // 补发额度
+        let usage = quota.grant("app-1", 5).await.unwrap();
+        assert_eq!((usage.used, usage.remaining()), (-3, Some(6)));
+        quota.consume("app-1", 6).await.unwrap();
+        assert!(quota.consume("app-1", 1).await.is_err());
+        assert!(quota.grant("app-1", i64::MIN).await.is_err());
+
+        // 额度覆盖
+        quota.set_limit("app-2", None).await.unwrap();
+        let usage = quota.consume("app-2", 100).await.unwrap();
+        assert_eq!(
+            (usage.used, usage.limit, usage.remaining()),
+            (100, None, None)
+        );
+        quota.set_limit("app-3", Some(0)).await.unwrap();
+        assert!(quota.consume("app-3", 1).await.is_err());
+
+        let list = quota.usages().await.unwrap();
+        let apps: Vec<&str> = list.iter().map(|v| v.app_id.as_str()).collect();
+        assert_eq!(apps, vec!["app-1", "app-2", "app-3"]);
+        assert_eq!(list[2].limit, Some(0));
+
+        quota.clear_limit("app-2").await.unwrap();
+        quota.reset("app-2").await.unwrap();
+        let usage = quota.usage("app-2").await.unwrap();
+        assert_eq!((usage.used, usage.limit), (0, Some(3)));
+    }
+}
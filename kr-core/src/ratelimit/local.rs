@@ -0,0 +1,253 @@
+use std::{
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use dashmap::DashMap;
+use tokio::time::Instant;
+
+// 相对 origin 的单调时钟（纳秒），使用 tokio 时钟以便测试中暂停、推进时间
+fn nanos_since(origin: Instant) -> u64 {
+    origin.elapsed().as_nanos() as u64
+}
+
+/// 进程内令牌桶（无锁，GCRA 实现：按时间摊还补充令牌，无需后台任务）
+///
+/// # Examples
+///
+/// ```
+/// // 每秒 100 个令牌，最多积攒 20 个
+/// let bucket = TokenBucket::new(100, Duration::from_secs(1), 20);
+///
+/// if !bucket.try_acquire() {
+///     return Err(codes::BUSY.into());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TokenBucket {
+    // 每个令牌的补充间隔（纳秒）
+    interval: u64,
+    // 桶容量对应的时长（纳秒）
+    tolerance: u64,
+    // 理论到达时间（纳秒）
+    tat: AtomicU64,
+    origin: Instant,
+}
+
+impl TokenBucket {
+    /// 每 `per` 时间补充 `rate` 个令牌，桶容量为 `burst`
+    pub fn new(rate: u32, per: Duration, burst: u32) -> Self {
+        let interval = (per.as_nanos() as u64 / rate.max(1) as u64).max(1);
+        Self {
+            interval,
+            tolerance: interval * burst.max(1) as u64,
+            tat: AtomicU64::new(0),
+            origin: Instant::now(),
+        }
+    }
+
+    /// 获取 1 个令牌
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_n(1)
+    }
+
+    /// 获取 n 个令牌（不足时不消耗）
+    pub fn try_acquire_n(&self, n: u32) -> bool {
+        let cost = self.interval * n as u64;
+        if cost > self.tolerance {
+            return false;
+        }
+
+        let now = nanos_since(self.origin);
+        let mut tat = self.tat.load(Ordering::Acquire);
+        loop {
+            let next = tat.max(now) + cost;
+            if next - now > self.tolerance {
+                return false;
+            }
+            match self
+                .tat
+                .compare_exchange_weak(tat, next, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => return true,
+                Err(v) => tat = v,
+            }
+        }
+    }
+
+    /// 当前可用令牌数
+    pub fn available(&self) -> u32 {
+        let now = nanos_since(self.origin);
+        let used = self.tat.load(Ordering::Acquire).saturating_sub(now);
+        ((self.tolerance - used.min(self.tolerance)) / self.interval) as u32
+    }
+}
+
+struct Entry {
+    bucket: TokenBucket,
+    // 最近访问时间（纳秒）
+    accessed: AtomicU64,
+}
+
+/// 按 key 限流的令牌桶，超过容量时淘汰最久未访问的 key（近似 LRU）
+///
+/// # Examples
+///
+/// ```
+/// // 每个用户每秒 10 次，最多记录 10000 个用户
+/// let limiter = KeyedTokenBucket::new(10, Duration::from_secs(1), 10, 10000);
+///
+/// if !limiter.try_acquire(&user_id) {
+///     return Err(codes::BUSY.into());
+/// }
+/// ```
+pub struct KeyedTokenBucket<K> {
+    rate: u32,
+    per: Duration,
+    burst: u32,
+    capacity: usize,
+    buckets: DashMap<K, Arc<Entry>>,
+    origin: Instant,
+}
+
+impl<K: Hash + Eq + Clone> KeyedTokenBucket<K> {
+    /// `capacity` 为最多记录的 key 数量
+    pub fn new(rate: u32, per: Duration, burst: u32, capacity: usize) -> Self {
+        Self {
+            rate,
+            per,
+            burst,
+            capacity: capacity.max(1),
+            buckets: DashMap::new(),
+            origin: Instant::now(),
+        }
+    }
+
+    pub fn try_acquire(&self, key: &K) -> bool {
+        self.try_acquire_n(key, 1)
+    }
+
+    pub fn try_acquire_n(&self, key: &K, n: u32) -> bool {
+        self.entry(key).bucket.try_acquire_n(n)
+    }
+
+    /// key 当前可用令牌数
+    pub fn available(&self, key: &K) -> u32 {
+        match self.buckets.get(key) {
+            Some(v) => v.bucket.available(),
+            None => self.burst.max(1),
+        }
+    }
+
+    /// 已记录的 key 数量
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    fn entry(&self, key: &K) -> Arc<Entry> {
+        let now = nanos_since(self.origin);
+        if let Some(v) = self.buckets.get(key) {
+            v.accessed.store(now, Ordering::Relaxed);
+            return v.clone();
+        }
+
+        if self.buckets.len() >= self.capacity {
+            self.evict();
+        }
+        self.buckets
+            .entry(key.clone())
+            .or_insert_with(|| {
+                Arc::new(Entry {
+                    bucket: TokenBucket::new(self.rate, self.per, self.burst),
+                    accessed: AtomicU64::new(now),
+                })
+            })
+            .clone()
+    }
+
+    // 批量淘汰最久未访问的 1/10，摊还淘汰开销
+    fn evict(&self) {
+        let mut list: Vec<(K, u64)> = self
+            .buckets
+            .iter()
+            .map(|v| (v.key().clone(), v.accessed.load(Ordering::Relaxed)))
+            .collect();
+        list.sort_by_key(|(_, t)| *t);
+
+        let n = (self.capacity / 10).max(1).min(list.len());
+        for (k, _) in list.into_iter().take(n) {
+            self.buckets.remove(&k);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::ratelimit::local::{KeyedTokenBucket, TokenBucket};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_bucket() {
+        let bucket = TokenBucket::new(10, Duration::from_millis(100), 5);
+        assert_eq!(bucket.available(), 5);
+        assert!(bucket.try_acquire_n(3));
+        assert!(!bucket.try_acquire_n(3));
+        assert!(bucket.try_acquire_n(2));
+        assert!(!bucket.try_acquire());
+        assert!(!bucket.try_acquire_n(6));
+
+        // 每 10ms 补充 1 个
+        tokio::time::advance(Duration::from_millis(25)).await;
+        assert_eq!(bucket.available(), 2);
+        assert!(bucket.try_acquire_n(2));
+        assert!(!bucket.try_acquire());
+
+        tokio::time::advance(Duration::from_millis(5)).await;
+        assert!(bucket.try_acquire());
+
+        // 补充不超过桶容量
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(bucket.available(), 5);
+    }
+
+    #[test]
+    fn test_token_bucket_concurrent() {
+        let bucket = Arc::new(TokenBucket::new(1, Duration::from_secs(60), 100));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let b = bucket.clone();
+                std::thread::spawn(move || (0..50).filter(|_| b.try_acquire()).count())
+            })
+            .collect();
+        let total: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(total, 100);
+    }
+
+    #[test]
+    fn test_keyed() {
+        let limiter = KeyedTokenBucket::new(1, Duration::from_secs(60), 2, 10);
+        let key = |s: &str| s.to_string();
+        assert!(limiter.try_acquire(&key("a")));
+        assert!(limiter.try_acquire(&key("a")));
+        assert!(!limiter.try_acquire(&key("a")));
+        assert!(limiter.try_acquire(&key("b")));
+        assert_eq!(limiter.available(&key("b")), 1);
+
+        // 淘汰最久未访问的 key
+        for i in 0..20 {
+            std::thread::sleep(Duration::from_millis(1));
+            limiter.try_acquire(&format!("k{}", i));
+        }
+        assert!(limiter.len() <= 10);
+        assert_eq!(limiter.available(&key("a")), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use std::future::Future;
+
+use anyhow::bail;
+use redis::FromRedisValue;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use super::SinglePool;
+
+/// 基于异步连接池（`bb8`）的同步封装，供 RedLock 等同步场景使用，与异步代码共用同一连接池
+///
+/// - 需在 tokio 运行时中创建（记录运行时句柄，连接由该运行时驱动）
+/// - 可在普通线程或多线程运行时的任务中调用（后者通过 `block_in_place`），在 `current_thread` 运行时中调用返回错误
+///
+/// # Examples
+///
+/// ```
+/// let pool = redix::open::<redix::Single>(vec!["dsn"], None).await?;
+/// let blocking = redix::BlockingPool::new(pool.clone())?;
+///
+/// std::thread::spawn(move || {
+///     let v: Option<String> = blocking.query(redis::cmd("GET").arg("key"))?;
+///     let lock = RedLock::with_blocking(blocking, "key", Duration::from_secs(10)).acquire()?;
+///     Ok::<_, anyhow::Error>(())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct BlockingPool {
+    pool: SinglePool,
+    handle: Handle,
+}
+
+impl BlockingPool {
+    /// 使用当前 tokio 运行时
+    pub fn new(pool: SinglePool) -> anyhow::Result<Self> {
+        let handle = Handle::try_current()
+            .map_err(|e| anyhow::anyhow!("redix/blocking: no tokio runtime: {}", e))?;
+        Ok(Self::with_handle(pool, handle))
+    }
+
+    /// 使用指定的 tokio 运行时
+    pub fn with_handle(pool: SinglePool, handle: Handle) -> Self {
+        Self { pool, handle }
+    }
+
+    /// 底层异步连接池
+    pub fn pool(&self) -> &SinglePool {
+        &self.pool
+    }
+
+    /// 创建时记录的运行时句柄（可用于不阻塞地派发异步任务）
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// 同步执行异步任务，在 `current_thread` 运行时中调用返回错误（`block_in_place` 会 panic）
+    pub fn block_on<F: Future>(&self, fut: F) -> anyhow::Result<F::Output> {
+        match Handle::try_current() {
+            Ok(h) if h.runtime_flavor() == RuntimeFlavor::CurrentThread => {
+                bail!("redix/blocking: cannot block on a current_thread runtime")
+            }
+            Ok(_) => Ok(tokio::task::block_in_place(|| self.handle.block_on(fut))),
+            Err(_) => Ok(self.handle.block_on(fut)),
+        }
+    }
+
+    /// 执行命令
+    pub fn query<T: FromRedisValue>(&self, cmd: &redis::Cmd) -> anyhow::Result<T> {
+        self.block_on(async {
+            let mut conn = self.pool.get().await?;
+            let v = cmd.query_async(&mut *conn).await?;
+            Ok(v)
+        })?
+    }
+
+    /// 执行 Lua 脚本
+    pub fn invoke<T: FromRedisValue>(
+        &self,
+        invocation: &redis::ScriptInvocation<'_>,
+    ) -> anyhow::Result<T> {
+        self.block_on(async {
+            let mut conn = self.pool.get().await?;
+            let v = invocation.invoke_async(&mut *conn).await?;
+            Ok(v)
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::redix::{self, BlockingPool};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_blocking() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let blocking = BlockingPool::new(pool).unwrap();
+
+        // 运行时任务中
+        let _: () = blocking
+            .query(redis::cmd("SET").arg("test_blocking").arg("1"))
+            .unwrap();
+
+        // 普通线程中
+        let b = blocking.clone();
+        let v: Option<String> =
+            std::thread::spawn(move || b.query(redis::cmd("GET").arg("test_blocking")))
+                .join()
+                .unwrap()
+                .unwrap();
+        assert_eq!(v.as_deref(), Some("1"));
+
+        let n: i64 = blocking
+            .invoke(
+                redis::Script::new("return redis.call('DEL', KEYS[1])")
+                    .key("test_blocking")
+                    .arg("1"),
+            )
+            .unwrap();
+        assert_eq!(n, 1);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_current_thread() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let blocking = BlockingPool::new(pool).unwrap();
+
+        let ret: anyhow::Result<Option<String>> =
+            blocking.query(redis::cmd("GET").arg("test_blocking_current_thread"));
+        assert!(ret.unwrap_err().to_string().contains("current_thread"));
+    }
+}
@@ -28,7 +28,10 @@ impl bb8::ManageConnection for RedisClusterManager {
     }
 
     async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
-        let pong: String = redis::cmd("PING").query_async(conn).await?;
+        let pong: String = redis::cmd("PING")
+            .query_async(conn)
+            .await
+            .inspect_err(|e| tracing::warn!(err = %e, "[redix] invalid connection, recycling"))?;
         match pong.as_str() {
             "PONG" => Ok(()),
             _ => Err((redis::ErrorKind::ResponseError, "ping request").into()),
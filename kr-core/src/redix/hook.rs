@@ -0,0 +1,64 @@
+use std::{fmt, future::Future, pin::Pin};
+
+/// bb8 错误收集：记录连接失败等后台错误
+#[derive(Debug, Clone, Copy)]
+pub struct TracingErrorSink;
+
+impl<E: fmt::Display + 'static> bb8::ErrorSink<E> for TracingErrorSink {
+    fn sink(&self, error: E) {
+        tracing::error!(err = %error, "[redix] pool connection error");
+    }
+
+    fn boxed_clone(&self) -> Box<dyn bb8::ErrorSink<E>> {
+        Box::new(*self)
+    }
+}
+
+/// bb8 连接定制：记录新建连接
+#[derive(Debug, Clone, Copy)]
+pub struct TracingCustomizer;
+
+impl<C: Send + 'static, E: 'static> bb8::CustomizeConnection<C, E> for TracingCustomizer {
+    fn on_acquire<'a>(
+        &'a self,
+        _: &'a mut C,
+    ) -> Pin<Box<dyn Future<Output = Result<(), E>> + Send + 'a>> {
+        tracing::debug!("[redix] pool connection established");
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// r2d2 错误处理：记录连接失败
+#[derive(Debug, Clone, Copy)]
+pub struct TracingErrorHandler;
+
+impl<E: fmt::Display> r2d2::HandleError<E> for TracingErrorHandler {
+    fn handle_error(&self, error: E) {
+        tracing::error!(err = %error, "[redix] sync pool connection error");
+    }
+}
+
+/// r2d2 事件处理：记录连接新建、回收与获取超时
+#[derive(Debug, Clone, Copy)]
+pub struct TracingEventHandler;
+
+impl r2d2::HandleEvent for TracingEventHandler {
+    fn handle_acquire(&self, event: r2d2::event::AcquireEvent) {
+        tracing::debug!(
+            id = event.connection_id(),
+            "[redix] sync pool connection established"
+        );
+    }
+
+    fn handle_release(&self, event: r2d2::event::ReleaseEvent) {
+        tracing::debug!(
+            id = event.connection_id(),
+            age = ?event.age(),
+            "[redix] sync pool connection released"
+        );
+    }
+
+    fn handle_timeout(&self, event: r2d2::event::TimeoutEvent) {
+        tracing::warn!(timeout = ?event.timeout(), "[redix] sync pool checkout timeout");
+    }
+}
@@ -0,0 +1,1079 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use redis::{
+    aio::ConnectionLike, Cmd, ErrorKind, Pipeline, RedisError, RedisFuture, RedisResult, Value,
+};
+
+#[derive(Clone)]
+enum Data {
+    Str(Vec<u8>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+    List(VecDeque<Vec<u8>>),
+    ZSet(HashMap<Vec<u8>, f64>),
+    Set(HashSet<Vec<u8>>),
+}
+
+struct Entry {
+    data: Data,
+    expire_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Inner {
+    db: HashMap<Vec<u8>, Entry>,
+    // sha1 => 脚本内容
+    loaded: HashMap<String, String>,
+    // DUMP 的快照，序列化值为下标
+    dumps: Vec<Data>,
+}
+
+/// 进程内 Redis 存储（测试用）
+///
+/// 支持命令：PING、GET、SET(EX/PX/NX/XX)、SETEX、PSETEX、MGET、DEL、EXISTS、EXPIRE、TTL、PTTL、RENAME、DUMP、RESTORE(REPLACE)、INCR/INCRBY、
+/// HSET、HGET、HMGET、HGETALL、HDEL、HINCRBY、HLEN、LPUSH、RPUSH、LPOP、RPOP、LLEN、LINDEX、LRANGE、
+/// ZADD(NX/XX/CH)、ZREM、ZSCORE、ZCARD、ZRANGEBYSCORE(LIMIT)、SADD、SREM、SMEMBERS、SISMEMBER、SCARD、
+/// SCAN(MATCH/COUNT)、TIME、FLUSHDB、SCRIPT LOAD、EVAL/EVALSHA、MULTI/EXEC（仅 pipeline）
+///
+/// 脚本在内嵌的 Lua 5.1 中执行（与 Redis 相同），支持 `redis.call`、`redis.pcall`、`redis.error_reply`、`redis.status_reply`、`redis.replicate_commands`
+///
+/// # Examples
+///
+/// ```
+/// let pool = redix::open::<redix::Mock>(vec![], None).await?;
+/// let redis = Redis::Single(pool);
+/// ```
+#[derive(Clone, Default)]
+pub struct MockStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MockStore {
+    /// 执行单条命令
+    pub fn exec(&self, args: Vec<Vec<u8>>) -> RedisResult<Value> {
+        let name = args
+            .first()
+            .map(|v| v.to_ascii_uppercase())
+            .unwrap_or_default();
+        match name.as_slice() {
+            b"EVAL" | b"EVALSHA" => self.eval(&name, &args[1..]),
+            _ => self.inner.lock().unwrap().exec(&name, &args[1..]),
+        }
+    }
+
+    /// 执行 pipeline：MULTI 与 EXEC 之间的命令返回 QUEUED，结果在 EXEC 中一并返回
+    pub fn exec_pipeline(&self, cmds: Vec<Vec<Vec<u8>>>) -> RedisResult<Vec<Value>> {
+        let mut out = Vec::with_capacity(cmds.len());
+        let mut queued: Option<Vec<Value>> = None;
+        for args in cmds {
+            let name = args
+                .first()
+                .map(|v| v.to_ascii_uppercase())
+                .unwrap_or_default();
+            match name.as_slice() {
+                b"MULTI" => {
+                    queued = Some(Vec::new());
+                    out.push(Value::Okay);
+                }
+                b"EXEC" => {
+                    let v = queued.take().ok_or_else(|| err("EXEC without MULTI"))?;
+                    out.push(Value::Array(v));
+                }
+                _ => {
+                    let v = self.exec(args)?;
+                    match &mut queued {
+                        Some(q) => {
+                            q.push(v);
+                            out.push(Value::SimpleString("QUEUED".to_string()));
+                        }
+                        None => out.push(v),
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn eval(&self, name: &[u8], args: &[Vec<u8>]) -> RedisResult<Value> {
+        // 脚本执行期间持有锁，与 Redis 一样原子执行
+        let mut inner = self.inner.lock().unwrap();
+        let body = match name {
+            b"EVAL" => string(arg(args, 0)?)?,
+            _ => {
+                let sha = string(arg(args, 0)?)?;
+                match inner.loaded.get(&sha) {
+                    Some(v) => v.clone(),
+                    None => return Err((ErrorKind::NoScriptError, "NOSCRIPT", sha).into()),
+                }
+            }
+        };
+
+        let numkeys = int(arg(args, 1)?)?.max(0) as usize;
+        let rest = args.get(2..).unwrap_or_default();
+        if rest.len() < numkeys {
+            return Err(err("wrong number of keys"));
+        }
+        let (keys, argv) = rest.split_at(numkeys);
+
+        run_script(&mut inner, &body, keys, argv)
+            .map_err(|e| err(&format!("Error running script: {}", e)))?
+    }
+}
+
+// 在内嵌的 Lua 5.1 中执行脚本（与 Redis 相同的解释器及类型转换），redis.call 转发到存储
+fn run_script(
+    inner: &mut Inner,
+    body: &str,
+    keys: &[Vec<u8>],
+    argv: &[Vec<u8>],
+) -> mlua::Result<RedisResult<Value>> {
+    let lua = mlua::Lua::new();
+    let inner = RefCell::new(inner);
+    let call = |args: Vec<Vec<u8>>| {
+        let name = args
+            .first()
+            .map(|v| v.to_ascii_uppercase())
+            .unwrap_or_default();
+        match name.as_slice() {
+            b"" | b"EVAL" | b"EVALSHA" => Err(err("command not allowed from script")),
+            _ => inner.borrow_mut().exec(&name, &args[1..]),
+        }
+    };
+
+    lua.scope(|scope| {
+        let redis = lua.create_table()?;
+        redis.set(
+            "call",
+            scope.create_function(|lua, args: mlua::Variadic<mlua::Value>| {
+                let v = call(lua_args(args)?).map_err(mlua::Error::external)?;
+                to_lua(lua, v)
+            })?,
+        )?;
+        redis.set(
+            "pcall",
+            scope.create_function(|lua, args: mlua::Variadic<mlua::Value>| {
+                match call(lua_args(args)?) {
+                    Ok(v) => to_lua(lua, v),
+                    Err(e) => reply(lua, "err", &e.to_string()),
+                }
+            })?,
+        )?;
+        redis.set(
+            "error_reply",
+            lua.create_function(|lua, msg: String| reply(lua, "err", &msg))?,
+        )?;
+        redis.set(
+            "status_reply",
+            lua.create_function(|lua, msg: String| reply(lua, "ok", &msg))?,
+        )?;
+        redis.set("replicate_commands", lua.create_function(|_, ()| Ok(true))?)?;
+
+        let globals = lua.globals();
+        globals.set("redis", redis)?;
+        globals.set("KEYS", lua_strings(&lua, keys)?)?;
+        globals.set("ARGV", lua_strings(&lua, argv)?)?;
+
+        let ret: mlua::Value = lua.load(body).call(())?;
+        from_lua(ret)
+    })
+}
+
+fn lua_strings<'lua>(lua: &'lua mlua::Lua, list: &[Vec<u8>]) -> mlua::Result<mlua::Table<'lua>> {
+    lua.create_sequence_from(
+        list.iter()
+            .map(|v| lua.create_string(v))
+            .collect::<mlua::Result<Vec<_>>>()?,
+    )
+}
+
+fn reply<'lua>(lua: &'lua mlua::Lua, field: &str, msg: &str) -> mlua::Result<mlua::Value<'lua>> {
+    let t = lua.create_table()?;
+    t.set(field, msg)?;
+    Ok(mlua::Value::Table(t))
+}
+
+// redis.call 的参数：字符串或数字
+fn lua_args(args: mlua::Variadic<mlua::Value>) -> mlua::Result<Vec<Vec<u8>>> {
+    args.iter()
+        .map(|v| match v {
+            mlua::Value::String(s) => Ok(s.as_bytes().to_vec()),
+            mlua::Value::Integer(n) => Ok(n.to_string().into_bytes()),
+            mlua::Value::Number(n) if n.fract() == 0.0 => Ok((*n as i64).to_string().into_bytes()),
+            mlua::Value::Number(n) => Ok(n.to_string().into_bytes()),
+            _ => Err(mlua::Error::runtime(
+                "Lua redis() command arguments must be strings or integers",
+            )),
+        })
+        .collect()
+}
+
+// Redis 回复 => Lua 值
+fn to_lua(lua: &mlua::Lua, v: Value) -> mlua::Result<mlua::Value<'_>> {
+    Ok(match v {
+        Value::Nil => mlua::Value::Boolean(false),
+        Value::Int(n) => mlua::Value::Number(n as f64),
+        Value::BulkString(b) => mlua::Value::String(lua.create_string(b)?),
+        Value::Okay => reply(lua, "ok", "OK")?,
+        Value::SimpleString(s) => reply(lua, "ok", &s)?,
+        Value::Double(f) => mlua::Value::Number(f),
+        Value::Array(list) => {
+            let t = lua.create_table()?;
+            for (i, v) in list.into_iter().enumerate() {
+                t.raw_set(i + 1, to_lua(lua, v)?)?;
+            }
+            mlua::Value::Table(t)
+        }
+        _ => return Err(mlua::Error::runtime("unsupported reply type")),
+    })
+}
+
+// Lua 返回值 => Redis 回复（数字截断为整数，数组遇到 nil 截止）
+fn from_lua(v: mlua::Value) -> mlua::Result<RedisResult<Value>> {
+    Ok(Ok(match v {
+        mlua::Value::Nil | mlua::Value::Boolean(false) => Value::Nil,
+        mlua::Value::Boolean(true) => Value::Int(1),
+        mlua::Value::Integer(n) => Value::Int(n),
+        mlua::Value::Number(n) => Value::Int(n as i64),
+        mlua::Value::String(s) => Value::BulkString(s.as_bytes().to_vec()),
+        mlua::Value::Table(t) => {
+            if let Some(msg) = t.get::<_, Option<String>>("err")? {
+                return Ok(Err(err(&msg)));
+            }
+            if let Some(msg) = t.get::<_, Option<String>>("ok")? {
+                return Ok(Ok(match msg.as_str() {
+                    "OK" => Value::Okay,
+                    _ => Value::SimpleString(msg),
+                }));
+            }
+            let mut list = Vec::new();
+            for i in 1.. {
+                let v: mlua::Value = t.raw_get(i)?;
+                if v == mlua::Value::Nil {
+                    break;
+                }
+                match from_lua(v)? {
+                    Ok(v) => list.push(v),
+                    Err(e) => return Ok(Err(e)),
+                }
+            }
+            Value::Array(list)
+        }
+        _ => return Err(mlua::Error::runtime("unsupported return type")),
+    }))
+}
+
+impl Inner {
+    fn exec(&mut self, name: &[u8], args: &[Vec<u8>]) -> RedisResult<Value> {
+        self.purge();
+
+        match name {
+            b"PING" => Ok(Value::SimpleString("PONG".to_string())),
+            b"TIME" => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default();
+                Ok(Value::Array(vec![
+                    Value::BulkString(now.as_secs().to_string().into_bytes()),
+                    Value::BulkString(now.subsec_micros().to_string().into_bytes()),
+                ]))
+            }
+            b"SCAN" => {
+                let cursor = int(arg(args, 0)?)?.max(0) as usize;
+                let mut pattern: &[u8] = b"*";
+                let mut count = 10;
+                let mut i = 1;
+                while i + 1 < args.len() {
+                    match args[i].to_ascii_uppercase().as_slice() {
+                        b"MATCH" => pattern = &args[i + 1],
+                        b"COUNT" => count = int(&args[i + 1])?.max(1) as usize,
+                        _ => return Err(err("syntax error")),
+                    }
+                    i += 2;
+                }
+                // 游标为有序 key 列表的偏移量
+                let mut keys: Vec<&Vec<u8>> = self.db.keys().collect();
+                keys.sort();
+                let end = (cursor + count).min(keys.len());
+                let next = if end >= keys.len() { 0 } else { end };
+                let page = keys
+                    .get(cursor..end)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|k| glob_match(pattern, k))
+                    .map(|k| Value::BulkString(k.to_vec()))
+                    .collect();
+                Ok(Value::Array(vec![
+                    Value::BulkString(next.to_string().into_bytes()),
+                    Value::Array(page),
+                ]))
+            }
+            b"FLUSHDB" | b"FLUSHALL" => {
+                self.db.clear();
+                Ok(Value::Okay)
+            }
+            b"GET" => match self.db.get(arg(args, 0)?) {
+                None => Ok(Value::Nil),
+                Some(Entry {
+                    data: Data::Str(v), ..
+                }) => Ok(Value::BulkString(v.clone())),
+                Some(_) => Err(wrong_type()),
+            },
+            b"SET" => self.set(args),
+            b"SETEX" => {
+                let secs = int(arg(args, 1)?)?;
+                self.put(
+                    arg(args, 0)?,
+                    arg(args, 2)?,
+                    Some(Duration::from_secs(secs.max(0) as u64)),
+                );
+                Ok(Value::Okay)
+            }
+            b"PSETEX" => {
+                let ms = int(arg(args, 1)?)?;
+                self.put(
+                    arg(args, 0)?,
+                    arg(args, 2)?,
+                    Some(Duration::from_millis(ms.max(0) as u64)),
+                );
+                Ok(Value::Okay)
+            }
+            b"MGET" => Ok(Value::Array(
+                args.iter()
+                    .map(|k| match self.db.get(k) {
+                        Some(Entry {
+                            data: Data::Str(v), ..
+                        }) => Value::BulkString(v.clone()),
+                        _ => Value::Nil,
+                    })
+                    .collect(),
+            )),
+            b"DEL" => Ok(Value::Int(
+                args.iter().filter(|k| self.db.remove(*k).is_some()).count() as i64,
+            )),
+            b"EXISTS" => Ok(Value::Int(
+                args.iter().filter(|k| self.db.contains_key(*k)).count() as i64,
+            )),
+            b"EXPIRE" => {
+                let secs = int(arg(args, 1)?)?;
+                match self.db.get_mut(arg(args, 0)?) {
+                    Some(e) => {
+                        e.expire_at =
+                            Some(Instant::now() + Duration::from_secs(secs.max(0) as u64));
+                        Ok(Value::Int(1))
+                    }
+                    None => Ok(Value::Int(0)),
+                }
+            }
+            b"TTL" => Ok(Value::Int(match self.db.get(arg(args, 0)?) {
+                None => -2,
+                Some(Entry {
+                    expire_at: None, ..
+                }) => -1,
+                Some(Entry {
+                    expire_at: Some(t), ..
+                }) => t
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+                    .round() as i64,
+            })),
+            b"PTTL" => Ok(Value::Int(match self.db.get(arg(args, 0)?) {
+                None => -2,
+                Some(Entry {
+                    expire_at: None, ..
+                }) => -1,
+                Some(Entry {
+                    expire_at: Some(t), ..
+                }) => t.saturating_duration_since(Instant::now()).as_millis() as i64,
+            })),
+            b"DUMP" => match self.db.get(arg(args, 0)?) {
+                None => Ok(Value::Nil),
+                Some(e) => {
+                    let data = e.data.clone();
+                    self.dumps.push(data);
+                    Ok(Value::BulkString(
+                        (self.dumps.len() - 1).to_string().into_bytes(),
+                    ))
+                }
+            },
+            b"RESTORE" => {
+                let key = arg(args, 0)?;
+                let ms = int(arg(args, 1)?)?;
+                let data = usize::try_from(int(arg(args, 2)?)?)
+                    .ok()
+                    .and_then(|i| self.dumps.get(i))
+                    .ok_or_else(|| err("DUMP payload version or checksum are wrong"))?
+                    .clone();
+                let replace = args[3..].iter().any(|v| v.eq_ignore_ascii_case(b"REPLACE"));
+                if !replace && self.db.contains_key(key) {
+                    return Err(err("BUSYKEY Target key name already exists."));
+                }
+                let expire_at = (ms > 0).then(|| Instant::now() + Duration::from_millis(ms as u64));
+                self.db.insert(key.clone(), Entry { data, expire_at });
+                Ok(Value::Okay)
+            }
+            b"RENAME" => {
+                let entry = self
+                    .db
+                    .remove(arg(args, 0)?)
+                    .ok_or_else(|| err("no such key"))?;
+                self.db.insert(arg(args, 1)?.clone(), entry);
+                Ok(Value::Okay)
+            }
+            b"INCR" | b"INCRBY" => {
+                let delta = if name == b"INCR" {
+                    1
+                } else {
+                    int(arg(args, 1)?)?
+                };
+                let key = arg(args, 0)?;
+                let (cur, expire_at) = match self.db.get(key) {
+                    None => (0, None),
+                    Some(Entry {
+                        data: Data::Str(v),
+                        expire_at,
+                    }) => (int(v)?, *expire_at),
+                    Some(_) => return Err(wrong_type()),
+                };
+                let v = cur + delta;
+                self.db.insert(
+                    key.clone(),
+                    Entry {
+                        data: Data::Str(v.to_string().into_bytes()),
+                        expire_at,
+                    },
+                );
+                Ok(Value::Int(v))
+            }
+            b"HSET" | b"HMSET" => {
+                let key = arg(args, 0)?;
+                let pairs = args.get(1..).unwrap_or_default();
+                if pairs.is_empty() || pairs.len() % 2 != 0 {
+                    return Err(err("wrong number of arguments for 'hset'"));
+                }
+                let hash = self.hash_mut(key)?;
+                let mut added = 0;
+                for kv in pairs.chunks(2) {
+                    if hash.insert(kv[0].clone(), kv[1].clone()).is_none() {
+                        added += 1;
+                    }
+                }
+                match name {
+                    b"HMSET" => Ok(Value::Okay),
+                    _ => Ok(Value::Int(added)),
+                }
+            }
+            b"HGET" => Ok(self
+                .hash(arg(args, 0)?)?
+                .and_then(|h| h.get(arg(args, 1).ok()?).cloned())
+                .map_or(Value::Nil, Value::BulkString)),
+            b"HMGET" => {
+                let hash = self.hash(arg(args, 0)?)?;
+                Ok(Value::Array(
+                    args.get(1..)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|f| {
+                            hash.and_then(|h| h.get(f).cloned())
+                                .map_or(Value::Nil, Value::BulkString)
+                        })
+                        .collect(),
+                ))
+            }
+            b"HGETALL" => Ok(Value::Array(
+                self.hash(arg(args, 0)?)?
+                    .map(|h| {
+                        h.iter()
+                            .flat_map(|(k, v)| {
+                                [Value::BulkString(k.clone()), Value::BulkString(v.clone())]
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            )),
+            b"HDEL" => {
+                let key = arg(args, 0)?;
+                let Some(Entry {
+                    data: Data::Hash(h),
+                    ..
+                }) = self.db.get_mut(key)
+                else {
+                    return Ok(Value::Int(0));
+                };
+                let n = args[1..].iter().filter(|f| h.remove(*f).is_some()).count();
+                if h.is_empty() {
+                    self.db.remove(key);
+                }
+                Ok(Value::Int(n as i64))
+            }
+            b"HINCRBY" => {
+                let delta = int(arg(args, 2)?)?;
+                let field = arg(args, 1)?.clone();
+                let hash = self.hash_mut(arg(args, 0)?)?;
+                let cur = match hash.get(&field) {
+                    Some(v) => int(v)?,
+                    None => 0,
+                };
+                let v = cur + delta;
+                hash.insert(field, v.to_string().into_bytes());
+                Ok(Value::Int(v))
+            }
+            b"HLEN" => Ok(Value::Int(
+                self.hash(arg(args, 0)?)?.map_or(0, |h| h.len()) as i64
+            )),
+            b"LPUSH" | b"RPUSH" => {
+                let values = args.get(1..).unwrap_or_default();
+                if values.is_empty() {
+                    return Err(err("wrong number of arguments for 'push'"));
+                }
+                let list = self.list_mut(arg(args, 0)?)?;
+                for v in values {
+                    match name {
+                        b"LPUSH" => list.push_front(v.clone()),
+                        _ => list.push_back(v.clone()),
+                    }
+                }
+                Ok(Value::Int(list.len() as i64))
+            }
+            b"LPOP" | b"RPOP" => {
+                let key = arg(args, 0)?;
+                if !self.db.contains_key(key) {
+                    return Ok(Value::Nil);
+                }
+                let list = self.list_mut(key)?;
+                let v = match name {
+                    b"LPOP" => list.pop_front(),
+                    _ => list.pop_back(),
+                };
+                self.remove_if_empty(key);
+                Ok(v.map_or(Value::Nil, Value::BulkString))
+            }
+            b"LLEN" => Ok(Value::Int(match self.db.get(arg(args, 0)?) {
+                None => 0,
+                Some(Entry {
+                    data: Data::List(l),
+                    ..
+                }) => l.len() as i64,
+                Some(_) => return Err(wrong_type()),
+            })),
+            b"LINDEX" => {
+                let list = match self.db.get(arg(args, 0)?) {
+                    None => return Ok(Value::Nil),
+                    Some(Entry {
+                        data: Data::List(l),
+                        ..
+                    }) => l,
+                    Some(_) => return Err(wrong_type()),
+                };
+                let i = int(arg(args, 1)?)?;
+                let i = if i < 0 { list.len() as i64 + i } else { i };
+                Ok(usize::try_from(i)
+                    .ok()
+                    .and_then(|i| list.get(i))
+                    .map_or(Value::Nil, |v| Value::BulkString(v.clone())))
+            }
+            b"LRANGE" => {
+                let list = match self.db.get(arg(args, 0)?) {
+                    None => return Ok(Value::Array(vec![])),
+                    Some(Entry {
+                        data: Data::List(l),
+                        ..
+                    }) => l,
+                    Some(_) => return Err(wrong_type()),
+                };
+                let len = list.len() as i64;
+                let index = |v: i64| if v < 0 { (len + v).max(0) } else { v };
+                let start = index(int(arg(args, 1)?)?);
+                let stop = index(int(arg(args, 2)?)?).min(len - 1);
+                Ok(Value::Array(
+                    (start..=stop)
+                        .filter_map(|i| list.get(i as usize))
+                        .map(|v| Value::BulkString(v.clone()))
+                        .collect(),
+                ))
+            }
+            b"ZADD" => {
+                let key = arg(args, 0)?;
+                let mut nx = false;
+                let mut xx = false;
+                let mut ch = false;
+                let mut i = 1;
+                while let Some(v) = args.get(i) {
+                    match v.to_ascii_uppercase().as_slice() {
+                        b"NX" => nx = true,
+                        b"XX" => xx = true,
+                        b"CH" => ch = true,
+                        _ => break,
+                    }
+                    i += 1;
+                }
+                let pairs = args.get(i..).unwrap_or_default();
+                if pairs.is_empty() || pairs.len() % 2 != 0 {
+                    return Err(err("wrong number of arguments for 'zadd'"));
+                }
+                let zset = self.zset_mut(key)?;
+                let mut n = 0;
+                for kv in pairs.chunks(2) {
+                    let score = float(&kv[0])?;
+                    match zset.get(&kv[1]) {
+                        Some(_) if nx => {}
+                        None if xx => {}
+                        Some(old) => {
+                            if ch && *old != score {
+                                n += 1;
+                            }
+                            zset.insert(kv[1].clone(), score);
+                        }
+                        None => {
+                            n += 1;
+                            zset.insert(kv[1].clone(), score);
+                        }
+                    }
+                }
+                self.remove_if_empty(key);
+                Ok(Value::Int(n))
+            }
+            b"ZREM" => {
+                let key = arg(args, 0)?;
+                let Some(Entry {
+                    data: Data::ZSet(z),
+                    ..
+                }) = self.db.get_mut(key)
+                else {
+                    return Ok(Value::Int(0));
+                };
+                let n = args[1..].iter().filter(|m| z.remove(*m).is_some()).count();
+                self.remove_if_empty(key);
+                Ok(Value::Int(n as i64))
+            }
+            b"ZSCORE" => Ok(self
+                .zset(arg(args, 0)?)?
+                .and_then(|z| z.get(arg(args, 1).ok()?))
+                .map_or(Value::Nil, |v| {
+                    Value::BulkString(v.to_string().into_bytes())
+                })),
+            b"ZCARD" => Ok(Value::Int(
+                self.zset(arg(args, 0)?)?.map_or(0, |z| z.len()) as i64
+            )),
+            b"ZRANGEBYSCORE" => {
+                let min = float(arg(args, 1)?)?;
+                let max = float(arg(args, 2)?)?;
+                let (offset, count) = match args.get(3) {
+                    Some(v) if v.eq_ignore_ascii_case(b"LIMIT") => {
+                        (int(arg(args, 4)?)?.max(0) as usize, int(arg(args, 5)?)?)
+                    }
+                    _ => (0, -1),
+                };
+                let mut members: Vec<(&Vec<u8>, f64)> = self
+                    .zset(arg(args, 0)?)?
+                    .map(|z| {
+                        z.iter()
+                            .filter(|(_, s)| **s >= min && **s <= max)
+                            .map(|(m, s)| (m, *s))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                members.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+                let count = if count < 0 {
+                    members.len()
+                } else {
+                    count as usize
+                };
+                Ok(Value::Array(
+                    members
+                        .into_iter()
+                        .skip(offset)
+                        .take(count)
+                        .map(|(m, _)| Value::BulkString(m.clone()))
+                        .collect(),
+                ))
+            }
+            b"SADD" => {
+                let key = arg(args, 0)?;
+                let members = args.get(1..).unwrap_or_default();
+                if members.is_empty() {
+                    return Err(err("wrong number of arguments for 'sadd'"));
+                }
+                let set = self.sset_mut(key)?;
+                let n = members.iter().filter(|m| set.insert(m.to_vec())).count();
+                Ok(Value::Int(n as i64))
+            }
+            b"SREM" => {
+                let key = arg(args, 0)?;
+                let Some(Entry {
+                    data: Data::Set(set),
+                    ..
+                }) = self.db.get_mut(key)
+                else {
+                    return Ok(Value::Int(0));
+                };
+                let n = args[1..].iter().filter(|m| set.remove(*m)).count();
+                self.remove_if_empty(key);
+                Ok(Value::Int(n as i64))
+            }
+            b"SMEMBERS" => Ok(Value::Array(
+                self.sset(arg(args, 0)?)?
+                    .map(|set| set.iter().map(|v| Value::BulkString(v.clone())).collect())
+                    .unwrap_or_default(),
+            )),
+            b"SISMEMBER" => Ok(Value::Int(
+                self.sset(arg(args, 0)?)?
+                    .is_some_and(|set| set.contains(arg(args, 1).unwrap_or(&Vec::new())))
+                    as i64,
+            )),
+            b"SCARD" => Ok(Value::Int(
+                self.sset(arg(args, 0)?)?.map_or(0, |set| set.len()) as i64,
+            )),
+            b"SCRIPT" => {
+                let sub = arg(args, 0)?.to_ascii_uppercase();
+                if sub != b"LOAD" {
+                    return Err(err("unsupported SCRIPT subcommand"));
+                }
+                let body = string(arg(args, 1)?)?;
+                let sha = redis::Script::new(&body).get_hash().to_string();
+                self.loaded.insert(sha.clone(), body);
+                Ok(Value::BulkString(sha.into_bytes()))
+            }
+            _ => Err(err(&format!(
+                "unsupported command '{}'",
+                String::from_utf8_lossy(name)
+            ))),
+        }
+    }
+
+    fn set(&mut self, args: &[Vec<u8>]) -> RedisResult<Value> {
+        let key = arg(args, 0)?;
+        let value = arg(args, 1)?;
+
+        let mut ttl = None;
+        let mut nx = false;
+        let mut xx = false;
+        let mut i = 2;
+        while i < args.len() {
+            match args[i].to_ascii_uppercase().as_slice() {
+                b"NX" => nx = true,
+                b"XX" => xx = true,
+                b"EX" => {
+                    i += 1;
+                    ttl = Some(Duration::from_secs(int(arg(args, i)?)?.max(0) as u64));
+                }
+                b"PX" => {
+                    i += 1;
+                    ttl = Some(Duration::from_millis(int(arg(args, i)?)?.max(0) as u64));
+                }
+                _ => return Err(err("syntax error")),
+            }
+            i += 1;
+        }
+
+        let exists = self.db.contains_key(key);
+        if (nx && exists) || (xx && !exists) {
+            return Ok(Value::Nil);
+        }
+        self.put(key, value, ttl);
+        Ok(Value::Okay)
+    }
+
+    fn put(&mut self, key: &[u8], value: &[u8], ttl: Option<Duration>) {
+        self.db.insert(
+            key.to_vec(),
+            Entry {
+                data: Data::Str(value.to_vec()),
+                expire_at: ttl.map(|d| Instant::now() + d),
+            },
+        );
+    }
+
+    fn hash(&self, key: &[u8]) -> RedisResult<Option<&HashMap<Vec<u8>, Vec<u8>>>> {
+        match self.db.get(key) {
+            None => Ok(None),
+            Some(Entry {
+                data: Data::Hash(h),
+                ..
+            }) => Ok(Some(h)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn hash_mut(&mut self, key: &[u8]) -> RedisResult<&mut HashMap<Vec<u8>, Vec<u8>>> {
+        let e = self.db.entry(key.to_vec()).or_insert_with(|| Entry {
+            data: Data::Hash(HashMap::new()),
+            expire_at: None,
+        });
+        match &mut e.data {
+            Data::Hash(h) => Ok(h),
+            _ => Err(wrong_type()),
+        }
+    }
+
+    fn list_mut(&mut self, key: &[u8]) -> RedisResult<&mut VecDeque<Vec<u8>>> {
+        let e = self.db.entry(key.to_vec()).or_insert_with(|| Entry {
+            data: Data::List(VecDeque::new()),
+            expire_at: None,
+        });
+        match &mut e.data {
+            Data::List(l) => Ok(l),
+            _ => Err(wrong_type()),
+        }
+    }
+
+    fn zset(&self, key: &[u8]) -> RedisResult<Option<&HashMap<Vec<u8>, f64>>> {
+        match self.db.get(key) {
+            None => Ok(None),
+            Some(Entry {
+                data: Data::ZSet(z),
+                ..
+            }) => Ok(Some(z)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn zset_mut(&mut self, key: &[u8]) -> RedisResult<&mut HashMap<Vec<u8>, f64>> {
+        let e = self.db.entry(key.to_vec()).or_insert_with(|| Entry {
+            data: Data::ZSet(HashMap::new()),
+            expire_at: None,
+        });
+        match &mut e.data {
+            Data::ZSet(z) => Ok(z),
+            _ => Err(wrong_type()),
+        }
+    }
+
+    fn sset(&self, key: &[u8]) -> RedisResult<Option<&HashSet<Vec<u8>>>> {
+        match self.db.get(key) {
+            None => Ok(None),
+            Some(Entry {
+                data: Data::Set(set),
+                ..
+            }) => Ok(Some(set)),
+            Some(_) => Err(wrong_type()),
+        }
+    }
+
+    fn sset_mut(&mut self, key: &[u8]) -> RedisResult<&mut HashSet<Vec<u8>>> {
+        let e = self.db.entry(key.to_vec()).or_insert_with(|| Entry {
+            data: Data::Set(HashSet::new()),
+            expire_at: None,
+        });
+        match &mut e.data {
+            Data::Set(set) => Ok(set),
+            _ => Err(wrong_type()),
+        }
+    }
+
+    // 集合类型为空时删除 key（与 Redis 行为一致）
+    fn remove_if_empty(&mut self, key: &[u8]) {
+        let empty = match self.db.get(key) {
+            Some(Entry {
+                data: Data::Hash(h),
+                ..
+            }) => h.is_empty(),
+            Some(Entry {
+                data: Data::List(l),
+                ..
+            }) => l.is_empty(),
+            Some(Entry {
+                data: Data::ZSet(z),
+                ..
+            }) => z.is_empty(),
+            Some(Entry {
+                data: Data::Set(set),
+                ..
+            }) => set.is_empty(),
+            _ => false,
+        };
+        if empty {
+            self.db.remove(key);
+        }
+    }
+
+    fn purge(&mut self) {
+        let now = Instant::now();
+        self.db.retain(|_, e| e.expire_at.is_none_or(|t| t > now));
+    }
+}
+
+/// 基于 MockStore 的异步连接
+#[derive(Clone)]
+pub struct MockConnection {
+    store: MockStore,
+}
+
+impl MockConnection {
+    pub fn new(store: MockStore) -> Self {
+        Self { store }
+    }
+}
+
+impl ConnectionLike for MockConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        let ret = self.store.exec(args_of(cmd));
+        Box::pin(async move { ret })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        let mut cmds: Vec<Vec<Vec<u8>>> = cmd.cmd_iter().map(args_of).collect();
+        if cmd.is_transaction() {
+            cmds.insert(0, vec![b"MULTI".to_vec()]);
+            cmds.push(vec![b"EXEC".to_vec()]);
+        }
+        let ret = self
+            .store
+            .exec_pipeline(cmds)
+            .map(|v| v.into_iter().skip(offset).take(count).collect());
+        Box::pin(async move { ret })
+    }
+
+    fn get_db(&self) -> i64 {
+        0
+    }
+}
+
+fn args_of(cmd: &Cmd) -> Vec<Vec<u8>> {
+    cmd.args_iter()
+        .filter_map(|v| match v {
+            redis::Arg::Simple(v) => Some(v.to_vec()),
+            redis::Arg::Cursor => None,
+        })
+        .collect()
+}
+
+fn arg(args: &[Vec<u8>], i: usize) -> RedisResult<&Vec<u8>> {
+    args.get(i).ok_or_else(|| err("wrong number of arguments"))
+}
+
+fn string(v: &[u8]) -> RedisResult<String> {
+    String::from_utf8(v.to_vec()).map_err(|_| err("invalid utf-8"))
+}
+
+fn int(v: &[u8]) -> RedisResult<i64> {
+    string(v)?
+        .parse()
+        .map_err(|_| err("value is not an integer or out of range"))
+}
+
+fn float(v: &[u8]) -> RedisResult<f64> {
+    match string(v)?.to_ascii_lowercase().as_str() {
+        "-inf" => Ok(f64::NEG_INFINITY),
+        "+inf" | "inf" => Ok(f64::INFINITY),
+        s => s.parse().map_err(|_| err("value is not a valid float")),
+    }
+}
+
+// glob 匹配（支持 `*`、`?`）
+fn glob_match(pattern: &[u8], s: &[u8]) -> bool {
+    match (pattern.first(), s.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], s) || (!s.is_empty() && glob_match(pattern, &s[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &s[1..]),
+        (Some(p), Some(c)) if p == c => glob_match(&pattern[1..], &s[1..]),
+        _ => false,
+    }
+}
+
+fn err(msg: &str) -> RedisError {
+    (ErrorKind::ResponseError, "mock", msg.to_string()).into()
+}
+
+fn wrong_type() -> RedisError {
+    (
+        ErrorKind::TypeError,
+        "WRONGTYPE",
+        "Operation against a key holding the wrong kind of value".to_string(),
+    )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::AsyncCommands;
+
+    use crate::redix;
+
+    #[tokio::test]
+    async fn test_mock() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let mut conn = pool.get().await.unwrap();
+
+        let _: () = conn.set_ex("a", "1", 10).await.unwrap();
+        let _: () = conn.hset("h", "f", "v").await.unwrap();
+        let v: Vec<Option<String>> = conn.mget(&["a", "b"]).await.unwrap();
+        assert_eq!(v, vec![Some("1".to_string()), None]);
+        let ttl: i64 = conn.ttl("a").await.unwrap();
+        assert_eq!(ttl, 10);
+        assert!(conn.get::<_, String>("h").await.is_err());
+
+        // SET NX
+        let opts = redis::SetOptions::default().conditional_set(redis::ExistenceCheck::NX);
+        let ok: bool = conn.set_options("a", "2", opts).await.unwrap();
+        assert!(!ok);
+
+        // 脚本：EVALSHA => NOSCRIPT => SCRIPT LOAD => EVALSHA
+        let script = redis::Script::new(
+            r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#,
+        );
+        let n: i64 = script
+            .key("a")
+            .arg("1")
+            .invoke_async(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(n, 1);
+        let exists: bool = conn.exists("a").await.unwrap();
+        assert!(!exists);
+
+        // 脚本的类型转换：nil => false，数组遇 nil 截止，状态/错误回复
+        let v: (Vec<Option<String>>, String, i64) = redis::Script::new(
+            r#"
+local v = redis.call('GET', 'missing')
+local list = {tostring(v), 'x', nil, 'y'}
+return {list, redis.call('SET', 'a', 1)['ok'], 3.7}
+"#,
+        )
+        .invoke_async(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            v,
+            (
+                vec![Some("false".to_string()), Some("x".to_string())],
+                "OK".to_string(),
+                3
+            )
+        );
+        let ret: redis::RedisResult<()> = redis::Script::new("return redis.error_reply('boom')")
+            .invoke_async(&mut *conn)
+            .await;
+        assert!(ret.unwrap_err().to_string().contains("boom"));
+        let ret: redis::RedisResult<()> = redis::Script::new("return redis.call('HGET', 'a', 'f')")
+            .invoke_async(&mut *conn)
+            .await;
+        assert!(ret.is_err());
+        let time: Vec<String> = redis::Script::new("return redis.call('TIME')")
+            .invoke_async(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(time.len(), 2);
+
+        // 过期
+        let _: () = conn.pset_ex("p", "1", 20).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+        let v: Option<String> = conn.get("p").await.unwrap();
+        assert!(v.is_none());
+    }
+}
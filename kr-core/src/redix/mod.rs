@@ -1,10 +1,17 @@
+pub mod blocking;
 pub mod cluster;
+pub mod hook;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
+pub mod script;
 pub mod single;
 
 use std::time::Duration;
 
 use bb8::ManageConnection;
 
+pub use blocking::BlockingPool;
+
 pub type SinglePool = bb8::Pool<single::RedisConnManager>;
 
 pub type ClusterPool = bb8::Pool<cluster::RedisClusterManager>;
@@ -13,6 +20,11 @@ pub trait Factory {
     type Manager: ManageConnection<Error: std::error::Error + Send + Sync + 'static>;
 
     fn build(dsn: Vec<String>) -> anyhow::Result<Self::Manager>;
+
+    /// 不检测连通性（延迟连接）
+    fn build_lazy(dsn: Vec<String>) -> anyhow::Result<Self::Manager> {
+        Self::build(dsn)
+    }
 }
 
 pub struct Single;
@@ -28,6 +40,13 @@ impl Factory for Single {
 
         Ok(single::RedisConnManager::new(client))
     }
+
+    fn build_lazy(dsn: Vec<String>) -> anyhow::Result<Self::Manager> {
+        let first = dsn.first().ok_or_else(|| anyhow::anyhow!("DSN is empty"))?;
+        let client = redis::Client::open(first.as_ref())?;
+
+        Ok(single::RedisConnManager::new(client))
+    }
 }
 
 pub struct Cluster;
@@ -42,6 +61,31 @@ impl Factory for Cluster {
 
         Ok(cluster::RedisClusterManager::new(client))
     }
+
+    fn build_lazy(dsn: Vec<String>) -> anyhow::Result<Self::Manager> {
+        let client = redis::cluster::ClusterClient::new(dsn)?;
+
+        Ok(cluster::RedisClusterManager::new(client))
+    }
+}
+
+/// 进程内 Mock（测试用，需开启 `test-util` feature），忽略 DSN
+///
+/// # Examples
+///
+/// ```
+/// let pool = redix::open::<redix::Mock>(vec![], None).await?;
+/// ```
+#[cfg(any(test, feature = "test-util"))]
+pub struct Mock;
+
+#[cfg(any(test, feature = "test-util"))]
+impl Factory for Mock {
+    type Manager = single::RedisConnManager;
+
+    fn build(_: Vec<String>) -> anyhow::Result<Self::Manager> {
+        Ok(single::RedisConnManager::mock(mock::MockStore::default()))
+    }
 }
 
 #[derive(Default, Debug)]
@@ -51,6 +95,8 @@ pub struct Params {
     pub conn_timeout: Option<Duration>,
     pub idle_timeout: Option<Duration>,
     pub max_lifetime: Option<Duration>,
+    /// 延迟连接：启动时不检测连通性、不建立连接，默认：false（仅 `open`）
+    pub lazy: Option<bool>,
 }
 
 /// 生成 Redis 连接池
@@ -68,23 +114,164 @@ pub struct Params {
 ///
 /// // 集群
 /// let x = redix::open::<redix::Cluster>(vec!["dsn1", "dsn2"], None).await;
+///
+/// // 延迟连接，并在后台预热
+/// let pool = redix::open::<redix::Single>(
+///     vec!["dsn"],
+///     Some(redix::Params {
+///         lazy: Some(true),
+///         ..Default::default()
+///     }),
+/// )
+/// .await?;
+/// redix::warm(&pool, 10);
 /// ```
 pub async fn open<F>(dsn: Vec<String>, opt: Option<Params>) -> anyhow::Result<bb8::Pool<F::Manager>>
 where
     F: Factory,
 {
-    let manager = F::build(dsn)?;
+    let params = opt.unwrap_or_default();
+    let lazy = params.lazy.unwrap_or(false);
+    tracing::info!(
+        dsn = ?dsn.iter().map(|v| crate::dsn::redact(v)).collect::<Vec<_>>(),
+        params = ?params,
+        "[redix::open] open pool"
+    );
+
+    let manager = if lazy {
+        F::build_lazy(dsn)?
+    } else {
+        F::build(dsn)?
+    };
+
+    let builder = bb8::Pool::builder()
+        .max_size(params.max_size.unwrap_or(100))
+        .min_idle(params.min_idle)
+        .connection_timeout(params.conn_timeout.unwrap_or(Duration::from_secs(10)))
+        .idle_timeout(params.idle_timeout)
+        .max_lifetime(params.max_lifetime)
+        .error_sink(Box::new(hook::TracingErrorSink))
+        .connection_customizer(Box::new(hook::TracingCustomizer));
+    let pool = if lazy {
+        builder.build_unchecked(manager)
+    } else {
+        builder.build(manager).await?
+    };
+
+    Ok(pool)
+}
+
+/// 后台预热连接池：预先建立 n 个连接（不超过最大连接数），并记录进度
+///
+/// 连接建立后立即归还连接池，预热期间不占用业务可用的连接
+///
+/// # Examples
+///
+/// ```
+/// let handle = redix::warm(&pool, 10);
+/// ```
+pub fn warm<M: ManageConnection>(pool: &bb8::Pool<M>, n: u32) -> tokio::task::JoinHandle<()> {
+    let pool = pool.clone();
+    let n = n.min(pool.config().max_size);
+
+    tokio::spawn(async move {
+        let start = std::time::Instant::now();
+
+        // 并发获取连接迫使连接池新建；已归还的空闲连接可能被复用，不足时再补一轮
+        loop {
+            let size = pool.state().connections;
+            if size >= n {
+                break;
+            }
+            let mut futs: futures::stream::FuturesUnordered<_> =
+                (size..n).map(|_| pool.get()).collect();
+            let mut failed = false;
+            while let Some(ret) = futures::StreamExt::next(&mut futs).await {
+                match ret {
+                    Ok(conn) => {
+                        drop(conn);
+                        tracing::info!(
+                            ready = pool.state().connections,
+                            total = n,
+                            "[redix::warm] connection ready"
+                        );
+                    }
+                    Err(e) => {
+                        failed = true;
+                        tracing::error!(err = ?e, "[redix::warm] connect failed");
+                    }
+                }
+            }
+            if failed || pool.state().connections <= size {
+                break;
+            }
+        }
+
+        tracing::info!(
+            ready = pool.state().connections,
+            total = n,
+            cost_ms = start.elapsed().as_millis(),
+            "[redix::warm] done"
+        );
+    })
+}
+
+/// 生成同步 Redis 连接池（`r2d2`，用于 RedLock 等同步场景）
+///
+/// # Examples
+///
+/// ```
+/// let pool = redix::open_sync("redis://127.0.0.1:6379", None)?;
+/// let lock = RedLock::new(pool, "key", Duration::from_secs(10));
+/// ```
+pub fn open_sync(
+    dsn: impl AsRef<str>,
+    opt: Option<Params>,
+) -> anyhow::Result<r2d2::Pool<redis::Client>> {
+    let client = redis::Client::open(dsn.as_ref())?;
 
     let params = opt.unwrap_or_default();
+    tracing::info!(dsn = %crate::dsn::redact(dsn.as_ref()), params = ?params, "[redix::open_sync] open pool");
 
-    let pool = bb8::Pool::builder()
+    let pool = r2d2::Pool::builder()
         .max_size(params.max_size.unwrap_or(100))
         .min_idle(params.min_idle)
         .connection_timeout(params.conn_timeout.unwrap_or(Duration::from_secs(10)))
         .idle_timeout(params.idle_timeout)
         .max_lifetime(params.max_lifetime)
-        .build(manager)
-        .await?;
+        .error_handler(Box::new(hook::TracingErrorHandler))
+        .event_handler(Box::new(hook::TracingEventHandler))
+        .build(client)?;
 
     Ok(pool)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{helper::pool::Stats, redix};
+
+    #[tokio::test]
+    async fn test_lazy_warm() {
+        // 不检测连通性
+        let params = redix::Params {
+            max_size: Some(3),
+            lazy: Some(true),
+            ..Default::default()
+        };
+        let pool = redix::open::<redix::Single>(vec!["redis://127.0.0.1:1".into()], Some(params))
+            .await
+            .unwrap();
+        assert_eq!(pool.stats().size, 0);
+
+        let params = redix::Params {
+            max_size: Some(3),
+            ..Default::default()
+        };
+        let pool = redix::open::<redix::Mock>(vec![], Some(params))
+            .await
+            .unwrap();
+        redix::warm(&pool, 5).await.unwrap();
+        let stats = pool.stats();
+        assert_eq!((stats.size, stats.idle), (3, 3));
+    }
+}
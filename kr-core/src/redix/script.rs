@@ -0,0 +1,310 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use anyhow::anyhow;
+use redis::{FromRedisValue, Script, ToRedisArgs};
+
+use crate::helper::redkit::{key, Redis};
+
+/// 内置脚本：各模块通过 `inventory::submit!` 注册，`ScriptRegistry::builtin` 时收集
+///
+/// # Examples
+///
+/// ```
+/// pub const DEL: &str = "...";
+///
+/// inventory::submit! { redix::script::Builtin { name: "mutex:del", body: DEL } }
+/// ```
+pub struct Builtin {
+    pub name: &'static str,
+    pub body: &'static str,
+}
+
+inventory::collect!(Builtin);
+
+static GLOBAL: OnceLock<ScriptRegistry> = OnceLock::new();
+
+/// 全局脚本注册表（已包含内置脚本）
+pub fn global() -> &'static ScriptRegistry {
+    GLOBAL.get_or_init(ScriptRegistry::builtin)
+}
+
+/// Lua 脚本注册表
+///
+/// - 按名称注册脚本，调用时使用 EVALSHA，服务端无缓存（NOSCRIPT）时自动 SCRIPT LOAD 后重试
+/// - `load` 启动时预加载所有脚本（集群模式下 SCRIPT LOAD 由 redis-rs 发往当前所有节点；
+///   之后新增或切换的节点缺少脚本时，调用遇到 NOSCRIPT 会自动加载）
+/// - 集群模式下调用前校验所有 key 位于同一 slot
+///
+/// # Examples
+///
+/// ```
+/// let scripts = redix::script::global();
+/// scripts.register("incr_cap", r#"
+/// local v = redis.call('INCR', KEYS[1])
+/// if v > tonumber(ARGV[1]) then
+///     redis.call('DECR', KEYS[1])
+///     return -1
+/// end
+/// return v
+/// "#);
+/// scripts.load(&redis).await?;
+///
+/// let n: i64 = scripts.call("incr_cap")?.key("quota:1").arg(100).invoke(&redis).await?;
+/// ```
+#[derive(Default)]
+pub struct ScriptRegistry {
+    scripts: RwLock<BTreeMap<String, Arc<Script>>>,
+}
+
+impl ScriptRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 包含各模块注册的内置脚本（如 `mutex:del`、`redkit:hset`、`queue:priority:*`）
+    pub fn builtin() -> Self {
+        let registry = Self::new();
+        for v in inventory::iter::<Builtin> {
+            registry.register(v.name, v.body);
+        }
+        registry
+    }
+
+    /// 注册脚本（同名覆盖）
+    pub fn register(&self, name: &str, body: &str) {
+        let script = Arc::new(Script::new(body));
+        if let Some(old) = self
+            .scripts
+            .write()
+            .unwrap()
+            .insert(name.to_string(), script.clone())
+        {
+            if old.get_hash() != script.get_hash() {
+                tracing::warn!(name = name, "[redix::script::register] script replaced");
+            }
+        }
+    }
+
+    /// 脚本的 SHA1
+    pub fn sha(&self, name: &str) -> Option<String> {
+        self.scripts
+            .read()
+            .unwrap()
+            .get(name)
+            .map(|v| v.get_hash().to_string())
+    }
+
+    /// 已注册的脚本名称（有序）
+    pub fn names(&self) -> Vec<String> {
+        self.scripts.read().unwrap().keys().cloned().collect()
+    }
+
+    /// SCRIPT LOAD 所有脚本，返回加载的数量
+    pub async fn load(&self, redis: &Redis) -> anyhow::Result<usize> {
+        let scripts: Vec<(String, Arc<Script>)> = self
+            .scripts
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        for (name, script) in &scripts {
+            let sha: String = match redis {
+                Redis::Single(pool) => {
+                    script
+                        .prepare_invoke()
+                        .load_async(&mut *pool.get().await?)
+                        .await?
+                }
+                Redis::Cluster(pool) => {
+                    script
+                        .prepare_invoke()
+                        .load_async(&mut *pool.get().await?)
+                        .await?
+                }
+            };
+            if sha != script.get_hash() {
+                return Err(anyhow!(
+                    "redix/script: sha mismatch for {} ({} != {})",
+                    name,
+                    sha,
+                    script.get_hash()
+                ));
+            }
+        }
+        tracing::info!(
+            count = scripts.len(),
+            "[redix::script::load] scripts loaded"
+        );
+        Ok(scripts.len())
+    }
+
+    /// 获取脚本（未注册时返回错误），用于在已有连接上调用
+    pub fn get(&self, name: &str) -> anyhow::Result<Arc<Script>> {
+        self.scripts
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("redix/script: script {} not registered", name))
+    }
+
+    /// 准备调用脚本（未注册时返回错误）
+    pub fn call(&self, name: &str) -> anyhow::Result<Invocation> {
+        let script = self.get(name)?;
+        Ok(Invocation {
+            name: name.to_string(),
+            script,
+            keys: Vec::new(),
+            args: Vec::new(),
+        })
+    }
+}
+
+/// 一次脚本调用（KEYS 与 ARGV）
+pub struct Invocation {
+    name: String,
+    script: Arc<Script>,
+    keys: Vec<Vec<Vec<u8>>>,
+    args: Vec<Vec<Vec<u8>>>,
+}
+
+impl Invocation {
+    pub fn key<T: ToRedisArgs>(mut self, key: T) -> Self {
+        self.keys.push(key.to_redis_args());
+        self
+    }
+
+    pub fn arg<T: ToRedisArgs>(mut self, arg: T) -> Self {
+        self.args.push(arg.to_redis_args());
+        self
+    }
+
+    /// EVALSHA 执行，NOSCRIPT 时自动加载后重试
+    pub async fn invoke<T: FromRedisValue>(&self, redis: &Redis) -> anyhow::Result<T> {
+        let mut inv = self.script.prepare_invoke();
+        for v in self.keys.iter().flatten() {
+            inv.key(v.as_slice());
+        }
+        for v in self.args.iter().flatten() {
+            inv.arg(v.as_slice());
+        }
+
+        let ret = match redis {
+            Redis::Single(pool) => inv.invoke_async(&mut *pool.get().await?).await?,
+            Redis::Cluster(pool) => {
+                self.check_slot()?;
+                inv.invoke_async(&mut *pool.get().await?).await?
+            }
+        };
+        Ok(ret)
+    }
+
+    // 集群模式下脚本的所有 key 必须位于同一 slot
+    fn check_slot(&self) -> anyhow::Result<()> {
+        let mut keys = self.keys.iter().flatten();
+        let Some(first) = keys.next() else {
+            return Ok(());
+        };
+        let slot = key::slot(first);
+        if let Some(v) = keys.find(|v| key::slot(v) != slot) {
+            return Err(anyhow!(
+                "redix/script: keys of {} in different slots ({} vs {}), use hash tag",
+                self.name,
+                String::from_utf8_lossy(first),
+                String::from_utf8_lossy(v)
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::AsyncCommands;
+
+    use crate::{
+        helper::redkit::Redis,
+        redix,
+        redix::script::{self, ScriptRegistry},
+    };
+
+    const DEL: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+    #[tokio::test]
+    async fn test_registry() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let redis = Redis::Single(pool.clone());
+
+        let scripts = ScriptRegistry::new();
+        scripts.register("test:del", DEL);
+        assert_eq!(scripts.names(), vec!["test:del".to_string()]);
+        assert_eq!(
+            scripts.sha("test:del").unwrap(),
+            redis::Script::new(DEL).get_hash()
+        );
+        assert!(scripts.sha("missing").is_none());
+        assert!(scripts.call("missing").is_err());
+        assert!(scripts.get("missing").is_err());
+
+        // 未加载：NOSCRIPT => SCRIPT LOAD => EVALSHA
+        let _: () = pool.get().await.unwrap().set("k", "v").await.unwrap();
+        let n: i64 = scripts
+            .call("test:del")
+            .unwrap()
+            .key("k")
+            .arg("x")
+            .invoke(&redis)
+            .await
+            .unwrap();
+        assert_eq!(n, 0);
+
+        assert_eq!(scripts.load(&redis).await.unwrap(), 1);
+        let n: i64 = scripts
+            .call("test:del")
+            .unwrap()
+            .key("k")
+            .arg("v")
+            .invoke(&redis)
+            .await
+            .unwrap();
+        assert_eq!(n, 1);
+
+        // 同名覆盖
+        scripts.register("test:del", "return 0");
+        assert_eq!(
+            scripts.sha("test:del").unwrap(),
+            redis::Script::new("return 0").get_hash()
+        );
+
+        // 各模块注册的内置脚本
+        let names = ScriptRegistry::builtin().names();
+        for name in ["mutex:del", "redkit:hset", "quota:consume"] {
+            assert!(names.contains(&name.to_string()), "{}", name);
+        }
+        assert!(script::global().sha("redkit:hset").is_some());
+    }
+
+    #[test]
+    fn test_check_slot() {
+        let scripts = ScriptRegistry::new();
+        scripts.register("test:del", DEL);
+        let inv = scripts
+            .call("test:del")
+            .unwrap()
+            .key("cnt:{1}:a")
+            .key("cnt:{1}:b");
+        assert!(inv.check_slot().is_ok());
+        let inv = scripts.call("test:del").unwrap().key("cnt:a").key("cnt:b");
+        assert!(inv.check_slot().is_err());
+    }
+}
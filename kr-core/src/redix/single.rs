@@ -1,11 +1,75 @@
+#[cfg(any(test, feature = "test-util"))]
+use redis::{aio::ConnectionLike, Cmd, Pipeline, RedisFuture, Value};
+
+#[cfg(any(test, feature = "test-util"))]
+use super::mock::{MockConnection, MockStore};
+
+#[derive(Clone)]
+enum Backend {
+    Client(redis::Client),
+    #[cfg(any(test, feature = "test-util"))]
+    Mock(MockStore),
+}
+
 #[derive(Clone)]
 pub struct RedisConnManager {
-    client: redis::Client,
+    backend: Backend,
 }
 
 impl RedisConnManager {
     pub fn new(c: redis::Client) -> Self {
-        Self { client: c }
+        Self {
+            backend: Backend::Client(c),
+        }
+    }
+
+    /// 基于进程内存储的管理器（测试用）
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn mock(store: MockStore) -> Self {
+        Self {
+            backend: Backend::Mock(store),
+        }
+    }
+}
+
+/// 单节点连接
+#[cfg(not(any(test, feature = "test-util")))]
+pub type Connection = redis::aio::MultiplexedConnection;
+
+/// 单节点连接（开启 `test-util` 时可为 Mock 连接）
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Clone)]
+pub enum Connection {
+    Multiplexed(redis::aio::MultiplexedConnection),
+    Mock(MockConnection),
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl ConnectionLike for Connection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Connection::Multiplexed(c) => c.req_packed_command(cmd),
+            Connection::Mock(c) => c.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Connection::Multiplexed(c) => c.req_packed_commands(cmd, offset, count),
+            Connection::Mock(c) => c.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Connection::Multiplexed(c) => c.get_db(),
+            Connection::Mock(c) => c.get_db(),
+        }
     }
 }
 
@@ -17,15 +81,27 @@ impl RedisConnManager {
 /// let manager = RedisConnectionManager::new(redis::Client::open("redis://127.0.0.1:6379").unwrap());
 /// ```
 impl bb8::ManageConnection for RedisConnManager {
-    type Connection = redis::aio::MultiplexedConnection;
+    type Connection = Connection;
     type Error = redis::RedisError;
 
     async fn connect(&self) -> Result<Self::Connection, Self::Error> {
-        self.client.get_multiplexed_async_connection().await
+        match &self.backend {
+            Backend::Client(c) => {
+                let conn = c.get_multiplexed_async_connection().await?;
+                #[cfg(any(test, feature = "test-util"))]
+                let conn = Connection::Multiplexed(conn);
+                Ok(conn)
+            }
+            #[cfg(any(test, feature = "test-util"))]
+            Backend::Mock(store) => Ok(Connection::Mock(MockConnection::new(store.clone()))),
+        }
     }
 
     async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
-        let pong: String = redis::cmd("PING").query_async(conn).await?;
+        let pong: String = redis::cmd("PING")
+            .query_async(conn)
+            .await
+            .inspect_err(|e| tracing::warn!(err = %e, "[redix] invalid connection, recycling"))?;
         match pong.as_str() {
             "PONG" => Ok(()),
             _ => Err((redis::ErrorKind::ResponseError, "ping request").into()),
@@ -0,0 +1,310 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    helper::redkit::{key, Redis},
+    redix::script::{self, Builtin},
+};
+
+// 脚本中调用 TIME 后再写入，Redis 7 以下需要 `redis.replicate_commands()`
+
+/// 上报心跳（以 Redis 服务器时间为准）：KEYS[1]=实例 hash，KEYS[2]=心跳 hash；ARGV[1]=实例ID，ARGV[2]=实例数据
+pub const HEARTBEAT: &str = r#"
+redis.replicate_commands()
+local now = redis.call('TIME')[1]
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+redis.call('HSET', KEYS[2], ARGV[1], now)
+return now
+"#;
+
+/// 读取所有实例：KEYS[1]=实例 hash，KEYS[2]=心跳 hash
+///
+/// 返回 {服务器时间, 实例, 心跳}
+pub const SNAPSHOT: &str = r#"
+local now = redis.call('TIME')[1]
+return {now, redis.call('HGETALL', KEYS[1]), redis.call('HGETALL', KEYS[2])}
+"#;
+
+/// 移除失效实例并返回其数据：KEYS[1]=实例 hash，KEYS[2]=心跳 hash；ARGV[1]=心跳超时（秒）
+pub const REAP: &str = r#"
+redis.replicate_commands()
+local now = tonumber(redis.call('TIME')[1])
+local ttl = tonumber(ARGV[1])
+local beats = redis.call('HGETALL', KEYS[2])
+local dead = {}
+for i = 1, #beats, 2 do
+    if now - tonumber(beats[i + 1]) > ttl then
+        local v = redis.call('HGET', KEYS[1], beats[i])
+        redis.call('HDEL', KEYS[1], beats[i])
+        redis.call('HDEL', KEYS[2], beats[i])
+        if v then
+            dead[#dead + 1] = v
+        end
+    end
+end
+return dead
+"#;
+
+inventory::submit! { Builtin { name: "registry:heartbeat", body: HEARTBEAT } }
+inventory::submit! { Builtin { name: "registry:snapshot", body: SNAPSHOT } }
+inventory::submit! { Builtin { name: "registry:reap", body: REAP } }
+
+/// 实例信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instance {
+    /// 实例ID（如：hostname:pid）
+    pub id: String,
+    /// 自定义元数据（地址、版本等）
+    #[serde(default)]
+    pub meta: HashMap<String, String>,
+    /// 注册时间（Unix秒）
+    pub started_at: i64,
+    /// 最近心跳时间（Unix秒，从注册表读取时为 Redis 服务器时间）
+    pub heartbeat_at: i64,
+}
+
+impl Instance {
+    pub fn new(id: impl AsRef<str>) -> Self {
+        let now = now();
+        Self {
+            id: id.as_ref().to_string(),
+            meta: HashMap::new(),
+            started_at: now,
+            heartbeat_at: now,
+        }
+    }
+
+    pub fn meta(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.meta
+            .insert(key.as_ref().to_string(), value.as_ref().to_string());
+        self
+    }
+
+    /// 在 ttl 内有心跳视为存活
+    pub fn is_alive(&self, ttl: Duration) -> bool {
+        now() - self.heartbeat_at <= ttl.as_secs() as i64
+    }
+}
+
+fn now() -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// 实例注册表：实例定期上报心跳，超过 ttl 未上报视为失效，便于任务重新分配
+///
+/// 心跳时间及存活判断均以 Redis 服务器时间为准，不受各实例时钟偏差影响
+///
+/// # Examples
+///
+/// ```
+/// let registry = Registry::new(Redis::Single(pool), "order-worker", None);
+///
+/// // 注册并后台上报心跳
+/// let heartbeat = registry
+///     .register(Instance::new("host-1:1234").meta("version", "1.0.0"))
+///     .await?;
+///
+/// // 存活实例
+/// let live = registry.list().await?;
+///
+/// // 清理失效实例，接管其任务
+/// for v in registry.reap().await? {
+///     // ...
+/// }
+///
+/// // 退出
+/// heartbeat.abort();
+/// registry.deregister("host-1:1234").await?;
+/// ```
+#[derive(Clone)]
+pub struct Registry {
+    redis: Redis,
+    key: String,
+    ttl: Duration,
+}
+
+impl Registry {
+    /// `ttl` 为心跳超时时间，默认：30s（心跳间隔为 ttl/3）
+    pub fn new(redis: Redis, service: impl AsRef<str>, ttl: Option<Duration>) -> Self {
+        Self {
+            redis,
+            // 使用 hash tag 保证集群模式下实例与心跳位于同一 slot
+            key: key::with_prefix(format!("kr:registry:{{{}}}", service.as_ref())),
+            ttl: ttl.unwrap_or(Duration::from_secs(30)),
+        }
+    }
+
+    /// 注册实例并在后台上报心跳（abort 返回的 JoinHandle 停止心跳）
+    pub async fn register(
+        &self,
+        instance: Instance,
+    ) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+        let mut instance = instance;
+        self.heartbeat(&mut instance).await?;
+
+        let registry = self.clone();
+        let interval = (self.ttl / 3).max(Duration::from_secs(1));
+        Ok(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = registry.heartbeat(&mut instance).await {
+                    tracing::error!(err = ?e, key = registry.key, id = instance.id, "[registry] heartbeat failed");
+                }
+            }
+        }))
+    }
+
+    /// 上报心跳
+    pub async fn heartbeat(&self, instance: &mut Instance) -> anyhow::Result<()> {
+        instance.heartbeat_at = now();
+        let data = serde_json::to_string(instance)?;
+        instance.heartbeat_at = script::global()
+            .call("registry:heartbeat")?
+            .key(&self.key)
+            .key(self.heartbeat_key())
+            .arg(&instance.id)
+            .arg(data)
+            .invoke(&self.redis)
+            .await?;
+        Ok(())
+    }
+
+    /// 注销实例
+    pub async fn deregister(&self, id: impl AsRef<str>) -> anyhow::Result<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hdel(&self.key, id.as_ref())
+            .ignore()
+            .hdel(self.heartbeat_key(), id.as_ref())
+            .ignore();
+        match &self.redis {
+            Redis::Single(pool) => {
+                let _: () = pipe.query_async(&mut *pool.get().await?).await?;
+            }
+            Redis::Cluster(pool) => {
+                let _: () = pipe.query_async(&mut *pool.get().await?).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 所有已注册的实例（含失效的）
+    pub async fn all(&self) -> anyhow::Result<Vec<Instance>> {
+        Ok(self.snapshot().await?.1)
+    }
+
+    /// 存活的实例
+    pub async fn list(&self) -> anyhow::Result<Vec<Instance>> {
+        let (now, mut list) = self.snapshot().await?;
+        list.retain(|v| now - v.heartbeat_at <= self.ttl.as_secs() as i64);
+        Ok(list)
+    }
+
+    /// 失效的实例
+    pub async fn dead(&self) -> anyhow::Result<Vec<Instance>> {
+        let (now, mut list) = self.snapshot().await?;
+        list.retain(|v| now - v.heartbeat_at > self.ttl.as_secs() as i64);
+        Ok(list)
+    }
+
+    /// 移除失效的实例并返回（判断与删除在同一脚本中完成，不会误删刚恢复心跳的实例）
+    pub async fn reap(&self) -> anyhow::Result<Vec<Instance>> {
+        let data: Vec<String> = script::global()
+            .call("registry:reap")?
+            .key(&self.key)
+            .key(self.heartbeat_key())
+            .arg(self.ttl.as_secs())
+            .invoke(&self.redis)
+            .await?;
+
+        let mut list = self.parse(data.into_iter().map(|v| (String::new(), v)));
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(list)
+    }
+
+    fn heartbeat_key(&self) -> String {
+        format!("{}:heartbeat", self.key)
+    }
+
+    // (Redis 服务器时间, 所有实例)
+    async fn snapshot(&self) -> anyhow::Result<(i64, Vec<Instance>)> {
+        let (now, data, beats): (i64, HashMap<String, String>, HashMap<String, i64>) =
+            script::global()
+                .call("registry:snapshot")?
+                .key(&self.key)
+                .key(self.heartbeat_key())
+                .invoke(&self.redis)
+                .await?;
+
+        let mut list = self.parse(data.into_iter());
+        for v in list.iter_mut() {
+            if let Some(t) = beats.get(&v.id) {
+                v.heartbeat_at = *t;
+            }
+        }
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok((now, list))
+    }
+
+    fn parse(&self, data: impl Iterator<Item = (String, String)>) -> Vec<Instance> {
+        data.filter_map(|(id, v)| match serde_json::from_str::<Instance>(&v) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                tracing::warn!(err = ?e, key = self.key, id = id, "[registry] invalid instance");
+                None
+            }
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        helper::redkit::Redis,
+        redix,
+        registry::{Instance, Registry},
+    };
+
+    #[tokio::test]
+    async fn test_registry() {
+        let pool = redix::open::<redix::Mock>(vec![], None).await.unwrap();
+        let registry = Registry::new(Redis::Single(pool), "worker", Some(Duration::from_secs(10)));
+
+        let heartbeat = registry
+            .register(Instance::new("a").meta("version", "1.0.0"))
+            .await
+            .unwrap();
+
+        // 模拟已停止心跳的实例
+        let mut dead = Instance::new("b");
+        dead.heartbeat_at -= 60;
+        let data = serde_json::to_string(&dead).unwrap();
+        let Redis::Single(pool) = &registry.redis else {
+            unreachable!()
+        };
+        let _: () = redis::pipe()
+            .hset(&registry.key, "b", data)
+            .hset(registry.heartbeat_key(), "b", dead.heartbeat_at)
+            .query_async(&mut *pool.get().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(registry.dead().await.unwrap()[0].id, "b");
+
+        let live = registry.list().await.unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].id, "a");
+        assert_eq!(live[0].meta.get("version").unwrap(), "1.0.0");
+
+        let reaped = registry.reap().await.unwrap();
+        assert_eq!(reaped, vec![dead]);
+        assert_eq!(registry.all().await.unwrap().len(), 1);
+
+        heartbeat.abort();
+        registry.deregister("a").await.unwrap();
+        assert!(registry.all().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,411 @@
+use std::{collections::HashMap, fmt, future::Future, pin::Pin, time::Duration};
+
+use anyhow::anyhow;
+use redis::AsyncCommands;
+
+use crate::helper::redkit::{key, Redis};
+
+type StepFn =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send + Sync>;
+
+struct Step {
+    name: String,
+    action: StepFn,
+    compensate: StepFn,
+}
+
+/// Saga 状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// 正向执行中
+    Running,
+    /// 补偿执行中
+    Compensating,
+    /// 全部步骤执行成功
+    Completed,
+    /// 补偿完成
+    Compensated,
+    /// 补偿失败（需人工介入）
+    Aborted,
+}
+
+impl State {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            State::Running => "running",
+            State::Compensating => "compensating",
+            State::Completed => "completed",
+            State::Compensated => "compensated",
+            State::Aborted => "aborted",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "running" => Some(State::Running),
+            "compensating" => Some(State::Compensating),
+            "completed" => Some(State::Completed),
+            "compensated" => Some(State::Compensated),
+            "aborted" => Some(State::Aborted),
+            _ => None,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        matches!(self, State::Completed | State::Compensated | State::Aborted)
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Saga 执行失败
+#[derive(Debug)]
+pub struct SagaError {
+    /// 最终状态：Compensated 或 Aborted
+    pub state: State,
+    /// 失败的步骤
+    pub step: String,
+    pub cause: String,
+}
+
+impl fmt::Display for SagaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "saga: step({}) failed, state={}: {}",
+            self.step, self.state, self.cause
+        )
+    }
+}
+
+impl std::error::Error for SagaError {}
+
+/// Saga 补偿事务
+///
+/// 按注册顺序执行步骤；任一步骤失败，则按逆序执行已完成步骤的补偿（失败重试）。
+/// 设置 Redis 后，每次状态变更都会持久化，实例崩溃后使用相同的 id 再次 `run` 即可从断点继续。
+///
+/// # Examples
+///
+/// ```
+/// let saga = Saga::new("order")
+///     .store(redis.clone(), Duration::from_secs(86400))
+///     .retry(3, Duration::from_millis(200))
+///     .step(
+///         "stock",
+///         move || async move { deduct_stock(order_id).await },
+///         move || async move { restore_stock(order_id).await },
+///     )
+///     .step(
+///         "pay",
+///         move || async move { pay(order_id).await },
+///         move || async move { refund(order_id).await },
+///     );
+///
+/// match saga.run(&order_id.to_string()).await {
+///     Ok(_) => {}
+///     Err(e) => {
+///         if let Some(e) = e.downcast_ref::<SagaError>() {
+///             // e.state == State::Aborted => 补偿失败，需人工介入
+///         }
+///     }
+/// }
+/// ```
+pub struct Saga {
+    name: String,
+    steps: Vec<Step>,
+    retries: u32,
+    backoff: Duration,
+    store: Option<(Redis, Duration)>,
+}
+
+impl Saga {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            steps: Vec::new(),
+            retries: 3,
+            backoff: Duration::from_millis(100),
+            store: None,
+        }
+    }
+
+    /// 使用 Redis 持久化执行进度（ttl：进度保留时长）
+    pub fn store(mut self, redis: Redis, ttl: Duration) -> Self {
+        self.store = Some((redis, ttl));
+        self
+    }
+
+    /// 补偿失败时的重试次数及间隔（指数退避），默认：3次，100ms
+    pub fn retry(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// 注册步骤：正向操作 & 补偿操作
+    pub fn step<A, AFut, C, CFut>(mut self, name: &str, action: A, compensate: C) -> Self
+    where
+        A: Fn() -> AFut + Send + Sync + 'static,
+        AFut: Future<Output = anyhow::Result<()>> + Send + 'static,
+        C: Fn() -> CFut + Send + Sync + 'static,
+        CFut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.steps.push(Step {
+            name: name.to_string(),
+            action: Box::new(move || Box::pin(action())),
+            compensate: Box::new(move || Box::pin(compensate())),
+        });
+        self
+    }
+
+    /// 执行（或从断点恢复）指定 id 的 Saga
+    pub async fn run(&self, id: &str) -> anyhow::Result<()> {
+        let key = key::with_prefix(format!("kr:saga:{}:{}", self.name, id));
+
+        let (mut state, mut cursor, mut failed) = match self.load(&key).await? {
+            Some(v) => v,
+            None => (State::Running, 0, None),
+        };
+        if state.is_terminal() {
+            return self.finish(state, failed);
+        }
+        if cursor > self.steps.len() {
+            return Err(anyhow!(
+                "saga({}): invalid cursor {} for {} steps",
+                self.name,
+                cursor,
+                self.steps.len()
+            ));
+        }
+
+        // 正向执行
+        if state == State::Running {
+            while cursor < self.steps.len() {
+                let step = &self.steps[cursor];
+                match (step.action)().await {
+                    Ok(_) => {
+                        cursor += 1;
+                        self.save(&key, state, cursor, None).await?;
+                    }
+                    Err(e) => {
+                        tracing::error!(saga = self.name, id = id, step = step.name, err = ?e, "[saga] step failed");
+                        failed = Some((step.name.clone(), e.to_string()));
+                        state = self
+                            .transit(&key, id, state, State::Compensating, cursor, &failed)
+                            .await?;
+                        break;
+                    }
+                }
+            }
+            if state == State::Running {
+                self.transit(&key, id, state, State::Completed, cursor, &None)
+                    .await?;
+                return Ok(());
+            }
+        }
+
+        // 逆序补偿
+        while cursor > 0 {
+            let step = &self.steps[cursor - 1];
+            if let Err(e) = self.compensate(step).await {
+                tracing::error!(saga = self.name, id = id, step = step.name, err = ?e, "[saga] compensation failed");
+                let failed = failed.or_else(|| Some((step.name.clone(), e.to_string())));
+                let state = self
+                    .transit(&key, id, state, State::Aborted, cursor, &failed)
+                    .await?;
+                return self.finish(state, failed);
+            }
+            cursor -= 1;
+            self.save(&key, state, cursor, failed.as_ref()).await?;
+        }
+        let state = self
+            .transit(&key, id, state, State::Compensated, cursor, &failed)
+            .await?;
+        self.finish(state, failed)
+    }
+
+    async fn compensate(&self, step: &Step) -> anyhow::Result<()> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match (step.compensate)().await {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt >= self.retries => return Err(e),
+                Err(e) => {
+                    attempt += 1;
+                    tracing::warn!(saga = self.name, step = step.name, attempt = attempt, err = ?e, "[saga] compensation retry");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+            }
+        }
+    }
+
+    async fn transit(
+        &self,
+        key: &str,
+        id: &str,
+        from: State,
+        to: State,
+        cursor: usize,
+        failed: &Option<(String, String)>,
+    ) -> anyhow::Result<State> {
+        tracing::info!(saga = self.name, id = id, from = %from, to = %to, cursor = cursor, "[saga] transition");
+        self.save(key, to, cursor, failed.as_ref()).await?;
+        Ok(to)
+    }
+
+    fn finish(&self, state: State, failed: Option<(String, String)>) -> anyhow::Result<()> {
+        match state {
+            State::Completed => Ok(()),
+            _ => {
+                let (step, cause) = failed.unwrap_or_default();
+                Err(SagaError { state, step, cause }.into())
+            }
+        }
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+    ) -> anyhow::Result<Option<(State, usize, Option<(String, String)>)>> {
+        let Some((redis, _)) = &self.store else {
+            return Ok(None);
+        };
+
+        let m: HashMap<String, String> = match redis {
+            Redis::Single(pool) => pool.get().await?.hgetall(key).await?,
+            Redis::Cluster(pool) => pool.get().await?.hgetall(key).await?,
+        };
+        let Some(state) = m.get("state").and_then(|v| State::parse(v)) else {
+            return Ok(None);
+        };
+        let cursor = m.get("cursor").and_then(|v| v.parse().ok()).unwrap_or(0);
+        let failed = m.get("failed_step").map(|v| {
+            (
+                v.clone(),
+                m.get("failed_cause").cloned().unwrap_or_default(),
+            )
+        });
+        Ok(Some((state, cursor, failed)))
+    }
+
+    async fn save(
+        &self,
+        key: &str,
+        state: State,
+        cursor: usize,
+        failed: Option<&(String, String)>,
+    ) -> anyhow::Result<()> {
+        let Some((redis, ttl)) = &self.store else {
+            return Ok(());
+        };
+
+        let mut items = vec![
+            ("state", state.as_str().to_string()),
+            ("cursor", cursor.to_string()),
+        ];
+        if let Some((step, cause)) = failed {
+            items.push(("failed_step", step.clone()));
+            items.push(("failed_cause", cause.clone()));
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hset_multiple(key, &items)
+            .ignore()
+            .expire(key, ttl.as_secs().max(1) as i64)
+            .ignore();
+        match redis {
+            Redis::Single(pool) => {
+                let mut conn = pool.get().await?;
+                pipe.query_async::<()>(&mut *conn).await?
+            }
+            Redis::Cluster(pool) => {
+                let mut conn = pool.get().await?;
+                pipe.query_async::<()>(&mut *conn).await?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    use crate::saga::{Saga, SagaError, State};
+
+    #[tokio::test]
+    async fn test_saga_compensate() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let tries = Arc::new(AtomicUsize::new(0));
+
+        let (l1, l2, l3, l4) = (log.clone(), log.clone(), log.clone(), log.clone());
+        let t = tries.clone();
+        let saga = Saga::new("test")
+            .retry(2, std::time::Duration::from_millis(1))
+            .step(
+                "a",
+                move || {
+                    let l = l1.clone();
+                    async move {
+                        l.lock().unwrap().push("a");
+                        Ok(())
+                    }
+                },
+                move || {
+                    let l = l2.clone();
+                    let t = t.clone();
+                    async move {
+                        // 第一次补偿失败，重试成功
+                        if t.fetch_add(1, Ordering::SeqCst) == 0 {
+                            return Err(anyhow::anyhow!("oops"));
+                        }
+                        l.lock().unwrap().push("undo a");
+                        Ok(())
+                    }
+                },
+            )
+            .step(
+                "b",
+                move || {
+                    let l = l3.clone();
+                    async move {
+                        l.lock().unwrap().push("b");
+                        Err(anyhow::anyhow!("b failed"))
+                    }
+                },
+                move || {
+                    let l = l4.clone();
+                    async move {
+                        l.lock().unwrap().push("undo b");
+                        Ok(())
+                    }
+                },
+            );
+
+        let err = saga.run("1").await.unwrap_err();
+        let err = err.downcast_ref::<SagaError>().unwrap();
+        assert_eq!(err.state, State::Compensated);
+        assert_eq!(err.step, "b");
+        assert_eq!(*log.lock().unwrap(), vec!["a", "b", "undo a"]);
+        assert_eq!(tries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_saga_completed() {
+        let saga = Saga::new("test")
+            .step("a", || async { Ok(()) }, || async { Ok(()) })
+            .step("b", || async { Ok(()) }, || async { Ok(()) });
+        assert!(saga.run("1").await.is_ok());
+    }
+}
@@ -0,0 +1,520 @@
+pub mod query;
+
+pub use query::Query;
+
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use reqwest::{header, Method, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::helper::{
+    http::{self, Retry},
+    PageData,
+};
+
+// 单次批量写入的文档数
+const BULK_SIZE: usize = 1000;
+
+/// 搜索引擎
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// Elasticsearch（兼容 OpenSearch）
+    Elasticsearch,
+    Meilisearch,
+}
+
+/// 可写入索引的文档
+///
+/// # Examples
+///
+/// ```
+/// #[derive(sqlx::FromRow, Model, Serialize, Deserialize)]
+/// pub struct Product {
+///     pub id: i64,
+///     pub title: String,
+///     pub price: i64,
+/// }
+///
+/// impl search::Document for Product {
+///     fn id(&self) -> String {
+///         self.id.to_string()
+///     }
+/// }
+/// ```
+pub trait Document: Serialize {
+    /// 文档ID（Meilisearch 写入时若文档中无 `id` 字段则自动补充）
+    fn id(&self) -> String;
+}
+
+#[derive(Debug, Clone)]
+enum Auth {
+    None,
+    Basic(String, String),
+    ApiKey(String),
+}
+
+#[derive(Debug)]
+enum Body {
+    None,
+    Json(Value),
+    NdJson(String),
+}
+
+/// 搜索客户端（Elasticsearch、Meilisearch），网络错误、429 及 5xx 时自动重试
+///
+/// - 写入操作在数据可被搜索后返回（Elasticsearch 使用 `refresh=wait_for`，Meilisearch 等待任务完成）
+/// - 请求记录 `tracing` 日志（debug：路径、状态码、耗时）
+///
+/// # Examples
+///
+/// ```
+/// let client = search::Client::new(Engine::Meilisearch, "http://127.0.0.1:7700")
+///     .api_key("masterKey");
+///
+/// client
+///     .create_index("products", Some(json!({"filterableAttributes": ["status", "price"]})))
+///     .await?;
+/// client.upsert("products", &products).await?;
+///
+/// let q = Query::new().text("耳机").eq("status", 1).page(1, 20);
+/// let page: PageData<Product> = client.search("products", &q).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    engine: Engine,
+    endpoint: String,
+    auth: Auth,
+    retry: Retry,
+}
+
+impl Client {
+    /// 默认：超时 10s，最多尝试 3 次，首次重试间隔 100ms（指数退避 + 随机抖动）
+    pub fn new(engine: Engine, endpoint: impl AsRef<str>) -> Self {
+        Self {
+            http: http::build_client("search", Duration::from_secs(10)),
+            engine,
+            endpoint: endpoint.as_ref().trim_end_matches('/').to_string(),
+            auth: Auth::None,
+            retry: Retry {
+                attempts: 3,
+                backoff: Duration::from_millis(100),
+            },
+        }
+    }
+
+    /// Elasticsearch 为 `ApiKey`，Meilisearch 为 `Bearer`
+    pub fn api_key(mut self, key: impl Into<String>) -> Self {
+        self.auth = Auth::ApiKey(key.into());
+        self
+    }
+
+    /// Basic 认证（Elasticsearch）
+    pub fn basic_auth(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.auth = Auth::Basic(user.into(), password.into());
+        self
+    }
+
+    /// 单次请求超时
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.http = http::build_client("search", d);
+        self
+    }
+
+    /// 最大尝试次数与首次重试间隔
+    pub fn retry(mut self, attempts: u32, backoff: Duration) -> Self {
+        self.retry = Retry {
+            attempts: attempts.max(1),
+            backoff,
+        };
+        self
+    }
+
+    /// 创建索引，已存在时忽略；`settings` 为 Elasticsearch 的 `settings`/`mappings`
+    /// 或 Meilisearch 的索引设置（主键固定为 `id`）
+    pub async fn create_index(&self, index: &str, settings: Option<Value>) -> anyhow::Result<()> {
+        match self.engine {
+            Engine::Elasticsearch => {
+                let body = settings.map(Body::Json).unwrap_or(Body::None);
+                let (status, v) = self.send(Method::PUT, index, body).await?;
+                let exists = v["error"]["type"] == "resource_already_exists_exception";
+                if !status.is_success() && !exists {
+                    return Err(error("create index", status, &v));
+                }
+            }
+            Engine::Meilisearch => {
+                let body = json!({"uid": index, "primaryKey": "id"});
+                let (status, v) = self.send(Method::POST, "indexes", Body::Json(body)).await?;
+                check("create index", status, &v)?;
+                if let Err(e) = self.wait_task(&v).await {
+                    if !e.to_string().contains("index_already_exists") {
+                        return Err(e);
+                    }
+                }
+                if let Some(settings) = settings {
+                    let path = format!("indexes/{}/settings", index);
+                    let (status, v) = self
+                        .send(Method::PATCH, &path, Body::Json(settings))
+                        .await?;
+                    check("update settings", status, &v)?;
+                    self.wait_task(&v).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 删除索引，不存在时返回 false
+    pub async fn delete_index(&self, index: &str) -> anyhow::Result<bool> {
+        let path = match self.engine {
+            Engine::Elasticsearch => index.to_string(),
+            Engine::Meilisearch => format!("indexes/{}", index),
+        };
+        let (status, v) = self.send(Method::DELETE, &path, Body::None).await?;
+        if status == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        check("delete index", status, &v)?;
+        if self.engine == Engine::Meilisearch {
+            self.wait_task(&v).await?;
+        }
+        Ok(true)
+    }
+
+    /// 索引是否存在
+    pub async fn index_exists(&self, index: &str) -> anyhow::Result<bool> {
+        let (method, path) = match self.engine {
+            Engine::Elasticsearch => (Method::HEAD, index.to_string()),
+            Engine::Meilisearch => (Method::GET, format!("indexes/{}", index)),
+        };
+        let (status, v) = self.send(method, &path, Body::None).await?;
+        if status == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        check("index exists", status, &v)?;
+        Ok(true)
+    }
+
+    /// 批量写入（存在则覆盖），返回写入数量
+    pub async fn upsert<T: Document>(&self, index: &str, docs: &[T]) -> anyhow::Result<usize> {
+        for chunk in docs.chunks(BULK_SIZE) {
+            match self.engine {
+                Engine::Elasticsearch => {
+                    let mut body = String::new();
+                    for doc in chunk {
+                        body.push_str(
+                            &json!({"index": {"_index": index, "_id": doc.id()}}).to_string(),
+                        );
+                        body.push('\n');
+                        body.push_str(&serde_json::to_string(doc)?);
+                        body.push('\n');
+                    }
+                    self.bulk(body).await?;
+                }
+                Engine::Meilisearch => {
+                    let mut list = Vec::with_capacity(chunk.len());
+                    for doc in chunk {
+                        let mut v = serde_json::to_value(doc)?;
+                        let obj = v
+                            .as_object_mut()
+                            .ok_or_else(|| anyhow!("search: document must be an object"))?;
+                        obj.entry("id").or_insert_with(|| Value::String(doc.id()));
+                        list.push(v);
+                    }
+                    let path = format!("indexes/{}/documents", index);
+                    let (status, v) = self
+                        .send(Method::POST, &path, Body::Json(json!(list)))
+                        .await?;
+                    check("upsert", status, &v)?;
+                    self.wait_task(&v).await?;
+                }
+            }
+        }
+        Ok(docs.len())
+    }
+
+    /// 批量删除文档
+    pub async fn delete<S: AsRef<str>>(&self, index: &str, ids: &[S]) -> anyhow::Result<()> {
+        for chunk in ids.chunks(BULK_SIZE) {
+            match self.engine {
+                Engine::Elasticsearch => {
+                    let mut body = String::new();
+                    for id in chunk {
+                        body.push_str(
+                            &json!({"delete": {"_index": index, "_id": id.as_ref()}}).to_string(),
+                        );
+                        body.push('\n');
+                    }
+                    self.bulk(body).await?;
+                }
+                Engine::Meilisearch => {
+                    let ids: Vec<&str> = chunk.iter().map(|v| v.as_ref()).collect();
+                    let path = format!("indexes/{}/documents/delete-batch", index);
+                    let (status, v) = self
+                        .send(Method::POST, &path, Body::Json(json!(ids)))
+                        .await?;
+                    check("delete", status, &v)?;
+                    self.wait_task(&v).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 搜索，返回分页数据
+    pub async fn search<T: DeserializeOwned>(
+        &self,
+        index: &str,
+        query: &Query,
+    ) -> anyhow::Result<PageData<T>> {
+        let (path, body) = match self.engine {
+            Engine::Elasticsearch => (format!("{}/_search", index), query.to_elasticsearch()),
+            Engine::Meilisearch => (format!("indexes/{}/search", index), query.to_meilisearch()),
+        };
+        let (status, v) = self.send(Method::POST, &path, Body::Json(body)).await?;
+        check("search", status, &v)?;
+
+        let (hits, total) = parse_hits(self.engine, v)?;
+        let list = hits
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<T>, _>>()?;
+        let (page, size) = query.page_size();
+        Ok(PageData::new(list, total, page, size))
+    }
+
+    async fn bulk(&self, body: String) -> anyhow::Result<()> {
+        let (status, v) = self
+            .send(Method::POST, "_bulk?refresh=wait_for", Body::NdJson(body))
+            .await?;
+        check("bulk", status, &v)?;
+        if v["errors"].as_bool().unwrap_or(false) {
+            // 返回第一条失败原因
+            let reason = v["items"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item.as_object()?.values().next())
+                .find(|op| !op["error"].is_null())
+                .map(|op| op["error"].to_string())
+                .unwrap_or_default();
+            return Err(anyhow!("search: bulk failed: {}", reason));
+        }
+        Ok(())
+    }
+
+    // 等待 Meilisearch 异步任务完成
+    async fn wait_task(&self, v: &Value) -> anyhow::Result<()> {
+        let Some(uid) = v["taskUid"].as_u64() else {
+            return Ok(());
+        };
+        let path = format!("tasks/{}", uid);
+        let start = Instant::now();
+        let mut interval = Duration::from_millis(20);
+        loop {
+            let (status, v) = self.send(Method::GET, &path, Body::None).await?;
+            check("get task", status, &v)?;
+            match v["status"].as_str() {
+                Some("succeeded") => return Ok(()),
+                Some("failed") | Some("canceled") => {
+                    return Err(anyhow!(
+                        "search: task {} {}: {} {}",
+                        uid,
+                        v["status"].as_str().unwrap_or_default(),
+                        v["error"]["code"].as_str().unwrap_or_default(),
+                        v["error"]["message"].as_str().unwrap_or_default()
+                    ));
+                }
+                _ => {}
+            }
+            if start.elapsed() > Duration::from_secs(60) {
+                return Err(anyhow!("search: task {} timeout", uid));
+            }
+            tokio::time::sleep(interval).await;
+            interval = (interval * 2).min(Duration::from_millis(500));
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Body,
+    ) -> anyhow::Result<(StatusCode, Value)> {
+        let url = format!("{}/{}", self.endpoint, path);
+        let send = || async {
+            let start = Instant::now();
+            let ret = self.request(method.clone(), &url, &body).await;
+            let cost = start.elapsed();
+            match &ret {
+                Ok((status, _)) => {
+                    tracing::debug!(method = %method, path = path, status = status.as_u16(), cost_ms = cost.as_millis(), "[search] request");
+                }
+                Err(e) => {
+                    tracing::debug!(method = %method, path = path, cost_ms = cost.as_millis(), err = ?e, "[search] request");
+                }
+            }
+            ret
+        };
+        self.retry
+            .run("search", send, |ret| match ret {
+                Ok((status, _)) => {
+                    *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+                }
+                Err(_) => true,
+            })
+            .await
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        url: &str,
+        body: &Body,
+    ) -> anyhow::Result<(StatusCode, Value)> {
+        let mut req = self.http.request(method, url);
+        req = match &self.auth {
+            Auth::None => req,
+            Auth::Basic(user, password) => req.basic_auth(user, Some(password)),
+            Auth::ApiKey(key) => match self.engine {
+                Engine::Elasticsearch => {
+                    req.header(header::AUTHORIZATION, format!("ApiKey {}", key))
+                }
+                Engine::Meilisearch => req.bearer_auth(key),
+            },
+        };
+        req = match body {
+            Body::None => req,
+            Body::Json(v) => req.json(v),
+            Body::NdJson(s) => req
+                .header(header::CONTENT_TYPE, "application/x-ndjson")
+                .body(s.clone()),
+        };
+
+        let resp = req.send().await?;
+        let status = resp.status();
+        let bytes = resp.bytes().await?;
+        let v = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).to_string()))
+        };
+        Ok((status, v))
+    }
+}
+
+fn check(op: &str, status: StatusCode, v: &Value) -> anyhow::Result<()> {
+    if status.is_success() {
+        return Ok(());
+    }
+    Err(error(op, status, v))
+}
+
+fn error(op: &str, status: StatusCode, v: &Value) -> anyhow::Error {
+    anyhow!("search: {} failed: {} {}", op, status, v)
+}
+
+// 解析搜索结果，返回（文档列表，总数）
+fn parse_hits(engine: Engine, mut v: Value) -> anyhow::Result<(Vec<Value>, i64)> {
+    match engine {
+        Engine::Elasticsearch => {
+            let total = v["hits"]["total"]["value"].as_i64().unwrap_or_default();
+            let hits = match v["hits"]["hits"].take() {
+                Value::Array(list) => list.into_iter().map(|mut v| v["_source"].take()).collect(),
+                _ => return Err(anyhow!("search: invalid response {}", v)),
+            };
+            Ok((hits, total))
+        }
+        Engine::Meilisearch => {
+            let total = v["totalHits"].as_i64().unwrap_or_default();
+            let hits = match v["hits"].take() {
+                Value::Array(list) => list,
+                _ => return Err(anyhow!("search: invalid response {}", v)),
+            };
+            Ok((hits, total))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use serde::Deserialize;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::search::{self, Engine, Query};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Product {
+        id: i64,
+    }
+
+    #[tokio::test]
+    async fn test_search_retry() {
+        // 第一次返回 503，之后返回结果
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let n = Arc::new(AtomicU32::new(0));
+        let count = n.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut conn, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let _ = conn.read(&mut buf).await.unwrap();
+                let resp = if count.fetch_add(1, Ordering::SeqCst) == 0 {
+                    "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = json!({"hits": [{"id": 1}], "totalHits": 21}).to_string();
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                conn.write_all(resp.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = search::Client::new(Engine::Meilisearch, format!("http://{}", addr))
+            .retry(2, Duration::from_millis(1));
+        let page = client
+            .search::<Product>("products", &Query::new().page(1, 20))
+            .await
+            .unwrap();
+        assert_eq!(page.list, vec![Product { id: 1 }]);
+        assert_eq!(page.total, 21);
+        assert!(page.has_more);
+        assert_eq!(n.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_parse_hits() {
+        let v = json!({
+            "hits": {
+                "total": {"value": 42, "relation": "eq"},
+                "hits": [{"_id": "1", "_source": {"id": 1, "title": "a"}}],
+            }
+        });
+        let (hits, total) = search::parse_hits(Engine::Elasticsearch, v).unwrap();
+        assert_eq!(hits, vec![json!({"id": 1, "title": "a"})]);
+        assert_eq!(total, 42);
+
+        let v = json!({"hits": [{"id": 2}], "totalHits": 7, "page": 1, "hitsPerPage": 20});
+        let (hits, total) = search::parse_hits(Engine::Meilisearch, v).unwrap();
+        assert_eq!(hits, vec![json!({"id": 2})]);
+        assert_eq!(total, 7);
+
+        assert!(search::parse_hits(Engine::Meilisearch, json!({"message": "x"})).is_err());
+    }
+}
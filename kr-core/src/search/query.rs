@@ -0,0 +1,282 @@
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Eq(String, Value),
+    In(String, Vec<Value>),
+    Gte(String, Value),
+    Lte(String, Value),
+    Exists(String),
+}
+
+/// 搜索条件（多个过滤条件之间为 AND）
+///
+/// # Examples
+///
+/// ```
+/// let q = Query::new()
+///     .text("无线耳机")
+///     .fields(&["title", "brand"])
+///     .eq("status", 1)
+///     .any("category", ["audio", "phone"])
+///     .gte("price", 100)
+///     .lte("price", 500)
+///     .sort_desc("sales")
+///     .page(1, 20);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query {
+    text: Option<String>,
+    fields: Vec<String>,
+    filters: Vec<Filter>,
+    sorts: Vec<(String, bool)>,
+    page: i32,
+    size: i32,
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Query {
+    /// 默认：第 1 页，每页 20 条
+    pub fn new() -> Self {
+        Self {
+            text: None,
+            fields: Vec::new(),
+            filters: Vec::new(),
+            sorts: Vec::new(),
+            page: 1,
+            size: 20,
+        }
+    }
+
+    /// 全文检索关键词
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// 全文检索的字段，默认为全部字段
+    pub fn fields(mut self, fields: &[&str]) -> Self {
+        self.fields = fields.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    /// field = value
+    pub fn eq(mut self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.filters.push(Filter::Eq(field.into(), value.into()));
+        self
+    }
+
+    /// field IN values
+    pub fn any<I, V>(mut self, field: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        self.filters.push(Filter::In(field.into(), values));
+        self
+    }
+
+    /// field >= value
+    pub fn gte(mut self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.filters.push(Filter::Gte(field.into(), value.into()));
+        self
+    }
+
+    /// field <= value
+    pub fn lte(mut self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.filters.push(Filter::Lte(field.into(), value.into()));
+        self
+    }
+
+    /// 字段存在
+    pub fn exists(mut self, field: impl Into<String>) -> Self {
+        self.filters.push(Filter::Exists(field.into()));
+        self
+    }
+
+    pub fn sort_asc(mut self, field: impl Into<String>) -> Self {
+        self.sorts.push((field.into(), false));
+        self
+    }
+
+    pub fn sort_desc(mut self, field: impl Into<String>) -> Self {
+        self.sorts.push((field.into(), true));
+        self
+    }
+
+    /// 分页，默认值与 `PageData` 一致（page <= 0 => 1，size <= 0 => 20）
+    pub fn page(mut self, page: i32, size: i32) -> Self {
+        self.page = if page <= 0 { 1 } else { page };
+        self.size = if size <= 0 { 20 } else { size };
+        self
+    }
+
+    pub(crate) fn page_size(&self) -> (i32, i32) {
+        (self.page, self.size)
+    }
+
+    /// Elasticsearch 请求体
+    pub fn to_elasticsearch(&self) -> Value {
+        let must = match &self.text {
+            Some(text) if self.fields.is_empty() => {
+                json!([{"multi_match": {"query": text}}])
+            }
+            Some(text) => json!([{"multi_match": {"query": text, "fields": self.fields}}]),
+            None => json!([{"match_all": {}}]),
+        };
+        let filter: Vec<Value> = self
+            .filters
+            .iter()
+            .map(|f| match f {
+                Filter::Eq(k, v) => json!({"term": {k: v}}),
+                Filter::In(k, v) => json!({"terms": {k: v}}),
+                Filter::Gte(k, v) => json!({"range": {k: {"gte": v}}}),
+                Filter::Lte(k, v) => json!({"range": {k: {"lte": v}}}),
+                Filter::Exists(k) => json!({"exists": {"field": k}}),
+            })
+            .collect();
+        let sort: Vec<Value> = self
+            .sorts
+            .iter()
+            .map(|(k, desc)| json!({k: if *desc { "desc" } else { "asc" }}))
+            .collect();
+
+        let mut body = json!({
+            "query": {"bool": {"must": must, "filter": filter}},
+            "from": (self.page - 1) as i64 * self.size as i64,
+            "size": self.size,
+            "track_total_hits": true,
+        });
+        if !sort.is_empty() {
+            body["sort"] = Value::Array(sort);
+        }
+        body
+    }
+
+    /// Meilisearch 请求体（过滤、排序的字段需在索引设置中声明为 filterable、sortable）
+    pub fn to_meilisearch(&self) -> Value {
+        let filter: Vec<String> = self
+            .filters
+            .iter()
+            .map(|f| match f {
+                Filter::Eq(k, v) => format!("{} = {}", k, meili_value(v)),
+                Filter::In(k, v) => format!(
+                    "{} IN [{}]",
+                    k,
+                    v.iter().map(meili_value).collect::<Vec<_>>().join(", ")
+                ),
+                Filter::Gte(k, v) => format!("{} >= {}", k, meili_value(v)),
+                Filter::Lte(k, v) => format!("{} <= {}", k, meili_value(v)),
+                Filter::Exists(k) => format!("{} EXISTS", k),
+            })
+            .collect();
+        let sort: Vec<String> = self
+            .sorts
+            .iter()
+            .map(|(k, desc)| format!("{}:{}", k, if *desc { "desc" } else { "asc" }))
+            .collect();
+
+        let mut body = json!({
+            "q": self.text.clone().unwrap_or_default(),
+            "page": self.page,
+            "hitsPerPage": self.size,
+        });
+        if !self.fields.is_empty() {
+            body["attributesToSearchOn"] = json!(self.fields);
+        }
+        if !filter.is_empty() {
+            body["filter"] = json!(filter);
+        }
+        if !sort.is_empty() {
+            body["sort"] = json!(sort);
+        }
+        body
+    }
+}
+
+// 字符串加引号并转义，其余原样输出
+fn meili_value(v: &Value) -> String {
+    match v {
+        Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::search::Query;
+
+    #[test]
+    fn test_query() {
+        let q = Query::new()
+            .text("phone")
+            .fields(&["title"])
+            .eq("status", 1)
+            .any("tag", ["a", "b\"c"])
+            .gte("price", 10)
+            .lte("price", 99.5)
+            .exists("cover")
+            .sort_desc("sales")
+            .page(3, 10);
+
+        assert_eq!(
+            q.to_elasticsearch(),
+            json!({
+                "query": {"bool": {
+                    "must": [{"multi_match": {"query": "phone", "fields": ["title"]}}],
+                    "filter": [
+                        {"term": {"status": 1}},
+                        {"terms": {"tag": ["a", "b\"c"]}},
+                        {"range": {"price": {"gte": 10}}},
+                        {"range": {"price": {"lte": 99.5}}},
+                        {"exists": {"field": "cover"}},
+                    ],
+                }},
+                "from": 20,
+                "size": 10,
+                "track_total_hits": true,
+                "sort": [{"sales": "desc"}],
+            })
+        );
+        assert_eq!(
+            q.to_meilisearch(),
+            json!({
+                "q": "phone",
+                "page": 3,
+                "hitsPerPage": 10,
+                "attributesToSearchOn": ["title"],
+                "filter": [
+                    "status = 1",
+                    r#"tag IN ["a", "b\"c"]"#,
+                    "price >= 10",
+                    "price <= 99.5",
+                    "cover EXISTS",
+                ],
+                "sort": ["sales:desc"],
+            })
+        );
+
+        let q = Query::new().page(0, 0);
+        assert_eq!(
+            q.to_elasticsearch(),
+            json!({
+                "query": {"bool": {"must": [{"match_all": {}}], "filter": []}},
+                "from": 0,
+                "size": 20,
+                "track_total_hits": true,
+            })
+        );
+        assert_eq!(
+            q.to_meilisearch(),
+            json!({"q": "", "page": 1, "hitsPerPage": 20})
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::crypto::hash::murmur3_32;
+
+/// 分片键：整数按值取模，字符串按 murmur3 哈希取模
+pub trait ShardKey {
+    fn shard_hash(&self) -> u64;
+}
+
+macro_rules! impl_int_key {
+    ($($t:ty),*) => {
+        $(
+            impl ShardKey for $t {
+                fn shard_hash(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_int_key!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl ShardKey for str {
+    fn shard_hash(&self) -> u64 {
+        murmur3_32(self, 0) as u64
+    }
+}
+
+impl ShardKey for String {
+    fn shard_hash(&self) -> u64 {
+        self.as_str().shard_hash()
+    }
+}
+
+impl<T: ShardKey + ?Sized> ShardKey for &T {
+    fn shard_hash(&self) -> u64 {
+        (**self).shard_hash()
+    }
+}
+
+/// 分片序号：`hash(key) % n`
+pub fn index<K: ShardKey + ?Sized>(key: &K, n: u32) -> u32 {
+    (key.shard_hash() % n.max(1) as u64) as u32
+}
+
+/// 分表后缀，按分片数补零（如：n=16 时为 `_00` ~ `_15`）
+///
+/// # Examples
+///
+/// ```
+/// // order_07
+/// let table = format!("order{}", shard::table_suffix(&user_id, 16));
+/// ```
+pub fn table_suffix<K: ShardKey + ?Sized>(key: &K, n: u32) -> String {
+    suffix(index(key, n), n)
+}
+
+/// 分表名：`{base}_{序号}`
+pub fn table_name<K: ShardKey + ?Sized>(base: &str, key: &K, n: u32) -> String {
+    format!("{}{}", base, table_suffix(key, n))
+}
+
+/// 所有分表名
+pub fn table_names(base: &str, n: u32) -> Vec<String> {
+    (0..n.max(1))
+        .map(|i| format!("{}{}", base, suffix(i, n)))
+        .collect()
+}
+
+fn suffix(i: u32, n: u32) -> String {
+    let width = (n.max(2) - 1).to_string().len();
+    format!("_{:0width$}", i, width = width)
+}
+
+/// 哈希区间 `(start, end]` 的归属变化（start 为 0 时包含 0）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub start: u32,
+    pub end: u32,
+    pub from: String,
+    pub to: String,
+}
+
+/// 一致性哈希环（虚拟节点）
+///
+/// # Examples
+///
+/// ```
+/// let mut ring = Ring::new(160);
+/// ring.add("redis-1");
+/// ring.add("redis-2");
+///
+/// let node = ring.node_for("user:10086");
+///
+/// // 扩容：计算需要迁移的哈希区间
+/// let mut next = ring.clone();
+/// next.add_weighted("redis-3", 2);
+/// for m in ring.diff(&next) {
+///     // (m.start, m.end] 从 m.from 迁移到 m.to
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ring {
+    replicas: u32,
+    // 哈希冲突时同一位置可能有多个节点，名称最小的节点生效
+    points: BTreeMap<u32, BTreeSet<String>>,
+    nodes: BTreeMap<String, u32>,
+}
+
+impl Ring {
+    /// `replicas` 为每个节点（权重 1）的虚拟节点数
+    pub fn new(replicas: u32) -> Self {
+        Self {
+            replicas: replicas.max(1),
+            points: BTreeMap::new(),
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, node: impl AsRef<str>) {
+        self.add_weighted(node, 1)
+    }
+
+    /// 按权重添加节点（虚拟节点数 = replicas * weight），已存在时更新权重
+    pub fn add_weighted(&mut self, node: impl AsRef<str>, weight: u32) {
+        let node = node.as_ref();
+        self.remove(node);
+        for i in 0..self.replicas * weight.max(1) {
+            let h = murmur3_32(format!("{}#{}", node, i), 0);
+            // 冲突的节点一并保留：移除生效节点后由其余节点接管，结果与加入、移除顺序无关
+            self.points.entry(h).or_default().insert(node.to_string());
+        }
+        self.nodes.insert(node.to_string(), weight.max(1));
+    }
+
+    pub fn remove(&mut self, node: impl AsRef<str>) {
+        let node = node.as_ref();
+        if self.nodes.remove(node).is_some() {
+            self.points.retain(|_, v| {
+                v.remove(node);
+                !v.is_empty()
+            });
+        }
+    }
+
+    /// 节点及权重
+    pub fn nodes(&self) -> &BTreeMap<String, u32> {
+        &self.nodes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// key 所属节点
+    pub fn node_for(&self, key: impl AsRef<[u8]>) -> Option<&str> {
+        self.node_at(murmur3_32(key, 0))
+    }
+
+    // 顺时针方向第一个虚拟节点
+    fn node_at(&self, h: u32) -> Option<&str> {
+        self.points
+            .range(h..)
+            .next()
+            .or_else(|| self.points.iter().next())
+            .and_then(|(_, v)| v.first())
+            .map(|v| v.as_str())
+    }
+
+    /// 与新环相比归属发生变化的哈希区间（相邻且迁移方向相同的区间会合并）
+    pub fn diff(&self, other: &Ring) -> Vec<Move> {
+        let bounds: BTreeSet<u32> = self
+            .points
+            .keys()
+            .chain(other.points.keys())
+            .copied()
+            .collect();
+        let Some(&last) = bounds.last() else {
+            return Vec::new();
+        };
+
+        let mut moves: Vec<Move> = Vec::new();
+        let mut push = |start: u32, end: u32| {
+            let (Some(from), Some(to)) = (self.node_at(end), other.node_at(end)) else {
+                return;
+            };
+            if from == to {
+                return;
+            }
+            if let Some(m) = moves.last_mut() {
+                if m.end == start && m.from == from && m.to == to {
+                    m.end = end;
+                    return;
+                }
+            }
+            moves.push(Move {
+                start,
+                end,
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        };
+
+        // 跨越 0 的区间：(last, u32::MAX] 和 [0, first]，后者在循环中处理
+        if last < u32::MAX {
+            push(last, u32::MAX);
+        }
+        let mut prev = 0;
+        for h in bounds {
+            if h > 0 {
+                push(prev, h);
+            }
+            prev = h;
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        crypto::hash::murmur3_32,
+        shard::{self, Ring},
+    };
+
+    #[test]
+    fn test_table_suffix() {
+        assert_eq!(shard::table_suffix(&10086u64, 16), "_06");
+        assert_eq!(shard::table_suffix(&7, 8), "_7");
+        assert_eq!(shard::table_suffix(&7, 1), "_0");
+        assert_eq!(shard::table_name("order", &123, 100), "order_23");
+        assert_eq!(shard::index("user:1", 16), murmur3_32("user:1", 0) % 16);
+        assert_eq!(
+            shard::table_names("log", 12),
+            (0..12).map(|i| format!("log_{:02}", i)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_ring() {
+        let mut ring = Ring::new(100);
+        assert_eq!(ring.node_for("a"), None);
+        for n in ["n1", "n2", "n3"] {
+            ring.add(n);
+        }
+
+        // 分布大致均衡
+        let mut count: HashMap<String, usize> = HashMap::new();
+        let keys: Vec<String> = (0..3000).map(|i| format!("key:{}", i)).collect();
+        for k in &keys {
+            *count
+                .entry(ring.node_for(k).unwrap().to_string())
+                .or_default() += 1;
+        }
+        assert!(count.values().all(|v| *v > 500), "{:?}", count);
+
+        // 与加入顺序无关
+        let mut other = Ring::new(100);
+        for n in ["n3", "n1", "n2"] {
+            other.add(n);
+        }
+        assert!(keys.iter().all(|k| ring.node_for(k) == other.node_for(k)));
+        assert!(ring.diff(&other).is_empty());
+
+        // 扩容：仅迁移到新节点，且与 diff 结果一致
+        let mut next = ring.clone();
+        next.add("n4");
+        let moves = ring.diff(&next);
+        assert!(!moves.is_empty());
+        assert!(moves.iter().all(|m| m.to == "n4" && m.start < m.end));
+        for k in &keys {
+            let (from, to) = (ring.node_for(k).unwrap(), next.node_for(k).unwrap());
+            let h = murmur3_32(k, 0);
+            let moved = moves
+                .iter()
+                .find(|m| (h > m.start || (m.start == 0 && h == 0)) && h <= m.end);
+            match moved {
+                Some(m) => assert_eq!((from, to), (m.from.as_str(), m.to.as_str())),
+                None => assert_eq!(from, to),
+            }
+        }
+
+        // 缩容
+        next.remove("n4");
+        next.remove("n2");
+        assert_eq!(next.nodes().len(), 2);
+        assert!(ring.diff(&next).iter().all(|m| m.from == "n2"));
+    }
+
+    #[test]
+    fn test_ring_collision() {
+        // 两个节点的虚拟节点哈希相同
+        let (a, b) = ("node-217558", "node-246151");
+        let h = murmur3_32(format!("{}#0", a), 0);
+        assert_eq!(h, murmur3_32(format!("{}#0", b), 0));
+
+        let mut ring = Ring::new(1);
+        ring.add(b);
+        ring.add(a);
+        ring.add("n1");
+        assert_eq!(ring.node_at(h), Some(a));
+
+        // 移除生效节点后，冲突节点接管该位置，与从未加入时一致
+        ring.remove(a);
+        let mut expected = Ring::new(1);
+        expected.add(b);
+        expected.add("n1");
+        assert_eq!(ring.node_at(h), Some(b));
+        assert!(ring.diff(&expected).is_empty());
+        assert!(expected.diff(&ring).is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use std::{
+    any::Any,
+    future::Future,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::anyhow;
+use sqlx::{pool::PoolConnection, Database, Pool, Transaction};
+use tokio::sync::{Mutex, OwnedMappedMutexGuard, OwnedMutexGuard};
+
+tokio::task_local! {
+    static CTX: Arc<dyn Any + Send + Sync>;
+}
+
+/// 请求级 DB 上下文：持有连接池及当前事务，嵌套的数据层函数通过 [`conn`] 获取连接，
+/// 处于事务中时自动共享该事务，否则从连接池获取
+pub struct DbCtx<DB: Database> {
+    pool: Pool<DB>,
+    tx: Arc<Mutex<Option<Transaction<'static, DB>>>>,
+    // 嵌套事务失败时标记，外层边界只能回滚
+    rollback_only: AtomicBool,
+}
+
+impl<DB: Database> DbCtx<DB> {
+    /// 当前上下文
+    pub fn current() -> anyhow::Result<Arc<Self>> {
+        let ctx = CTX
+            .try_with(|v| v.clone())
+            .map_err(|_| anyhow!("sql/ctx: no DbCtx in context"))?;
+        ctx.downcast::<Self>()
+            .map_err(|_| anyhow!("sql/ctx: DbCtx database type mismatch"))
+    }
+
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+
+    /// 是否处于事务中
+    pub async fn in_transaction(&self) -> bool {
+        self.tx.lock().await.is_some()
+    }
+
+    /// 获取连接：处于事务中时返回事务连接（持有期间独占该事务），否则从连接池获取
+    ///
+    /// 事务连接不可重入：持有 `DbConn` 期间再次获取连接或开启事务会返回错误（而非死锁）
+    pub async fn conn(&self) -> anyhow::Result<DbConn<DB>> {
+        let guard = self.lock_tx()?;
+        if guard.is_some() {
+            let tx = OwnedMutexGuard::map(guard, |v| v.as_mut().unwrap());
+            return Ok(DbConn::Tx(tx));
+        }
+        drop(guard);
+        Ok(DbConn::Pool(self.pool.acquire().await?))
+    }
+
+    fn lock_tx(&self) -> anyhow::Result<OwnedMutexGuard<Option<Transaction<'static, DB>>>> {
+        self.tx.clone().try_lock_owned().map_err(|_| {
+            anyhow!("sql/ctx: transaction connection is in use, drop the held DbConn first")
+        })
+    }
+}
+
+// 事务边界的清理：future 在提交/回滚前被取消时，丢弃事务（sqlx 自动回滚）并重置标记，
+// 避免后续调用加入失效的事务
+struct TxGuard<DB: Database> {
+    ctx: Arc<DbCtx<DB>>,
+    armed: bool,
+}
+
+impl<DB: Database> Drop for TxGuard<DB> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        self.ctx.rollback_only.store(false, Ordering::Release);
+        if let Ok(mut tx) = self.ctx.tx.try_lock() {
+            tx.take();
+            return;
+        }
+        // 事务连接仍被持有（随同一 future 一起释放），稍后清理
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let tx = self.ctx.tx.clone();
+            handle.spawn(async move {
+                tx.lock().await.take();
+            });
+        }
+    }
+}
+
+/// 上下文连接，可解引用为 `DB::Connection` 作为 Executor 使用：`&mut *conn`
+pub enum DbConn<DB: Database> {
+    Pool(PoolConnection<DB>),
+    Tx(OwnedMappedMutexGuard<Option<Transaction<'static, DB>>, Transaction<'static, DB>>),
+}
+
+impl<DB: Database> Deref for DbConn<DB> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            DbConn::Pool(c) => c,
+            DbConn::Tx(tx) => tx,
+        }
+    }
+}
+
+impl<DB: Database> DerefMut for DbConn<DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            DbConn::Pool(c) => c,
+            DbConn::Tx(tx) => tx,
+        }
+    }
+}
+
+/// 在 DB 上下文中执行（通常由中间件在每个请求开始时调用）
+///
+/// 结束时未提交的事务自动回滚
+///
+/// # Examples
+///
+/// ```
+/// let ret = sql::ctx::scope(pool.clone(), async {
+///     service::create_order(req).await
+/// })
+/// .await;
+/// ```
+pub async fn scope<DB, F>(pool: Pool<DB>, f: F) -> F::Output
+where
+    DB: Database,
+    F: Future,
+{
+    let ctx: Arc<dyn Any + Send + Sync> = Arc::new(DbCtx {
+        pool,
+        tx: Arc::new(Mutex::new(None)),
+        rollback_only: AtomicBool::new(false),
+    });
+    CTX.scope(ctx, f).await
+}
+
+/// 获取当前上下文的连接
+///
+/// # Examples
+///
+/// ```
+/// async fn find_user(id: i64) -> anyhow::Result<Option<model::User>> {
+///     let stmt = Query::select()
+///         .from(table::User::Table)
+///         .expr(Expr::cust("*"))
+///         .and_where(Expr::col(table::User::Id).eq(id))
+///         .to_owned();
+///     mysql::find_one(&mut *sql::ctx::conn::<MySql>().await?, stmt).await
+/// }
+/// ```
+pub async fn conn<DB: Database>() -> anyhow::Result<DbConn<DB>> {
+    DbCtx::<DB>::current()?.conn().await
+}
+
+/// 事务边界：不在事务中时开启事务，执行成功提交、失败回滚；已在事务中时加入外层事务，
+/// 失败时将外层事务标记为只能回滚；future 被取消时事务回滚
+///
+/// # Examples
+///
+/// ```
+/// sql::ctx::transaction::<MySql, _, _, _>(|| async {
+///     // 内部通过 sql::ctx::conn 获取的连接均使用该事务
+///     repo::stock::decrease(stock_id, 1).await?;
+///     repo::order::create(&order).await
+/// })
+/// .await?;
+/// ```
+pub async fn transaction<DB, T, F, Fut>(f: F) -> anyhow::Result<T>
+where
+    DB: Database,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let ctx = DbCtx::<DB>::current()?;
+
+    let mut guard = ctx.lock_tx()?;
+    if guard.is_some() {
+        drop(guard);
+        let ret = f().await;
+        if ret.is_err() {
+            ctx.rollback_only.store(true, Ordering::Release);
+        }
+        return ret;
+    }
+    *guard = Some(ctx.pool.begin().await?);
+    ctx.rollback_only.store(false, Ordering::Release);
+    drop(guard);
+
+    let mut tx_guard = TxGuard {
+        ctx: ctx.clone(),
+        armed: true,
+    };
+    let ret = f().await;
+
+    let tx = ctx.lock_tx()?.take();
+    tx_guard.armed = false;
+    let Some(tx) = tx else {
+        return ret;
+    };
+    let rollback_only = ctx.rollback_only.swap(false, Ordering::AcqRel);
+    match ret {
+        Ok(v) if !rollback_only => {
+            tx.commit().await?;
+            Ok(v)
+        }
+        Ok(_) => {
+            tx.rollback().await?;
+            Err(anyhow!("sql/ctx: transaction is marked rollback-only"))
+        }
+        Err(e) => {
+            if let Err(err) = tx.rollback().await {
+                tracing::error!(err = ?err, "[sql::ctx::transaction] rollback failed");
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sea_query::{Alias, Query};
+    use sqlx::Sqlite;
+
+    use crate::sql::{self, ctx, sqlite};
+
+    async fn create(name: &str) -> anyhow::Result<i64> {
+        let stmt = Query::insert()
+            .into_table(Alias::new("demo"))
+            .columns([Alias::new("name")])
+            .values_panic([name.into()])
+            .to_owned();
+        sqlite::create(&mut *ctx::conn::<Sqlite>().await?, stmt).await
+    }
+
+    async fn count() -> anyhow::Result<i64> {
+        let stmt = Query::select().from(Alias::new("demo")).to_owned();
+        sqlite::count(&mut *ctx::conn::<Sqlite>().await?, stmt).await
+    }
+
+    #[tokio::test]
+    async fn test_ctx() {
+        let pool = sql::test::memory_pool(Some(
+            "CREATE TABLE demo (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+        ))
+        .await
+        .unwrap();
+
+        assert!(ctx::conn::<Sqlite>().await.is_err());
+
+        ctx::scope(pool, async {
+            // 无事务
+            create("a").await.unwrap();
+
+            // 失败回滚
+            let ret = ctx::transaction::<Sqlite, (), _, _>(|| async {
+                create("b").await?;
+                assert_eq!(count().await?, 2);
+                anyhow::bail!("oops")
+            })
+            .await;
+            assert!(ret.is_err());
+            assert_eq!(count().await.unwrap(), 1);
+
+            // 嵌套失败：外层只能回滚
+            let ret = ctx::transaction::<Sqlite, _, _, _>(|| async {
+                create("c").await?;
+                let _ = ctx::transaction::<Sqlite, (), _, _>(|| async {
+                    create("d").await?;
+                    anyhow::bail!("oops")
+                })
+                .await;
+                Ok(())
+            })
+            .await;
+            assert!(ret.is_err());
+            assert_eq!(count().await.unwrap(), 1);
+
+            // 提交
+            ctx::transaction::<Sqlite, _, _, _>(|| async {
+                create("e").await?;
+                ctx::transaction::<Sqlite, _, _, _>(|| create("f")).await
+            })
+            .await
+            .unwrap();
+            assert_eq!(count().await.unwrap(), 3);
+
+            // 持有事务连接时再次获取：报错而非死锁
+            ctx::transaction::<Sqlite, _, _, _>(|| async {
+                let conn = ctx::conn::<Sqlite>().await?;
+                assert!(ctx::conn::<Sqlite>().await.is_err());
+                drop(conn);
+                create("g").await
+            })
+            .await
+            .unwrap();
+            assert_eq!(count().await.unwrap(), 4);
+
+            // 事务中途取消：回滚且不影响后续调用
+            let ret = tokio::time::timeout(
+                Duration::from_millis(50),
+                ctx::transaction::<Sqlite, (), _, _>(|| async {
+                    create("h").await?;
+                    std::future::pending().await
+                }),
+            )
+            .await;
+            assert!(ret.is_err());
+            let ctx = sql::DbCtx::<Sqlite>::current().unwrap();
+            assert!(!ctx.in_transaction().await);
+            create("i").await.unwrap();
+            assert_eq!(count().await.unwrap(), 5);
+        })
+        .await;
+    }
+}
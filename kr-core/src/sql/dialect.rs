@@ -0,0 +1,128 @@
+use std::time::Instant;
+
+use futures::future::BoxFuture;
+use sea_query::{
+    InsertStatement, MysqlQueryBuilder, PostgresQueryBuilder, SelectStatement, SqliteQueryBuilder,
+    UpdateStatement,
+};
+use sea_query_binder::SqlxBinder;
+use sqlx::{Database, Executor, FromRow, MySql, Postgres, Sqlite};
+
+use crate::sql::{tenant, trace_stmt};
+
+/// 数据库方言：按数据库生成 SQL 并执行（用于批量写入及跨数据库的内置模块）
+pub trait Dialect: Database {
+    /// 单条语句的绑定参数上限
+    const MAX_BINDS: usize;
+
+    fn insert<'e, E>(db: E, stmt: InsertStatement) -> BoxFuture<'e, anyhow::Result<u64>>
+    where
+        E: Executor<'e, Database = Self> + 'e;
+
+    /// 执行更新，返回影响行数
+    fn update<'e, E>(db: E, stmt: UpdateStatement) -> BoxFuture<'e, anyhow::Result<u64>>
+    where
+        E: Executor<'e, Database = Self> + 'e;
+
+    /// 查询多条数据
+    fn select<'e, E, T>(db: E, stmt: SelectStatement) -> BoxFuture<'e, anyhow::Result<Vec<T>>>
+    where
+        E: Executor<'e, Database = Self> + 'e,
+        T: for<'r> FromRow<'r, Self::Row> + Send + Unpin + 'e;
+}
+
+macro_rules! impl_dialect {
+    ($db:ty, $builder:expr, $max:expr) => {
+        impl Dialect for $db {
+            const MAX_BINDS: usize = $max;
+
+            fn insert<'e, E>(db: E, stmt: InsertStatement) -> BoxFuture<'e, anyhow::Result<u64>>
+            where
+                E: Executor<'e, Database = Self> + 'e,
+            {
+                Box::pin(async move {
+                    let (sql, values) = stmt.build_sqlx($builder);
+                    tenant::guard(&sql)?;
+
+                    let start = Instant::now();
+                    let ret = sqlx::query_with(&sql, values).execute(db).await;
+                    let cost = start.elapsed();
+
+                    match ret {
+                        Ok(v) => {
+                            trace_stmt(&stmt, $builder, cost, None);
+                            Ok(v.rows_affected())
+                        }
+                        Err(e) => {
+                            let err = anyhow::Error::from(e);
+                            trace_stmt(&stmt, $builder, cost, Some(&err));
+                            Err(err)
+                        }
+                    }
+                })
+            }
+
+            fn update<'e, E>(db: E, stmt: UpdateStatement) -> BoxFuture<'e, anyhow::Result<u64>>
+            where
+                E: Executor<'e, Database = Self> + 'e,
+            {
+                Box::pin(async move {
+                    let (sql, values) = stmt.build_sqlx($builder);
+                    tenant::guard(&sql)?;
+
+                    let start = Instant::now();
+                    let ret = sqlx::query_with(&sql, values).execute(db).await;
+                    let cost = start.elapsed();
+
+                    match ret {
+                        Ok(v) => {
+                            trace_stmt(&stmt, $builder, cost, None);
+                            Ok(v.rows_affected())
+                        }
+                        Err(e) => {
+                            let err = anyhow::Error::from(e);
+                            trace_stmt(&stmt, $builder, cost, Some(&err));
+                            Err(err)
+                        }
+                    }
+                })
+            }
+
+            fn select<'e, E, T>(
+                db: E,
+                stmt: SelectStatement,
+            ) -> BoxFuture<'e, anyhow::Result<Vec<T>>>
+            where
+                E: Executor<'e, Database = Self> + 'e,
+                T: for<'r> FromRow<'r, Self::Row> + Send + Unpin + 'e,
+            {
+                Box::pin(async move {
+                    let (sql, values) = stmt.build_sqlx($builder);
+                    tenant::guard(&sql)?;
+
+                    let start = Instant::now();
+                    let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
+                        .fetch_all(db)
+                        .await;
+                    let cost = start.elapsed();
+
+                    match ret {
+                        Ok(v) => {
+                            trace_stmt(&stmt, $builder, cost, None);
+                            Ok(v)
+                        }
+                        Err(e) => {
+                            let err = anyhow::Error::from(e);
+                            trace_stmt(&stmt, $builder, cost, Some(&err));
+                            Err(err)
+                        }
+                    }
+                })
+            }
+        }
+    };
+}
+
+impl_dialect!(MySql, MysqlQueryBuilder, 65535);
+impl_dialect!(Postgres, PostgresQueryBuilder, 65535);
+impl_dialect!(Sqlite, SqliteQueryBuilder, 32766);
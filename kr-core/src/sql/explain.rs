@@ -0,0 +1,265 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::sql::Opts;
+
+/// 执行计划摘要
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Explain {
+    /// 各表的访问方式
+    pub tables: Vec<TablePlan>,
+    /// 预估扫描行数（各表之和）
+    pub rows_examined: f64,
+    /// 是否存在全表扫描
+    pub full_scan: bool,
+    /// 原始执行计划(JSON)
+    pub plan: Value,
+}
+
+/// 单表访问方式
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TablePlan {
+    pub table: String,
+    /// MySQL: access_type(ALL/index/range/ref/eq_ref/const)；PgSQL: Node Type(Seq Scan/Index Scan/...)；
+    /// SQLite: SCAN/SEARCH
+    pub access: String,
+    /// 使用的索引
+    pub index: Option<String>,
+    /// 预估扫描行数（SQLite 无该信息，始终为 0）
+    pub rows: f64,
+}
+
+/// 是否允许 EXPLAIN（生产环境 `KR_PROFILE=prod` 时禁用，见 [`env::is_production`](crate::env::is_production)）
+pub fn is_enabled() -> bool {
+    !crate::env::is_production()
+}
+
+pub(crate) fn ensure_enabled() -> anyhow::Result<()> {
+    if !is_enabled() {
+        return Err(anyhow::anyhow!("sql/explain: disabled in production"));
+    }
+    Ok(())
+}
+
+/// 解析 MySQL `EXPLAIN FORMAT=JSON` 结果
+pub(crate) fn parse_mysql(plan: Value) -> Explain {
+    let mut tables = Vec::new();
+    walk(&plan, &mut |k, v| {
+        if k != "table" {
+            return;
+        }
+        let Some(name) = v.get("table_name").and_then(Value::as_str) else {
+            return;
+        };
+        tables.push(TablePlan {
+            table: name.to_string(),
+            access: str_of(v, "access_type"),
+            index: v.get("key").and_then(Value::as_str).map(String::from),
+            rows: num_of(v, "rows_examined_per_scan"),
+        });
+    });
+    summary(tables, plan, "ALL")
+}
+
+/// 解析 PgSQL `EXPLAIN (FORMAT JSON)` 结果
+pub(crate) fn parse_pgsql(plan: Value) -> Explain {
+    let mut tables = Vec::new();
+    walk(&plan, &mut |_, v| {
+        let Some(name) = v.get("Relation Name").and_then(Value::as_str) else {
+            return;
+        };
+        tables.push(TablePlan {
+            table: name.to_string(),
+            access: str_of(v, "Node Type"),
+            index: v
+                .get("Index Name")
+                .and_then(Value::as_str)
+                .map(String::from),
+            rows: num_of(v, "Plan Rows"),
+        });
+    });
+    summary(tables, plan, "Seq Scan")
+}
+
+/// 解析 SQLite `EXPLAIN QUERY PLAN` 结果：[(id, parent, detail)]
+///
+/// detail 形如 `SCAN demo`、`SEARCH demo USING INDEX idx_name (name=?)`
+pub(crate) fn parse_sqlite(rows: Vec<(i64, i64, String)>) -> Explain {
+    let mut tables = Vec::new();
+    for (_, _, detail) in &rows {
+        let words: Vec<&str> = detail.split_whitespace().collect();
+        let Some(access) = words.first().filter(|v| matches!(**v, "SCAN" | "SEARCH")) else {
+            continue;
+        };
+        // 旧版本为 `SCAN TABLE demo`
+        let name = match words.get(1) {
+            Some(&"TABLE") => words.get(2),
+            v => v,
+        };
+        let Some(name) = name else {
+            continue;
+        };
+        let index = match words.iter().position(|v| *v == "INDEX") {
+            Some(i) => words.get(i + 1).map(|v| v.to_string()),
+            None if detail.contains("PRIMARY KEY") => Some("PRIMARY KEY".to_string()),
+            None => None,
+        };
+        tables.push(TablePlan {
+            table: name.to_string(),
+            access: access.to_string(),
+            index,
+            rows: 0.0,
+        });
+    }
+
+    let plan = Value::Array(
+        rows.into_iter()
+            .map(|(id, parent, detail)| json!({"id": id, "parent": parent, "detail": detail}))
+            .collect(),
+    );
+    Explain {
+        rows_examined: 0.0,
+        full_scan: tables
+            .iter()
+            .any(|v| v.access == "SCAN" && v.index.is_none()),
+        tables,
+        plan,
+    }
+}
+
+// 设置了 explain_slow 且耗时超过阈值时需要记录执行计划
+pub(crate) fn should_explain(opts: &Opts, cost: Duration) -> bool {
+    opts.explain_slow.is_some_and(|v| cost >= v) && is_enabled()
+}
+
+// 记录慢查询的执行计划，查询执行计划失败不影响原查询
+pub(crate) fn log_slow(sql: String, cost: Duration, ret: anyhow::Result<Explain>) {
+    match ret {
+        Ok(v) => tracing::warn!(
+            sql = sql,
+            cost_ms = cost.as_millis(),
+            full_scan = v.full_scan,
+            rows_examined = v.rows_examined,
+            tables = ?v.tables,
+            "[sql::explain] slow sql plan"
+        ),
+        Err(e) => tracing::warn!(
+            sql = sql,
+            cost_ms = cost.as_millis(),
+            err = ?e,
+            "[sql::explain] explain slow sql failed"
+        ),
+    }
+}
+
+fn summary(tables: Vec<TablePlan>, plan: Value, full_scan: &str) -> Explain {
+    Explain {
+        rows_examined: tables.iter().map(|v| v.rows).sum(),
+        full_scan: tables.iter().any(|v| v.access == full_scan),
+        tables,
+        plan,
+    }
+}
+
+// 深度优先遍历所有对象：f(所在字段名, 对象)
+fn walk(v: &Value, f: &mut impl FnMut(&str, &Value)) {
+    fn inner(key: &str, v: &Value, f: &mut impl FnMut(&str, &Value)) {
+        match v {
+            Value::Object(m) => {
+                f(key, v);
+                for (k, child) in m {
+                    inner(k, child, f);
+                }
+            }
+            Value::Array(list) => {
+                for child in list {
+                    inner(key, child, f);
+                }
+            }
+            _ => {}
+        }
+    }
+    inner("", v, f)
+}
+
+fn str_of(v: &Value, key: &str) -> String {
+    v.get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn num_of(v: &Value, key: &str) -> f64 {
+    match v.get(key) {
+        Some(Value::Number(n)) => n.as_f64().unwrap_or_default(),
+        Some(Value::String(s)) => s.parse().unwrap_or_default(),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::sql::explain;
+
+    #[test]
+    fn test_parse_mysql() {
+        let plan = json!({
+            "query_block": {
+                "select_id": 1,
+                "nested_loop": [
+                    {"table": {"table_name": "o", "access_type": "ALL", "rows_examined_per_scan": 1000}},
+                    {"table": {"table_name": "u", "access_type": "eq_ref", "key": "PRIMARY", "rows_examined_per_scan": 1}}
+                ]
+            }
+        });
+        let ret = explain::parse_mysql(plan);
+        assert_eq!(ret.tables.len(), 2);
+        assert_eq!(ret.tables[1].index.as_deref(), Some("PRIMARY"));
+        assert_eq!(ret.rows_examined, 1001.0);
+        assert!(ret.full_scan);
+    }
+
+    #[test]
+    fn test_parse_pgsql() {
+        let plan = json!([{
+            "Plan": {
+                "Node Type": "Nested Loop",
+                "Plan Rows": 10,
+                "Plans": [
+                    {"Node Type": "Index Scan", "Relation Name": "orders", "Index Name": "idx_user", "Plan Rows": 10},
+                    {"Node Type": "Index Scan", "Relation Name": "users", "Index Name": "users_pkey", "Plan Rows": 1}
+                ]
+            }
+        }]);
+        let ret = explain::parse_pgsql(plan);
+        assert_eq!(ret.tables.len(), 2);
+        assert_eq!(ret.tables[0].index.as_deref(), Some("idx_user"));
+        assert_eq!(ret.rows_examined, 11.0);
+        assert!(!ret.full_scan);
+    }
+
+    #[test]
+    fn test_parse_sqlite() {
+        let ret = explain::parse_sqlite(vec![
+            (2, 0, "SCAN o".to_string()),
+            (
+                5,
+                0,
+                "SEARCH u USING INTEGER PRIMARY KEY (rowid=?)".to_string(),
+            ),
+            (9, 0, "SCAN TABLE t USING COVERING INDEX idx_t".to_string()),
+            (12, 0, "USE TEMP B-TREE FOR ORDER BY".to_string()),
+        ]);
+        assert_eq!(ret.tables.len(), 3);
+        assert_eq!(ret.tables[0].table, "o");
+        assert_eq!(ret.tables[1].index.as_deref(), Some("PRIMARY KEY"));
+        assert_eq!(ret.tables[2].table, "t");
+        assert_eq!(ret.tables[2].index.as_deref(), Some("idx_t"));
+        assert!(ret.full_scan);
+        assert_eq!(ret.plan.as_array().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,81 @@
+use sqlx::Executor;
+
+use crate::sql::{
+    seed::{self, Seed},
+    Dialect,
+};
+
+/// 写入单条数据（配合 `#[derive(Factory)]` 生成的 `XxxFactory::create`），返回写入行数
+///
+/// # Examples
+///
+/// ```
+/// UserFactory::new().name("kr").create(&pool).await?;
+/// // 等价于
+/// sql::factory::create(&pool, UserFactory::new().name("kr").build()).await?;
+/// ```
+pub async fn create<'e, E, T>(db: E, row: T) -> anyhow::Result<u64>
+where
+    E: Executor<'e> + Copy + 'e,
+    E::Database: Dialect,
+    T: Seed,
+{
+    seed::seed(db, vec![row]).await
+}
+
+/// 按构造函数批量写入 n 条数据，返回写入行数
+///
+/// # Examples
+///
+/// ```
+/// UserFactory::create_many(&pool, 10).await?;
+/// // 按序号覆盖字段
+/// sql::factory::create_many(&pool, 10, |i| UserFactory::new().name(format!("user{}", i)).build()).await?;
+/// ```
+pub async fn create_many<'e, E, T, F>(db: E, n: usize, f: F) -> anyhow::Result<u64>
+where
+    E: Executor<'e> + Copy + 'e,
+    E::Database: Dialect,
+    T: Seed,
+    F: FnMut(usize) -> T,
+{
+    seed::seed(db, (0..n).map(f).collect()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::{
+        self,
+        seed::tests::{Demo, SCHEMA},
+    };
+
+    #[tokio::test]
+    async fn test_create() {
+        let pool = sql::test::memory_pool(Some(SCHEMA)).await.unwrap();
+
+        let n = sql::factory::create(
+            &pool,
+            Demo {
+                name: "kr".into(),
+                level: 1,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(n, 1);
+
+        let n = sql::factory::create_many(&pool, 3, |i| Demo {
+            name: format!("demo{}", i),
+            level: i as i32,
+        })
+        .await
+        .unwrap();
+        assert_eq!(n, 3);
+
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM demo ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["kr", "demo0", "demo1", "demo2"]);
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::{MySqlTypeInfo, MySqlValueRef},
+    postgres::{PgTypeInfo, PgValueFormat, PgValueRef},
+    Decode, Encode, MySql, Postgres, Type, TypeInfo,
+};
+
+/// WGS84
+pub const SRID_WGS84: u32 = 4326;
+
+// WKB 类型：Point
+const WKB_POINT: u32 = 1;
+// EWKB 标志位：包含 SRID
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+// 地球平均半径（米）
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// 地理坐标点（经度、纬度），对应 MySQL `POINT` 与 PostGIS `geometry(Point)`
+///
+/// # Examples
+///
+/// ```
+/// #[derive(sqlx::FromRow, Model)]
+/// pub struct Store {
+///     pub id: i64,
+///     pub location: GeoPoint,
+/// }
+///
+/// let p = GeoPoint::new(121.4737, 31.2304);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    /// 经度
+    pub lng: f64,
+    /// 纬度
+    pub lat: f64,
+    /// 空间参考，默认：4326
+    #[serde(default = "default_srid")]
+    pub srid: u32,
+}
+
+fn default_srid() -> u32 {
+    SRID_WGS84
+}
+
+impl GeoPoint {
+    pub fn new(lng: f64, lat: f64) -> Self {
+        Self {
+            lng,
+            lat,
+            srid: SRID_WGS84,
+        }
+    }
+
+    pub fn with_srid(mut self, srid: u32) -> Self {
+        self.srid = srid;
+        self
+    }
+
+    /// 球面距离（米，Haversine）
+    pub fn distance(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lng = (other.lng - self.lng).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
+
+    /// 以当前点为中心、半径(米)的外接矩形：(西南角, 东北角)，用于索引预筛选
+    pub fn bbox(&self, meters: f64) -> (GeoPoint, GeoPoint) {
+        let meters_per_degree = EARTH_RADIUS.to_radians();
+        let d_lat = meters / meters_per_degree;
+        // 以离赤道最远的纬度计算经度跨度，保证矩形完整覆盖圆
+        let far_lat = (self.lat.abs() + d_lat).min(90.0);
+        let d_lng = meters / (meters_per_degree * far_lat.to_radians().cos().max(1e-6));
+        (
+            GeoPoint::new(self.lng - d_lng, (self.lat - d_lat).max(-90.0)).with_srid(self.srid),
+            GeoPoint::new(self.lng + d_lng, (self.lat + d_lat).min(90.0)).with_srid(self.srid),
+        )
+    }
+
+    /// WKB(小端)
+    fn to_wkb(self, buf: &mut Vec<u8>, srid: Option<u32>) {
+        buf.push(1);
+        match srid {
+            Some(v) => {
+                buf.extend_from_slice(&(WKB_POINT | EWKB_SRID_FLAG).to_le_bytes());
+                buf.extend_from_slice(&v.to_le_bytes());
+            }
+            None => buf.extend_from_slice(&WKB_POINT.to_le_bytes()),
+        }
+        buf.extend_from_slice(&self.lng.to_le_bytes());
+        buf.extend_from_slice(&self.lat.to_le_bytes());
+    }
+
+    /// 解析 WKB/EWKB（支持大小端）
+    fn from_wkb(buf: &[u8], default_srid: u32) -> anyhow::Result<Self> {
+        let le = match buf.first() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err(anyhow!("sql/geo: invalid wkb byte order")),
+        };
+        let u32_at = |i: usize| -> anyhow::Result<u32> {
+            let b: [u8; 4] = buf
+                .get(i..i + 4)
+                .ok_or_else(|| anyhow!("sql/geo: wkb too short"))?
+                .try_into()?;
+            Ok(if le {
+                u32::from_le_bytes(b)
+            } else {
+                u32::from_be_bytes(b)
+            })
+        };
+        let f64_at = |i: usize| -> anyhow::Result<f64> {
+            let b: [u8; 8] = buf
+                .get(i..i + 8)
+                .ok_or_else(|| anyhow!("sql/geo: wkb too short"))?
+                .try_into()?;
+            Ok(if le {
+                f64::from_le_bytes(b)
+            } else {
+                f64::from_be_bytes(b)
+            })
+        };
+
+        let ty = u32_at(1)?;
+        if ty & 0xff != WKB_POINT {
+            return Err(anyhow!("sql/geo: not a point (type={})", ty));
+        }
+        let (srid, offset) = match ty & EWKB_SRID_FLAG {
+            0 => (default_srid, 5),
+            _ => (u32_at(5)?, 9),
+        };
+        Ok(Self {
+            lng: f64_at(offset)?,
+            lat: f64_at(offset + 8)?,
+            srid,
+        })
+    }
+}
+
+// MySQL 内部格式：SRID(4字节小端) + WKB
+impl Type<MySql> for GeoPoint {
+    fn type_info() -> MySqlTypeInfo {
+        <[u8] as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        ty.name() == "GEOMETRY" || <[u8] as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, MySql> for GeoPoint {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        let mut v = Vec::with_capacity(25);
+        v.extend_from_slice(&self.srid.to_le_bytes());
+        self.to_wkb(&mut v, None);
+        <Vec<u8> as Encode<MySql>>::encode(v, buf)
+    }
+}
+
+impl<'r> Decode<'r, MySql> for GeoPoint {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let buf = <&[u8] as Decode<MySql>>::decode(value)?;
+        if buf.len() < 4 {
+            return Err("sql/geo: invalid mysql geometry".into());
+        }
+        let srid = u32::from_le_bytes(buf[..4].try_into()?);
+        Ok(GeoPoint::from_wkb(&buf[4..], srid)?)
+    }
+}
+
+// PostGIS：EWKB
+impl Type<Postgres> for GeoPoint {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("geometry")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        matches!(ty.name(), "geometry" | "geography")
+    }
+}
+
+impl Encode<'_, Postgres> for GeoPoint {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> Result<IsNull, BoxDynError> {
+        let mut v = Vec::with_capacity(25);
+        self.to_wkb(&mut v, Some(self.srid));
+        buf.extend_from_slice(&v);
+        Ok(IsNull::No)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for GeoPoint {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => Ok(GeoPoint::from_wkb(value.as_bytes()?, 0)?),
+            // 文本格式为十六进制 EWKB
+            PgValueFormat::Text => {
+                let hex = value.as_str()?;
+                let buf = (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or_default(), 16))
+                    .collect::<Result<Vec<u8>, _>>()?;
+                Ok(GeoPoint::from_wkb(&buf, 0)?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_query::{Alias, MysqlQueryBuilder, PostgresQueryBuilder, Query};
+
+    use crate::sql::{geo::GeoPoint, mysql, pgsql};
+
+    #[test]
+    fn test_wkb() {
+        let p = GeoPoint::new(121.4737, 31.2304);
+
+        let mut buf = Vec::new();
+        p.to_wkb(&mut buf, Some(p.srid));
+        assert_eq!(buf.len(), 25);
+        assert_eq!(GeoPoint::from_wkb(&buf, 0).unwrap(), p);
+
+        // PostGIS: SELECT ST_AsHexEWKB(ST_SetSRID(ST_MakePoint(1, 2), 4326))
+        let hex = "0101000020E6100000000000000000F03F0000000000000040";
+        let buf: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        assert_eq!(
+            GeoPoint::from_wkb(&buf, 0).unwrap(),
+            GeoPoint::new(1.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        // 上海人民广场 => 陆家嘴 约 3.5km
+        let a = GeoPoint::new(121.4737, 31.2304);
+        let b = GeoPoint::new(121.5055, 31.2453);
+        let d = a.distance(&b);
+        assert!((3000.0..5000.0).contains(&d), "{}", d);
+
+        let (sw, ne) = a.bbox(1000.0);
+        assert!(sw.lat < a.lat && sw.lng < a.lng);
+        assert!(ne.lat > a.lat && ne.lng > a.lng);
+        assert!((a.distance(&GeoPoint::new(a.lng, ne.lat)) - 1000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_query_expr() {
+        let p = GeoPoint::new(1.0, 2.0);
+
+        let (sql, _) = Query::select()
+            .from(Alias::new("store"))
+            .column(Alias::new("id"))
+            .and_where(mysql::geo_within(Alias::new("location"), p, 100.0))
+            .build(MysqlQueryBuilder);
+        assert_eq!(
+            sql,
+            "SELECT `id` FROM `store` WHERE MBRContains(ST_GeomFromText(?, ?, ?), `location`) AND ST_Distance_Sphere(`location`, ST_SRID(POINT(?, ?), ?)) <= ?"
+        );
+
+        let (sql, _) = Query::select()
+            .from(Alias::new("store"))
+            .column(Alias::new("id"))
+            .and_where(pgsql::geo_within(Alias::new("location"), p, 100.0))
+            .build(PostgresQueryBuilder);
+        assert_eq!(
+            sql,
+            r#"SELECT "id" FROM "store" WHERE ("location" && ST_MakeEnvelope($1, $2, $3, $4, $5)) AND ST_DistanceSphere("location", ST_SetSRID(ST_MakePoint($6, $7), $8)) <= $9"#
+        );
+    }
+}
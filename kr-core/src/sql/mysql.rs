@@ -1,12 +1,20 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use sea_query::{
-    DeleteStatement, Expr, InsertStatement, MysqlQueryBuilder, SelectStatement, UpdateStatement,
+    Alias, DeleteStatement, Expr, Func, InsertStatement, IntoColumnRef, MysqlQueryBuilder,
+    SelectStatement, SimpleExpr, UpdateStatement,
 };
 use sea_query_binder::SqlxBinder;
-use sqlx::{mysql::MySqlRow, Executor, FromRow, MySql};
+use sqlx::{mysql::MySqlRow, Acquire, Executor, FromRow, MySql};
 
-use crate::sql::trace_sql;
+use crate::sql::{
+    chunk_values, ensure_chunkable,
+    explain::{self, Explain},
+    geo::GeoPoint,
+    run_chunked,
+    shard::{self, ShardParams, ShardRouter},
+    tenant, trace_bound, trace_stmt, with_timeout, ChunkParams, Opts,
+};
 
 /// 插入记录
 ///
@@ -26,6 +34,7 @@ where
     E: Executor<'e, Database = MySql>,
 {
     let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -33,12 +42,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
             Ok(v.last_insert_id())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -62,6 +71,7 @@ where
     E: Executor<'e, Database = MySql>,
 {
     let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -69,12 +79,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
             Ok(v.rows_affected())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -97,6 +107,7 @@ where
     E: Executor<'e, Database = MySql>,
 {
     let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -104,12 +115,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
             Ok(v.rows_affected())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -137,6 +148,7 @@ where
     stmt.expr(Expr::cust("COUNT(*)"));
 
     let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret: Result<i64, sqlx::Error> = sqlx::query_scalar_with(&sql, values).fetch_one(db).await;
@@ -144,12 +156,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -175,6 +187,7 @@ where
 {
     stmt.limit(1);
     let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -184,12 +197,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -214,6 +227,7 @@ where
     T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
 {
     let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -223,12 +237,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -265,6 +279,7 @@ where
     count.expr(Expr::cust("COUNT(*)"));
 
     let (count_sql, count_values) = count.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&count_sql)?;
 
     let count_start = Instant::now();
     let ret: Result<i64, sqlx::Error> = sqlx::query_scalar_with(&count_sql, count_values)
@@ -274,12 +289,12 @@ where
 
     let total = match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), count_cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, count_cost, None);
             v
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), count_cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, count_cost, Some(&err));
             return Err(err);
         }
     };
@@ -297,6 +312,7 @@ where
     stmt.limit(size as u64).offset(((page - 1) * size) as u64);
 
     let (query_sql, query_values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&query_sql)?;
 
     let query_start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&query_sql, query_values)
@@ -306,13 +322,409 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(MysqlQueryBuilder), query_cost, None);
+            trace_stmt(&stmt, MysqlQueryBuilder, query_cost, None);
             Ok((v, total))
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(MysqlQueryBuilder), query_cost, Some(&err));
+            trace_stmt(&stmt, MysqlQueryBuilder, query_cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 统计记录数（支持超时等选项）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = mysql::count_opts(&pool, stmt, opts).await;
+/// ```
+pub async fn count_opts<'e, E>(db: E, mut stmt: SelectStatement, opts: Opts) -> anyhow::Result<i64>
+where
+    E: Executor<'e, Database = MySql>,
+{
+    stmt.clear_selects();
+    stmt.clear_order_by();
+    stmt.expr(Expr::cust("COUNT(*)"));
+
+    let (mut sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
+    if let Some(d) = opts.timeout {
+        sql = max_execution_time(&sql, d);
+    }
+
+    let start = Instant::now();
+    let ret = with_timeout(
+        opts.timeout,
+        sqlx::query_scalar_with::<_, i64, _>(&sql, values).fetch_one(db),
+    )
+    .await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
 }
+
+/// 查询单条记录（支持超时等选项）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = mysql::find_one_opts::<model::Demo>(&pool, stmt, opts).await;
+/// ```
+pub async fn find_one_opts<'e, E, T>(
+    db: E,
+    mut stmt: SelectStatement,
+    opts: Opts,
+) -> anyhow::Result<Option<T>>
+where
+    E: Executor<'e, Database = MySql>,
+    T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+{
+    stmt.limit(1);
+    let (mut sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
+    if let Some(d) = opts.timeout {
+        sql = max_execution_time(&sql, d);
+    }
+
+    let start = Instant::now();
+    let ret = with_timeout(
+        opts.timeout,
+        sqlx::query_as_with::<_, T, _>(&sql, values).fetch_optional(db),
+    )
+    .await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 查询多条记录（支持超时、慢查询执行计划等选项）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     explain_slow: Some(Duration::from_millis(200)),
+/// };
+/// let ret = mysql::find_all_opts::<_, model::Demo>(&pool, stmt, opts).await;
+/// ```
+pub async fn find_all_opts<'a, A, T>(
+    db: A,
+    stmt: SelectStatement,
+    opts: Opts,
+) -> anyhow::Result<Vec<T>>
+where
+    A: Acquire<'a, Database = MySql>,
+    T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+{
+    let (mut sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    tenant::guard(&sql)?;
+    if let Some(d) = opts.timeout {
+        sql = max_execution_time(&sql, d);
+    }
+
+    let mut conn = db.acquire().await?;
+    let start = Instant::now();
+    let ret = with_timeout(
+        opts.timeout,
+        sqlx::query_as_with::<_, T, _>(&sql, values).fetch_all(&mut *conn),
+    )
+    .await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, None);
+            if explain::should_explain(&opts, cost) {
+                let plan = explain(&mut *conn, stmt.clone()).await;
+                explain::log_slow(stmt.to_string(MysqlQueryBuilder), cost, plan);
+            }
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, MysqlQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 分页查询（支持超时等选项，超时作用于每条语句）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = mysql::paginate_opts::<_, model::Demo>(&pool, stmt, 1, 10, opts).await;
+/// ```
+pub async fn paginate_opts<'e, E, T>(
+    db: E,
+    mut stmt: SelectStatement,
+    mut page: i32,
+    mut size: i32,
+    opts: Opts,
+) -> anyhow::Result<(Vec<T>, i64)>
+where
+    E: Executor<'e, Database = MySql> + Acquire<'e, Database = MySql> + Copy,
+    T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+{
+    let total = count_opts(db, stmt.clone(), opts.clone()).await?;
+    if total == 0 {
+        return Ok((Vec::new(), total));
+    }
+
+    if page <= 0 {
+        page = 1
+    }
+    if size <= 0 {
+        size = 20
+    }
+    stmt.limit(size as u64).offset(((page - 1) * size) as u64);
+
+    let list = find_all_opts(db, stmt, opts).await?;
+    Ok((list, total))
+}
+
+/// 查询执行计划（EXPLAIN FORMAT=JSON），生产环境（`KR_PROFILE=prod`）禁用
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(Expr::col(table::Demo::Name).like("%demo%"))
+///     .to_owned();
+///
+/// let ret = mysql::explain(&pool, stmt).await?;
+/// if ret.full_scan {
+///     tracing::warn!(plan = ?ret.tables, "full table scan");
+/// }
+/// ```
+pub async fn explain<'e, E>(db: E, stmt: SelectStatement) -> anyhow::Result<Explain>
+where
+    E: Executor<'e, Database = MySql>,
+{
+    explain::ensure_enabled()?;
+
+    let (sql, values) = stmt.build_sqlx(MysqlQueryBuilder);
+    let sql = format!("EXPLAIN FORMAT=JSON {}", sql);
+
+    let start = Instant::now();
+    let ret = sqlx::query_scalar_with::<_, String, _>(&sql, values)
+        .fetch_one(db)
+        .await;
+    let cost = start.elapsed();
+
+    let raw = || format!("EXPLAIN FORMAT=JSON {}", stmt.to_string(MysqlQueryBuilder));
+    match ret {
+        Ok(v) => {
+            trace_bound(&sql, raw, cost, None);
+            Ok(explain::parse_mysql(serde_json::from_str(&v)?))
+        }
+        Err(e) => {
+            let err = anyhow::Error::from(e);
+            trace_bound(&sql, raw, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// IN 查询分片：按绑定参数上限将 `col IN (...)` 拆分为多次查询，有界并发执行，结果按分片顺序合并
+///
+/// 注意：语句不能包含 ORDER BY、LIMIT、OFFSET（无法跨分片生效，返回错误），需要时对结果自行排序
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .to_owned();
+///
+/// let ret = mysql::find_all_chunked::<_, model::Demo, _, _>(&pool, stmt, table::Demo::Id, ids, None).await;
+/// ```
+pub async fn find_all_chunked<'e, E, T, C, V>(
+    db: E,
+    stmt: SelectStatement,
+    col: C,
+    values: impl IntoIterator<Item = V>,
+    opt: Option<ChunkParams>,
+) -> anyhow::Result<Vec<T>>
+where
+    E: Executor<'e, Database = MySql> + Copy,
+    T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+    C: IntoColumnRef + Clone,
+    V: Into<sea_query::Value>,
+{
+    let values: Vec<sea_query::Value> = values.into_iter().map(Into::into).collect();
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let params = opt.unwrap_or_default();
+    ensure_chunkable(&stmt, &MysqlQueryBuilder)?;
+    let (_, binds) = stmt.build(MysqlQueryBuilder);
+    let chunks = chunk_values(values, 65535, binds.0.len(), &params)?;
+
+    run_chunked(chunks, params.concurrency, |v| {
+        let mut stmt = stmt.clone();
+        stmt.and_where(Expr::col(col.clone()).is_in(v));
+        find_all(db, stmt)
+    })
+    .await
+}
+
+/// 跨分片查询：将 FROM 依次替换为各物理表，有界并发执行，合并结果后排序、截取
+///
+/// 注意：语句中的 ORDER BY、LIMIT 在各分片内生效，LIMIT 需 >= offset + limit
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Order::Table)
+///     .expr(Expr::cust("*"))
+///     .order_by(table::Order::Id, Order::Desc)
+///     .limit(20)
+///     .to_owned();
+///
+/// let ret = mysql::find_all_sharded::<_, model::Order>(
+///     &pool,
+///     &router,
+///     router.tables()?,
+///     stmt,
+///     Some(ShardParams {
+///         sort_by: Some(|a, b| b.id.cmp(&a.id)),
+///         limit: Some(20),
+///         ..Default::default()
+///     }),
+/// )
+/// .await;
+/// ```
+pub async fn find_all_sharded<'e, E, T>(
+    db: E,
+    router: &ShardRouter,
+    tables: Vec<String>,
+    stmt: SelectStatement,
+    opt: Option<ShardParams<T>>,
+) -> anyhow::Result<Vec<T>>
+where
+    E: Executor<'e, Database = MySql> + Copy,
+    T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin,
+{
+    let params = opt.unwrap_or_default();
+    let list = run_chunked(tables, params.concurrency, |table| {
+        let mut stmt = stmt.clone();
+        router.route_select(&mut stmt, &table);
+        find_all(db, stmt)
+    })
+    .await?;
+    Ok(shard::merge(list, &params))
+}
+
+/// 坐标点表达式：`ST_SRID(POINT(lng, lat), srid)`，用于写入 POINT 列
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::insert()
+///     .into_table(table::Store::Table)
+///     .columns([table::Store::Name, table::Store::Location])
+///     .values_panic(["demo".into(), mysql::geo_point(GeoPoint::new(121.4737, 31.2304))])
+///     .to_owned();
+/// ```
+pub fn geo_point(p: GeoPoint) -> SimpleExpr {
+    Func::cust(Alias::new("ST_SRID"))
+        .args([
+            Func::cust(Alias::new("POINT"))
+                .args([Expr::val(p.lng).into(), Expr::val(p.lat).into()])
+                .into(),
+            Expr::val(p.srid).into(),
+        ])
+        .into()
+}
+
+/// 球面距离（米）：`ST_Distance_Sphere(col, point)`
+///
+/// # Examples
+///
+/// ```
+/// let center = GeoPoint::new(121.4737, 31.2304);
+/// let stmt = Query::select()
+///     .from(table::Store::Table)
+///     .expr(Expr::cust("*"))
+///     .expr_as(mysql::geo_distance(table::Store::Location, center), Alias::new("distance"))
+///     .and_where(mysql::geo_within(table::Store::Location, center, 3000.0))
+///     .order_by(Alias::new("distance"), Order::Asc)
+///     .to_owned();
+/// ```
+pub fn geo_distance<C: IntoColumnRef>(col: C, p: GeoPoint) -> SimpleExpr {
+    Func::cust(Alias::new("ST_Distance_Sphere"))
+        .args([Expr::col(col).into(), geo_point(p)])
+        .into()
+}
+
+/// 矩形范围：`MBRContains(envelope, col)`，可使用空间索引
+pub fn geo_bbox<C: IntoColumnRef>(col: C, sw: GeoPoint, ne: GeoPoint) -> SimpleExpr {
+    let wkt = format!(
+        "POLYGON(({w} {s}, {e} {s}, {e} {n}, {w} {n}, {w} {s}))",
+        w = sw.lng,
+        s = sw.lat,
+        e = ne.lng,
+        n = ne.lat
+    );
+    let envelope = Func::cust(Alias::new("ST_GeomFromText")).args([
+        Expr::val(wkt).into(),
+        Expr::val(sw.srid).into(),
+        Expr::val("axis-order=long-lat").into(),
+    ]);
+    Func::cust(Alias::new("MBRContains"))
+        .args([envelope.into(), Expr::col(col).into()])
+        .into()
+}
+
+/// 半径范围（米）：外接矩形预筛选（走空间索引）+ 球面距离精确过滤
+pub fn geo_within<C: IntoColumnRef + Clone>(col: C, p: GeoPoint, meters: f64) -> SimpleExpr {
+    let (sw, ne) = p.bbox(meters);
+    geo_bbox(col.clone(), sw, ne).and(Expr::expr(geo_distance(col, p)).lte(meters))
+}
+
+// SELECT /*+ MAX_EXECUTION_TIME(ms) */ ...
+fn max_execution_time(sql: &str, d: Duration) -> String {
+    sql.replacen(
+        "SELECT",
+        &format!("SELECT /*+ MAX_EXECUTION_TIME({}) */", d.as_millis().max(1)),
+        1,
+    )
+}
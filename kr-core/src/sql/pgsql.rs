@@ -1,12 +1,28 @@
-use std::time::Instant;
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
 
+use futures::{Stream, StreamExt};
 use sea_query::{
-    DeleteStatement, Expr, InsertStatement, PostgresQueryBuilder, SelectStatement, UpdateStatement,
+    extension::postgres::PgBinOper, Alias, BinOper, DeleteStatement, Expr, Func, InsertStatement,
+    IntoColumnRef, PostgresQueryBuilder, SelectStatement, SimpleExpr, UpdateStatement,
 };
 use sea_query_binder::SqlxBinder;
-use sqlx::{postgres::PgRow, Executor, FromRow, Postgres};
+use serde::Serialize;
+use sqlx::{
+    postgres::{PgListener, PgNotification, PgRow},
+    Acquire, Executor, FromRow, Pool, Postgres, Transaction,
+};
 
-use crate::sql::trace_sql;
+use crate::sql::{
+    chunk_values, ensure_chunkable,
+    explain::{self, Explain},
+    geo::GeoPoint,
+    run_chunked,
+    shard::{self, ShardParams, ShardRouter},
+    tenant, trace_bound, trace_sql, trace_stmt, with_timeout, ChunkParams, Opts,
+};
 
 /// 插入记录
 ///
@@ -27,6 +43,7 @@ where
     T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
 {
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -36,12 +53,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -67,6 +84,7 @@ where
     T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
 {
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -76,12 +94,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -105,6 +123,7 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -112,12 +131,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v.rows_affected())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -140,6 +159,7 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -147,12 +167,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v.rows_affected())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -180,6 +200,7 @@ where
     stmt.expr(Expr::cust("COUNT(*)"));
 
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret: Result<i64, sqlx::Error> = sqlx::query_scalar_with(&sql, values).fetch_one(db).await;
@@ -187,12 +208,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -218,6 +239,7 @@ where
 {
     stmt.limit(1);
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -227,12 +249,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -257,6 +279,7 @@ where
     T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
 {
     let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -266,12 +289,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -308,6 +331,7 @@ where
     count.expr(Expr::cust("COUNT(*)"));
 
     let (count_sql, count_values) = count.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&count_sql)?;
 
     let count_start = Instant::now();
     let ret: Result<i64, sqlx::Error> = sqlx::query_scalar_with(&count_sql, count_values)
@@ -317,12 +341,12 @@ where
 
     let total = match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), count_cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, count_cost, None);
             v
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), count_cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, count_cost, Some(&err));
             return Err(err);
         }
     };
@@ -340,6 +364,7 @@ where
     stmt.limit(size as u64).offset(((page - 1) * size) as u64);
 
     let (query_sql, query_values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&query_sql)?;
 
     let query_start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&query_sql, query_values)
@@ -349,13 +374,1004 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(PostgresQueryBuilder), query_cost, None);
+            trace_stmt(&stmt, PostgresQueryBuilder, query_cost, None);
             Ok((v, total))
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(PostgresQueryBuilder), query_cost, Some(&err));
+            trace_stmt(&stmt, PostgresQueryBuilder, query_cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 发送通知（NOTIFY）
+///
+/// # Examples
+///
+/// ```
+/// let ret = pgsql::notify(&pool, "cache_invalidate", "user:1").await;
+/// ```
+pub async fn notify<'e, E>(
+    db: E,
+    channel: impl AsRef<str>,
+    payload: impl AsRef<str>,
+) -> anyhow::Result<()>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let channel = channel.as_ref();
+    let payload = payload.as_ref();
+
+    let sql = "SELECT pg_notify($1, $2)";
+
+    let start = Instant::now();
+    let ret = sqlx::query(sql)
+        .bind(channel)
+        .bind(payload)
+        .execute(db)
+        .await;
+    let cost = start.elapsed();
+
+    let raw = || {
+        format!(
+            "SELECT pg_notify('{}', '{}')",
+            channel.replace('\'', "''"),
+            payload.replace('\'', "''")
+        )
+    };
+    match ret {
+        Ok(_) => {
+            trace_bound(sql, raw, cost, None);
+            Ok(())
+        }
+        Err(e) => {
+            let err = anyhow::Error::from(e);
+            trace_bound(sql, raw, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 监听通知（LISTEN）
+///
+/// 使用独立连接监听指定频道，连接断开后自动重连（指数退避，最长30s），
+/// 收到的通知交由 `handler` 处理，`handler` 返回的错误仅记录日志；
+/// 连接池关闭后返回。
+///
+/// # Examples
+///
+/// ```
+/// tokio::spawn(async move {
+///     let ret = pgsql::listen(&pool, &["cache_invalidate"], |n| async move {
+///         println!("channel: {}, payload: {}", n.channel(), n.payload());
+///         Ok(())
+///     })
+///     .await;
+/// });
+/// ```
+pub async fn listen<F, Fut>(
+    pool: &Pool<Postgres>,
+    channels: &[&str],
+    handler: F,
+) -> anyhow::Result<()>
+where
+    F: Fn(PgNotification) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut backoff = Duration::from_millis(100);
+    let max_backoff = Duration::from_secs(30);
+
+    loop {
+        let mut listener = match PgListener::connect_with(pool).await {
+            Ok(v) => v,
+            Err(sqlx::Error::PoolClosed) => return Ok(()),
+            Err(e) => {
+                tracing::error!(err = ?e, channels = ?channels, "[pgsql::listen] connect failed");
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(max_backoff);
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen_all(channels.iter().copied()).await {
+            tracing::error!(err = ?e, channels = ?channels, "[pgsql::listen] listen failed");
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(max_backoff);
+            continue;
+        }
+        backoff = Duration::from_millis(100);
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(n)) => {
+                    backoff = Duration::from_millis(100);
+                    if let Err(e) = handler(n).await {
+                        tracing::error!(err = ?e, "[pgsql::listen] handle notification failed");
+                    }
+                }
+                // 连接断开，下次 try_recv 时自动重连并重新 LISTEN（退避后重连，避免服务不可用时空转）
+                Ok(None) => {
+                    tracing::warn!(channels = ?channels, backoff_ms = backoff.as_millis(), "[pgsql::listen] connection lost, reconnecting");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+                Err(sqlx::Error::PoolClosed) => return Ok(()),
+                Err(e) => {
+                    tracing::error!(err = ?e, channels = ?channels, "[pgsql::listen] recv failed");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// 统计记录数（支持超时等选项）
+///
+/// 设置超时后在事务中执行 `SET LOCAL statement_timeout`，提交前还原（`db` 为事务时不影响后续语句）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = pgsql::count_opts(&pool, stmt, opts).await;
+/// ```
+pub async fn count_opts<'a, A>(db: A, mut stmt: SelectStatement, opts: Opts) -> anyhow::Result<i64>
+where
+    A: Acquire<'a, Database = Postgres>,
+{
+    stmt.clear_selects();
+    stmt.clear_order_by();
+    stmt.expr(Expr::cust("COUNT(*)"));
+
+    let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
+
+    let start = Instant::now();
+    let ret = match opts.timeout {
+        Some(d) => {
+            let mut tx = db.begin().await?;
+            let prev = set_statement_timeout(&mut tx, d).await?;
+            let v = with_timeout(
+                Some(d),
+                sqlx::query_scalar_with::<_, i64, _>(&sql, values).fetch_one(&mut *tx),
+            )
+            .await;
+            finish_statement_timeout(tx, prev, v).await
+        }
+        None => {
+            let mut conn = db.acquire().await?;
+            with_timeout(
+                None,
+                sqlx::query_scalar_with::<_, i64, _>(&sql, values).fetch_one(&mut *conn),
+            )
+            .await
+        }
+    };
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 查询单条记录（支持超时等选项）
+///
+/// 设置超时后在事务中执行 `SET LOCAL statement_timeout`，提交前还原（`db` 为事务时不影响后续语句）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = pgsql::find_one_opts(&pool, stmt, opts).await;
+/// ```
+pub async fn find_one_opts<'a, A, T>(
+    db: A,
+    mut stmt: SelectStatement,
+    opts: Opts,
+) -> anyhow::Result<Option<T>>
+where
+    A: Acquire<'a, Database = Postgres>,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    stmt.limit(1);
+    let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
+
+    let start = Instant::now();
+    let ret = match opts.timeout {
+        Some(d) => {
+            let mut tx = db.begin().await?;
+            let prev = set_statement_timeout(&mut tx, d).await?;
+            let v = with_timeout(
+                Some(d),
+                sqlx::query_as_with::<_, T, _>(&sql, values).fetch_optional(&mut *tx),
+            )
+            .await;
+            finish_statement_timeout(tx, prev, v).await
+        }
+        None => {
+            let mut conn = db.acquire().await?;
+            with_timeout(
+                None,
+                sqlx::query_as_with::<_, T, _>(&sql, values).fetch_optional(&mut *conn),
+            )
+            .await
+        }
+    };
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 查询多条记录（支持超时、慢查询执行计划等选项）
+///
+/// 设置超时后在事务中执行 `SET LOCAL statement_timeout`，提交前还原（`db` 为事务时不影响后续语句）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     explain_slow: Some(Duration::from_millis(200)),
+/// };
+/// let ret = pgsql::find_all_opts(&pool, stmt, opts).await;
+/// ```
+pub async fn find_all_opts<'a, A, T>(
+    db: A,
+    stmt: SelectStatement,
+    opts: Opts,
+) -> anyhow::Result<Vec<T>>
+where
+    A: Acquire<'a, Database = Postgres>,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    tenant::guard(&sql)?;
+
+    let start = Instant::now();
+    let (ret, cost) = match opts.timeout {
+        Some(d) => {
+            let mut tx = db.begin().await?;
+            let prev = set_statement_timeout(&mut tx, d).await?;
+            let v = with_timeout(
+                Some(d),
+                sqlx::query_as_with::<_, T, _>(&sql, values).fetch_all(&mut *tx),
+            )
+            .await;
+            let cost = start.elapsed();
+            if v.is_ok() && explain::should_explain(&opts, cost) {
+                let plan = explain(&mut *tx, stmt.clone()).await;
+                explain::log_slow(stmt.to_string(PostgresQueryBuilder), cost, plan);
+            }
+            (finish_statement_timeout(tx, prev, v).await, cost)
+        }
+        None => {
+            let mut conn = db.acquire().await?;
+            let v = with_timeout(
+                None,
+                sqlx::query_as_with::<_, T, _>(&sql, values).fetch_all(&mut *conn),
+            )
+            .await;
+            let cost = start.elapsed();
+            if v.is_ok() && explain::should_explain(&opts, cost) {
+                let plan = explain(&mut *conn, stmt.clone()).await;
+                explain::log_slow(stmt.to_string(PostgresQueryBuilder), cost, plan);
+            }
+            (v, cost)
+        }
+    };
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, PostgresQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 分页查询（支持超时等选项，超时作用于每条语句）
+///
+/// 设置超时后 count 与分页查询分别在各自事务中执行 `SET LOCAL statement_timeout`
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = pgsql::paginate_opts::<model::Demo>(&pool, stmt, 1, 10, opts).await;
+/// ```
+pub async fn paginate_opts<'a, A, T>(
+    db: A,
+    mut stmt: SelectStatement,
+    mut page: i32,
+    mut size: i32,
+    opts: Opts,
+) -> anyhow::Result<(Vec<T>, i64)>
+where
+    A: Acquire<'a, Database = Postgres> + Copy,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    let total = count_opts(db, stmt.clone(), opts.clone()).await?;
+    if total == 0 {
+        return Ok((Vec::new(), total));
+    }
+
+    if page <= 0 {
+        page = 1
+    }
+    if size <= 0 {
+        size = 20
+    }
+    stmt.limit(size as u64).offset(((page - 1) * size) as u64);
+
+    let list = find_all_opts(db, stmt, opts).await?;
+    Ok((list, total))
+}
+
+// 设置事务内的 statement_timeout，返回原值；
+// `db` 本身为事务时 begin 得到的是 savepoint，SET LOCAL 在 RELEASE 后仍作用于外层事务，需在提交前还原
+async fn set_statement_timeout(
+    tx: &mut Transaction<'_, Postgres>,
+    d: Duration,
+) -> anyhow::Result<String> {
+    let prev: String = sqlx::query_scalar("SELECT current_setting('statement_timeout')")
+        .fetch_one(&mut **tx)
+        .await?;
+    let sql = format!("SET LOCAL statement_timeout = {}", d.as_millis().max(1));
+    sqlx::query(&sql).execute(&mut **tx).await?;
+    Ok(prev)
+}
+
+// 成功时还原 statement_timeout 并提交，失败时回滚（回滚会撤销 SET LOCAL）
+async fn finish_statement_timeout<T>(
+    mut tx: Transaction<'_, Postgres>,
+    prev: String,
+    v: anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let v = v?;
+    sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+        .bind(prev)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(v)
+}
+
+#[derive(Default, Debug)]
+pub struct CopyParams {
+    /// 每批发送的行数（同时也是进度回调的粒度），默认: 5000
+    pub batch_size: Option<usize>,
+    /// 进度回调，参数为已发送的行数
+    pub progress: Option<fn(u64)>,
+    /// 冲突列：指定后先 COPY 至临时表，再 upsert 至目标表
+    pub upsert_keys: Option<Vec<String>>,
+}
+
+/// 通过 COPY 协议批量导入（CSV 格式，`None` 表示 NULL）
+///
+/// 整个导入在同一事务中完成，返回写入（或 upsert）的行数；
+/// 表名、列名自动加引号（区分大小写），每行的值个数须与 `columns` 一致，否则在发送前返回错误
+///
+/// # Examples
+///
+/// ```
+/// let rows = futures::stream::iter(vec![
+///     vec![Some("1".to_string()), Some("foo".to_string())],
+///     vec![Some("2".to_string()), None],
+/// ]);
+///
+/// // 直接导入
+/// let ret = pgsql::copy_in(&pool, "demo", &["id", "name"], rows, None).await;
+///
+/// // 临时表 + upsert
+/// let ret = pgsql::copy_in(
+///     &pool,
+///     "demo",
+///     &["id", "name"],
+///     rows,
+///     Some(pgsql::CopyParams {
+///         upsert_keys: Some(vec!["id".to_string()]),
+///         progress: Some(|n| println!("copied {} rows", n)),
+///         ..Default::default()
+///     }),
+/// )
+/// .await;
+/// ```
+pub async fn copy_in<S>(
+    pool: &Pool<Postgres>,
+    table: &str,
+    columns: &[&str],
+    rows: S,
+    opt: Option<CopyParams>,
+) -> anyhow::Result<u64>
+where
+    S: Stream<Item = Vec<Option<String>>>,
+{
+    let params = opt.unwrap_or_default();
+    let batch_size = params.batch_size.unwrap_or(5000).max(1);
+    let cols = columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let table_ident = quote_table(table);
+
+    let mut tx = pool.begin().await?;
+
+    // upsert 模式先导入临时表
+    let target = match &params.upsert_keys {
+        Some(_) => {
+            let tmp = quote_ident(&format!("_kr_copy_{}", table.replace('.', "_")));
+            let sql = format!(
+                "CREATE TEMP TABLE {} (LIKE {} INCLUDING DEFAULTS) ON COMMIT DROP",
+                tmp, table_ident
+            );
+            exec_raw(&mut *tx, sql).await?;
+            tmp
+        }
+        None => table_ident.clone(),
+    };
+
+    let copy_sql = format!("COPY {} ({}) FROM STDIN WITH (FORMAT csv)", target, cols);
+
+    let start = Instant::now();
+    let ret: anyhow::Result<u64> = async {
+        let mut copy = tx.copy_in_raw(&copy_sql).await?;
+        let mut sent = 0u64;
+        let mut buf = Vec::new();
+
+        let mut chunks = std::pin::pin!(rows.chunks(batch_size));
+        while let Some(chunk) = chunks.next().await {
+            buf.clear();
+            if let Err(e) = write_csv_rows(&mut buf, &chunk, columns.len()) {
+                copy.abort(e.to_string()).await?;
+                return Err(e);
+            }
+            copy.send(buf.as_slice()).await?;
+
+            sent += chunk.len() as u64;
+            if let Some(f) = params.progress {
+                f(sent)
+            }
+        }
+
+        Ok(copy.finish().await?)
+    }
+    .await;
+    let cost = start.elapsed();
+
+    let copied = match ret {
+        Ok(v) => {
+            trace_sql(copy_sql, cost, None);
+            v
+        }
+        Err(err) => {
+            trace_sql(copy_sql, cost, Some(&err));
+            return Err(err);
+        }
+    };
+
+    let affected = match &params.upsert_keys {
+        Some(keys) => {
+            let updates: Vec<String> = columns
+                .iter()
+                .filter(|c| !keys.iter().any(|k| k == *c))
+                .map(|c| format!("{0} = EXCLUDED.{0}", quote_ident(c)))
+                .collect();
+            let action = if updates.is_empty() {
+                "DO NOTHING".to_string()
+            } else {
+                format!("DO UPDATE SET {}", updates.join(", "))
+            };
+            let sql = format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {} ON CONFLICT ({}) {}",
+                table_ident,
+                cols,
+                cols,
+                target,
+                keys.iter()
+                    .map(|k| quote_ident(k))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                action
+            );
+            exec_raw(&mut *tx, sql).await?
+        }
+        None => copied,
+    };
+
+    tx.commit().await?;
+
+    Ok(affected)
+}
+
+/// 查询执行计划（EXPLAIN (FORMAT JSON)），生产环境（`KR_PROFILE=prod`）禁用
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(Expr::col(table::Demo::Name).like("%demo%"))
+///     .to_owned();
+///
+/// let ret = pgsql::explain(&pool, stmt).await?;
+/// if ret.full_scan {
+///     tracing::warn!(plan = ?ret.tables, "full table scan");
+/// }
+/// ```
+pub async fn explain<'e, E>(db: E, stmt: SelectStatement) -> anyhow::Result<Explain>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    explain::ensure_enabled()?;
+
+    let (sql, values) = stmt.build_sqlx(PostgresQueryBuilder);
+    let sql = format!("EXPLAIN (FORMAT JSON) {}", sql);
+
+    let start = Instant::now();
+    let ret = sqlx::query_scalar_with::<_, serde_json::Value, _>(&sql, values)
+        .fetch_one(db)
+        .await;
+    let cost = start.elapsed();
+
+    let raw = || {
+        format!(
+            "EXPLAIN (FORMAT JSON) {}",
+            stmt.to_string(PostgresQueryBuilder)
+        )
+    };
+    match ret {
+        Ok(v) => {
+            trace_bound(&sql, raw, cost, None);
+            Ok(explain::parse_pgsql(v))
+        }
+        Err(e) => {
+            let err = anyhow::Error::from(e);
+            trace_bound(&sql, raw, cost, Some(&err));
             Err(err)
         }
     }
 }
+
+async fn exec_raw<'e, E>(db: E, sql: String) -> anyhow::Result<u64>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let start = Instant::now();
+    let ret = sqlx::query(&sql).execute(db).await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_sql(sql, cost, None);
+            Ok(v.rows_affected())
+        }
+        Err(e) => {
+            let err = anyhow::Error::from(e);
+            trace_sql(sql, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+// 标识符加双引号，内部的 `"` 转义为 `""`
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+// 表名按 `.` 拆分后分别加引号（支持 `schema.table`）
+fn quote_table(name: &str) -> String {
+    name.split('.')
+        .map(quote_ident)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+// 写入一批 CSV 行，列数与 columns 不一致时返回错误（在发送之前，避免 COPY 数据流错位）
+fn write_csv_rows(
+    buf: &mut Vec<u8>,
+    rows: &[Vec<Option<String>>],
+    width: usize,
+) -> anyhow::Result<()> {
+    for row in rows {
+        if row.len() != width {
+            return Err(anyhow::anyhow!(
+                "pgsql::copy_in: row has {} values, expected {} columns",
+                row.len(),
+                width
+            ));
+        }
+        write_csv_row(buf, row);
+    }
+    Ok(())
+}
+
+fn write_csv_row(buf: &mut Vec<u8>, row: &[Option<String>]) {
+    for (i, v) in row.iter().enumerate() {
+        if i > 0 {
+            buf.push(b',');
+        }
+        // NULL 为空（不加引号），其余值均加引号以区分空字符串
+        if let Some(s) = v {
+            buf.push(b'"');
+            buf.extend_from_slice(s.replace('"', "\"\"").as_bytes());
+            buf.push(b'"');
+        }
+    }
+    buf.push(b'\n');
+}
+
+/// IN 查询分片：按绑定参数上限将 `col IN (...)` 拆分为多次查询，有界并发执行，结果按分片顺序合并
+///
+/// 注意：语句不能包含 ORDER BY、LIMIT、OFFSET（无法跨分片生效，返回错误），需要时对结果自行排序
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .to_owned();
+///
+/// let ret = pgsql::find_all_chunked::<_, model::Demo, _, _>(&pool, stmt, table::Demo::Id, ids, None).await;
+/// ```
+pub async fn find_all_chunked<'e, E, T, C, V>(
+    db: E,
+    stmt: SelectStatement,
+    col: C,
+    values: impl IntoIterator<Item = V>,
+    opt: Option<ChunkParams>,
+) -> anyhow::Result<Vec<T>>
+where
+    E: Executor<'e, Database = Postgres> + Copy,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    C: IntoColumnRef + Clone,
+    V: Into<sea_query::Value>,
+{
+    let values: Vec<sea_query::Value> = values.into_iter().map(Into::into).collect();
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let params = opt.unwrap_or_default();
+    ensure_chunkable(&stmt, &PostgresQueryBuilder)?;
+    let (_, binds) = stmt.build(PostgresQueryBuilder);
+    let chunks = chunk_values(values, 65535, binds.0.len(), &params)?;
+
+    run_chunked(chunks, params.concurrency, |v| {
+        let mut stmt = stmt.clone();
+        stmt.and_where(Expr::col(col.clone()).is_in(v));
+        find_all(db, stmt)
+    })
+    .await
+}
+
+/// 跨分片查询：将 FROM 依次替换为各物理表，有界并发执行，合并结果后排序、截取
+///
+/// 注意：语句中的 ORDER BY、LIMIT 在各分片内生效，LIMIT 需 >= offset + limit
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Order::Table)
+///     .expr(Expr::cust("*"))
+///     .order_by(table::Order::Id, Order::Desc)
+///     .limit(20)
+///     .to_owned();
+///
+/// let ret = pgsql::find_all_sharded::<_, model::Order>(
+///     &pool,
+///     &router,
+///     router.tables()?,
+///     stmt,
+///     Some(ShardParams {
+///         sort_by: Some(|a, b| b.id.cmp(&a.id)),
+///         limit: Some(20),
+///         ..Default::default()
+///     }),
+/// )
+/// .await;
+/// ```
+pub async fn find_all_sharded<'e, E, T>(
+    db: E,
+    router: &ShardRouter,
+    tables: Vec<String>,
+    stmt: SelectStatement,
+    opt: Option<ShardParams<T>>,
+) -> anyhow::Result<Vec<T>>
+where
+    E: Executor<'e, Database = Postgres> + Copy,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    let params = opt.unwrap_or_default();
+    let list = run_chunked(tables, params.concurrency, |table| {
+        let mut stmt = stmt.clone();
+        router.route_select(&mut stmt, &table);
+        find_all(db, stmt)
+    })
+    .await?;
+    Ok(shard::merge(list, &params))
+}
+
+/// 坐标点表达式：`ST_SetSRID(ST_MakePoint(lng, lat), srid)`，用于写入 geometry 列
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::insert()
+///     .into_table(table::Store::Table)
+///     .columns([table::Store::Name, table::Store::Location])
+///     .values_panic(["demo".into(), pgsql::geo_point(GeoPoint::new(121.4737, 31.2304))])
+///     .to_owned();
+/// ```
+pub fn geo_point(p: GeoPoint) -> SimpleExpr {
+    Func::cust(Alias::new("ST_SetSRID"))
+        .args([
+            Func::cust(Alias::new("ST_MakePoint"))
+                .args([Expr::val(p.lng).into(), Expr::val(p.lat).into()])
+                .into(),
+            Expr::val(p.srid as i32).into(),
+        ])
+        .into()
+}
+
+/// 球面距离（米）：`ST_DistanceSphere(col, point)`
+///
+/// # Examples
+///
+/// ```
+/// let center = GeoPoint::new(121.4737, 31.2304);
+/// let stmt = Query::select()
+///     .from(table::Store::Table)
+///     .expr(Expr::cust("*"))
+///     .expr_as(pgsql::geo_distance(table::Store::Location, center), Alias::new("distance"))
+///     .and_where(pgsql::geo_within(table::Store::Location, center, 3000.0))
+///     .order_by(Alias::new("distance"), Order::Asc)
+///     .to_owned();
+/// ```
+pub fn geo_distance<C: IntoColumnRef>(col: C, p: GeoPoint) -> SimpleExpr {
+    Func::cust(Alias::new("ST_DistanceSphere"))
+        .args([Expr::col(col).into(), geo_point(p)])
+        .into()
+}
+
+/// 矩形范围：`col && ST_MakeEnvelope(...)`，可使用 GiST 索引
+pub fn geo_bbox<C: IntoColumnRef>(col: C, sw: GeoPoint, ne: GeoPoint) -> SimpleExpr {
+    let envelope = Func::cust(Alias::new("ST_MakeEnvelope")).args([
+        Expr::val(sw.lng).into(),
+        Expr::val(sw.lat).into(),
+        Expr::val(ne.lng).into(),
+        Expr::val(ne.lat).into(),
+        Expr::val(sw.srid as i32).into(),
+    ]);
+    Expr::col(col).binary(BinOper::Custom("&&"), envelope)
+}
+
+/// 半径范围（米）：外接矩形预筛选（走空间索引）+ 球面距离精确过滤
+pub fn geo_within<C: IntoColumnRef + Clone>(col: C, p: GeoPoint, meters: f64) -> SimpleExpr {
+    let (sw, ne) = p.bbox(meters);
+    geo_bbox(col.clone(), sw, ne).and(Expr::expr(geo_distance(col, p)).lte(meters))
+}
+
+/// JSONB 包含查询：`col @> $1::jsonb`
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(pgsql::where_jsonb_contains(table::Demo::Extra, json!({"tags": ["vip"]}))?)
+///     .to_owned();
+/// ```
+pub fn where_jsonb_contains<C, V>(col: C, value: V) -> anyhow::Result<SimpleExpr>
+where
+    C: IntoColumnRef,
+    V: Serialize,
+{
+    Ok(Expr::col(col).binary(PgBinOper::Contains, jsonb_value(value)?))
+}
+
+/// JSONB 路径等值查询：`col #> '{a,b}' = $1::jsonb`
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(pgsql::where_jsonb_path_eq(table::Demo::Extra, &["profile", "age"], 18)?)
+///     .to_owned();
+/// ```
+pub fn where_jsonb_path_eq<C, V>(col: C, path: &[&str], value: V) -> anyhow::Result<SimpleExpr>
+where
+    C: IntoColumnRef,
+    V: Serialize,
+{
+    Ok(Expr::col(col)
+        .binary(BinOper::Custom("#>"), jsonb_path(path))
+        .eq(jsonb_value(value)?))
+}
+
+/// JSONB 路径取文本：`col #>> '{a,b}'`，可用于比较、排序或 select
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(pgsql::jsonb_path_text(table::Demo::Extra, &["profile", "city"]).eq("shanghai"))
+///     .to_owned();
+/// ```
+pub fn jsonb_path_text<C>(col: C, path: &[&str]) -> SimpleExpr
+where
+    C: IntoColumnRef,
+{
+    Expr::col(col).binary(BinOper::Custom("#>>"), jsonb_path(path))
+}
+
+/// JSONB 局部更新：`jsonb_set(col, '{a,b}', $1::jsonb, true)`（路径不存在时创建）
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::update()
+///     .table(table::Demo::Table)
+///     .value(
+///         table::Demo::Extra,
+///         pgsql::jsonb_set_update(table::Demo::Extra, &["profile", "age"], 20)?,
+///     )
+///     .and_where(Expr::col(table::Demo::Id).eq(1))
+///     .to_owned();
+/// ```
+pub fn jsonb_set_update<C, V>(col: C, path: &[&str], value: V) -> anyhow::Result<SimpleExpr>
+where
+    C: IntoColumnRef,
+    V: Serialize,
+{
+    Ok(Func::cust(Alias::new("jsonb_set"))
+        .args([
+            // 列为 NULL 时从空对象开始
+            Func::coalesce([Expr::col(col).into(), Expr::cust("'{}'::jsonb")]).into(),
+            jsonb_path(path),
+            jsonb_value(value)?,
+            Expr::val(true).into(),
+        ])
+        .into())
+}
+
+fn jsonb_value<V: Serialize>(value: V) -> anyhow::Result<SimpleExpr> {
+    let v = serde_json::to_value(value)?;
+    Ok(Expr::val(v).cast_as(Alias::new("jsonb")))
+}
+
+// 路径以 text[] 字面量绑定：{"a","b"}
+fn jsonb_path(path: &[&str]) -> SimpleExpr {
+    let elems: Vec<String> = path
+        .iter()
+        .map(|v| format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    Expr::val(format!("{{{}}}", elems.join(","))).cast_as(Alias::new("text[]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_query::{Alias, Expr, PostgresQueryBuilder, Query, Value};
+    use serde_json::json;
+
+    use super::{quote_table, write_csv_row, write_csv_rows};
+    use crate::sql::pgsql;
+
+    #[test]
+    fn csv_row() {
+        let mut buf = Vec::new();
+        write_csv_row(
+            &mut buf,
+            &[
+                Some("1".to_string()),
+                None,
+                Some(String::new()),
+                Some("a\"b,c\nd".to_string()),
+            ],
+        );
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "\"1\",,\"\",\"a\"\"b,c\nd\"\n"
+        );
+    }
+
+    #[test]
+    fn csv_rows_width() {
+        let mut buf = Vec::new();
+        let rows = vec![
+            vec![Some("1".to_string()), Some("a".to_string())],
+            vec![Some("2".to_string())],
+        ];
+        let err = write_csv_rows(&mut buf, &rows, 2).unwrap_err();
+        assert!(err.to_string().contains("expected 2 columns"), "{}", err);
+
+        buf.clear();
+        write_csv_rows(&mut buf, &rows[..1], 2).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "\"1\",\"a\"\n");
+    }
+
+    #[test]
+    fn quote_identifiers() {
+        assert_eq!(quote_table("demo"), r#""demo""#);
+        assert_eq!(quote_table("public.demo"), r#""public"."demo""#);
+        assert_eq!(quote_table(r#"a"b"#), r#""a""b""#);
+    }
+
+    #[test]
+    fn jsonb_helpers() {
+        let (sql, values) = Query::select()
+            .from(Alias::new("demo"))
+            .column(Alias::new("id"))
+            .and_where(
+                pgsql::where_jsonb_contains(Alias::new("extra"), json!({"vip": true})).unwrap(),
+            )
+            .and_where(pgsql::where_jsonb_path_eq(Alias::new("extra"), &["a", "b\"c"], 18).unwrap())
+            .and_where(pgsql::jsonb_path_text(Alias::new("extra"), &["city"]).eq("sh"))
+            .build(PostgresQueryBuilder);
+        assert_eq!(
+            sql,
+            r#"SELECT "id" FROM "demo" WHERE "extra" @> CAST($1 AS jsonb) AND ("extra" #> CAST($2 AS text[])) = CAST($3 AS jsonb) AND ("extra" #>> CAST($4 AS text[])) = $5"#
+        );
+        assert_eq!(
+            values.0[0],
+            Value::Json(Some(Box::new(json!({"vip": true}))))
+        );
+        assert_eq!(
+            values.0[1],
+            Value::String(Some(Box::new(r#"{"a","b\"c"}"#.to_string())))
+        );
+        assert_eq!(values.0[2], Value::Json(Some(Box::new(json!(18)))));
+
+        let (sql, _) = Query::update()
+            .table(Alias::new("demo"))
+            .value(
+                Alias::new("extra"),
+                pgsql::jsonb_set_update(Alias::new("extra"), &["age"], 20).unwrap(),
+            )
+            .and_where(Expr::col(Alias::new("id")).eq(1))
+            .build(PostgresQueryBuilder);
+        assert_eq!(
+            sql,
+            r#"UPDATE "demo" SET "extra" = jsonb_set(COALESCE("extra", '{}'::jsonb), CAST($1 AS text[]), CAST($2 AS jsonb), $3) WHERE "id" = $4"#
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use serde::Serialize;
+
+/// 默认最多保留的记录数
+const DEFAULT_MAX_RECORDS: usize = 1000;
+
+tokio::task_local! {
+    static RECORDER: SqlRecorder;
+}
+
+/// 单条 SQL 执行记录
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub sql: String,
+    pub cost_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub err: Option<String>,
+}
+
+/// 请求级 SQL 收集器（收集作用域内经由 sql 辅助方法执行的所有语句及耗时，用于排查 N+1 等问题）
+///
+/// - 语句以占位符形式记录（不含绑定参数），同一语句不同参数可被 `repeated` 聚合
+/// - 最多保留 `max_records` 条（默认 1000），超出部分只计数，见 `dropped`
+///
+/// # Examples
+///
+/// ```
+/// // 中间件：带调试头时开启
+/// if req.headers().contains_key("x-debug-sql") {
+///     let recorder = SqlRecorder::new().max_records(500);
+///     let resp = recorder.scope(next.run(req)).await;
+///     for (sql, n) in recorder.repeated(3) {
+///         tracing::warn!(sql = sql, count = n, "possible N+1");
+///     }
+///     tracing::info!(sqls = ?recorder.records(), dropped = recorder.dropped(), "sql recorder");
+///     return resp;
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SqlRecorder {
+    records: Arc<Mutex<Vec<Record>>>,
+    dropped: Arc<AtomicUsize>,
+    max: usize,
+}
+
+impl Default for SqlRecorder {
+    fn default() -> Self {
+        Self {
+            records: Arc::new(Mutex::new(Vec::new())),
+            dropped: Arc::new(AtomicUsize::new(0)),
+            max: DEFAULT_MAX_RECORDS,
+        }
+    }
+}
+
+impl SqlRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置最多保留的记录数
+    pub fn max_records(mut self, n: usize) -> Self {
+        self.max = n;
+        self
+    }
+
+    /// 在收集作用域内执行
+    pub async fn scope<F: Future>(&self, f: F) -> F::Output {
+        RECORDER.scope(self.clone(), f).await
+    }
+
+    /// 已收集的记录（按执行顺序）
+    pub fn records(&self) -> Vec<Record> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// 超出上限未保留的记录数
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 总耗时（仅统计已保留的记录）
+    pub fn total(&self) -> Duration {
+        let ms: f64 = self.records.lock().unwrap().iter().map(|v| v.cost_ms).sum();
+        Duration::from_secs_f64(ms / 1000.0)
+    }
+
+    /// 执行次数 >= min 的语句（按次数倒序）
+    pub fn repeated(&self, min: usize) -> Vec<(String, usize)> {
+        let mut counter: HashMap<String, usize> = HashMap::new();
+        for v in self.records.lock().unwrap().iter() {
+            *counter.entry(v.sql.clone()).or_default() += 1;
+        }
+        let mut ret: Vec<(String, usize)> =
+            counter.into_iter().filter(|(_, n)| *n >= min).collect();
+        ret.sort_by_key(|v| std::cmp::Reverse(v.1));
+        ret
+    }
+}
+
+/// 当前是否处于收集作用域
+pub fn is_recording() -> bool {
+    RECORDER.try_with(|_| ()).is_ok()
+}
+
+pub(crate) fn record(sql: &str, cost: Duration, err: Option<&anyhow::Error>) {
+    let _ = RECORDER.try_with(|r| {
+        let mut records = r.records.lock().unwrap();
+        if records.len() >= r.max {
+            r.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        records.push(Record {
+            sql: sql.to_string(),
+            cost_ms: cost.as_secs_f64() * 1000.0,
+            err: err.map(|e| e.to_string()),
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::sql::recorder::{self, SqlRecorder};
+
+    #[tokio::test]
+    async fn test_recorder() {
+        let r = SqlRecorder::new();
+        r.scope(async {
+            assert!(recorder::is_recording());
+            recorder::record("SELECT 1", Duration::from_millis(2), None);
+            recorder::record("SELECT 1", Duration::from_millis(3), None);
+            recorder::record("SELECT 2", Duration::from_millis(5), None);
+        })
+        .await;
+        assert!(!recorder::is_recording());
+        recorder::record("SELECT 3", Duration::from_millis(1), None);
+
+        assert_eq!(r.records().len(), 3);
+        assert_eq!(r.total(), Duration::from_millis(10));
+        assert_eq!(r.repeated(2), vec![("SELECT 1".to_string(), 2)]);
+        assert_eq!(r.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_recorder_max_records() {
+        let r = SqlRecorder::new().max_records(2);
+        r.scope(async {
+            for _ in 0..5 {
+                recorder::record("SELECT 1", Duration::from_millis(1), None);
+            }
+        })
+        .await;
+
+        assert_eq!(r.records().len(), 2);
+        assert_eq!(r.dropped(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_recorder_unbound_sql() {
+        use sea_query::{Expr, Query, SqliteQueryBuilder};
+
+        let r = SqlRecorder::new();
+        r.scope(async {
+            for id in 1..=2 {
+                let stmt = Query::select()
+                    .column("id")
+                    .from("users")
+                    .and_where(Expr::col("id").eq(id))
+                    .to_owned();
+                crate::sql::trace_stmt(&stmt, SqliteQueryBuilder, Duration::ZERO, None);
+            }
+        })
+        .await;
+
+        // 参数不写入记录，不同参数的同一语句可被聚合
+        assert_eq!(
+            r.repeated(2),
+            vec![(r#"SELECT "id" FROM "users" WHERE "id" = ?"#.to_string(), 2)]
+        );
+    }
+}
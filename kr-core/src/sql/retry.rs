@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use rand::Rng;
+use sqlx::{mysql::MySqlDatabaseError, Database, Pool, Transaction};
+
+#[derive(Default, Debug)]
+pub struct RetryParams {
+    /// 最大尝试次数，默认：3
+    pub attempts: Option<u32>,
+    /// 首次重试间隔（指数退避 + 随机抖动），默认：50ms
+    pub backoff: Option<Duration>,
+}
+
+/// 在事务中执行，遇到死锁(MySQL 1213)或序列化冲突(PgSQL 40001/40P01)时自动重试整个事务
+///
+/// # Examples
+///
+/// ```
+/// let ret = sql::with_retry_tx(&pool, None, |tx| {
+///     Box::pin(async move {
+///         let stmt = Query::update()
+///             .table(table::Stock::Table)
+///             .value(table::Stock::Num, Expr::col(table::Stock::Num).sub(1))
+///             .and_where(Expr::col(table::Stock::Id).eq(1))
+///             .to_owned();
+///         mysql::update(&mut **tx, stmt).await?;
+///
+///         let stmt = Query::insert()
+///             .into_table(table::Order::Table)
+///             .columns([table::Order::StockId])
+///             .values_panic([1.into()])
+///             .to_owned();
+///         mysql::create(&mut **tx, stmt).await
+///     })
+/// })
+/// .await;
+/// ```
+pub async fn with_retry_tx<DB, T, F>(
+    pool: &Pool<DB>,
+    opt: Option<RetryParams>,
+    mut f: F,
+) -> anyhow::Result<T>
+where
+    DB: Database,
+    F: for<'c> FnMut(&'c mut Transaction<'static, DB>) -> BoxFuture<'c, anyhow::Result<T>>,
+{
+    let params = opt.unwrap_or_default();
+    let attempts = params.attempts.unwrap_or(3).max(1);
+    let mut backoff = params.backoff.unwrap_or(Duration::from_millis(50));
+
+    let mut attempt = 1;
+    loop {
+        let ret = async {
+            let mut tx = pool.begin().await?;
+            let v = f(&mut tx).await?;
+            tx.commit().await?;
+            Ok::<T, anyhow::Error>(v)
+        }
+        .await;
+
+        match ret {
+            Err(e) if attempt < attempts && is_retryable(&e) => {
+                tracing::warn!(attempt = attempt, err = ?e, "[sql::with_retry_tx] transient conflict, retrying");
+                let jitter = rand::thread_rng().gen_range(0..=backoff.as_millis() as u64);
+                tokio::time::sleep(backoff + Duration::from_millis(jitter)).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            _ => return ret,
+        }
+    }
+}
+
+/// 是否为可重试的事务冲突（死锁、序列化失败）
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    let Some(sqlx::Error::Database(db_err)) = err.downcast_ref::<sqlx::Error>() else {
+        return false;
+    };
+    if let Some(e) = db_err.try_downcast_ref::<MySqlDatabaseError>() {
+        // ER_LOCK_DEADLOCK
+        return e.number() == 1213;
+    }
+    // serialization_failure, deadlock_detected
+    matches!(db_err.code().as_deref(), Some("40001") | Some("40P01"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    use crate::sql::{self, RetryParams};
+
+    // 模拟 PgSQL 序列化冲突
+    #[derive(Debug)]
+    struct Conflict;
+
+    impl std::fmt::Display for Conflict {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("could not serialize access")
+        }
+    }
+
+    impl std::error::Error for Conflict {}
+
+    impl DatabaseError for Conflict {
+        fn message(&self) -> &str {
+            "could not serialize access"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed("40001"))
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn conflict() -> anyhow::Error {
+        sqlx::Error::Database(Box::new(Conflict)).into()
+    }
+
+    fn params() -> Option<RetryParams> {
+        Some(RetryParams {
+            attempts: Some(3),
+            backoff: Some(Duration::from_millis(1)),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_tx() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        assert!(sql::is_retryable(&conflict()));
+
+        // 首次冲突，第二次成功
+        let n = AtomicU32::new(0);
+        let ret: i64 = sql::with_retry_tx(&pool, params(), |tx| {
+            let attempt = n.fetch_add(1, Ordering::SeqCst) + 1;
+            Box::pin(async move {
+                let v = sqlx::query_scalar("SELECT 1").fetch_one(&mut **tx).await?;
+                if attempt == 1 {
+                    return Err(conflict());
+                }
+                Ok(v)
+            })
+        })
+        .await
+        .unwrap();
+        assert_eq!(ret, 1);
+        assert_eq!(n.load(Ordering::SeqCst), 2);
+
+        // 持续冲突：达到最大尝试次数后返回错误
+        let n = AtomicU32::new(0);
+        let ret = sql::with_retry_tx::<_, i64, _>(&pool, params(), |_| {
+            n.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Err(conflict()) })
+        })
+        .await;
+        assert!(sql::is_retryable(&ret.unwrap_err()));
+        assert_eq!(n.load(Ordering::SeqCst), 3);
+
+        // 非冲突错误不重试
+        let n = AtomicU32::new(0);
+        let ret = sql::with_retry_tx(&pool, params(), |tx| {
+            n.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                Ok(sqlx::query_scalar::<_, i64>("SELECT x FROM missing")
+                    .fetch_one(&mut **tx)
+                    .await?)
+            })
+        })
+        .await;
+        assert!(!sql::is_retryable(&ret.unwrap_err()));
+        assert_eq!(n.load(Ordering::SeqCst), 1);
+    }
+}
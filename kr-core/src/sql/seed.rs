@@ -0,0 +1,199 @@
+use rand::Rng;
+use sea_query::{Alias, Query, SimpleExpr};
+use sqlx::Executor;
+
+use crate::{helper, idgen, sql::Dialect};
+
+/// 可批量写入的数据（由 `#[derive(Factory)]` 生成）
+pub trait Seed {
+    /// 表名
+    fn table() -> &'static str;
+
+    /// 写入的列
+    fn columns() -> Vec<&'static str>;
+
+    /// 写入的值（与 columns 一一对应，加密字段为密文）
+    fn values(self) -> anyhow::Result<Vec<SimpleExpr>>;
+}
+
+/// 随机测试数据
+pub trait Fake {
+    fn fake() -> Self;
+}
+
+macro_rules! fake_int {
+    ($($t:ty => $max:expr),*) => {
+        $(
+            impl Fake for $t {
+                fn fake() -> Self {
+                    rand::thread_rng().gen_range(1..=$max)
+                }
+            }
+        )*
+    };
+}
+
+fake_int!(i8 => 100, i16 => 10_000, i32 => 1_000_000, i64 => 1_000_000_000);
+fake_int!(u8 => 100, u16 => 10_000, u32 => 1_000_000, u64 => 1_000_000_000);
+
+impl Fake for f32 {
+    fn fake() -> Self {
+        rand::thread_rng().gen_range(0.0..1000.0)
+    }
+}
+
+impl Fake for f64 {
+    fn fake() -> Self {
+        rand::thread_rng().gen_range(0.0..1000.0)
+    }
+}
+
+impl Fake for bool {
+    fn fake() -> Self {
+        rand::thread_rng().gen()
+    }
+}
+
+impl Fake for String {
+    fn fake() -> Self {
+        helper::nonce(12)
+    }
+}
+
+impl<T: Fake> Fake for Option<T> {
+    fn fake() -> Self {
+        Some(T::fake())
+    }
+}
+
+impl Fake for uuid::Uuid {
+    fn fake() -> Self {
+        idgen::uuid_v7()
+    }
+}
+
+impl Fake for idgen::ShortId {
+    fn fake() -> Self {
+        idgen::ShortId(i64::fake())
+    }
+}
+
+impl Fake for time::OffsetDateTime {
+    fn fake() -> Self {
+        time::OffsetDateTime::now_utc()
+    }
+}
+
+impl Fake for time::PrimitiveDateTime {
+    fn fake() -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        time::PrimitiveDateTime::new(now.date(), now.time())
+    }
+}
+
+impl Fake for time::Date {
+    fn fake() -> Self {
+        time::OffsetDateTime::now_utc().date()
+    }
+}
+
+impl Fake for serde_json::Value {
+    fn fake() -> Self {
+        serde_json::Value::Object(Default::default())
+    }
+}
+
+/// 批量写入（按绑定参数上限自动分批），返回写入行数
+///
+/// # Examples
+///
+/// ```
+/// #[derive(sqlx::FromRow, Model, Factory)]
+/// #[factory(table = "demo")]
+/// pub struct Demo {
+///     #[factory(skip)]
+///     pub id: i64,
+///     pub name: String,
+/// }
+///
+/// let rows = DemoFactory::batch(100);
+/// let n = sql::seed(&pool, rows).await?;
+/// ```
+pub async fn seed<'e, E, T>(db: E, rows: Vec<T>) -> anyhow::Result<u64>
+where
+    E: Executor<'e> + Copy + 'e,
+    E::Database: Dialect,
+    T: Seed,
+{
+    let columns = T::columns();
+    if rows.is_empty() || columns.is_empty() {
+        return Ok(0);
+    }
+
+    let size = (<E::Database as Dialect>::MAX_BINDS / columns.len()).max(1);
+    let mut rows = rows.into_iter().peekable();
+    let mut total = 0;
+    while rows.peek().is_some() {
+        let mut stmt = Query::insert();
+        stmt.into_table(Alias::new(T::table()))
+            .columns(columns.iter().map(|v| Alias::new(*v)));
+        for row in rows.by_ref().take(size) {
+            stmt.values(row.values()?)?;
+        }
+        total += <E::Database as Dialect>::insert(db, stmt).await?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use sea_query::SimpleExpr;
+
+    use crate::sql::{
+        self,
+        seed::{Fake, Seed},
+    };
+
+    // seed、factory 测试共用
+    pub(crate) const SCHEMA: &str =
+        "CREATE TABLE demo (id INTEGER PRIMARY KEY, name TEXT NOT NULL, level INT NOT NULL)";
+
+    pub(crate) struct Demo {
+        pub name: String,
+        pub level: i32,
+    }
+
+    impl Seed for Demo {
+        fn table() -> &'static str {
+            "demo"
+        }
+
+        fn columns() -> Vec<&'static str> {
+            vec!["name", "level"]
+        }
+
+        fn values(self) -> anyhow::Result<Vec<SimpleExpr>> {
+            Ok(vec![self.name.into(), self.level.into()])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed() {
+        let pool = sql::test::memory_pool(Some(SCHEMA)).await.unwrap();
+
+        // 超过单条语句参数上限时自动分批
+        let rows: Vec<Demo> = (0..20000)
+            .map(|_| Demo {
+                name: String::fake(),
+                level: i32::fake(),
+            })
+            .collect();
+        assert_eq!(sql::seed(&pool, rows).await.unwrap(), 20000);
+
+        let n: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM demo")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(n, 20000);
+    }
+}
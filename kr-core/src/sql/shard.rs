@@ -0,0 +1,332 @@
+use std::cmp::Ordering;
+
+use anyhow::anyhow;
+use jiff::{civil, Zoned};
+use sea_query::{Alias, DeleteStatement, Iden, InsertStatement, SelectStatement, UpdateStatement};
+
+use crate::shard::{self, ShardKey};
+
+/// 分表策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// 按分片键取模，参数为分表数：`order_00` ~ `order_15`
+    Hash(u32),
+    /// 按月分表：`log_202601`
+    Monthly,
+}
+
+/// 分片键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardValue {
+    Hash(u64),
+    Date(civil::Date),
+}
+
+impl<K: ShardKey> From<K> for ShardValue {
+    fn from(v: K) -> Self {
+        ShardValue::Hash(v.shard_hash())
+    }
+}
+
+impl From<civil::Date> for ShardValue {
+    fn from(v: civil::Date) -> Self {
+        ShardValue::Date(v)
+    }
+}
+
+impl From<civil::DateTime> for ShardValue {
+    fn from(v: civil::DateTime) -> Self {
+        ShardValue::Date(v.date())
+    }
+}
+
+impl From<&Zoned> for ShardValue {
+    fn from(v: &Zoned) -> Self {
+        ShardValue::Date(v.date())
+    }
+}
+
+/// 分表路由：按分片键改写语句中的表名
+///
+/// # Examples
+///
+/// ```
+/// let orders = ShardRouter::hash(table::Order::Table, 16);
+///
+/// // SELECT * FROM `order_06` AS `order` WHERE ...
+/// let mut stmt = Query::select()
+///     .from(table::Order::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(Expr::col(table::Order::UserId).eq(user_id))
+///     .to_owned();
+/// orders.apply_select(&mut stmt, user_id)?;
+///
+/// // 按月分表
+/// let logs = ShardRouter::monthly(table::Log::Table);
+/// let mut stmt = Query::insert().into_table(table::Log::Table).to_owned();
+/// logs.apply_insert(&mut stmt, &Zoned::now())?;
+///
+/// // 跨分片查询：各分片并发查询后合并、排序
+/// let tables = logs.tables_between(date(2026, 1, 1), date(2026, 3, 31))?;
+/// let list = mysql::find_all_sharded::<_, model::Log>(
+///     &pool,
+///     &logs,
+///     tables,
+///     stmt,
+///     Some(ShardParams {
+///         sort_by: Some(|a, b| b.created_at.cmp(&a.created_at)),
+///         limit: Some(20),
+///         ..Default::default()
+///     }),
+/// )
+/// .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShardRouter {
+    base: String,
+    strategy: Strategy,
+}
+
+impl ShardRouter {
+    pub fn new(base: impl Iden, strategy: Strategy) -> Self {
+        Self {
+            base: base.to_string(),
+            strategy,
+        }
+    }
+
+    /// 按分片键取模分为 n 张表
+    pub fn hash(base: impl Iden, n: u32) -> Self {
+        Self::new(base, Strategy::Hash(n.max(1)))
+    }
+
+    /// 按月分表
+    pub fn monthly(base: impl Iden) -> Self {
+        Self::new(base, Strategy::Monthly)
+    }
+
+    /// 逻辑表名
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// 分片键对应的物理表名
+    pub fn table(&self, key: impl Into<ShardValue>) -> anyhow::Result<String> {
+        match (self.strategy, key.into()) {
+            (Strategy::Hash(n), ShardValue::Hash(v)) => Ok(shard::table_name(&self.base, &v, n)),
+            (Strategy::Monthly, ShardValue::Date(d)) => Ok(month_table(&self.base, d)),
+            (strategy, key) => Err(anyhow!(
+                "sql/shard: table({}) key {:?} mismatch strategy {:?}",
+                self.base,
+                key,
+                strategy
+            )),
+        }
+    }
+
+    /// 所有物理表（仅 Hash 策略）
+    pub fn tables(&self) -> anyhow::Result<Vec<String>> {
+        match self.strategy {
+            Strategy::Hash(n) => Ok(shard::table_names(&self.base, n)),
+            Strategy::Monthly => Err(anyhow!(
+                "sql/shard: table({}) is monthly sharded, use tables_between",
+                self.base
+            )),
+        }
+    }
+
+    /// 时间范围内的物理表（仅 Monthly 策略，包含首尾所在月）
+    pub fn tables_between(
+        &self,
+        start: civil::Date,
+        end: civil::Date,
+    ) -> anyhow::Result<Vec<String>> {
+        if self.strategy != Strategy::Monthly {
+            return Err(anyhow!(
+                "sql/shard: table({}) is not monthly sharded",
+                self.base
+            ));
+        }
+        let mut list = Vec::new();
+        let mut cur = start.first_of_month();
+        while cur <= end {
+            list.push(month_table(&self.base, cur));
+            cur = cur.checked_add(jiff::Span::new().months(1))?;
+        }
+        Ok(list)
+    }
+
+    /// 将查询的 FROM 替换为物理表，并以逻辑表名作为别名（带表名的字段无需改写）
+    pub fn apply_select(
+        &self,
+        stmt: &mut SelectStatement,
+        key: impl Into<ShardValue>,
+    ) -> anyhow::Result<()> {
+        let table = self.table(key)?;
+        self.route_select(stmt, &table);
+        Ok(())
+    }
+
+    pub fn apply_update(
+        &self,
+        stmt: &mut UpdateStatement,
+        key: impl Into<ShardValue>,
+    ) -> anyhow::Result<()> {
+        stmt.table(Alias::new(self.table(key)?));
+        Ok(())
+    }
+
+    pub fn apply_delete(
+        &self,
+        stmt: &mut DeleteStatement,
+        key: impl Into<ShardValue>,
+    ) -> anyhow::Result<()> {
+        stmt.from_table(Alias::new(self.table(key)?));
+        Ok(())
+    }
+
+    pub fn apply_insert(
+        &self,
+        stmt: &mut InsertStatement,
+        key: impl Into<ShardValue>,
+    ) -> anyhow::Result<()> {
+        stmt.into_table(Alias::new(self.table(key)?));
+        Ok(())
+    }
+
+    pub(crate) fn route_select(&self, stmt: &mut SelectStatement, table: &str) {
+        stmt.from_clear()
+            .from_as(Alias::new(table), Alias::new(&self.base));
+    }
+}
+
+fn month_table(base: &str, d: civil::Date) -> String {
+    format!("{}_{:04}{:02}", base, d.year(), d.month())
+}
+
+/// 跨分片查询选项
+pub struct ShardParams<T> {
+    /// 最大并发数，默认：4
+    pub concurrency: Option<usize>,
+    /// 合并后排序（未设置时按分片顺序合并）
+    pub sort_by: Option<fn(&T, &T) -> Ordering>,
+    /// 合并排序后跳过的条数（各分片语句的 LIMIT 需 >= offset + limit）
+    pub offset: Option<usize>,
+    /// 合并排序后截取的条数
+    pub limit: Option<usize>,
+}
+
+impl<T> Default for ShardParams<T> {
+    fn default() -> Self {
+        Self {
+            concurrency: None,
+            sort_by: None,
+            offset: None,
+            limit: None,
+        }
+    }
+}
+
+// 合并各分片结果：排序 + 截取
+pub(crate) fn merge<T>(mut list: Vec<T>, params: &ShardParams<T>) -> Vec<T> {
+    if let Some(f) = params.sort_by {
+        list.sort_by(f);
+    }
+    let offset = params.offset.unwrap_or(0);
+    match params.limit {
+        Some(n) => list.into_iter().skip(offset).take(n).collect(),
+        None => list.into_iter().skip(offset).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::date;
+    use sea_query::{Alias, Expr, MysqlQueryBuilder, Order, Query};
+
+    use crate::sql::{
+        self,
+        shard::{ShardParams, ShardRouter},
+        sqlite,
+    };
+
+    #[test]
+    fn test_router() {
+        let orders = ShardRouter::hash(Alias::new("orders"), 4);
+        assert_eq!(orders.table(10086u64).unwrap(), "orders_2");
+        assert!(orders.table(date(2026, 1, 1)).is_err());
+        assert_eq!(orders.tables().unwrap().len(), 4);
+
+        let mut stmt = Query::select()
+            .from(Alias::new("orders"))
+            .column((Alias::new("orders"), Alias::new("id")))
+            .and_where(Expr::col(Alias::new("user_id")).eq(10086))
+            .to_owned();
+        orders.apply_select(&mut stmt, 10086).unwrap();
+        assert_eq!(
+            stmt.to_string(MysqlQueryBuilder),
+            "SELECT `orders`.`id` FROM `orders_2` AS `orders` WHERE `user_id` = 10086"
+        );
+
+        let mut stmt = Query::delete().from_table(Alias::new("orders")).to_owned();
+        orders.apply_delete(&mut stmt, 3).unwrap();
+        assert_eq!(stmt.to_string(MysqlQueryBuilder), "DELETE FROM `orders_3`");
+
+        let logs = ShardRouter::monthly(Alias::new("log"));
+        assert_eq!(logs.table(date(2026, 3, 31)).unwrap(), "log_202603");
+        assert!(logs.tables().is_err());
+        assert_eq!(
+            logs.tables_between(date(2025, 11, 15), date(2026, 2, 1))
+                .unwrap(),
+            vec!["log_202511", "log_202512", "log_202601", "log_202602"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_all_sharded() {
+        let pool = sql::test::memory_pool(Some(
+            "CREATE TABLE orders_0 (id INTEGER PRIMARY KEY, user_id INTEGER);
+             CREATE TABLE orders_1 (id INTEGER PRIMARY KEY, user_id INTEGER);",
+        ))
+        .await
+        .unwrap();
+
+        let orders = ShardRouter::hash(Alias::new("orders"), 2);
+        for (id, user_id) in [(1, 10), (2, 11), (3, 12), (4, 13), (5, 11)] {
+            let mut stmt = Query::insert()
+                .into_table(Alias::new("orders"))
+                .columns([Alias::new("id"), Alias::new("user_id")])
+                .values_panic([id.into(), user_id.into()])
+                .to_owned();
+            orders.apply_insert(&mut stmt, user_id).unwrap();
+            sqlite::create(&pool, stmt).await.unwrap();
+        }
+
+        let stmt = Query::select()
+            .from(Alias::new("orders"))
+            .column((Alias::new("orders"), Alias::new("id")))
+            .order_by(Alias::new("id"), Order::Desc)
+            .limit(3)
+            .to_owned();
+        let ret = sqlite::find_all_sharded::<_, (i64,)>(
+            &pool,
+            &orders,
+            orders.tables().unwrap(),
+            stmt,
+            Some(ShardParams {
+                sort_by: Some(|a, b| b.0.cmp(&a.0)),
+                offset: Some(1),
+                limit: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        let ids: Vec<i64> = ret.into_iter().map(|v| v.0).collect();
+        assert_eq!(ids, vec![4, 3]);
+    }
+}
@@ -1,12 +1,25 @@
-use std::time::Instant;
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use sea_query::{
-    DeleteStatement, Expr, InsertStatement, SelectStatement, SqliteQueryBuilder, UpdateStatement,
+    DeleteStatement, Expr, InsertStatement, IntoColumnRef, SelectStatement, SqliteQueryBuilder,
+    UpdateStatement,
 };
 use sea_query_binder::SqlxBinder;
-use sqlx::{sqlite::SqliteRow, Executor, FromRow, Sqlite};
+use sqlx::{
+    sqlite::{SqliteJournalMode, SqliteRow},
+    Acquire, Executor, FromRow, Pool, Sqlite,
+};
 
-use crate::sql::trace_sql;
+use crate::sql::{
+    chunk_values, ensure_chunkable,
+    explain::{self, Explain},
+    run_chunked,
+    shard::{self, ShardParams, ShardRouter},
+    tenant, trace_bound, trace_sql, trace_stmt, with_timeout, ChunkParams, Opts,
+};
 
 /// 插入记录
 ///
@@ -26,6 +39,7 @@ where
     E: Executor<'e, Database = Sqlite>,
 {
     let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -33,12 +47,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
             Ok(v.last_insert_rowid())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -62,6 +76,7 @@ where
     E: Executor<'e, Database = Sqlite>,
 {
     let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -69,12 +84,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
             Ok(v.rows_affected())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -97,6 +112,7 @@ where
     E: Executor<'e, Database = Sqlite>,
 {
     let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_with(&sql, values).execute(db).await;
@@ -104,12 +120,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
             Ok(v.rows_affected())
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -136,6 +152,7 @@ where
     stmt.expr(Expr::cust("COUNT(*)"));
 
     let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret: Result<i64, sqlx::Error> = sqlx::query_scalar_with(&sql, values).fetch_one(db).await;
@@ -143,12 +160,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -174,6 +191,7 @@ where
 {
     stmt.limit(1);
     let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -183,12 +201,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -213,6 +231,7 @@ where
     T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
 {
     let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
 
     let start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&sql, values)
@@ -222,12 +241,12 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
             Ok(v)
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
             Err(err)
         }
     }
@@ -265,6 +284,7 @@ where
     count.expr(Expr::cust("COUNT(*)"));
 
     let (count_sql, count_values) = count.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&count_sql)?;
 
     let count_start = Instant::now();
     let ret: Result<i64, sqlx::Error> = sqlx::query_scalar_with(&count_sql, count_values)
@@ -274,12 +294,12 @@ where
 
     let total = match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), count_cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, count_cost, None);
             v
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), count_cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, count_cost, Some(&err));
             return Err(err);
         }
     };
@@ -297,6 +317,7 @@ where
     stmt.limit(size as u64).offset(((page - 1) * size) as u64);
 
     let (query_sql, query_values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&query_sql)?;
 
     let query_start = Instant::now();
     let ret = sqlx::query_as_with::<_, T, _>(&query_sql, query_values)
@@ -306,13 +327,718 @@ where
 
     match ret {
         Ok(v) => {
-            trace_sql(stmt.to_string(SqliteQueryBuilder), query_cost, None);
+            trace_stmt(&stmt, SqliteQueryBuilder, query_cost, None);
             Ok((v, total))
         }
         Err(e) => {
             let err = anyhow::Error::from(e);
-            trace_sql(stmt.to_string(SqliteQueryBuilder), query_cost, Some(&err));
+            trace_stmt(&stmt, SqliteQueryBuilder, query_cost, Some(&err));
             Err(err)
         }
     }
 }
+
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl Synchronous {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Synchronous::Off => "OFF",
+            Synchronous::Normal => "NORMAL",
+            Synchronous::Full => "FULL",
+            Synchronous::Extra => "EXTRA",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Pragmas {
+    /// journal_mode=WAL
+    pub wal: Option<bool>,
+    pub busy_timeout: Option<Duration>,
+    pub foreign_keys: Option<bool>,
+    pub synchronous: Option<Synchronous>,
+}
+
+impl Pragmas {
+    /// 对应的 PRAGMA 语句，可用于 `sql::Params::after_connect`，在每个新连接上执行
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let pragmas = sqlite::Pragmas {
+    ///     wal: Some(true),
+    ///     foreign_keys: Some(true),
+    ///     ..Default::default()
+    /// };
+    /// let pool = sql::open::<sql::SQLite>(
+    ///     "dsn",
+    ///     Some(sql::Params {
+    ///         after_connect: Some(pragmas.statements()),
+    ///         ..Default::default()
+    ///     }),
+    /// )
+    /// .await?;
+    /// ```
+    pub fn statements(&self) -> Vec<String> {
+        let mut list = Vec::new();
+        if let Some(v) = self.wal {
+            list.push(format!(
+                "PRAGMA journal_mode = {}",
+                if v { "WAL" } else { "DELETE" }
+            ));
+        }
+        if let Some(v) = self.busy_timeout {
+            list.push(format!("PRAGMA busy_timeout = {}", v.as_millis()));
+        }
+        if let Some(v) = self.foreign_keys {
+            list.push(format!(
+                "PRAGMA foreign_keys = {}",
+                if v { "ON" } else { "OFF" }
+            ));
+        }
+        if let Some(v) = &self.synchronous {
+            list.push(format!("PRAGMA synchronous = {}", v.as_str()));
+        }
+        list
+    }
+}
+
+/// 设置 PRAGMA
+///
+/// 更新连接池的连接参数（对之后新建的连接生效），并关闭当前的空闲连接，由连接池按新参数重建；
+/// 调用时正在使用的连接归还后仍为原设置，需所有连接一致时，建议在打开连接池时通过
+/// `sql::Params::after_connect`（见 [`Pragmas::statements`]）设置
+///
+/// # Examples
+///
+/// ```
+/// let pool = sql::open::<sql::SQLite>("dsn", None).await?;
+///
+/// sqlite::configure(
+///     &pool,
+///     sqlite::Pragmas {
+///         wal: Some(true),
+///         busy_timeout: Some(Duration::from_secs(5)),
+///         foreign_keys: Some(true),
+///         synchronous: Some(sqlite::Synchronous::Normal),
+///     },
+/// )
+/// .await?;
+/// ```
+pub async fn configure(pool: &Pool<Sqlite>, opts: Pragmas) -> anyhow::Result<()> {
+    let mut connect_opts = (*pool.connect_options()).clone();
+    if let Some(v) = opts.wal {
+        let mode = if v {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        };
+        connect_opts = connect_opts.journal_mode(mode);
+    }
+    if let Some(v) = opts.busy_timeout {
+        connect_opts = connect_opts.busy_timeout(v);
+    }
+    if let Some(v) = opts.foreign_keys {
+        connect_opts = connect_opts.foreign_keys(v);
+    }
+    if let Some(v) = &opts.synchronous {
+        connect_opts = connect_opts.pragma("synchronous", v.as_str());
+    }
+    pool.set_connect_options(connect_opts);
+
+    // 逐个关闭空闲连接（不同时占用），之后按新参数建立
+    for _ in 0..pool.num_idle() {
+        match pool.try_acquire() {
+            Some(conn) => conn.close().await?,
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// 将数据库压缩并导出至指定文件（VACUUM INTO，目标文件须不存在）
+///
+/// # Examples
+///
+/// ```
+/// sqlite::vacuum_into(&pool, "/data/backup.db").await?;
+/// ```
+pub async fn vacuum_into<'e, E>(db: E, path: impl AsRef<Path>) -> anyhow::Result<()>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let path = path
+        .as_ref()
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("sqlite: invalid path"))?;
+    let sql = format!("VACUUM INTO '{}'", path.replace('\'', "''"));
+    exec_raw(db, sql).await?;
+    Ok(())
+}
+
+/// 在线备份
+///
+/// 先 VACUUM INTO 至同目录下的临时文件，再原子替换目标文件，
+/// 备份期间不阻塞其它读写
+///
+/// # Examples
+///
+/// ```
+/// sqlite::backup(&pool, "/data/backup.db").await?;
+/// ```
+pub async fn backup(pool: &Pool<Sqlite>, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", crate::helper::nonce(8)));
+
+    if let Err(e) = vacuum_into(pool, &tmp).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+    if let Err(e) = tokio::fs::rename(&tmp, path).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+async fn exec_raw<'e, E>(db: E, sql: String) -> anyhow::Result<u64>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let start = Instant::now();
+    let ret = sqlx::query(&sql).execute(db).await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_sql(sql, cost, None);
+            Ok(v.rows_affected())
+        }
+        Err(e) => {
+            let err = anyhow::Error::from(e);
+            trace_sql(sql, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 统计记录数（支持超时等选项）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = sqlite::count_opts(&pool, stmt, opts).await;
+/// ```
+pub async fn count_opts<'e, E>(db: E, mut stmt: SelectStatement, opts: Opts) -> anyhow::Result<i64>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    stmt.clear_selects();
+    stmt.clear_order_by();
+    stmt.expr(Expr::cust("COUNT(*)"));
+
+    let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
+
+    let start = Instant::now();
+    let ret = with_timeout(
+        opts.timeout,
+        sqlx::query_scalar_with::<_, i64, _>(&sql, values).fetch_one(db),
+    )
+    .await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 查询单条记录（支持超时等选项）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = sqlite::find_one_opts::<model::Demo>(&pool, stmt, opts).await;
+/// ```
+pub async fn find_one_opts<'e, E, T>(
+    db: E,
+    mut stmt: SelectStatement,
+    opts: Opts,
+) -> anyhow::Result<Option<T>>
+where
+    E: Executor<'e, Database = Sqlite>,
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    stmt.limit(1);
+    let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
+
+    let start = Instant::now();
+    let ret = with_timeout(
+        opts.timeout,
+        sqlx::query_as_with::<_, T, _>(&sql, values).fetch_optional(db),
+    )
+    .await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 查询多条记录（支持超时、慢查询执行计划等选项）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     explain_slow: Some(Duration::from_millis(200)),
+/// };
+/// let ret = sqlite::find_all_opts::<_, model::Demo>(&pool, stmt, opts).await;
+/// ```
+pub async fn find_all_opts<'a, A, T>(
+    db: A,
+    stmt: SelectStatement,
+    opts: Opts,
+) -> anyhow::Result<Vec<T>>
+where
+    A: Acquire<'a, Database = Sqlite>,
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    tenant::guard(&sql)?;
+
+    let mut conn = db.acquire().await?;
+    let start = Instant::now();
+    let ret = with_timeout(
+        opts.timeout,
+        sqlx::query_as_with::<_, T, _>(&sql, values).fetch_all(&mut *conn),
+    )
+    .await;
+    let cost = start.elapsed();
+
+    match ret {
+        Ok(v) => {
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, None);
+            if explain::should_explain(&opts, cost) {
+                let plan = explain(&mut *conn, stmt.clone()).await;
+                explain::log_slow(stmt.to_string(SqliteQueryBuilder), cost, plan);
+            }
+            Ok(v)
+        }
+        Err(err) => {
+            trace_stmt(&stmt, SqliteQueryBuilder, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// 分页查询（支持超时等选项，超时作用于每条语句）
+///
+/// # Examples
+///
+/// ```
+/// let opts = sql::Opts {
+///     timeout: Some(Duration::from_secs(3)),
+///     ..Default::default()
+/// };
+/// let ret = sqlite::paginate_opts::<_, model::Demo>(&pool, stmt, 1, 10, opts).await;
+/// ```
+pub async fn paginate_opts<'e, E, T>(
+    db: E,
+    mut stmt: SelectStatement,
+    mut page: i32,
+    mut size: i32,
+    opts: Opts,
+) -> anyhow::Result<(Vec<T>, i64)>
+where
+    E: Executor<'e, Database = Sqlite> + Acquire<'e, Database = Sqlite> + Copy,
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    let total = count_opts(db, stmt.clone(), opts.clone()).await?;
+    if total == 0 {
+        return Ok((Vec::new(), total));
+    }
+
+    if page <= 0 {
+        page = 1
+    }
+    if size <= 0 {
+        size = 20
+    }
+    stmt.limit(size as u64).offset(((page - 1) * size) as u64);
+
+    let list = find_all_opts(db, stmt, opts).await?;
+    Ok((list, total))
+}
+
+/// 查询执行计划（EXPLAIN QUERY PLAN），生产环境（`KR_PROFILE=prod`）禁用
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .and_where(Expr::col(table::Demo::Name).like("%demo%"))
+///     .to_owned();
+///
+/// let ret = sqlite::explain(&pool, stmt).await?;
+/// if ret.full_scan {
+///     tracing::warn!(plan = ?ret.tables, "full table scan");
+/// }
+/// ```
+pub async fn explain<'e, E>(db: E, stmt: SelectStatement) -> anyhow::Result<Explain>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    explain::ensure_enabled()?;
+
+    let (sql, values) = stmt.build_sqlx(SqliteQueryBuilder);
+    let sql = format!("EXPLAIN QUERY PLAN {}", sql);
+
+    let start = Instant::now();
+    let ret = sqlx::query_as_with::<_, (i64, i64, i64, String), _>(&sql, values)
+        .fetch_all(db)
+        .await;
+    let cost = start.elapsed();
+
+    let raw = || format!("EXPLAIN QUERY PLAN {}", stmt.to_string(SqliteQueryBuilder));
+    match ret {
+        Ok(rows) => {
+            trace_bound(&sql, raw, cost, None);
+            Ok(explain::parse_sqlite(
+                rows.into_iter()
+                    .map(|(id, parent, _, detail)| (id, parent, detail))
+                    .collect(),
+            ))
+        }
+        Err(e) => {
+            let err = anyhow::Error::from(e);
+            trace_bound(&sql, raw, cost, Some(&err));
+            Err(err)
+        }
+    }
+}
+
+/// IN 查询分片：按绑定参数上限将 `col IN (...)` 拆分为多次查询，有界并发执行，结果按分片顺序合并
+///
+/// 注意：语句不能包含 ORDER BY、LIMIT、OFFSET（无法跨分片生效，返回错误），需要时对结果自行排序
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Demo::Table)
+///     .expr(Expr::cust("*"))
+///     .to_owned();
+///
+/// let ret = sqlite::find_all_chunked::<_, model::Demo, _, _>(&pool, stmt, table::Demo::Id, ids, None).await;
+/// ```
+pub async fn find_all_chunked<'e, E, T, C, V>(
+    db: E,
+    stmt: SelectStatement,
+    col: C,
+    values: impl IntoIterator<Item = V>,
+    opt: Option<ChunkParams>,
+) -> anyhow::Result<Vec<T>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+    C: IntoColumnRef + Clone,
+    V: Into<sea_query::Value>,
+{
+    let values: Vec<sea_query::Value> = values.into_iter().map(Into::into).collect();
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let params = opt.unwrap_or_default();
+    ensure_chunkable(&stmt, &SqliteQueryBuilder)?;
+    let (_, binds) = stmt.build(SqliteQueryBuilder);
+    let chunks = chunk_values(values, 32766, binds.0.len(), &params)?;
+
+    run_chunked(chunks, params.concurrency, |v| {
+        let mut stmt = stmt.clone();
+        stmt.and_where(Expr::col(col.clone()).is_in(v));
+        find_all(db, stmt)
+    })
+    .await
+}
+
+/// 跨分片查询：将 FROM 依次替换为各物理表，有界并发执行，合并结果后排序、截取
+///
+/// 注意：语句中的 ORDER BY、LIMIT 在各分片内生效，LIMIT 需 >= offset + limit
+///
+/// # Examples
+///
+/// ```
+/// let stmt = Query::select()
+///     .from(table::Order::Table)
+///     .expr(Expr::cust("*"))
+///     .order_by(table::Order::Id, Order::Desc)
+///     .limit(20)
+///     .to_owned();
+///
+/// let ret = sqlite::find_all_sharded::<_, model::Order>(
+///     &pool,
+///     &router,
+///     router.tables()?,
+///     stmt,
+///     Some(ShardParams {
+///         sort_by: Some(|a, b| b.id.cmp(&a.id)),
+///         limit: Some(20),
+///         ..Default::default()
+///     }),
+/// )
+/// .await;
+/// ```
+pub async fn find_all_sharded<'e, E, T>(
+    db: E,
+    router: &ShardRouter,
+    tables: Vec<String>,
+    stmt: SelectStatement,
+    opt: Option<ShardParams<T>>,
+) -> anyhow::Result<Vec<T>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    let params = opt.unwrap_or_default();
+    let list = run_chunked(tables, params.concurrency, |table| {
+        let mut stmt = stmt.clone();
+        router.route_select(&mut stmt, &table);
+        find_all(db, stmt)
+    })
+    .await?;
+    Ok(shard::merge(list, &params))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sea_query::{Alias, Expr, Order, Query};
+
+    use crate::sql::{self, sqlite};
+
+    #[tokio::test]
+    async fn test_configure_and_backup() {
+        let dir = std::env::temp_dir().join(format!("kr_sqlite_{}", crate::helper::nonce(8)));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let dsn = format!("sqlite://{}?mode=rwc", dir.join("test.db").display());
+        let pool = sql::open::<sql::SQLite>(
+            dsn,
+            Some(sql::Params {
+                min_conns: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        sqlite::configure(
+            &pool,
+            sqlite::Pragmas {
+                wal: Some(true),
+                busy_timeout: Some(Duration::from_secs(3)),
+                foreign_keys: Some(true),
+                synchronous: Some(sqlite::Synchronous::Normal),
+            },
+        )
+        .await
+        .unwrap();
+
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(mode, "wal");
+        let fk: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(fk, 1);
+        let sync: i64 = sqlx::query_scalar("PRAGMA synchronous")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(sync, 1);
+
+        let pragmas = sqlite::Pragmas {
+            foreign_keys: Some(true),
+            synchronous: Some(sqlite::Synchronous::Normal),
+            ..Default::default()
+        };
+        assert_eq!(
+            pragmas.statements(),
+            vec!["PRAGMA foreign_keys = ON", "PRAGMA synchronous = NORMAL"]
+        );
+
+        sqlx::query("CREATE TABLE demo (id INTEGER PRIMARY KEY, name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let target = dir.join("backup.db");
+        sqlite::backup(&pool, &target).await.unwrap();
+        // 目标已存在时可再次备份
+        sqlite::backup(&pool, &target).await.unwrap();
+        assert!(target.exists());
+
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_paginate_opts() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE demo (id INTEGER PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO demo (id) VALUES (1), (2), (3)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stmt = Query::select()
+            .from(Alias::new("demo"))
+            .column(Alias::new("id"))
+            .order_by(Alias::new("id"), Order::Asc)
+            .to_owned();
+        let opts = sql::Opts {
+            timeout: Some(Duration::from_secs(3)),
+            ..Default::default()
+        };
+        let (list, total) = sqlite::paginate_opts::<_, (i64,)>(&pool, stmt, 2, 2, opts)
+            .await
+            .unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(list, vec![(3,)]);
+    }
+
+    #[tokio::test]
+    async fn test_explain() {
+        let pool = sql::test::memory_pool(Some(
+            "CREATE TABLE demo (id INTEGER PRIMARY KEY, name TEXT, age INTEGER); CREATE INDEX idx_name ON demo (name)",
+        ))
+        .await
+        .unwrap();
+
+        let stmt = Query::select()
+            .from(Alias::new("demo"))
+            .column(Alias::new("id"))
+            .and_where(Expr::col(Alias::new("name")).eq("kr"))
+            .to_owned();
+        let ret = sqlite::explain(&pool, stmt.clone()).await.unwrap();
+        assert_eq!(ret.tables.len(), 1);
+        assert_eq!(ret.tables[0].access, "SEARCH");
+        assert!(ret.tables[0]
+            .index
+            .as_deref()
+            .unwrap()
+            .starts_with("idx_name"));
+        assert!(!ret.full_scan);
+
+        // 全表扫描
+        let scan = Query::select()
+            .from(Alias::new("demo"))
+            .column(Alias::new("age"))
+            .and_where(Expr::col(Alias::new("age")).gt(18))
+            .to_owned();
+        assert!(sqlite::explain(&pool, scan).await.unwrap().full_scan);
+
+        // 超过阈值时记录执行计划，不影响查询结果
+        let opts = sql::Opts {
+            explain_slow: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        let list = sqlite::find_all_opts::<_, (i64,)>(&pool, stmt, opts)
+            .await
+            .unwrap();
+        assert!(list.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_all_chunked() {
+        let pool = sql::test::memory_pool(Some("CREATE TABLE demo (id INTEGER PRIMARY KEY)"))
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO demo (id) SELECT value FROM json_each('[1,2,3,4,5,6,7,8,9,10]')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stmt = Query::select()
+            .from(Alias::new("demo"))
+            .column(Alias::new("id"))
+            .to_owned();
+        let ret = sqlite::find_all_chunked::<_, (i64,), _, _>(
+            &pool,
+            stmt.clone(),
+            Alias::new("id"),
+            [1, 2, 3, 7, 8, 9, 42],
+            Some(sql::ChunkParams {
+                size: Some(3),
+                concurrency: Some(2),
+            }),
+        )
+        .await
+        .unwrap();
+        // 分片：[1,2,3] [7,8,9] [42]
+        let mut ids: Vec<i64> = ret.into_iter().map(|v| v.0).collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3, 7, 8, 9]);
+
+        // 排序、LIMIT 无法跨分片生效
+        for stmt in [
+            stmt.clone()
+                .order_by(Alias::new("id"), Order::Desc)
+                .to_owned(),
+            stmt.clone().limit(2).to_owned(),
+        ] {
+            let ret = sqlite::find_all_chunked::<_, (i64,), _, _>(
+                &pool,
+                stmt,
+                Alias::new("id"),
+                [1, 2],
+                None,
+            )
+            .await;
+            assert!(ret.is_err());
+        }
+    }
+}
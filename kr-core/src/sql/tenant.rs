@@ -0,0 +1,384 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{OnceLock, RwLock},
+};
+
+use anyhow::bail;
+use sea_query::{
+    Alias, DeleteStatement, DynIden, Expr, Iden, InsertStatement, IntoIden, Query, SelectStatement,
+    SimpleExpr, UpdateStatement, Value,
+};
+
+tokio::task_local! {
+    static TENANT: Value;
+    static BYPASS: ();
+}
+
+static TABLES: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+
+fn tables() -> &'static RwLock<HashMap<String, String>> {
+    TABLES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 注册租户隔离的表及其租户字段
+///
+/// 注册后，`mysql`/`pgsql`/`sqlite` 执行涉及该表的语句前会校验租户条件：
+/// 查询、更新、删除须带 `<table>.<column>` 条件（通过本模块的方法构建），
+/// 插入须包含租户字段（通过 [`insert_into`] 自动填充），否则返回错误
+///
+/// # Examples
+///
+/// ```
+/// tenant::register(table::Order::Table, "tenant_id");
+/// ```
+pub fn register(table: impl Iden, column: impl AsRef<str>) {
+    tables()
+        .write()
+        .unwrap()
+        .insert(table.to_string(), column.as_ref().to_string());
+}
+
+/// 在指定租户下执行
+///
+/// # Examples
+///
+/// ```
+/// let ret = tenant::scope(10086, async {
+///     let stmt = tenant::select_from(table::Order::Table)?
+///         .expr(Expr::cust("*"))
+///         .to_owned();
+///     mysql::find_all::<model::Order>(&pool, stmt).await
+/// })
+/// .await;
+/// ```
+pub async fn scope<F: Future>(tenant: impl Into<Value>, f: F) -> F::Output {
+    TENANT.scope(tenant.into(), f).await
+}
+
+/// 跳过租户校验执行（跨租户的后台任务、数据迁移等）
+///
+/// # Examples
+///
+/// ```
+/// let n = tenant::unscoped(async {
+///     let stmt = Query::select().from(table::Order::Table).to_owned();
+///     mysql::count(&pool, stmt).await
+/// })
+/// .await?;
+/// ```
+pub async fn unscoped<F: Future>(f: F) -> F::Output {
+    BYPASS.scope((), f).await
+}
+
+/// 当前租户
+pub fn current() -> Option<Value> {
+    TENANT.try_with(|v| v.clone()).ok()
+}
+
+/// 表的租户条件：`<table>.<column> = <tenant>`
+///
+/// 未注册的表返回 None；已注册但不在租户上下文中时返回错误
+pub fn condition<T>(table: T) -> anyhow::Result<Option<SimpleExpr>>
+where
+    T: IntoIden,
+{
+    let table = table.into_iden();
+    let name = table.to_string();
+
+    let column = match tables().read().unwrap().get(&name) {
+        Some(v) => v.clone(),
+        None => return Ok(None),
+    };
+
+    match current() {
+        Some(v) => Ok(Some(Expr::col((table, Alias::new(column))).eq(v))),
+        None => bail!(
+            "sql/tenant: table({}) is tenant-scoped but no tenant in context",
+            name
+        ),
+    }
+}
+
+/// 为已有查询追加租户条件（适用于 JOIN 的表）
+pub fn apply_select<T: IntoIden>(stmt: &mut SelectStatement, table: T) -> anyhow::Result<()> {
+    if let Some(cond) = condition(table)? {
+        stmt.and_where(cond);
+    }
+    Ok(())
+}
+
+/// 为已有更新追加租户条件
+pub fn apply_update<T: IntoIden>(stmt: &mut UpdateStatement, table: T) -> anyhow::Result<()> {
+    if let Some(cond) = condition(table)? {
+        stmt.and_where(cond);
+    }
+    Ok(())
+}
+
+/// 为已有删除追加租户条件
+pub fn apply_delete<T: IntoIden>(stmt: &mut DeleteStatement, table: T) -> anyhow::Result<()> {
+    if let Some(cond) = condition(table)? {
+        stmt.and_where(cond);
+    }
+    Ok(())
+}
+
+/// `SELECT ... FROM <table> WHERE <tenant>`
+pub fn select_from<T>(table: T) -> anyhow::Result<SelectStatement>
+where
+    T: IntoIden,
+{
+    let table = table.into_iden();
+    let mut stmt = Query::select().from(table.clone()).to_owned();
+    apply_select(&mut stmt, table)?;
+    Ok(stmt)
+}
+
+/// `UPDATE <table> ... WHERE <tenant>`
+pub fn update<T>(table: T) -> anyhow::Result<UpdateStatement>
+where
+    T: IntoIden,
+{
+    let table = table.into_iden();
+    let mut stmt = Query::update().table(table.clone()).to_owned();
+    apply_update(&mut stmt, table)?;
+    Ok(stmt)
+}
+
+/// `DELETE FROM <table> WHERE <tenant>`
+pub fn delete_from<T>(table: T) -> anyhow::Result<DeleteStatement>
+where
+    T: IntoIden,
+{
+    let table = table.into_iden();
+    let mut stmt = Query::delete().from_table(table.clone()).to_owned();
+    apply_delete(&mut stmt, table)?;
+    Ok(stmt)
+}
+
+/// `INSERT INTO <table> (<tenant>, columns...)`，每行自动填充当前租户
+///
+/// # Examples
+///
+/// ```
+/// let stmt = tenant::insert_into(table::Order::Table, [table::Order::Amount])?
+///     .values([100.into()])?
+///     .to_statement();
+/// mysql::create(&pool, stmt).await?;
+/// ```
+pub fn insert_into<T, C, I>(table: T, columns: I) -> anyhow::Result<Insert>
+where
+    T: IntoIden,
+    C: IntoIden,
+    I: IntoIterator<Item = C>,
+{
+    let table = table.into_iden();
+    let name = table.to_string();
+
+    let mut cols: Vec<DynIden> = Vec::new();
+    let column = tables().read().unwrap().get(&name).cloned();
+    let tenant = match column {
+        Some(column) => {
+            let Some(v) = current() else {
+                bail!(
+                    "sql/tenant: table({}) is tenant-scoped but no tenant in context",
+                    name
+                );
+            };
+            cols.push(Alias::new(column).into_iden());
+            Some(v)
+        }
+        None => None,
+    };
+    cols.extend(columns.into_iter().map(IntoIden::into_iden));
+
+    let stmt = Query::insert().into_table(table).columns(cols).to_owned();
+
+    Ok(Insert { stmt, tenant })
+}
+
+/// 租户表的插入语句，见 [`insert_into`]
+pub struct Insert {
+    stmt: InsertStatement,
+    tenant: Option<Value>,
+}
+
+impl Insert {
+    /// 追加一行（不含租户字段）
+    pub fn values<I>(&mut self, values: I) -> anyhow::Result<&mut Self>
+    where
+        I: IntoIterator<Item = SimpleExpr>,
+    {
+        let row = self
+            .tenant
+            .clone()
+            .map(SimpleExpr::from)
+            .into_iter()
+            .chain(values);
+        self.stmt.values(row)?;
+        Ok(self)
+    }
+
+    pub fn to_statement(&self) -> InsertStatement {
+        self.stmt.clone()
+    }
+}
+
+/// 执行前校验（由 `mysql`/`pgsql`/`sqlite` 调用）：语句涉及已注册的表时，
+/// 查询、更新、删除须带 `<table>.<column>` 条件，插入须包含租户字段
+pub(crate) fn guard(sql: &str) -> anyhow::Result<()> {
+    let tables = tables().read().unwrap();
+    if tables.is_empty() || BYPASS.try_with(|_| ()).is_ok() {
+        return Ok(());
+    }
+
+    let insert = sql.trim_start().starts_with("INSERT");
+    for (table, column) in tables.iter() {
+        // sea-query 生成的标识符均带引号：MySQL 为 `，PgSQL/SQLite 为 "
+        for q in ['`', '"'] {
+            let t = format!("{q}{table}{q}");
+            if !sql.contains(&t) {
+                continue;
+            }
+            let scoped = if insert {
+                sql.contains(&format!("{q}{column}{q}"))
+            } else {
+                sql.contains(&format!("{t}.{q}{column}{q}"))
+            };
+            if !scoped {
+                bail!(
+                    "sql/tenant: statement on tenant-scoped table({}) without tenant condition",
+                    table
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_query::{Alias, Expr, MysqlQueryBuilder, Query};
+
+    use crate::sql::{self, mysql, sqlite, tenant};
+
+    #[tokio::test]
+    async fn test_tenant() {
+        tenant::register(Alias::new("tenant_orders"), "tenant_id");
+
+        // 未注册的表
+        let stmt = tenant::select_from(Alias::new("users"))
+            .unwrap()
+            .expr(Expr::cust("*"))
+            .to_owned();
+        assert_eq!(stmt.to_string(MysqlQueryBuilder), "SELECT * FROM `users`");
+
+        // 缺少租户
+        assert!(tenant::select_from(Alias::new("tenant_orders")).is_err());
+
+        tenant::scope(7, async {
+            let stmt = tenant::select_from(Alias::new("tenant_orders"))
+                .unwrap()
+                .expr(Expr::cust("*"))
+                .and_where(Expr::col(Alias::new("id")).eq(1))
+                .to_owned();
+            assert_eq!(
+                stmt.to_string(MysqlQueryBuilder),
+                "SELECT * FROM `tenant_orders` WHERE `tenant_orders`.`tenant_id` = 7 AND `id` = 1"
+            );
+
+            let stmt = tenant::delete_from(Alias::new("tenant_orders")).unwrap();
+            assert_eq!(
+                stmt.to_string(MysqlQueryBuilder),
+                "DELETE FROM `tenant_orders` WHERE `tenant_orders`.`tenant_id` = 7"
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_tenant_guard() {
+        tenant::register(Alias::new("tenant_items"), "tenant_id");
+        let pool = sql::test::memory_pool(Some(
+            "CREATE TABLE tenant_items (id INTEGER PRIMARY KEY, tenant_id INTEGER NOT NULL, name TEXT NOT NULL);",
+        ))
+        .await
+        .unwrap();
+
+        let table = || Alias::new("tenant_items");
+        for (tenant, name) in [(1, "a"), (1, "b"), (2, "c")] {
+            tenant::scope(tenant, async {
+                // 自动填充租户字段
+                let stmt = tenant::insert_into(table(), [Alias::new("name")])
+                    .unwrap()
+                    .values([name.into()])
+                    .unwrap()
+                    .to_statement();
+                sqlite::create(&pool, stmt).await.unwrap();
+            })
+            .await;
+        }
+
+        tenant::scope(1, async {
+            // 未带租户条件的语句拒绝执行
+            let stmt = Query::select().from(table()).to_owned();
+            assert!(sqlite::count(&pool, stmt).await.is_err());
+            let stmt = Query::update()
+                .table(table())
+                .values([(Alias::new("name"), "x".into())])
+                .to_owned();
+            assert!(sqlite::update(&pool, stmt).await.is_err());
+            let stmt = Query::insert()
+                .into_table(table())
+                .columns([Alias::new("name")])
+                .values_panic(["x".into()])
+                .to_owned();
+            assert!(sqlite::create(&pool, stmt).await.is_err());
+
+            let stmt = tenant::select_from(table()).unwrap();
+            assert_eq!(sqlite::count(&pool, stmt).await.unwrap(), 2);
+            let stmt = tenant::update(table())
+                .unwrap()
+                .values([(Alias::new("name"), "x".into())])
+                .to_owned();
+            assert_eq!(sqlite::update(&pool, stmt).await.unwrap(), 2);
+        })
+        .await;
+
+        // 不在租户上下文中
+        let stmt = Query::select().from(table()).to_owned();
+        assert!(sqlite::count(&pool, stmt.clone()).await.is_err());
+        assert!(tenant::insert_into(table(), [Alias::new("name")]).is_err());
+
+        let n = tenant::unscoped(sqlite::count(&pool, stmt)).await.unwrap();
+        assert_eq!(n, 3);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_guard_mysql_opts() {
+        tenant::register(Alias::new("tenant_invoices"), "tenant_id");
+        // 延迟连接：校验在连接数据库之前执行
+        let pool = sqlx::mysql::MySqlPoolOptions::new()
+            .connect_lazy("mysql://root@127.0.0.1:1/test")
+            .unwrap();
+
+        tenant::scope(1, async {
+            let stmt = Query::select()
+                .from(Alias::new("tenant_invoices"))
+                .expr(Expr::cust("*"))
+                .to_owned();
+
+            let err = mysql::find_all_opts::<_, (i64,)>(&pool, stmt.clone(), sql::Opts::default())
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("sql/tenant"), "{}", err);
+
+            let err = mysql::paginate_opts::<_, (i64,)>(&pool, stmt, 1, 10, sql::Opts::default())
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("sql/tenant"), "{}", err);
+        })
+        .await;
+    }
+}
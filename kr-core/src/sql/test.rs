@@ -0,0 +1,107 @@
+use futures::future::BoxFuture;
+use sqlx::{sqlite::SqlitePoolOptions, Database, Pool, Sqlite, Transaction};
+
+use crate::helper;
+
+/// 创建内存 SQLite 连接池（各连接共享同一内存库），可选初始化表结构
+///
+/// # Examples
+///
+/// ```
+/// let pool = sql::test::memory_pool(Some(
+///     "CREATE TABLE demo (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+/// ))
+/// .await?;
+/// ```
+pub async fn memory_pool(schema: Option<&str>) -> anyhow::Result<Pool<Sqlite>> {
+    // 每次调用使用独立的库名，测试之间互不影响
+    let dsn = format!(
+        "sqlite:file:kr_test_{}?mode=memory&cache=shared",
+        helper::nonce(16)
+    );
+
+    // 共享内存库在最后一个连接关闭时销毁，保持至少一个连接
+    let pool = SqlitePoolOptions::new()
+        .min_connections(1)
+        .max_connections(5)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect(&dsn)
+        .await?;
+
+    if let Some(sql) = schema {
+        sqlx::raw_sql(sql).execute(&pool).await?;
+    }
+
+    Ok(pool)
+}
+
+/// 在事务中执行并始终回滚，用于隔离的数据层测试
+///
+/// # Examples
+///
+/// ```
+/// let ret = sql::test::with_rollback_txn(&pool, |tx| {
+///     Box::pin(async move {
+///         let stmt = Query::insert()
+///             .into_table(table::Demo::Table)
+///             .columns([table::Demo::Name])
+///             .values_panic(["demo".into()])
+///             .to_owned();
+///         sqlite::create(&mut **tx, stmt).await
+///     })
+/// })
+/// .await;
+/// ```
+pub async fn with_rollback_txn<DB, T, F>(pool: &Pool<DB>, f: F) -> anyhow::Result<T>
+where
+    DB: Database,
+    F: for<'c> FnOnce(&'c mut Transaction<'static, DB>) -> BoxFuture<'c, anyhow::Result<T>>,
+{
+    let mut tx = pool.begin().await?;
+    let ret = f(&mut tx).await;
+    tx.rollback().await?;
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql;
+
+    #[tokio::test]
+    async fn test_rollback_txn() {
+        let pool = sql::test::memory_pool(Some(
+            "CREATE TABLE demo (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+        ))
+        .await
+        .unwrap();
+
+        let n: i64 = sql::test::with_rollback_txn(&pool, |tx| {
+            Box::pin(async move {
+                sqlx::query("INSERT INTO demo (name) VALUES ('a'), ('b')")
+                    .execute(&mut **tx)
+                    .await?;
+                Ok(sqlx::query_scalar("SELECT COUNT(*) FROM demo")
+                    .fetch_one(&mut **tx)
+                    .await?)
+            })
+        })
+        .await
+        .unwrap();
+        assert_eq!(n, 2);
+
+        // 已回滚；且其他连接可见同一内存库
+        let n: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM demo")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(n, 0);
+
+        // 每个 pool 为独立的库
+        let other = sql::test::memory_pool(None).await.unwrap();
+        assert!(sqlx::query("SELECT 1 FROM demo")
+            .execute(&other)
+            .await
+            .is_err());
+    }
+}
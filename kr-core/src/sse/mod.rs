@@ -0,0 +1,194 @@
+use std::{fmt, time::Duration};
+
+use futures::Stream;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// SSE 响应的 Content-Type
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// SSE 响应需要设置的 Header（禁用缓存、nginx 缓冲）
+pub const HEADERS: [(&str, &str); 4] = [
+    ("Content-Type", CONTENT_TYPE),
+    ("Cache-Control", "no-cache"),
+    ("Connection", "keep-alive"),
+    ("X-Accel-Buffering", "no"),
+];
+
+/// SSE 事件
+///
+/// # Examples
+///
+/// ```
+/// let ev = Event::default()
+///     .event("progress")
+///     .id("42")
+///     .retry(Duration::from_secs(3))
+///     .json(&Progress { done: 10, total: 100 })?;
+///
+/// // event: progress
+/// // id: 42
+/// // retry: 3000
+/// // data: {"done":10,"total":100}
+/// let s = ev.to_string();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+    data: Option<String>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// 注释（客户端忽略，常用于保活）
+    pub fn comment(text: impl AsRef<str>) -> Self {
+        Self {
+            comment: Some(text.as_ref().to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// 事件类型（客户端 `addEventListener` 的名称，默认为 message）
+    pub fn event(mut self, name: impl AsRef<str>) -> Self {
+        self.event = Some(strip_newlines(name.as_ref()));
+        self
+    }
+
+    /// 事件ID（断线重连时客户端通过 `Last-Event-ID` 回传）
+    pub fn id(mut self, id: impl AsRef<str>) -> Self {
+        self.id = Some(strip_newlines(id.as_ref()));
+        self
+    }
+
+    /// 客户端断线重连间隔
+    pub fn retry(mut self, d: Duration) -> Self {
+        self.retry = Some(d);
+        self
+    }
+
+    /// 文本数据（多行时拆分为多个 `data:` 字段）
+    pub fn data(mut self, data: impl AsRef<str>) -> Self {
+        self.data = Some(data.as_ref().to_string());
+        self
+    }
+
+    /// JSON 数据
+    pub fn json<T: Serialize>(mut self, data: &T) -> anyhow::Result<Self> {
+        self.data = Some(serde_json::to_string(data)?);
+        Ok(self)
+    }
+}
+
+fn strip_newlines(s: &str) -> String {
+    s.replace(['\r', '\n'], "")
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(v) = &self.comment {
+            for line in v.lines() {
+                writeln!(f, ": {}", line)?;
+            }
+        }
+        if let Some(v) = &self.event {
+            writeln!(f, "event: {}", v)?;
+        }
+        if let Some(v) = &self.id {
+            writeln!(f, "id: {}", v)?;
+        }
+        if let Some(v) = &self.retry {
+            writeln!(f, "retry: {}", v.as_millis())?;
+        }
+        if let Some(v) = &self.data {
+            for line in v.split('\n') {
+                writeln!(f, "data: {}", line.trim_end_matches('\r'))?;
+            }
+        }
+        writeln!(f)
+    }
+}
+
+/// 将 broadcast 通道转为 SSE 文本流（作为响应 body），无事件时按 `keep_alive` 间隔发送注释保活；
+/// 通道关闭后结束，消费过慢丢失的事件记录警告后跳过
+///
+/// # Examples
+///
+/// ```
+/// let (tx, _) = tokio::sync::broadcast::channel(16);
+///
+/// // 导出任务
+/// let progress = tx.clone();
+/// tokio::spawn(async move {
+///     for i in 1..=100 {
+///         let _ = progress.send(Event::default().event("progress").data(i.to_string()));
+///     }
+///     let _ = progress.send(Event::default().event("done").data(url));
+/// });
+///
+/// // handler 中：设置 sse::HEADERS，body 使用 stream
+/// let body = sse::stream(tx.subscribe(), Duration::from_secs(15));
+/// ```
+pub fn stream(
+    rx: broadcast::Receiver<Event>,
+    keep_alive: Duration,
+) -> impl Stream<Item = String> + Send + 'static {
+    futures::stream::unfold(rx, move |mut rx| async move {
+        loop {
+            match tokio::time::timeout(keep_alive, rx.recv()).await {
+                Ok(Ok(ev)) => return Some((ev.to_string(), rx)),
+                Ok(Err(RecvError::Lagged(n))) => {
+                    tracing::warn!(skipped = n, "[sse] receiver lagged");
+                }
+                Ok(Err(RecvError::Closed)) => return None,
+                Err(_) => return Some((Event::comment("keep-alive").to_string(), rx)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use serde_json::json;
+
+    use crate::sse::{self, Event};
+
+    #[test]
+    fn test_event() {
+        let ev = Event::default()
+            .event("progress")
+            .id("1\n")
+            .retry(Duration::from_secs(3))
+            .json(&json!({"done": 1}))
+            .unwrap();
+        assert_eq!(
+            ev.to_string(),
+            "event: progress\nid: 1\nretry: 3000\ndata: {\"done\":1}\n\n"
+        );
+        assert_eq!(
+            Event::default().data("a\r\nb").to_string(),
+            "data: a\ndata: b\n\n"
+        );
+        assert_eq!(Event::comment("ping").to_string(), ": ping\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let (tx, rx) = tokio::sync::broadcast::channel(4);
+        let mut s = Box::pin(sse::stream(rx, Duration::from_millis(20)));
+
+        tx.send(Event::default().data("1")).unwrap();
+        assert_eq!(s.next().await.unwrap(), "data: 1\n\n");
+        // 保活
+        assert_eq!(s.next().await.unwrap(), ": keep-alive\n\n");
+
+        tx.send(Event::default().data("2")).unwrap();
+        drop(tx);
+        assert_eq!(s.next().await.unwrap(), "data: 2\n\n");
+        assert!(s.next().await.is_none());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{path::Path, sync::RwLock};
+
+use jiff::{civil, tz::TimeZone, Timestamp, Zoned};
+use minijinja::{Environment, ErrorKind, Value};
+use serde::Serialize;
+
+pub use minijinja::context;
+
+use crate::{
+    helper::{mask::Strategy, zoned},
+    money,
+};
+
+/// 模板渲染（基于 `minijinja`，Jinja2 语法）
+///
+/// - 从目录加载的模板首次使用时读取并缓存，`reload` 后重新读取
+/// - `.html`、`.htm`、`.xml` 模板自动转义
+/// - 内置过滤器：
+///   - `datetime(fmt?)`、`date`：格式化时间（unix 秒或时间字符串），默认使用系统时区
+///   - `mask(head?, tail?)`：保留前 head 位、后 tail 位（默认 3、4），`mask_email`：邮箱脱敏
+///   - `money(symbol?)`：金额（分）格式化为 `1,234.56`
+///
+/// # Examples
+///
+/// ```
+/// let tpl = Templates::new("templates").timezone("Asia/Shanghai")?;
+///
+/// // templates/order.html:
+/// // <p>{{ user.phone | mask }} 您好，订单 {{ order.no }} 已于 {{ order.paid_at | datetime }} 支付 {{ order.amount | money("¥") }}</p>
+/// let html = tpl.render("order.html", context! { user, order })?;
+///
+/// // 邮件标题等内联模板
+/// let subject = tpl.render_str("订单 {{ no }} 支付成功", context! { no => order.no })?;
+/// ```
+pub struct Templates {
+    env: RwLock<Environment<'static>>,
+    tz: TimeZone,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self::build(None, TimeZone::system())
+    }
+}
+
+impl Templates {
+    /// 从目录加载模板（模板名为相对路径，如：`mail/welcome.html`）
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self::build(Some(dir.as_ref()), TimeZone::system())
+    }
+
+    /// 时间过滤器使用的时区
+    pub fn timezone(mut self, tz: &str) -> anyhow::Result<Self> {
+        self.tz = TimeZone::get(tz)?;
+        // 重新注册以覆盖原过滤器
+        self.register();
+        Ok(self)
+    }
+
+    fn build(dir: Option<&Path>, tz: TimeZone) -> Self {
+        let mut env = Environment::new();
+        if let Some(v) = dir {
+            env.set_loader(minijinja::path_loader(v));
+        }
+        let tpl = Self {
+            env: RwLock::new(env),
+            tz,
+        };
+        tpl.register();
+        tpl
+    }
+
+    fn register(&self) {
+        let mut env = self.env.write().unwrap();
+
+        let tz = self.tz.clone();
+        env.add_filter("datetime", move |v: Value, fmt: Option<String>| {
+            let z = to_zoned(&v, &tz)?;
+            match fmt {
+                Some(fmt) => zoned::format(&z, &fmt)
+                    .map_err(|e| minijinja::Error::new(ErrorKind::InvalidOperation, e.to_string())),
+                None => Ok(zoned::format_datetime(&z)),
+            }
+        });
+        let tz = self.tz.clone();
+        env.add_filter(
+            "date",
+            move |v: Value| -> Result<String, minijinja::Error> {
+                Ok(zoned::format_date(&to_zoned(&v, &tz)?))
+            },
+        );
+        env.add_filter(
+            "mask",
+            |s: String, head: Option<usize>, tail: Option<usize>| {
+                Strategy::Partial(head.unwrap_or(3), tail.unwrap_or(4)).apply(&s)
+            },
+        );
+        env.add_filter("mask_email", |s: String| Strategy::Email.apply(&s));
+        env.add_filter("money", |cents: i64, symbol: Option<String>| {
+            format!("{}{}", symbol.unwrap_or_default(), money::format(cents))
+        });
+    }
+
+    /// 添加模板（如：`include_str!` 内嵌的模板）
+    pub fn add(&self, name: &str, source: &str) -> anyhow::Result<()> {
+        self.env
+            .write()
+            .unwrap()
+            .add_template_owned(name.to_string(), source.to_string())?;
+        Ok(())
+    }
+
+    /// 注册自定义过滤器等
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// tpl.with_env(|env| env.add_filter("upper", |s: String| s.to_uppercase()));
+    /// ```
+    pub fn with_env<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Environment<'static>),
+    {
+        f(&mut self.env.write().unwrap())
+    }
+
+    /// 渲染模板
+    pub fn render<S: Serialize>(&self, name: &str, ctx: S) -> anyhow::Result<String> {
+        let env = self.env.read().unwrap();
+        let tpl = env.get_template(name)?;
+        Ok(tpl.render(ctx)?)
+    }
+
+    /// 渲染字符串模板
+    pub fn render_str<S: Serialize>(&self, source: &str, ctx: S) -> anyhow::Result<String> {
+        Ok(self.env.read().unwrap().render_str(source, ctx)?)
+    }
+
+    /// 清除缓存，目录中的模板下次使用时重新读取（`add` 添加的模板也会被清除）
+    pub fn reload(&self) {
+        self.env.write().unwrap().clear_templates();
+    }
+}
+
+fn to_zoned(v: &Value, tz: &TimeZone) -> Result<Zoned, minijinja::Error> {
+    let err = || {
+        minijinja::Error::new(
+            ErrorKind::InvalidOperation,
+            format!("template: invalid time {}", v),
+        )
+    };
+
+    if let Some(n) = v.as_i64() {
+        let ts = Timestamp::from_second(n).map_err(|_| err())?;
+        return Ok(ts.to_zoned(tz.clone()));
+    }
+    let s = v.as_str().ok_or_else(err)?;
+    if let Ok(z) = s.parse::<Zoned>() {
+        return Ok(z.with_time_zone(tz.clone()));
+    }
+    if let Ok(ts) = s.parse::<Timestamp>() {
+        return Ok(ts.to_zoned(tz.clone()));
+    }
+    // 无时区信息的时间视为指定时区的本地时间
+    if let Ok(dt) = s.parse::<civil::DateTime>() {
+        return dt.to_zoned(tz.clone()).map_err(|_| err());
+    }
+    Err(err())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        helper,
+        template::{context, Templates},
+    };
+
+    #[test]
+    fn test_render() {
+        let tpl = Templates::default().timezone("Asia/Shanghai").unwrap();
+        let s = tpl
+            .render_str(
+                "{{ phone | mask }} {{ email | mask_email }} {{ ts | datetime }} {{ at | date }} {{ amount | money('¥') }} {{ -5 | money }}",
+                context! {
+                    phone => "13812345678",
+                    email => "alice@example.com",
+                    ts => 1700000000,
+                    at => "2024-01-02T23:30:00Z",
+                    amount => 123456789,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            s,
+            "138****5678 a***@example.com 2023-11-15 06:13:20 2024-01-03 ¥1,234,567.89 -0.05"
+        );
+        assert_eq!(
+            tpl.render_str(
+                "{{ t | datetime('%H:%M') }}",
+                context! { t => "2024-01-02 08:30:00" }
+            )
+            .unwrap(),
+            "08:30"
+        );
+        assert!(tpl
+            .render_str("{{ t | date }}", context! { t => "bad" })
+            .is_err());
+
+        // 目录模板：html 自动转义、缓存与重新加载
+        let dir = std::env::temp_dir().join(format!("kr_tpl_{}", helper::nonce(8)));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.html"), "<p>{{ name }}</p>").unwrap();
+        let tpl = Templates::new(&dir);
+        assert_eq!(
+            tpl.render("hello.html", context! { name => "<b>kr</b>" })
+                .unwrap(),
+            "<p>&lt;b&gt;kr&lt;&#x2f;b&gt;</p>"
+        );
+        std::fs::write(dir.join("hello.html"), "<div>{{ name }}</div>").unwrap();
+        assert_eq!(
+            tpl.render("hello.html", context! { name => "kr" }).unwrap(),
+            "<p>kr</p>"
+        );
+        tpl.reload();
+        assert_eq!(
+            tpl.render("hello.html", context! { name => "kr" }).unwrap(),
+            "<div>kr</div>"
+        );
+        assert!(tpl.render("missing.html", context! {}).is_err());
+
+        tpl.add("mail.txt", "Hi {{ name }}").unwrap();
+        tpl.with_env(|env| env.add_filter("upper", |s: String| s.to_uppercase()));
+        assert_eq!(
+            tpl.render_str("{{ name | upper }}", context! { name => "kr" })
+                .unwrap(),
+            "KR"
+        );
+        assert_eq!(
+            tpl.render("mail.txt", context! { name => "kr" }).unwrap(),
+            "Hi kr"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use anyhow::anyhow;
+use jiff::civil::{Date, Weekday};
+use serde::{Deserialize, Serialize};
+
+// 连续查找工作日的最大天数，防止配置错误导致死循环
+const MAX_SCAN_DAYS: usize = 366;
+
+static CALENDAR: OnceLock<RwLock<Arc<Calendar>>> = OnceLock::new();
+
+fn global() -> &'static RwLock<Arc<Calendar>> {
+    CALENDAR.get_or_init(|| RwLock::new(Arc::new(Calendar::new())))
+}
+
+/// 日期类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayKind {
+    /// 法定节假日（含调休放假）
+    Holiday,
+    /// 调休上班（周末补班）
+    Workday,
+}
+
+/// 节假日表中的一天
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Day {
+    pub date: Date,
+    pub kind: DayKind,
+    /// 节日名称（如：春节）
+    #[serde(default)]
+    pub name: String,
+}
+
+impl Day {
+    pub fn holiday(date: Date, name: impl AsRef<str>) -> Self {
+        Self {
+            date,
+            kind: DayKind::Holiday,
+            name: name.as_ref().to_string(),
+        }
+    }
+
+    pub fn workday(date: Date, name: impl AsRef<str>) -> Self {
+        Self {
+            date,
+            kind: DayKind::Workday,
+            name: name.as_ref().to_string(),
+        }
+    }
+}
+
+/// 节假日表数据源
+pub trait Source: Send + Sync {
+    fn load(&self) -> impl Future<Output = anyhow::Result<Vec<Day>>> + Send;
+}
+
+/// 基于闭包的数据源，便于从 DB 加载
+///
+/// # Examples
+///
+/// ```
+/// let source = || {
+///     let pool = pool.clone();
+///     async move {
+///         let stmt = Query::select()
+///             .from(table::Holiday::Table)
+///             .columns([table::Holiday::Date, table::Holiday::Kind, table::Holiday::Name])
+///             .to_owned();
+///         let rows = mysql::find_all::<model::Holiday>(&pool, stmt).await?;
+///         Ok(rows.into_iter().map(Into::into).collect())
+///     }
+/// };
+/// calendar::load(&source).await?;
+/// ```
+impl<F, Fut> Source for F
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<Vec<Day>>> + Send,
+{
+    fn load(&self) -> impl Future<Output = anyhow::Result<Vec<Day>>> + Send {
+        self()
+    }
+}
+
+/// 工作日历：周末休息，节假日表中的法定节假日休息、调休日上班
+///
+/// # Examples
+///
+/// ```
+/// // 节假日表可来自配置文件
+/// // [[days]]
+/// // date = "2025-01-28"
+/// // kind = "holiday"
+/// // name = "春节"
+/// let cal = Calendar::from_days(cfg.get().days.clone());
+///
+/// let d = jiff::civil::date(2025, 1, 27);
+/// assert!(cal.is_workday(d));
+/// // 3 个工作日后
+/// let due = cal.add_workdays(d, 3)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    days: HashMap<Date, Day>,
+    weekend: Vec<Weekday>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Calendar {
+    /// 空日历（仅周六、周日休息）
+    pub fn new() -> Self {
+        Self {
+            days: HashMap::new(),
+            weekend: vec![Weekday::Saturday, Weekday::Sunday],
+        }
+    }
+
+    pub fn from_days(days: impl IntoIterator<Item = Day>) -> Self {
+        let mut cal = Self::new();
+        cal.extend(days);
+        cal
+    }
+
+    /// 设置每周休息日，默认：周六、周日
+    pub fn weekend(mut self, days: impl IntoIterator<Item = Weekday>) -> Self {
+        self.weekend = days.into_iter().collect();
+        self
+    }
+
+    /// 添加节假日表（同一日期以后添加的为准）
+    pub fn extend(&mut self, days: impl IntoIterator<Item = Day>) {
+        for v in days {
+            self.days.insert(v.date, v);
+        }
+    }
+
+    /// 节假日表中的记录
+    pub fn get(&self, date: Date) -> Option<&Day> {
+        self.days.get(&date)
+    }
+
+    /// 是否为工作日
+    pub fn is_workday(&self, date: Date) -> bool {
+        match self.days.get(&date) {
+            Some(v) => v.kind == DayKind::Workday,
+            None => !self.weekend.contains(&date.weekday()),
+        }
+    }
+
+    /// 是否为法定节假日
+    pub fn is_holiday(&self, date: Date) -> bool {
+        self.days
+            .get(&date)
+            .is_some_and(|v| v.kind == DayKind::Holiday)
+    }
+
+    /// 下一个工作日（不含当天）
+    pub fn next_workday(&self, date: Date) -> anyhow::Result<Date> {
+        self.step(date, 1)
+    }
+
+    /// 上一个工作日（不含当天）
+    pub fn prev_workday(&self, date: Date) -> anyhow::Result<Date> {
+        self.step(date, -1)
+    }
+
+    /// 加 n 个工作日（n 为负数时向前），n 为 0 时返回当天
+    pub fn add_workdays(&self, date: Date, n: i32) -> anyhow::Result<Date> {
+        let dir = if n < 0 { -1 } else { 1 };
+        let mut d = date;
+        for _ in 0..n.unsigned_abs() {
+            d = self.step(d, dir)?;
+        }
+        Ok(d)
+    }
+
+    /// `(start, end]` 之间的工作日数（end 早于 start 时为负数）
+    pub fn workdays_between(&self, start: Date, end: Date) -> anyhow::Result<i32> {
+        let (from, to, sign) = if start <= end {
+            (start, end, 1)
+        } else {
+            (end, start, -1)
+        };
+        let mut n = 0;
+        let mut d = from;
+        while d < to {
+            d = d.tomorrow()?;
+            if self.is_workday(d) {
+                n += 1;
+            }
+        }
+        Ok(n * sign)
+    }
+
+    fn step(&self, date: Date, dir: i8) -> anyhow::Result<Date> {
+        let mut d = date;
+        for _ in 0..MAX_SCAN_DAYS {
+            d = if dir > 0 {
+                d.tomorrow()?
+            } else {
+                d.yesterday()?
+            };
+            if self.is_workday(d) {
+                return Ok(d);
+            }
+        }
+        Err(anyhow!(
+            "calendar: no workday within {} days from {}",
+            MAX_SCAN_DAYS,
+            date
+        ))
+    }
+}
+
+/// 设置全局工作日历
+pub fn set(cal: Calendar) {
+    *global().write().unwrap() = Arc::new(cal);
+}
+
+/// 全局工作日历
+pub fn current() -> Arc<Calendar> {
+    global().read().unwrap().clone()
+}
+
+/// 从数据源加载节假日表并设置为全局工作日历（可定时调用以刷新）
+pub async fn load<S: Source>(source: &S) -> anyhow::Result<()> {
+    let days = source.load().await?;
+    tracing::info!(days = days.len(), "[calendar::load] holidays loaded");
+    set(Calendar::from_days(days));
+    Ok(())
+}
+
+/// 是否为工作日（使用全局工作日历）
+pub fn is_workday(date: Date) -> bool {
+    current().is_workday(date)
+}
+
+/// 下一个工作日（使用全局工作日历）
+pub fn next_workday(date: Date) -> anyhow::Result<Date> {
+    current().next_workday(date)
+}
+
+/// 加 n 个工作日（使用全局工作日历）
+pub fn add_workdays(date: Date, n: i32) -> anyhow::Result<Date> {
+    current().add_workdays(date, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::{date, Weekday};
+
+    use crate::times::calendar::{self, Calendar, Day, DayKind};
+
+    // 2025 年春节：1/28-2/4 放假，1/26(周日)、2/8(周六) 上班
+    fn spring_festival() -> Vec<Day> {
+        let mut days: Vec<Day> = (28..=31)
+            .map(|d| Day::holiday(date(2025, 1, d), "春节"))
+            .chain((1..=4).map(|d| Day::holiday(date(2025, 2, d), "春节")))
+            .collect();
+        days.push(Day::workday(date(2025, 1, 26), "春节"));
+        days.push(Day::workday(date(2025, 2, 8), "春节"));
+        days
+    }
+
+    #[test]
+    fn test_calendar() {
+        let cal = Calendar::from_days(spring_festival());
+
+        assert!(cal.is_workday(date(2025, 1, 24))); // 周五
+        assert!(!cal.is_workday(date(2025, 1, 25))); // 周六
+        assert!(cal.is_workday(date(2025, 1, 26))); // 调休上班
+        assert!(!cal.is_workday(date(2025, 1, 29))); // 春节
+        assert!(cal.is_holiday(date(2025, 1, 29)));
+        assert!(!cal.is_holiday(date(2025, 1, 25)));
+        assert_eq!(cal.get(date(2025, 2, 8)).unwrap().kind, DayKind::Workday);
+
+        assert_eq!(
+            cal.next_workday(date(2025, 1, 27)).unwrap(),
+            date(2025, 2, 5)
+        );
+        assert_eq!(
+            cal.prev_workday(date(2025, 2, 5)).unwrap(),
+            date(2025, 1, 27)
+        );
+        assert_eq!(
+            cal.add_workdays(date(2025, 1, 24), 0).unwrap(),
+            date(2025, 1, 24)
+        );
+        // 1/26、1/27、2/5
+        assert_eq!(
+            cal.add_workdays(date(2025, 1, 24), 3).unwrap(),
+            date(2025, 2, 5)
+        );
+        assert_eq!(
+            cal.add_workdays(date(2025, 2, 5), -3).unwrap(),
+            date(2025, 1, 24)
+        );
+        // 2/5、2/6、2/7、2/8
+        assert_eq!(
+            cal.workdays_between(date(2025, 2, 4), date(2025, 2, 9))
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            cal.workdays_between(date(2025, 2, 9), date(2025, 2, 4))
+                .unwrap(),
+            -4
+        );
+
+        // 全周休息
+        let cal = Calendar::new().weekend([
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+            Weekday::Saturday,
+            Weekday::Sunday,
+        ]);
+        assert!(cal.next_workday(date(2025, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let v: Vec<Day> = serde_json::from_str(
+            r#"[{"date":"2025-10-01","kind":"holiday","name":"国庆节"},{"date":"2025-09-28","kind":"workday"}]"#,
+        )
+        .unwrap();
+        assert_eq!(v[0], Day::holiday(date(2025, 10, 1), "国庆节"));
+        assert_eq!(v[1].kind, DayKind::Workday);
+    }
+
+    #[tokio::test]
+    async fn test_load() {
+        calendar::load(&|| async { Ok(spring_festival()) })
+            .await
+            .unwrap();
+        assert!(!calendar::is_workday(date(2025, 2, 3)));
+        assert_eq!(
+            calendar::next_workday(date(2025, 2, 3)).unwrap(),
+            date(2025, 2, 5)
+        );
+        assert_eq!(
+            calendar::add_workdays(date(2025, 2, 7), 1).unwrap(),
+            date(2025, 2, 8)
+        );
+    }
+}
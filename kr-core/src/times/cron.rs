@@ -0,0 +1,389 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+use jiff::{
+    civil::{DateTime, Time},
+    tz::TimeZone,
+    ToSpan, Zoned,
+};
+
+// 向后查找的最大年数（如：仅 2 月 29 日执行）
+const MAX_SCAN_YEARS: i16 = 10;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// cron 表达式
+///
+/// 支持 5 段（分 时 日 月 周）和 6 段（秒 分 时 日 月 周），以及
+/// `*`、`?`、`a-b`、`*/n`、`a-b/n`、`a/n`、`a,b`，月份/星期英文缩写（JAN、MON），
+/// 星期 0 和 7 均表示周日；`@yearly`、`@monthly`、`@weekly`、`@daily`、`@hourly`
+///
+/// 日和周均指定时（均不为 `*`/`?`），满足其一即可（同标准 cron）
+///
+/// # Examples
+///
+/// ```
+/// let s: Schedule = "0 30 9 * * MON-FRI".parse()?;
+/// let next = s.next_after(&Zoned::now());
+///
+/// // 未来 5 次执行时间
+/// let list = cron::next_runs("*/15 * * * *", 5, "Asia/Shanghai")?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    expr: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Schedule {
+    pub fn parse(expr: &str) -> anyhow::Result<Self> {
+        let expr = expr.trim();
+        let fields = match expr.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 0 1 * *".to_string(),
+            "@weekly" => "0 0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 0 * * *".to_string(),
+            "@hourly" => "0 0 * * * *".to_string(),
+            _ => expr.to_string(),
+        };
+
+        let mut fields: Vec<&str> = fields.split_whitespace().collect();
+        match fields.len() {
+            5 => fields.insert(0, "0"),
+            6 => {}
+            n => {
+                return Err(anyhow!(
+                    "times/cron: expected 5 or 6 fields, got {} in `{}`",
+                    n,
+                    expr
+                ))
+            }
+        }
+
+        let mut weekdays = parse_field(fields[5], 0, 7, Some(&WEEKDAYS), expr)?;
+        // 7 => 周日
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expr: expr.to_string(),
+            seconds: parse_field(fields[0], 0, 59, None, expr)?,
+            minutes: parse_field(fields[1], 0, 59, None, expr)?,
+            hours: parse_field(fields[2], 0, 23, None, expr)?,
+            days: parse_field(fields[3], 1, 31, None, expr)?,
+            months: parse_field(fields[4], 1, 12, Some(&MONTHS), expr)?,
+            weekdays,
+            any_day: matches!(fields[3], "*" | "?"),
+            any_weekday: matches!(fields[5], "*" | "?"),
+        })
+    }
+
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
+    /// 晚于 after 的下一次执行时间（时区同 after）
+    pub fn next_after(&self, after: &Zoned) -> Option<Zoned> {
+        let tz = after.time_zone().clone();
+        let limit = after.year().saturating_add(MAX_SCAN_YEARS);
+
+        let start = after.datetime();
+        let mut dt = start
+            .with()
+            .subsec_nanosecond(0)
+            .build()
+            .ok()?
+            .checked_add(1.second())
+            .ok()?;
+        loop {
+            if dt.year() > limit {
+                return None;
+            }
+            if !has(self.months, dt.month() as u32) {
+                dt = dt
+                    .date()
+                    .first_of_month()
+                    .checked_add(1.month())
+                    .ok()?
+                    .to_datetime(Time::midnight());
+                continue;
+            }
+            if !self.match_day(&dt) {
+                dt = dt.date().tomorrow().ok()?.to_datetime(Time::midnight());
+                continue;
+            }
+            if !has(self.hours, dt.hour() as u32) {
+                dt = dt
+                    .date()
+                    .at(dt.hour(), 0, 0, 0)
+                    .checked_add(1.hour())
+                    .ok()?;
+                continue;
+            }
+            if !has(self.minutes, dt.minute() as u32) {
+                dt = dt
+                    .date()
+                    .at(dt.hour(), dt.minute(), 0, 0)
+                    .checked_add(1.minute())
+                    .ok()?;
+                continue;
+            }
+            if !has(self.seconds, dt.second() as u32) {
+                dt = dt.checked_add(1.second()).ok()?;
+                continue;
+            }
+
+            // 夏令时跳过的时间顺延，重复的时间仅执行一次
+            let z = dt.to_zoned(tz.clone()).ok()?;
+            if z > *after {
+                return Some(z);
+            }
+            dt = dt.checked_add(1.second()).ok()?;
+        }
+    }
+
+    /// 晚于 after 的 n 次执行时间
+    pub fn next_runs(&self, after: &Zoned, n: usize) -> Vec<Zoned> {
+        let mut list = Vec::with_capacity(n);
+        let mut cur = after.clone();
+        while list.len() < n {
+            let Some(v) = self.next_after(&cur) else {
+                break;
+            };
+            cur = v.clone();
+            list.push(v);
+        }
+        list
+    }
+
+    fn match_day(&self, dt: &DateTime) -> bool {
+        let day = has(self.days, dt.day() as u32);
+        let weekday = has(self.weekdays, dt.weekday().to_sunday_zero_offset() as u32);
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+/// 指定时区下，从当前时间起的 n 次执行时间
+///
+/// # Examples
+///
+/// ```
+/// for t in cron::next_runs("0 0 2 * * *", 3, "Asia/Shanghai")? {
+///     println!("{}", zoned::format_datetime(&t));
+/// }
+/// ```
+pub fn next_runs(expr: &str, n: usize, tz: &str) -> anyhow::Result<Vec<Zoned>> {
+    let schedule = Schedule::parse(expr)?;
+    let now = Zoned::now().with_time_zone(TimeZone::get(tz)?);
+    Ok(schedule.next_runs(&now, n))
+}
+
+/// 校验 cron 表达式
+pub fn validate(expr: &str) -> anyhow::Result<()> {
+    Schedule::parse(expr).map(|_| ())
+}
+
+fn has(bits: u64, v: u32) -> bool {
+    bits & (1 << v) != 0
+}
+
+// 解析单个字段为位图
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: Option<&[&str]>,
+    expr: &str,
+) -> anyhow::Result<u64> {
+    let invalid = || anyhow!("times/cron: invalid field `{}` in `{}`", field, expr);
+
+    let value = |s: &str| -> anyhow::Result<u32> {
+        if let Some(names) = names {
+            let upper = s.to_ascii_uppercase();
+            if let Some(i) = names.iter().position(|v| *v == upper) {
+                // 月份从 1 开始，星期从 0 开始
+                return Ok(i as u32 + min);
+            }
+        }
+        let v: u32 = s.parse().map_err(|_| invalid())?;
+        if v < min || v > max {
+            return Err(invalid());
+        }
+        Ok(v)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => {
+                let step: u32 = s.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (r, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = match range {
+            "*" | "?" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (value(a)?, value(b)?),
+                None => {
+                    let v = value(range)?;
+                    match step {
+                        Some(_) => (v, max),
+                        None => (v, v),
+                    }
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        let step = step.unwrap_or(1) as usize;
+        for v in (start..=end).step_by(step) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Zoned;
+
+    use crate::{
+        helper::zoned,
+        times::cron::{self, Schedule},
+    };
+
+    fn at(s: &str, tz: &str) -> Zoned {
+        zoned::parse_in_tz(s, zoned::DATE_TIME, tz).unwrap()
+    }
+
+    fn runs(expr: &str, after: &str, n: usize) -> Vec<String> {
+        Schedule::parse(expr)
+            .unwrap()
+            .next_runs(&at(after, "Asia/Shanghai"), n)
+            .iter()
+            .map(zoned::format_datetime)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(cron::validate("*/15 * * * *").is_ok());
+        assert!(cron::validate("0 0 12 ? * MON-FRI").is_ok());
+        assert!(cron::validate("@daily").is_ok());
+        assert!(cron::validate("* * * *").is_err());
+        assert!(cron::validate("60 * * * *").is_err());
+        assert!(cron::validate("*/0 * * * *").is_err());
+        assert!(cron::validate("5-1 * * * *").is_err());
+        assert!(cron::validate("0 0 L * *").is_err());
+        assert_eq!(
+            runs("0 0 * * 7", "2026-01-01 00:00:00", 2),
+            runs("0 0 * * sun", "2026-01-01 00:00:00", 2)
+        );
+        assert_eq!(
+            runs("0 0 1 jan-mar/2 *", "2026-01-01 00:00:00", 2),
+            vec!["2026-03-01 00:00:00", "2027-01-01 00:00:00"]
+        );
+    }
+
+    #[test]
+    fn test_next_runs() {
+        assert_eq!(
+            runs("*/15 * * * *", "2026-01-05 10:07:30", 3),
+            vec![
+                "2026-01-05 10:15:00",
+                "2026-01-05 10:30:00",
+                "2026-01-05 10:45:00"
+            ]
+        );
+        // 周五 -> 下周一
+        assert_eq!(
+            runs("0 30 9 * * MON-FRI", "2026-01-09 09:30:00", 2),
+            vec!["2026-01-12 09:30:00", "2026-01-13 09:30:00"]
+        );
+        assert_eq!(
+            runs("0 0 29 2 *", "2026-01-01 00:00:00", 2),
+            vec!["2028-02-29 00:00:00", "2032-02-29 00:00:00"]
+        );
+        assert_eq!(
+            runs("@monthly", "2026-12-15 08:00:00", 2),
+            vec!["2027-01-01 00:00:00", "2027-02-01 00:00:00"]
+        );
+        // 日、周均指定时满足其一即可：13 号或周五
+        assert_eq!(
+            runs("0 0 13 * FRI", "2026-02-01 00:00:00", 3),
+            vec![
+                "2026-02-06 00:00:00",
+                "2026-02-13 00:00:00",
+                "2026-02-20 00:00:00"
+            ]
+        );
+        assert_eq!(
+            runs("10,20 5 1-2 * * *", "2026-01-01 01:05:15", 3),
+            vec![
+                "2026-01-01 01:05:20",
+                "2026-01-01 02:05:10",
+                "2026-01-01 02:05:20"
+            ]
+        );
+        assert!(runs("0 0 30 2 *", "2026-01-01 00:00:00", 1).is_empty());
+
+        assert_eq!(cron::next_runs("@hourly", 3, "UTC").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_dst() {
+        // 2026-03-08 02:30 在纽约不存在（夏令时），顺延至 03:30
+        let s = Schedule::parse("30 2 * * *").unwrap();
+        let list = s.next_runs(&at("2026-03-07 12:00:00", "America/New_York"), 3);
+        let list: Vec<String> = list.iter().map(zoned::format_datetime).collect();
+        assert_eq!(
+            list,
+            vec![
+                "2026-03-08 03:30:00",
+                "2026-03-09 02:30:00",
+                "2026-03-10 02:30:00"
+            ]
+        );
+
+        // 2026-11-01 01:30 重复出现，仅执行一次
+        let s = Schedule::parse("30 1 * * *").unwrap();
+        let list = s.next_runs(&at("2026-10-31 12:00:00", "America/New_York"), 2);
+        let list: Vec<String> = list.iter().map(zoned::format_datetime).collect();
+        assert_eq!(list, vec!["2026-11-01 01:30:00", "2026-11-02 01:30:00"]);
+    }
+}
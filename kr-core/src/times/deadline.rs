@@ -0,0 +1,142 @@
+use std::{
+    fmt,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+tokio::task_local! {
+    static DEADLINE: Deadline;
+}
+
+/// 截止时间：在调用链中传递剩余时间，各环节按剩余时间设置超时
+///
+/// # Examples
+///
+/// ```
+/// let deadline = Deadline::after(Duration::from_secs(3));
+///
+/// // DB：超时取剩余时间
+/// let opts = sql::Opts::default().with_deadline(&deadline);
+/// let list = mysql::find_all_opts::<_, model::Demo>(&pool, stmt, opts).await?;
+///
+/// // Redis 等任意异步调用
+/// let v: Option<String> = deadline.run(conn.get("key")).await??;
+///
+/// // 在作用域内传递，下游通过 Deadline::current() 获取
+/// deadline.scope(handle(req)).await;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// 从现在起 d 之后
+    pub fn after(d: Duration) -> Self {
+        Self(Instant::now() + d)
+    }
+
+    pub fn at(instant: Instant) -> Self {
+        Self(instant)
+    }
+
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// 剩余时间（已过期为 0）
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// 取更早的截止时间
+    pub fn min(self, other: Deadline) -> Deadline {
+        std::cmp::min(self, other)
+    }
+
+    /// 在截止时间内执行，超时返回 [`Exceeded`]
+    pub async fn run<F: Future>(&self, fut: F) -> anyhow::Result<F::Output> {
+        if self.is_expired() {
+            return Err(Exceeded.into());
+        }
+        tokio::time::timeout_at(self.0.into(), fut)
+            .await
+            .map_err(|_| Exceeded.into())
+    }
+
+    /// 在作用域内设置当前截止时间（已有更早的截止时间时保留更早的）
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        let v = match Self::current() {
+            Some(cur) => cur.min(*self),
+            None => *self,
+        };
+        DEADLINE.scope(v, fut).await
+    }
+
+    /// 当前作用域的截止时间
+    pub fn current() -> Option<Deadline> {
+        DEADLINE.try_with(|v| *v).ok()
+    }
+}
+
+impl From<Deadline> for Duration {
+    fn from(v: Deadline) -> Self {
+        v.remaining()
+    }
+}
+
+/// 超过截止时间
+#[derive(Debug, Clone, Copy)]
+pub struct Exceeded;
+
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "times: deadline exceeded")
+    }
+}
+
+impl std::error::Error for Exceeded {}
+
+/// 是否为超过截止时间错误
+pub fn is_exceeded(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<Exceeded>().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::times::{deadline, Deadline};
+
+    #[tokio::test]
+    async fn test_deadline() {
+        let dl = Deadline::after(Duration::from_millis(50));
+        assert!(!dl.is_expired());
+        let remaining: Duration = dl.into();
+        assert!(remaining <= Duration::from_millis(50));
+
+        assert_eq!(dl.run(async { 1 }).await.unwrap(), 1);
+        let err = dl
+            .run(tokio::time::sleep(Duration::from_millis(100)))
+            .await
+            .unwrap_err();
+        assert!(deadline::is_exceeded(&err));
+        assert!(dl.is_expired());
+        assert_eq!(dl.remaining(), Duration::ZERO);
+        assert!(dl.run(async { 1 }).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert_eq!(Deadline::current(), None);
+
+        let outer = Deadline::after(Duration::from_secs(1));
+        let inner = Deadline::after(Duration::from_secs(10));
+        let cur = outer
+            .scope(async { inner.scope(async { Deadline::current() }).await })
+            .await;
+        assert_eq!(cur, Some(outer));
+    }
+}
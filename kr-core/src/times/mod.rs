@@ -0,0 +1,7 @@
+pub mod calendar;
+pub mod cron;
+pub mod deadline;
+pub mod stopwatch;
+
+pub use deadline::Deadline;
+pub use stopwatch::Stopwatch;
@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+/// 计时器：分段计时并输出到 tracing
+///
+/// # Examples
+///
+/// ```
+/// let mut sw = Stopwatch::start("order::create");
+///
+/// let user = load_user().await?;
+/// sw.lap("load_user");
+///
+/// let order = save_order().await?;
+/// sw.lap("save_order");
+///
+/// // 超过 200ms 时输出 warn 日志（含各分段耗时）
+/// sw.finish_slow(Duration::from_millis(200));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stopwatch {
+    name: String,
+    start: Instant,
+    last: Instant,
+    laps: Vec<(String, Duration)>,
+}
+
+impl Stopwatch {
+    pub fn start(name: impl AsRef<str>) -> Self {
+        let now = Instant::now();
+        Self {
+            name: name.as_ref().to_string(),
+            start: now,
+            last: now,
+            laps: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 总耗时
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// 记录分段，返回距上一分段的耗时
+    pub fn lap(&mut self, label: impl AsRef<str>) -> Duration {
+        let now = Instant::now();
+        let cost = now.duration_since(self.last);
+        self.last = now;
+        tracing::debug!(
+            name = self.name,
+            lap = label.as_ref(),
+            cost_ms = cost.as_millis(),
+            "[stopwatch] lap"
+        );
+        self.laps.push((label.as_ref().to_string(), cost));
+        cost
+    }
+
+    /// 各分段耗时
+    pub fn laps(&self) -> &[(String, Duration)] {
+        &self.laps
+    }
+
+    /// 重新开始计时
+    pub fn reset(&mut self) {
+        let now = Instant::now();
+        self.start = now;
+        self.last = now;
+        self.laps.clear();
+    }
+
+    /// 结束计时并输出 info 日志，返回总耗时
+    pub fn finish(self) -> Duration {
+        let cost = self.elapsed();
+        tracing::info!(
+            name = self.name,
+            cost_ms = cost.as_millis(),
+            laps = self.format_laps(),
+            "[stopwatch] finish"
+        );
+        cost
+    }
+
+    /// 结束计时，超过阈值时输出 warn 日志，返回总耗时
+    pub fn finish_slow(self, threshold: Duration) -> Duration {
+        let cost = self.elapsed();
+        if cost >= threshold {
+            tracing::warn!(
+                name = self.name,
+                cost_ms = cost.as_millis(),
+                laps = self.format_laps(),
+                "[stopwatch] slow"
+            );
+        }
+        cost
+    }
+
+    // load_user=12ms,save_order=3ms
+    fn format_laps(&self) -> String {
+        self.laps
+            .iter()
+            .map(|(k, v)| format!("{}={}ms", k, v.as_millis()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::times::Stopwatch;
+
+    #[test]
+    fn test_stopwatch() {
+        let mut sw = Stopwatch::start("test");
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(sw.lap("a") >= Duration::from_millis(10));
+        sw.lap("b");
+        assert_eq!(sw.laps().len(), 2);
+        assert_eq!(sw.laps()[1].0, "b");
+        assert!(sw.format_laps().starts_with("a="));
+
+        let total = sw.elapsed();
+        assert!(total >= Duration::from_millis(10));
+        assert!(sw.clone().finish() >= total);
+
+        sw.reset();
+        assert!(sw.laps().is_empty());
+        assert!(sw.finish_slow(Duration::from_secs(1)) < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,636 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use futures::StreamExt;
+use sea_query::{Alias, Expr, Order, Query};
+use serde::Serialize;
+use sqlx::{Executor, FromRow, Pool};
+
+use crate::{crypto::hash, idgen, sql::Dialect};
+
+/// 建表语句（PgSQL、SQLite，表名可通过 `Webhook::table` 修改；MySQL 见 [`MYSQL_SCHEMA`]）
+pub const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS webhook_delivery (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    endpoint VARCHAR(1024) NOT NULL,
+    event VARCHAR(128) NOT NULL,
+    payload TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at BIGINT NOT NULL,
+    last_status INT NULL,
+    last_error TEXT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_webhook_delivery_due ON webhook_delivery (status, next_attempt_at);
+"#;
+
+/// MySQL 建表语句（MySQL 不支持 `CREATE INDEX IF NOT EXISTS`，索引随建表创建）
+pub const MYSQL_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS webhook_delivery (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    endpoint VARCHAR(1024) NOT NULL,
+    event VARCHAR(128) NOT NULL,
+    payload TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at BIGINT NOT NULL,
+    last_status INT NULL,
+    last_error TEXT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    INDEX idx_webhook_delivery_due (status, next_attempt_at)
+);
+"#;
+
+/// 签名 Header：`t=<时间戳>,v1=<hex(hmac_sha256(secret, "<时间戳>.<body>"))>`
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// 投递状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// 待投递（含等待重试）
+    Pending,
+    /// 投递成功
+    Delivered,
+    /// 重试次数用尽
+    Failed,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pending => "pending",
+            Status::Delivered => "delivered",
+            Status::Failed => "failed",
+        }
+    }
+}
+
+/// 投递记录
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Delivery {
+    pub id: String,
+    pub endpoint: String,
+    pub event: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    /// 下次投递时间（Unix秒）
+    pub next_attempt_at: i64,
+    /// 最近一次的 HTTP 状态码
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Delivery {
+    pub fn status(&self) -> Status {
+        match self.status.as_str() {
+            "delivered" => Status::Delivered,
+            "failed" => Status::Failed,
+            _ => Status::Pending,
+        }
+    }
+}
+
+/// 待发送的 HTTP 请求（POST）
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// HTTP 发送方，返回响应状态码（2xx 视为成功）
+pub trait Sender: Send + Sync {
+    fn send(&self, req: Request) -> impl Future<Output = anyhow::Result<u16>> + Send;
+}
+
+/// 基于闭包的发送方，便于对接任意 HTTP 客户端
+///
+/// # Examples
+///
+/// ```
+/// let client = reqwest::Client::new();
+/// let sender = move |req: webhook::Request| {
+///     let client = client.clone();
+///     async move {
+///         let mut builder = client.post(&req.url).body(req.body);
+///         for (k, v) in req.headers {
+///             builder = builder.header(k, v);
+///         }
+///         Ok(builder.send().await?.status().as_u16())
+///     }
+/// };
+/// ```
+impl<F, Fut> Sender for F
+where
+    F: Fn(Request) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<u16>> + Send,
+{
+    fn send(&self, req: Request) -> impl Future<Output = anyhow::Result<u16>> + Send {
+        self(req)
+    }
+}
+
+type SecretFn = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    id: &'a str,
+    event: &'a str,
+    created_at: i64,
+    data: &'a T,
+}
+
+/// Webhook 投递：事件先落库，再由后台按指数退避重试投递（至少一次，接收方需按 `X-Webhook-Id` 幂等）
+///
+/// # Examples
+///
+/// ```
+/// let hook = Webhook::new(pool.clone(), "whsec_xxx")
+///     .max_attempts(8)
+///     .backoff(Duration::from_secs(30), Duration::from_secs(3600));
+///
+/// // 业务中（可与业务数据同一事务写入时，使用 outbox 后再调用）
+/// let id = hook.enqueue("https://example.com/hook", "order.paid", &order).await?;
+///
+/// // 后台投递
+/// let dispatcher = hook.clone();
+/// worker::Loop::new("webhook", Duration::from_secs(1)).spawn(move || {
+///     let hook = dispatcher.clone();
+///     let sender = sender.clone();
+///     async move { Ok(hook.dispatch(&sender).await? > 0) }
+/// });
+///
+/// // 查询投递状态
+/// let delivery = hook.get(&id).await?;
+/// ```
+pub struct Webhook<DB: Dialect> {
+    pool: Pool<DB>,
+    table: String,
+    secret: String,
+    secret_fn: Option<SecretFn>,
+    max_attempts: i32,
+    base_backoff: Duration,
+    max_backoff: Duration,
+    timeout: Duration,
+    batch: u64,
+    concurrency: usize,
+}
+
+impl<DB: Dialect> Clone for Webhook<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            table: self.table.clone(),
+            secret: self.secret.clone(),
+            secret_fn: self.secret_fn.clone(),
+            max_attempts: self.max_attempts,
+            base_backoff: self.base_backoff,
+            max_backoff: self.max_backoff,
+            timeout: self.timeout,
+            batch: self.batch,
+            concurrency: self.concurrency,
+        }
+    }
+}
+
+impl<DB> Webhook<DB>
+where
+    DB: Dialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    Delivery: for<'r> FromRow<'r, DB::Row>,
+{
+    /// 默认：最多投递 8 次、退避 10s ~ 1h、单次超时 10s、每批 100 条、并发 8
+    pub fn new(pool: Pool<DB>, secret: impl AsRef<str>) -> Self {
+        Self {
+            pool,
+            table: "webhook_delivery".to_string(),
+            secret: secret.as_ref().to_string(),
+            secret_fn: None,
+            max_attempts: 8,
+            base_backoff: Duration::from_secs(10),
+            max_backoff: Duration::from_secs(3600),
+            timeout: Duration::from_secs(10),
+            batch: 100,
+            concurrency: 8,
+        }
+    }
+
+    /// 表名
+    pub fn table(mut self, name: impl AsRef<str>) -> Self {
+        self.table = name.as_ref().to_string();
+        self
+    }
+
+    /// 按投递地址获取签名密钥（如：每个订阅方单独的密钥），返回 `None` 时使用默认密钥
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let hook = Webhook::new(pool, "whsec_default")
+    ///     .secret_with(move |endpoint| subscriptions.secret_of(endpoint));
+    /// ```
+    pub fn secret_with<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.secret_fn = Some(Arc::new(f));
+        self
+    }
+
+    /// 最大投递次数，用尽后状态为 failed
+    pub fn max_attempts(mut self, n: u32) -> Self {
+        self.max_attempts = n.max(1) as i32;
+        self
+    }
+
+    /// 重试间隔：base * 2^(attempts-1)，不超过 max
+    pub fn backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_backoff = base;
+        self.max_backoff = max;
+        self
+    }
+
+    /// 单次投递超时
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = d;
+        self
+    }
+
+    /// 每次 `dispatch` 处理的最大条数及并发数
+    pub fn batch(mut self, size: u64, concurrency: usize) -> Self {
+        self.batch = size.max(1);
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    fn col(name: &str) -> Alias {
+        Alias::new(name)
+    }
+
+    /// 写入待投递事件，返回投递ID
+    pub async fn enqueue<T: Serialize>(
+        &self,
+        endpoint: impl AsRef<str>,
+        event: impl AsRef<str>,
+        data: &T,
+    ) -> anyhow::Result<String> {
+        let id = idgen::uuid_v7().to_string();
+        let now = now();
+        let payload = serde_json::to_string(&Envelope {
+            id: &id,
+            event: event.as_ref(),
+            created_at: now,
+            data,
+        })?;
+
+        let stmt = Query::insert()
+            .into_table(Self::col(&self.table))
+            .columns(
+                [
+                    "id",
+                    "endpoint",
+                    "event",
+                    "payload",
+                    "status",
+                    "attempts",
+                    "next_attempt_at",
+                    "created_at",
+                    "updated_at",
+                ]
+                .map(Self::col),
+            )
+            .values_panic([
+                id.clone().into(),
+                endpoint.as_ref().into(),
+                event.as_ref().into(),
+                payload.into(),
+                Status::Pending.as_str().into(),
+                0.into(),
+                now.into(),
+                now.into(),
+                now.into(),
+            ])
+            .to_owned();
+        DB::insert(&self.pool, stmt).await?;
+        Ok(id)
+    }
+
+    /// 投递到期的事件，返回处理条数（含失败）
+    pub async fn dispatch<S: Sender>(&self, sender: &S) -> anyhow::Result<usize> {
+        let stmt = Query::select()
+            .expr(Expr::cust("*"))
+            .from(Self::col(&self.table))
+            .and_where(Expr::col(Self::col("status")).eq(Status::Pending.as_str()))
+            .and_where(Expr::col(Self::col("next_attempt_at")).lte(now()))
+            .order_by(Self::col("next_attempt_at"), Order::Asc)
+            .limit(self.batch)
+            .to_owned();
+        let due: Vec<Delivery> = DB::select(&self.pool, stmt).await?;
+
+        let results: Vec<anyhow::Result<bool>> = futures::stream::iter(due)
+            .map(|v| self.deliver(sender, v))
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        let mut count = 0;
+        for ret in results {
+            if ret? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    // 抢占并投递单条，其它实例已抢占时返回 false
+    async fn deliver<S: Sender>(&self, sender: &S, delivery: Delivery) -> anyhow::Result<bool> {
+        // 以 next_attempt_at 作乐观锁，抢占期间（超时的两倍）其它实例不会重复投递
+        let lease = now() + (self.timeout.as_secs() * 2).max(1) as i64;
+        let stmt = Query::update()
+            .table(Self::col(&self.table))
+            .value(Self::col("next_attempt_at"), lease)
+            .and_where(Expr::col(Self::col("id")).eq(&delivery.id))
+            .and_where(Expr::col(Self::col("status")).eq(Status::Pending.as_str()))
+            .and_where(Expr::col(Self::col("next_attempt_at")).eq(delivery.next_attempt_at))
+            .to_owned();
+        if DB::update(&self.pool, stmt).await? == 0 {
+            return Ok(false);
+        }
+
+        let secret = self
+            .secret_fn
+            .as_ref()
+            .and_then(|f| f(&delivery.endpoint))
+            .unwrap_or_else(|| self.secret.clone());
+        let ts = now();
+        let req = Request {
+            url: delivery.endpoint.clone(),
+            headers: vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("X-Webhook-Id".to_string(), delivery.id.clone()),
+                ("X-Webhook-Event".to_string(), delivery.event.clone()),
+                (
+                    SIGNATURE_HEADER.to_string(),
+                    sign(&secret, ts, &delivery.payload),
+                ),
+            ],
+            body: delivery.payload.clone(),
+        };
+        let ret = match tokio::time::timeout(self.timeout, sender.send(req)).await {
+            Ok(Ok(code)) if (200..300).contains(&code) => Ok(code),
+            Ok(Ok(code)) => Err((Some(code), format!("unexpected status {}", code))),
+            Ok(Err(e)) => Err((None, format!("{:#}", e))),
+            Err(_) => Err((None, format!("timeout after {:?}", self.timeout))),
+        };
+
+        let attempts = delivery.attempts + 1;
+        let now = now();
+        let mut stmt = Query::update();
+        stmt.table(Self::col(&self.table))
+            .value(Self::col("attempts"), attempts)
+            .value(Self::col("updated_at"), now)
+            .and_where(Expr::col(Self::col("id")).eq(&delivery.id));
+        match ret {
+            Ok(code) => {
+                stmt.value(Self::col("status"), Status::Delivered.as_str())
+                    .value(Self::col("last_status"), code as i32)
+                    .value(Self::col("last_error"), Option::<String>::None);
+            }
+            Err((code, err)) => {
+                stmt.value(Self::col("last_status"), code.map(|v| v as i32))
+                    .value(Self::col("last_error"), err.clone());
+                if attempts >= self.max_attempts {
+                    stmt.value(Self::col("status"), Status::Failed.as_str());
+                    tracing::error!(
+                        id = delivery.id,
+                        endpoint = delivery.endpoint,
+                        attempts = attempts,
+                        err = err,
+                        "[webhook] delivery failed"
+                    );
+                } else {
+                    let delay = self.backoff_of(attempts);
+                    stmt.value(Self::col("next_attempt_at"), now + delay.as_secs() as i64);
+                    tracing::warn!(
+                        id = delivery.id,
+                        endpoint = delivery.endpoint,
+                        attempts = attempts,
+                        retry_in_secs = delay.as_secs(),
+                        err = err,
+                        "[webhook] delivery retry"
+                    );
+                }
+            }
+        }
+        DB::update(&self.pool, stmt).await?;
+        Ok(true)
+    }
+
+    fn backoff_of(&self, attempts: i32) -> Duration {
+        let factor = 1u32 << (attempts - 1).clamp(0, 16);
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// 查询投递记录
+    pub async fn get(&self, id: impl AsRef<str>) -> anyhow::Result<Option<Delivery>> {
+        let stmt = Query::select()
+            .expr(Expr::cust("*"))
+            .from(Self::col(&self.table))
+            .and_where(Expr::col(Self::col("id")).eq(id.as_ref()))
+            .limit(1)
+            .to_owned();
+        let rows: Vec<Delivery> = DB::select(&self.pool, stmt).await?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// 按状态查询（最新的在前）
+    pub async fn list(&self, status: Status, limit: u64) -> anyhow::Result<Vec<Delivery>> {
+        let stmt = Query::select()
+            .expr(Expr::cust("*"))
+            .from(Self::col(&self.table))
+            .and_where(Expr::col(Self::col("status")).eq(status.as_str()))
+            .order_by(Self::col("created_at"), Order::Desc)
+            .limit(limit)
+            .to_owned();
+        DB::select(&self.pool, stmt).await
+    }
+
+    /// 将失败的投递重置为待投递（立即投递，重新计算次数），返回是否重置
+    pub async fn redeliver(&self, id: impl AsRef<str>) -> anyhow::Result<bool> {
+        let now = now();
+        let stmt = Query::update()
+            .table(Self::col(&self.table))
+            .value(Self::col("status"), Status::Pending.as_str())
+            .value(Self::col("attempts"), 0)
+            .value(Self::col("next_attempt_at"), now)
+            .value(Self::col("updated_at"), now)
+            .and_where(Expr::col(Self::col("id")).eq(id.as_ref()))
+            .and_where(Expr::col(Self::col("status")).eq(Status::Failed.as_str()))
+            .to_owned();
+        Ok(DB::update(&self.pool, stmt).await? == 1)
+    }
+}
+
+/// 生成签名 Header 的值
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mac = hash::hmac_sha256::<String>(secret, format!("{}.{}", timestamp, body));
+    format!("t={},v1={}", timestamp, mac)
+}
+
+/// 接收方校验签名（`tolerance` 为允许的时间偏差，防重放）
+///
+/// # Examples
+///
+/// ```
+/// let header = req.header(webhook::SIGNATURE_HEADER).unwrap_or_default();
+/// webhook::verify("whsec_xxx", header, &body, Duration::from_secs(300))?;
+/// ```
+pub fn verify(secret: &str, header: &str, body: &str, tolerance: Duration) -> anyhow::Result<()> {
+    let mut ts = None;
+    let mut sig = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", v)) => ts = v.parse::<i64>().ok(),
+            Some(("v1", v)) => sig = Some(v),
+            _ => {}
+        }
+    }
+    let (Some(ts), Some(sig)) = (ts, sig) else {
+        return Err(anyhow!("webhook: malformed signature"));
+    };
+    if (now() - ts).unsigned_abs() > tolerance.as_secs() {
+        return Err(anyhow!("webhook: signature expired"));
+    }
+
+    let expected = hash::hmac_sha256::<String>(secret, format!("{}.{}", ts, body));
+    // memcmp::eq 要求长度一致
+    if sig.len() != expected.len() || !openssl::memcmp::eq(sig.as_bytes(), expected.as_bytes()) {
+        return Err(anyhow!("webhook: invalid signature"));
+    }
+    Ok(())
+}
+
+fn now() -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use serde_json::json;
+
+    use crate::{
+        sql,
+        webhook::{self, Request, Status, Webhook, SCHEMA, SIGNATURE_HEADER},
+    };
+
+    #[tokio::test]
+    async fn test_webhook() {
+        let pool = sql::test::memory_pool(Some(SCHEMA)).await.unwrap();
+        // 可重复执行
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.unwrap();
+        let hook = Webhook::new(pool, "secret")
+            .secret_with(|endpoint| {
+                endpoint
+                    .starts_with("https://b.com")
+                    .then(|| "b-secret".into())
+            })
+            .max_attempts(2)
+            .backoff(Duration::ZERO, Duration::ZERO);
+
+        let ok_id = hook
+            .enqueue("https://a.com/hook", "order.paid", &json!({"order": 1}))
+            .await
+            .unwrap();
+        let bad_id = hook
+            .enqueue("https://b.com/hook", "order.paid", &json!({"order": 2}))
+            .await
+            .unwrap();
+
+        let received: Arc<Mutex<Vec<Request>>> = Arc::default();
+        let log = received.clone();
+        let sender = move |req: Request| {
+            let log = log.clone();
+            async move {
+                let ok = req.url.starts_with("https://a.com");
+                log.lock().unwrap().push(req);
+                Ok(if ok { 200 } else { 500 })
+            }
+        };
+
+        assert_eq!(hook.dispatch(&sender).await.unwrap(), 2);
+        let v = hook.get(&ok_id).await.unwrap().unwrap();
+        assert_eq!(
+            (v.status(), v.attempts, v.last_status),
+            (Status::Delivered, 1, Some(200))
+        );
+        let v = hook.get(&bad_id).await.unwrap().unwrap();
+        assert_eq!((v.status(), v.attempts), (Status::Pending, 1));
+        assert_eq!(v.last_error.as_deref(), Some("unexpected status 500"));
+
+        // 接收方校验签名
+        {
+            let reqs = received.lock().unwrap();
+            let req = reqs
+                .iter()
+                .find(|v| v.url.starts_with("https://a.com"))
+                .unwrap();
+            let header = &req
+                .headers
+                .iter()
+                .find(|(k, _)| k == SIGNATURE_HEADER)
+                .unwrap()
+                .1;
+            assert!(webhook::verify("secret", header, &req.body, Duration::from_secs(60)).is_ok());
+            assert!(webhook::verify("other", header, &req.body, Duration::from_secs(60)).is_err());
+            assert!(webhook::verify("secret", header, "{}", Duration::from_secs(60)).is_err());
+            let body: serde_json::Value = serde_json::from_str(&req.body).unwrap();
+            assert_eq!(body["id"], ok_id);
+            assert_eq!(body["data"]["order"], 1);
+
+            // 按投递地址使用各自的密钥
+            let req = reqs
+                .iter()
+                .find(|v| v.url.starts_with("https://b.com"))
+                .unwrap();
+            let header = &req
+                .headers
+                .iter()
+                .find(|(k, _)| k == SIGNATURE_HEADER)
+                .unwrap()
+                .1;
+            assert!(
+                webhook::verify("b-secret", header, &req.body, Duration::from_secs(60)).is_ok()
+            );
+            assert!(webhook::verify("secret", header, &req.body, Duration::from_secs(60)).is_err());
+        }
+
+        // 重试次数用尽
+        assert_eq!(hook.dispatch(&sender).await.unwrap(), 1);
+        assert_eq!(hook.dispatch(&sender).await.unwrap(), 0);
+        let failed = hook.list(Status::Failed, 10).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            (failed[0].id.as_str(), failed[0].attempts),
+            (bad_id.as_str(), 2)
+        );
+
+        // 手动重新投递
+        assert!(hook.redeliver(&bad_id).await.unwrap());
+        assert!(!hook.redeliver(&ok_id).await.unwrap());
+        assert_eq!(hook.list(Status::Pending, 10).await.unwrap().len(), 1);
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+}
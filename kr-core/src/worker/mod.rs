@@ -0,0 +1,249 @@
+use std::{
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::FutureExt;
+use rand::Rng;
+use tokio_util::sync::CancellationToken;
+
+pub use tokio_util::sync::CancellationToken as Shutdown;
+
+/// 轮询状态
+#[derive(Debug, Default)]
+pub struct Status {
+    runs: AtomicU64,
+    failures: AtomicU32,
+}
+
+impl Status {
+    /// 累计执行次数
+    pub fn runs(&self) -> u64 {
+        self.runs.load(Ordering::Relaxed)
+    }
+
+    /// 连续失败次数（成功后清零）
+    pub fn failures(&self) -> u32 {
+        self.failures.load(Ordering::Relaxed)
+    }
+}
+
+/// 后台轮询循环：按间隔（+ 随机抖动）执行，连续失败时指数退避，收到关闭信号后在当前轮次结束时退出
+///
+/// 轮询函数返回 `Ok(true)` 表示还有待处理的数据，让出调度后立即执行下一轮（适用于 outbox、队列消费等）；
+/// 轮询函数 panic 时记录日志并按失败处理，循环不会退出
+///
+/// # Examples
+///
+/// ```
+/// let shutdown = worker::Shutdown::new();
+///
+/// let outbox = worker::Loop::new("outbox", Duration::from_secs(1))
+///     .jitter(Duration::from_millis(200))
+///     .max_backoff(Duration::from_secs(60))
+///     .shutdown(shutdown.clone());
+/// let status = outbox.status();
+/// let handle = outbox.spawn(move || {
+///     let pool = pool.clone();
+///     async move { relay_outbox(&pool).await }
+/// });
+///
+/// // 退出时
+/// shutdown.cancel();
+/// handle.await?;
+/// ```
+pub struct Loop {
+    name: String,
+    interval: Duration,
+    jitter: Duration,
+    max_backoff: Duration,
+    shutdown: CancellationToken,
+    status: Arc<Status>,
+}
+
+impl Loop {
+    pub fn new(name: impl AsRef<str>, interval: Duration) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            interval,
+            jitter: Duration::ZERO,
+            max_backoff: Duration::from_secs(60),
+            shutdown: CancellationToken::new(),
+            status: Arc::new(Status::default()),
+        }
+    }
+
+    /// 每轮间隔额外增加 0 ~ jitter 的随机时长，避免多实例同时轮询
+    pub fn jitter(mut self, d: Duration) -> Self {
+        self.jitter = d;
+        self
+    }
+
+    /// 连续失败时的最大退避间隔，默认：60s
+    pub fn max_backoff(mut self, d: Duration) -> Self {
+        self.max_backoff = d;
+        self
+    }
+
+    /// 关闭信号
+    pub fn shutdown(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    pub fn status(&self) -> Arc<Status> {
+        self.status.clone()
+    }
+
+    pub fn spawn<F, Fut>(self, f: F) -> tokio::task::JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<bool>> + Send + 'static,
+    {
+        tokio::spawn(self.run(f))
+    }
+
+    /// 执行直到收到关闭信号
+    pub async fn run<F, Fut>(self, mut f: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<bool>>,
+    {
+        tracing::info!(worker = self.name, "[worker::loop] started");
+        while !self.shutdown.is_cancelled() {
+            self.status.runs.fetch_add(1, Ordering::Relaxed);
+            let ret = match std::panic::catch_unwind(AssertUnwindSafe(&mut f)) {
+                Ok(fut) => AssertUnwindSafe(fut).catch_unwind().await,
+                Err(e) => Err(e),
+            };
+            let ret = ret.unwrap_or_else(|e| {
+                let msg = e
+                    .downcast_ref::<&str>()
+                    .map(|v| v.to_string())
+                    .or_else(|| e.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                Err(anyhow::anyhow!("worker/loop: panicked: {}", msg))
+            });
+            let delay = match ret {
+                Ok(more) => {
+                    let failures = self.status.failures.swap(0, Ordering::Relaxed);
+                    if failures > 0 {
+                        tracing::info!(
+                            worker = self.name,
+                            failures = failures,
+                            "[worker::loop] recovered"
+                        );
+                    }
+                    if more {
+                        // 避免一直有数据时独占工作线程
+                        tokio::task::yield_now().await;
+                        continue;
+                    }
+                    self.interval
+                }
+                Err(e) => {
+                    let failures = self.status.failures.fetch_add(1, Ordering::Relaxed) + 1;
+                    let delay = self.backoff(failures);
+                    tracing::error!(
+                        worker = self.name,
+                        failures = failures,
+                        backoff_ms = delay.as_millis(),
+                        err = ?e,
+                        "[worker::loop] poll failed"
+                    );
+                    delay
+                }
+            };
+
+            let delay = delay + self.random_jitter();
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        tracing::info!(worker = self.name, "[worker::loop] stopped");
+    }
+
+    // interval * 2^(failures-1)，不超过 max_backoff
+    fn backoff(&self, failures: u32) -> Duration {
+        let factor = 1u32 << (failures - 1).min(16);
+        self.interval
+            .saturating_mul(factor)
+            .min(self.max_backoff.max(self.interval))
+    }
+
+    fn random_jitter(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return Duration::ZERO;
+        }
+        let ms = rand::thread_rng().gen_range(0..=self.jitter.as_millis() as u64);
+        Duration::from_millis(ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::worker::{Loop, Shutdown};
+
+    #[tokio::test]
+    async fn test_loop() {
+        let shutdown = Shutdown::new();
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let lp = Loop::new("test", Duration::from_millis(10))
+            .max_backoff(Duration::from_millis(40))
+            .shutdown(shutdown.clone());
+        assert_eq!(lp.backoff(1), Duration::from_millis(10));
+        assert_eq!(lp.backoff(3), Duration::from_millis(40));
+        assert_eq!(lp.backoff(100), Duration::from_millis(40));
+
+        let status = lp.status();
+        let n = calls.clone();
+        let handle = lp.spawn(move || {
+            let n = n.clone();
+            async move {
+                match n.fetch_add(1, Ordering::SeqCst) {
+                    // 连续失败 2 次（含 panic）
+                    0 => Err(anyhow::anyhow!("oops")),
+                    1 => panic!("boom"),
+                    // 还有数据，立即执行下一轮
+                    2 => Ok(true),
+                    _ => Ok(false),
+                }
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(status.failures(), 1);
+        for _ in 0..100 {
+            if calls.load(Ordering::SeqCst) >= 4 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(calls.load(Ordering::SeqCst) >= 4);
+        assert_eq!(status.failures(), 0);
+
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_millis(100), handle)
+            .await
+            .unwrap()
+            .unwrap();
+        let runs = status.runs();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(status.runs(), runs);
+    }
+}
@@ -0,0 +1,234 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Expr, Field, LitStr};
+
+use crate::derives::{encrypt_fields, is_json_field, unwrap_option};
+
+/// 字段上的 #[factory(...)]
+enum FieldMode {
+    /// 随机生成（默认）
+    Fake,
+    /// #[factory(skip)]：不参与写入（如自增主键），取默认值
+    Skip,
+    /// #[factory(default)]：取默认值
+    Default,
+    /// #[factory(value = expr)]：指定默认值
+    Value(Expr),
+}
+
+pub fn expand_factory(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        syn::Data::Struct(s) => &s.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Factory can only be derived for structs",
+            ))
+        }
+    };
+
+    // 解析 #[factory(table = "...")]，默认为结构体名的 snake_case
+    let mut table = snake_case(&input.ident.to_string());
+    for attr in &input.attrs {
+        if attr.path().is_ident("factory") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("table") {
+                    table = meta.value()?.parse::<LitStr>()?.value();
+                    return Ok(());
+                }
+                Err(meta.error("expected `table = \"...\"`"))
+            })?;
+        }
+    }
+
+    let ident = &input.ident;
+    let factory = format_ident!("{}Factory", ident);
+    let encrypt = encrypt_fields(&input.attrs)?;
+
+    let mut inits = Vec::new();
+    let mut setters = Vec::new();
+    let mut columns = Vec::new();
+    let mut values = Vec::new();
+    for f in fields.iter() {
+        let name = f
+            .ident
+            .as_ref()
+            .ok_or_else(|| syn::Error::new_spanned(f, "Factory requires named fields"))?;
+        let ty = &f.ty;
+
+        let mode = field_mode(f)?;
+        let init = match &mode {
+            FieldMode::Fake => quote! { <#ty as ::kr::sql::seed::Fake>::fake() },
+            FieldMode::Skip | FieldMode::Default => quote! { ::core::default::Default::default() },
+            FieldMode::Value(expr) => quote! { (#expr).into() },
+        };
+        inits.push(quote! { #name: #init });
+
+        setters.push(quote! {
+            pub fn #name(mut self, v: impl Into<#ty>) -> Self {
+                self.inner.#name = v.into();
+                self
+            }
+        });
+
+        if !matches!(mode, FieldMode::Skip) {
+            let column = column_name(f)?;
+            columns.push(quote! { #column });
+            let optional = !std::ptr::eq(unwrap_option(ty), ty);
+            let value = if is_json_field(f)? {
+                if optional {
+                    quote! { ::kr::sql::json_value(self.#name.as_ref())? }
+                } else {
+                    quote! { ::kr::sql::json_value(Some(&self.#name))? }
+                }
+            } else if !encrypt.contains(name) {
+                quote! { self.#name }
+            } else if optional {
+                quote! { self.#name.as_ref().map(::kr::crypto::field::encrypt).transpose()? }
+            } else {
+                quote! { ::kr::crypto::field::encrypt(&self.#name)? }
+            };
+            values.push(quote! { sea_query::SimpleExpr::from(#value) });
+        }
+    }
+
+    let doc = format!("`{}` 测试数据构造器", ident);
+    Ok(quote! {
+        #[doc = #doc]
+        pub struct #factory {
+            inner: #ident,
+        }
+
+        impl #factory {
+            pub fn new() -> Self {
+                Self {
+                    inner: #ident {
+                        #(#inits,)*
+                    },
+                }
+            }
+
+            #(#setters)*
+
+            pub fn build(self) -> #ident {
+                self.inner
+            }
+
+            /// 批量生成 n 条随机数据
+            pub fn batch(n: usize) -> Vec<#ident> {
+                (0..n).map(|_| Self::new().build()).collect()
+            }
+
+            /// 写入数据库，返回写入行数
+            pub async fn create<'e, E>(self, db: E) -> anyhow::Result<u64>
+            where
+                E: sqlx::Executor<'e> + Copy + 'e,
+                E::Database: ::kr::sql::Dialect,
+            {
+                ::kr::sql::factory::create(db, self.build()).await
+            }
+
+            /// 批量写入 n 条随机数据，返回写入行数
+            pub async fn create_many<'e, E>(db: E, n: usize) -> anyhow::Result<u64>
+            where
+                E: sqlx::Executor<'e> + Copy + 'e,
+                E::Database: ::kr::sql::Dialect,
+            {
+                ::kr::sql::factory::create_many(db, n, |_| Self::new().build()).await
+            }
+        }
+
+        impl ::core::default::Default for #factory {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ::kr::sql::seed::Seed for #ident {
+            fn table() -> &'static str {
+                #table
+            }
+
+            fn columns() -> Vec<&'static str> {
+                vec![#(#columns),*]
+            }
+
+            fn values(self) -> anyhow::Result<Vec<sea_query::SimpleExpr>> {
+                Ok(vec![#(#values),*])
+            }
+        }
+    })
+}
+
+fn field_mode(f: &Field) -> syn::Result<FieldMode> {
+    let mut mode = FieldMode::Fake;
+    for attr in &f.attrs {
+        if attr.path().is_ident("factory") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    mode = FieldMode::Skip;
+                    return Ok(());
+                }
+                if meta.path.is_ident("default") {
+                    mode = FieldMode::Default;
+                    return Ok(());
+                }
+                if meta.path.is_ident("value") {
+                    mode = FieldMode::Value(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                Err(meta.error("expected `skip`, `default` or `value = ...`"))
+            })?;
+        }
+    }
+    Ok(mode)
+}
+
+/// 列名：优先 #[sqlx(rename = "...")]
+fn column_name(f: &Field) -> syn::Result<String> {
+    let mut name = f
+        .ident
+        .as_ref()
+        .unwrap()
+        .to_string()
+        .trim_start_matches("r#")
+        .to_string();
+    for attr in &f.attrs {
+        if attr.path().is_ident("sqlx") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.input.peek(syn::Token![=]) {
+                    // 忽略其他 sqlx 属性
+                    meta.value()?.parse::<Expr>()?;
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(name)
+}
+
+fn snake_case(s: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -1,12 +1,37 @@
+pub mod factory;
 pub mod model;
 
 use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    Ident, Path, Token,
+    Attribute, Field, GenericArgument, Ident, Path, PathArguments, Token, Type,
 };
 
+/// 解析结构体上的 #[model(...)]
+enum ModelAttr {
+    /// #[model(Target (...))] 或 #[model(Target !(...))]
+    Partial(PartialAttr),
+    /// #[model(encrypt(field1, field2))]
+    Encrypt(Vec<Ident>),
+}
+
+impl Parse for ModelAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        let kw: Ident = fork.parse()?;
+        if kw == "encrypt" && fork.peek(syn::token::Paren) {
+            input.parse::<Ident>()?;
+            let content;
+            parenthesized!(content in input);
+            let list: Punctuated<Ident, Token![,]> =
+                content.parse_terminated(Ident::parse, Token![,])?;
+            return Ok(Self::Encrypt(list.into_iter().collect()));
+        }
+        Ok(Self::Partial(input.parse()?))
+    }
+}
+
 /// 解析 #[partial(Target { ... })] 或 #[partial(Target !{ ... })]
 struct PartialAttr {
     target: Ident,
@@ -62,3 +87,47 @@ impl Parse for PartialAttr {
         })
     }
 }
+
+/// Option<T> -> T
+fn unwrap_option(ty: &Type) -> &Type {
+    if let Type::Path(p) = ty {
+        if let Some(seg) = p.path.segments.last() {
+            if seg.ident == "Option" {
+                if let PathArguments::AngleBracketed(args) = &seg.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return inner;
+                    }
+                }
+            }
+        }
+    }
+    ty
+}
+
+/// 结构体上 #[model(encrypt(...))] 指定的加密字段
+fn encrypt_fields(attrs: &[Attribute]) -> syn::Result<Vec<Ident>> {
+    let mut fields = Vec::new();
+    for attr in attrs {
+        if attr.path().is_ident("model") {
+            if let ModelAttr::Encrypt(list) = attr.parse_args::<ModelAttr>()? {
+                fields.extend(list);
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// 字段是否标注了 #[model(json)]
+fn is_json_field(f: &Field) -> syn::Result<bool> {
+    let mut json = false;
+    for attr in &f.attrs {
+        if attr.path().is_ident("model") {
+            let kw: Ident = attr.parse_args()?;
+            if kw != "json" {
+                return Err(syn::Error::new_spanned(kw, "expected `json`"));
+            }
+            json = true;
+        }
+    }
+    Ok(json)
+}
@@ -1,9 +1,9 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use syn::DeriveInput;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Field, Ident};
 
-use crate::derives::PartialAttr;
+use crate::derives::{is_json_field, unwrap_option, ModelAttr, PartialAttr};
 
 pub fn expand_sqlx_model(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse_macro_input!(input as DeriveInput);
@@ -16,58 +16,232 @@ pub fn expand_sqlx_model(input: TokenStream) -> TokenStream {
         }
     };
 
+    // 解析字段上的 #[model(json)]
+    let mut json_fields: Vec<Ident> = Vec::new();
+    for f in fields.iter() {
+        match is_json_field(f) {
+            Ok(true) => json_fields.extend(f.ident.clone()),
+            Ok(false) => {}
+            Err(e) => return e.to_compile_error().into(),
+        }
+    }
+
     // 解析所有 #[model(...)]
-    let mut generated: Vec<TokenStream2> = Vec::new();
+    let mut partials: Vec<PartialAttr> = Vec::new();
+    let mut encrypt_fields: Vec<Ident> = Vec::new();
     for attr in &input.attrs {
         if attr.path().is_ident("model") {
-            match attr.parse_args::<PartialAttr>() {
-                Ok(p) => {
-                    let target_ident = &p.target;
-
-                    // 根据 include/exclude 模式筛选字段
-                    let keep_fields: Vec<_> = fields
-                        .iter()
-                        .filter(|f| {
-                            let ident = f.ident.as_ref().unwrap();
-                            if p.exclude {
-                                !p.fields.iter().any(|ex| ex == ident)
-                            } else {
-                                p.fields.iter().any(|ex| ex == ident)
-                            }
-                        })
-                        .collect();
-
-                    // 生成字段定义（保留属性）
-                    let gen_fields = keep_fields.iter().map(|f| {
-                        let ident = f.ident.as_ref().unwrap();
-                        let ty = &f.ty;
-                        let attrs = &f.attrs;
-                        quote! {
-                            #(#attrs)*
-                            pub #ident: #ty
-                        }
-                    });
-
-                    // 合并 derives: 默认(sqlx::FromRow) + 用户自定义
-                    let mut derives = Vec::new();
-                    derives.push(syn::parse_quote!(sqlx::FromRow));
-                    for d in p.derives {
-                        derives.push(d);
-                    }
-                    let derive_attr = quote! {
-                        #[derive(#(#derives),*)]
-                    };
-
-                    generated.push(quote! {
-                        #derive_attr
-                        pub struct #target_ident {
-                            #(#gen_fields,)*
-                        }
-                    });
-                }
+            match attr.parse_args::<ModelAttr>() {
+                Ok(ModelAttr::Partial(p)) => partials.push(p),
+                Ok(ModelAttr::Encrypt(list)) => encrypt_fields.extend(list),
                 Err(e) => return e.to_compile_error().into(),
             }
         }
     }
+    for ident in &encrypt_fields {
+        if !fields.iter().any(|f| f.ident.as_ref() == Some(ident)) {
+            return syn::Error::new_spanned(ident, "unknown field")
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    let mut generated: Vec<TokenStream2> = vec![expand_encrypt(
+        &input.ident,
+        fields.iter().collect(),
+        &encrypt_fields,
+    )];
+    // 有加密或 JSON 字段时由 Model 生成 FromRow（JSON 字段经 sqlx::types::Json 解码，读取后自动解密），
+    // 原结构体不再派生 sqlx::FromRow
+    if !encrypt_fields.is_empty() || !json_fields.is_empty() {
+        generated.push(expand_from_row(
+            &input.ident,
+            &fields.iter().collect::<Vec<_>>(),
+            &json_fields,
+            !encrypt_fields.is_empty(),
+        ));
+    }
+    for p in partials {
+        let target_ident = &p.target;
+
+        // 根据 include/exclude 模式筛选字段
+        let keep_fields: Vec<_> = fields
+            .iter()
+            .filter(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                if p.exclude {
+                    !p.fields.iter().any(|ex| ex == ident)
+                } else {
+                    p.fields.iter().any(|ex| ex == ident)
+                }
+            })
+            .collect();
+
+        // 合并 derives: 默认(sqlx::FromRow) + 用户自定义；含加密或 JSON 字段时单独生成 FromRow
+        let encrypted = keep_fields
+            .iter()
+            .any(|f| encrypt_fields.iter().any(|v| f.ident.as_ref() == Some(v)));
+        let json = keep_fields
+            .iter()
+            .any(|f| json_fields.iter().any(|v| f.ident.as_ref() == Some(v)));
+        let custom_row = encrypted || json;
+
+        // 生成字段定义（保留属性，#[model(...)]、#[factory(...)] 及单独生成 FromRow 时的 #[sqlx(...)] 除外）
+        let gen_fields = keep_fields.iter().map(|f| {
+            let ident = f.ident.as_ref().unwrap();
+            let ty = &f.ty;
+            let attrs = f.attrs.iter().filter(|a| {
+                let path = a.path();
+                let skip = path.is_ident("model")
+                    || path.is_ident("factory")
+                    || (custom_row && path.is_ident("sqlx"));
+                !skip
+            });
+            quote! {
+                #(#attrs)*
+                pub #ident: #ty
+            }
+        });
+
+        let mut derives = Vec::new();
+        if !custom_row {
+            derives.push(syn::parse_quote!(sqlx::FromRow));
+        }
+        for d in p.derives {
+            derives.push(d);
+        }
+        let derive_attr = quote! {
+            #[derive(#(#derives),*)]
+        };
+
+        generated.push(quote! {
+            #derive_attr
+            pub struct #target_ident {
+                #(#gen_fields,)*
+            }
+        });
+        if custom_row {
+            generated.push(expand_from_row(
+                target_ident,
+                &keep_fields,
+                &json_fields,
+                encrypted,
+            ));
+        }
+        generated.push(expand_encrypt(target_ident, keep_fields, &encrypt_fields));
+    }
+
     quote! { #(#generated)* }.into()
 }
+
+/// 为包含加密字段的结构体生成 encrypt_fields / decrypt_fields（支持 String、Option<String>）
+fn expand_encrypt(ident: &Ident, fields: Vec<&Field>, encrypt: &[Ident]) -> TokenStream2 {
+    let targets: Vec<&Field> = fields
+        .into_iter()
+        .filter(|f| encrypt.iter().any(|v| f.ident.as_ref() == Some(v)))
+        .collect();
+    if targets.is_empty() {
+        return quote! {};
+    }
+
+    let stmts = |func: TokenStream2| {
+        targets.iter().map(move |f| {
+            let name = f.ident.as_ref().unwrap();
+            if !std::ptr::eq(unwrap_option(&f.ty), &f.ty) {
+                quote! {
+                    if let Some(v) = &self.#name {
+                        self.#name = Some(#func(v)?);
+                    }
+                }
+            } else {
+                quote! {
+                    self.#name = #func(&self.#name)?;
+                }
+            }
+        })
+    };
+    let enc = stmts(quote!(::kr::crypto::field::encrypt));
+    let dec = stmts(quote!(::kr::crypto::field::decrypt));
+
+    quote! {
+        impl #ident {
+            /// 加密字段（`Factory` 生成的写入已自动调用）
+            pub fn encrypt_fields(&mut self) -> ::kr::crypto::field::Result<()> {
+                #(#enc)*
+                Ok(())
+            }
+
+            /// 解密字段（生成的 FromRow 读取后自动调用）
+            pub fn decrypt_fields(&mut self) -> ::kr::crypto::field::Result<()> {
+                #(#dec)*
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 生成 FromRow：先按同名字段（保留 #[sqlx(...)]）读取，JSON 字段经 `sqlx::types::Json` 解码，
+/// 有加密字段时再解密
+fn expand_from_row(
+    ident: &Ident,
+    fields: &[&Field],
+    json: &[Ident],
+    decrypt: bool,
+) -> TokenStream2 {
+    let raw = format_ident!("__{}Row", ident);
+    let is_json = |f: &Field| json.iter().any(|v| f.ident.as_ref() == Some(v));
+    let defs = fields.iter().map(|f| {
+        let name = f.ident.as_ref().unwrap();
+        let ty = &f.ty;
+        let inner = unwrap_option(ty);
+        let ty = match (is_json(f), std::ptr::eq(inner, ty)) {
+            (false, _) => quote! { #ty },
+            (true, true) => quote! { sqlx::types::Json<#ty> },
+            (true, false) => quote! { ::core::option::Option<sqlx::types::Json<#inner>> },
+        };
+        let attrs = f.attrs.iter().filter(|a| a.path().is_ident("sqlx"));
+        quote! {
+            #(#attrs)*
+            pub #name: #ty
+        }
+    });
+    let inits = fields.iter().map(|f| {
+        let name = f.ident.as_ref().unwrap();
+        match (is_json(f), std::ptr::eq(unwrap_option(&f.ty), &f.ty)) {
+            (false, _) => quote! { #name: raw.#name },
+            (true, true) => quote! { #name: raw.#name.0 },
+            (true, false) => quote! { #name: raw.#name.map(|v| v.0) },
+        }
+    });
+    let decrypt = if decrypt {
+        quote! {
+            v.decrypt_fields()
+                .map_err(|e| sqlx::Error::Decode(e.into()))?;
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[derive(sqlx::FromRow)]
+        pub struct #raw {
+            #(#defs,)*
+        }
+
+        impl<'r, R: sqlx::Row> sqlx::FromRow<'r, R> for #ident
+        where
+            #raw: sqlx::FromRow<'r, R>,
+        {
+            #[allow(unused_mut)]
+            fn from_row(row: &'r R) -> ::core::result::Result<Self, sqlx::Error> {
+                let raw = <#raw as sqlx::FromRow<'r, R>>::from_row(row)?;
+                let mut v = Self {
+                    #(#inits,)*
+                };
+                #decrypt
+                Ok(v)
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Ident, LitInt, Token, Type, Visibility,
+};
+
+/// binary_frame! { #[frame(little_endian)] pub struct Telemetry { pub voltage: f64 => fixed(u16, 2), } }
+struct FrameInput {
+    attrs: Vec<Attribute>,
+    little_endian: bool,
+    vis: Visibility,
+    name: Ident,
+    fields: Vec<FrameField>,
+}
+
+struct FrameField {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    ty: Type,
+    kind: FieldKind,
+}
+
+/// 字段的编码方式
+enum FieldKind {
+    /// 整数、浮点数按字节序编码，`[u8; N]` 原样写入
+    Raw,
+    /// `=> fixed(Storage, scale)`：浮点数按 `10^scale` 缩放后以整数存储
+    Fixed(Box<Type>, LitInt),
+    /// `=> bcd(len)`：整数以 `len` 字节 BCD 码存储
+    Bcd(LitInt),
+}
+
+impl Parse for FieldKind {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let kw: Ident = input.parse()?;
+        let content;
+        parenthesized!(content in input);
+        if kw == "fixed" {
+            let storage: Box<Type> = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let scale: LitInt = content.parse()?;
+            return Ok(Self::Fixed(storage, scale));
+        }
+        if kw == "bcd" {
+            return Ok(Self::Bcd(content.parse()?));
+        }
+        Err(syn::Error::new_spanned(
+            kw,
+            "expected `fixed(Type, scale)` or `bcd(len)`",
+        ))
+    }
+}
+
+impl Parse for FrameField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty: Type = input.parse()?;
+
+        let mut kind = FieldKind::Raw;
+        if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            kind = input.parse()?;
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            ty,
+            kind,
+        })
+    }
+}
+
+impl Parse for FrameInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = Vec::new();
+        let mut little_endian = false;
+        for attr in input.call(Attribute::parse_outer)? {
+            if !attr.path().is_ident("frame") {
+                attrs.push(attr);
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("little_endian") {
+                    little_endian = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("big_endian") {
+                    little_endian = false;
+                    return Ok(());
+                }
+                Err(meta.error("expected `little_endian` or `big_endian`"))
+            })?;
+        }
+
+        let vis: Visibility = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+        let list: Punctuated<FrameField, Token![,]> =
+            content.parse_terminated(FrameField::parse, Token![,])?;
+
+        Ok(Self {
+            attrs,
+            little_endian,
+            vis,
+            name,
+            fields: list.into_iter().collect(),
+        })
+    }
+}
+
+pub fn expand_binary_frame(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as FrameInput);
+    match expand(&input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &FrameInput) -> syn::Result<TokenStream2> {
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let name = &input.name;
+    let (to_bytes, from_bytes) = if input.little_endian {
+        (quote!(to_le_bytes), quote!(from_le_bytes))
+    } else {
+        (quote!(to_be_bytes), quote!(from_be_bytes))
+    };
+
+    let mut fields = Vec::new();
+    let mut sizes = Vec::new();
+    let mut encodes = Vec::new();
+    let mut decodes = Vec::new();
+    let mut names = Vec::new();
+    for f in &input.fields {
+        let f_attrs = &f.attrs;
+        let f_vis = &f.vis;
+        let f_name = &f.name;
+        let ty = &f.ty;
+        fields.push(quote! { #(#f_attrs)* #f_vis #f_name: #ty });
+        names.push(f_name);
+
+        let out_of_range = format!("codec/binary: field `{}` out of range", f_name);
+        match &f.kind {
+            FieldKind::Raw if matches!(ty, Type::Array(_)) => {
+                sizes.push(quote! { ::core::mem::size_of::<#ty>() });
+                encodes.push(quote! { buf.extend_from_slice(&self.#f_name); });
+                decodes.push(quote! { let #f_name = cursor.array()?; });
+            }
+            FieldKind::Raw => {
+                sizes.push(quote! { ::core::mem::size_of::<#ty>() });
+                encodes.push(quote! { buf.extend_from_slice(&self.#f_name.#to_bytes()); });
+                decodes.push(quote! { let #f_name = <#ty>::#from_bytes(cursor.array()?); });
+            }
+            FieldKind::Fixed(storage, scale) => {
+                check_fixed_storage(storage)?;
+                let scale: u32 = scale.base10_parse()?;
+                sizes.push(quote! { ::core::mem::size_of::<#storage>() });
+                encodes.push(quote! {
+                    let v = ::kr::codec::binary::to_fixed(f64::from(self.#f_name), #scale);
+                    let v = <#storage>::try_from(v)
+                        .map_err(|_| anyhow::anyhow!(#out_of_range))?;
+                    buf.extend_from_slice(&v.#to_bytes());
+                });
+                decodes.push(quote! {
+                    let #f_name = ::kr::codec::binary::from_fixed(
+                        i64::from(<#storage>::#from_bytes(cursor.array()?)),
+                        #scale,
+                    ) as _;
+                });
+            }
+            FieldKind::Bcd(len) => {
+                let len: usize = len.base10_parse()?;
+                sizes.push(quote! { #len });
+                encodes.push(quote! {
+                    let v = u64::try_from(self.#f_name)
+                        .map_err(|_| anyhow::anyhow!(#out_of_range))?;
+                    buf.extend_from_slice(&::kr::codec::binary::to_bcd(v, #len)?);
+                });
+                decodes.push(quote! {
+                    let #f_name = <#ty>::try_from(::kr::codec::binary::from_bcd(cursor.take(#len)?)?)
+                        .map_err(|_| anyhow::anyhow!(#out_of_range))?;
+                });
+            }
+        }
+    }
+
+    let size = if sizes.is_empty() {
+        quote! { 0 }
+    } else {
+        quote! { #(#sizes)+* }
+    };
+    let size_err = format!(
+        "codec/binary: `{}` expects {{}} bytes, got {{}}",
+        input.name
+    );
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis struct #name {
+            #(#fields,)*
+        }
+
+        impl ::kr::codec::binary::Frame for #name {
+            const SIZE: usize = #size;
+
+            fn encode(&self, buf: &mut ::std::vec::Vec<u8>) -> anyhow::Result<()> {
+                #(#encodes)*
+                Ok(())
+            }
+
+            fn decode(data: &[u8]) -> anyhow::Result<Self> {
+                if data.len() != Self::SIZE {
+                    return Err(anyhow::anyhow!(#size_err, Self::SIZE, data.len()));
+                }
+                let mut cursor = ::kr::codec::binary::Cursor::new(data);
+                #(#decodes)*
+                Ok(Self { #(#names),* })
+            }
+        }
+    })
+}
+
+// fixed 的存储类型需可无损转为 i64（u64、i128 等会截断或溢出）
+fn check_fixed_storage(ty: &Type) -> syn::Result<()> {
+    const ALLOWED: [&str; 7] = ["i8", "i16", "i32", "i64", "u8", "u16", "u32"];
+    if let Type::Path(p) = ty {
+        if p.qself.is_none() && ALLOWED.iter().any(|v| p.path.is_ident(v)) {
+            return Ok(());
+        }
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        "fixed storage must be one of i8/i16/i32/i64/u8/u16/u32 (lossless into i64)",
+    ))
+}
@@ -0,0 +1,158 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Ident, LitStr, Token,
+};
+
+/// cache_key!("order:{uid:tag}:items", uid = expr, ...)
+struct KeyInput {
+    template: LitStr,
+    args: Vec<(Ident, Expr)>,
+}
+
+impl Parse for KeyInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let template: LitStr = input.parse()?;
+        let mut args = Vec::new();
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: Expr = input.parse()?;
+            args.push((name, value));
+        }
+        Ok(Self { template, args })
+    }
+}
+
+/// 模板中的一段
+pub(crate) enum Segment {
+    Literal(String),
+    /// (变量名, 是否 hash tag)，位置占位符 `{}` 的变量名为空
+    Var(String, bool),
+}
+
+pub fn expand_cache_key(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as KeyInput);
+    match expand(&input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &KeyInput) -> syn::Result<TokenStream2> {
+    let segments = parse_template(&input.template, false)?;
+
+    let mut fmt = Vec::new();
+    let mut values = Vec::new();
+    let mut used = Vec::new();
+    for seg in &segments {
+        match seg {
+            Segment::Literal(v) => fmt.push(v.clone()),
+            Segment::Var(name, tag) => {
+                fmt.push("{}".to_string());
+                let value = match input.args.iter().find(|(k, _)| k == name) {
+                    Some((_, v)) => {
+                        used.push(name.clone());
+                        quote! { #v }
+                    }
+                    None => {
+                        let ident = Ident::new(name, Span::call_site());
+                        quote! { #ident }
+                    }
+                };
+                values.push(if *tag {
+                    quote! { ::kr::helper::redkit::key::hash_tag(&(#value)) }
+                } else {
+                    quote! { ::kr::helper::redkit::key::sanitize(&(#value)) }
+                });
+            }
+        }
+    }
+
+    for (k, _) in &input.args {
+        if !used.contains(&k.to_string()) {
+            return Err(syn::Error::new_spanned(
+                k,
+                format!("argument `{}` is not used in key template", k),
+            ));
+        }
+    }
+
+    let fmt = fmt.join(":");
+    Ok(quote! {
+        ::kr::helper::redkit::key::with_prefix(::std::format!(#fmt, #(#values),*))
+    })
+}
+
+pub(crate) fn parse_template(lit: &LitStr, positional: bool) -> syn::Result<Vec<Segment>> {
+    let template = lit.value();
+    let err = |msg: String| syn::Error::new_spanned(lit, msg);
+
+    let mut segments = Vec::new();
+    let mut tagged = false;
+    for seg in split(&template) {
+        if seg.is_empty() {
+            return Err(err(format!("empty segment in key template `{}`", template)));
+        }
+
+        if let Some(inner) = seg.strip_prefix('{') {
+            let inner = inner
+                .strip_suffix('}')
+                .ok_or_else(|| err(format!("unclosed placeholder `{}`", seg)))?;
+            let (name, tag) = match inner.split_once(':') {
+                Some((name, "tag")) => (name, true),
+                Some((_, m)) => return Err(err(format!("unknown placeholder modifier `{}`", m))),
+                None => (inner, false),
+            };
+            if !(positional && name.is_empty()) && syn::parse_str::<Ident>(name).is_err() {
+                return Err(err(format!("invalid placeholder name `{}`", name)));
+            }
+            if tag {
+                if tagged {
+                    return Err(err("only one hash tag is allowed in a key".to_string()));
+                }
+                tagged = true;
+            }
+            segments.push(Segment::Var(name.to_string(), tag));
+            continue;
+        }
+
+        if let Some(c) = seg
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        {
+            return Err(err(format!(
+                "invalid character `{}` in segment `{}`",
+                c, seg
+            )));
+        }
+        segments.push(Segment::Literal(seg.to_string()));
+    }
+    Ok(segments)
+}
+
+// 按 `:` 分段（占位符内的 `:` 不分段）
+fn split(s: &str) -> Vec<&str> {
+    let mut list = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ':' if depth == 0 => {
+                list.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    list.push(&s[start..]);
+    list
+}
@@ -0,0 +1,3 @@
+pub mod binary_frame;
+pub mod cache_key;
+pub mod redis_keys;
@@ -0,0 +1,257 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Ident, LitInt, LitStr, Token, Type, Visibility,
+};
+
+use crate::funcs::cache_key::{parse_template, Segment};
+
+syn::custom_keyword!(ttl);
+
+/// redis_keys! { pub enum CacheKey { UserProfile(user_id: i64) => "user:{}:profile", ttl = 10m, } }
+struct KeysInput {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    variants: Vec<KeyVariant>,
+}
+
+struct KeyVariant {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    fields: Vec<(Ident, Type)>,
+    template: LitStr,
+    ttl: Option<LitInt>,
+}
+
+struct Field {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for Field {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty: Type = input.parse()?;
+        Ok(Self { name, ty })
+    }
+}
+
+impl Parse for KeyVariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name: Ident = input.parse()?;
+
+        let mut fields = Vec::new();
+        if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let list: Punctuated<Field, Token![,]> =
+                content.parse_terminated(Field::parse, Token![,])?;
+            fields = list.into_iter().map(|f| (f.name, f.ty)).collect();
+        }
+
+        input.parse::<Token![=>]>()?;
+        let template: LitStr = input.parse()?;
+
+        let mut ttl = None;
+        if input.peek(Token![,]) && input.peek2(self::ttl) {
+            input.parse::<Token![,]>()?;
+            input.parse::<self::ttl>()?;
+            input.parse::<Token![=]>()?;
+            ttl = Some(input.parse()?);
+        }
+
+        Ok(Self {
+            attrs,
+            name,
+            fields,
+            template,
+            ttl,
+        })
+    }
+}
+
+impl Parse for KeysInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+        let list: Punctuated<KeyVariant, Token![,]> =
+            content.parse_terminated(KeyVariant::parse, Token![,])?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            variants: list.into_iter().collect(),
+        })
+    }
+}
+
+pub fn expand_redis_keys(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as KeysInput);
+    match expand(&input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &KeysInput) -> syn::Result<TokenStream2> {
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let name = &input.name;
+
+    let mut variants = Vec::new();
+    let mut key_arms = Vec::new();
+    let mut ttl_arms = Vec::new();
+    let mut template_arms = Vec::new();
+    for v in &input.variants {
+        let v_attrs = &v.attrs;
+        let v_name = &v.name;
+        let names: Vec<&Ident> = v.fields.iter().map(|(n, _)| n).collect();
+        let types: Vec<&Type> = v.fields.iter().map(|(_, t)| t).collect();
+
+        let (pattern, wildcard) = if v.fields.is_empty() {
+            variants.push(quote! { #(#v_attrs)* #v_name });
+            (quote! { Self::#v_name }, quote! { Self::#v_name })
+        } else {
+            variants.push(quote! { #(#v_attrs)* #v_name(#(#types),*) });
+            (
+                quote! { Self::#v_name(#(#names),*) },
+                quote! { Self::#v_name(..) },
+            )
+        };
+
+        let key = expand_key(v)?;
+        key_arms.push(quote! { #pattern => #key });
+
+        let ttl = match &v.ttl {
+            Some(lit) => {
+                let secs = parse_ttl(lit)?;
+                quote! { ::std::option::Option::Some(::std::time::Duration::from_secs(#secs)) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        ttl_arms.push(quote! { #wildcard => #ttl });
+
+        let template = &v.template;
+        template_arms.push(quote! { #wildcard => #template });
+    }
+
+    let key_body = if key_arms.is_empty() {
+        quote! { match *self {} }
+    } else {
+        quote! { match self { #(#key_arms),* } }
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis enum #name {
+            #(#variants),*
+        }
+
+        impl #name {
+            /// 完整的 key（含全局前缀）
+            #[allow(unused_variables)]
+            pub fn key(&self) -> ::std::string::String {
+                #key_body
+            }
+
+            /// 默认 TTL
+            pub fn ttl(&self) -> ::std::option::Option<::std::time::Duration> {
+                match self { #(#ttl_arms),* }
+            }
+
+            /// key 模板
+            pub fn template(&self) -> &'static str {
+                match self { #(#template_arms),* }
+            }
+        }
+    })
+}
+
+fn expand_key(v: &KeyVariant) -> syn::Result<TokenStream2> {
+    let segments = parse_template(&v.template, true)?;
+    let err = |msg: String| syn::Error::new_spanned(&v.template, msg);
+
+    let mut fmt = Vec::new();
+    let mut values = Vec::new();
+    let mut used = Vec::new();
+    let mut pos = 0;
+    for seg in &segments {
+        match seg {
+            Segment::Literal(s) => fmt.push(s.clone()),
+            Segment::Var(name, tag) => {
+                fmt.push("{}".to_string());
+                let ident = if name.is_empty() {
+                    let (ident, _) = v.fields.get(pos).ok_or_else(|| {
+                        err(format!(
+                            "placeholder #{} has no matching field in `{}`",
+                            pos + 1,
+                            v.name
+                        ))
+                    })?;
+                    pos += 1;
+                    ident
+                } else {
+                    let (ident, _) =
+                        v.fields.iter().find(|(k, _)| k == name).ok_or_else(|| {
+                            err(format!("unknown field `{}` in `{}`", name, v.name))
+                        })?;
+                    ident
+                };
+                used.push(ident.to_string());
+                values.push(if *tag {
+                    quote! { ::kr::helper::redkit::key::hash_tag(#ident) }
+                } else {
+                    quote! { ::kr::helper::redkit::key::sanitize(#ident) }
+                });
+            }
+        }
+    }
+
+    for (k, _) in &v.fields {
+        if !used.contains(&k.to_string()) {
+            return Err(syn::Error::new_spanned(
+                k,
+                format!("field `{}` is not used in key template", k),
+            ));
+        }
+    }
+
+    let fmt = fmt.join(":");
+    Ok(quote! {
+        ::kr::helper::redkit::key::with_prefix(::std::format!(#fmt, #(#values),*))
+    })
+}
+
+// 10s、10m、2h、1d，无单位为秒
+fn parse_ttl(lit: &LitInt) -> syn::Result<u64> {
+    let n: u64 = lit.base10_parse()?;
+    let unit = match lit.suffix() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        v => {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!("unknown ttl unit `{}`, expected s/m/h/d", v),
+            ))
+        }
+    };
+    if n == 0 {
+        return Err(syn::Error::new_spanned(lit, "ttl must be greater than 0"));
+    }
+    Ok(n * unit)
+}
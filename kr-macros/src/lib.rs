@@ -1,10 +1,99 @@
 mod derives;
+mod funcs;
 
 use proc_macro::TokenStream;
 
-use crate::derives::model;
+use crate::{
+    derives::{factory, model},
+    funcs::{binary_frame, cache_key, redis_keys},
+};
 
-#[proc_macro_derive(Model, attributes(model))]
+#[proc_macro_derive(Model, attributes(model, sqlx))]
 pub fn derive_sqlx_model(input: TokenStream) -> TokenStream {
     model::expand_sqlx_model(input)
 }
+
+#[proc_macro_derive(Factory, attributes(factory))]
+pub fn derive_factory(input: TokenStream) -> TokenStream {
+    factory::expand_factory(input)
+}
+
+/// 编译期校验的缓存 key 模板，自动加上全局前缀（`redkit::key::set_prefix`）
+///
+/// - 以 `:` 分段，字面段仅允许字母、数字、`_`、`-`、`.`
+/// - `{name}` 为占位符，取同名变量或 `name = expr` 参数
+/// - `{name:tag}` 为 hash tag（集群 slot 固定），最多一个
+///
+/// # Examples
+///
+/// ```
+/// // order:{10086}:items:1
+/// let k = cache_key!("order:{uid:tag}:items:{id}", uid = 10086, id = 1);
+///
+/// let id = 1;
+/// let k = cache_key!("user:{id}:profile");
+/// ```
+#[proc_macro]
+pub fn cache_key(input: TokenStream) -> TokenStream {
+    cache_key::expand_cache_key(input)
+}
+
+/// 强类型的 Redis key 定义：key 模板与默认 TTL 集中在一处，生成 `key()`、`ttl()`、`template()` 方法
+///
+/// - 模板规则同 [`cache_key!`]，`{}` 按顺序取字段，`{name}` 取同名字段，`{:tag}`、`{name:tag}` 为 hash tag
+/// - 所有字段都必须在模板中使用
+/// - `ttl` 可选，单位：`s`、`m`、`h`、`d`（无单位为秒）
+///
+/// # Examples
+///
+/// ```
+/// redis_keys! {
+///     #[derive(Debug, Clone)]
+///     pub enum CacheKey {
+///         UserProfile(user_id: i64) => "user:{}:profile", ttl = 10m,
+///         OrderItems(uid: i64, id: i64) => "order:{uid:tag}:items:{id}", ttl = 1h,
+///         Config => "sys:config",
+///     }
+/// }
+///
+/// let k = CacheKey::UserProfile(10086);
+/// // user:10086:profile
+/// let v = redis.get_or_set(k.key(), loader, k.ttl()).await?;
+/// ```
+#[proc_macro]
+pub fn redis_keys(input: TokenStream) -> TokenStream {
+    redis_keys::expand_redis_keys(input)
+}
+
+/// 定长二进制帧布局：按字段顺序编解码，生成 `kr::codec::binary::Frame` 实现
+///
+/// - 整数、浮点数按字节序编码（默认大端，`#[frame(little_endian)]` 为小端），`[u8; N]` 原样读写
+/// - `=> fixed(Storage, scale)`：浮点数乘以 `10^scale` 后以整数存储（`Storage` 为 `i8`~`i64`、`u8`~`u32`，其它类型编译报错）
+/// - `=> bcd(len)`：整数以 `len` 字节 BCD 码存储
+///
+/// # Examples
+///
+/// ```
+/// binary_frame! {
+///     #[derive(Debug, Clone)]
+///     pub struct Telemetry {
+///         pub device_id: u32,
+///         // 220.57V => 22057
+///         pub voltage: f64 => fixed(u16, 2),
+///         pub meter: u64 => bcd(6),
+///         pub status: u8,
+///         pub imei: [u8; 8],
+///     }
+/// }
+///
+/// let codec = FrameCodec::new().header(&[0xAA, 0x55]).crc(Crc::Crc16Modbus);
+/// codec.write_frame(&mut stream, &telemetry.to_bytes()?).await?;
+///
+/// while let Some(payload) = codec.read_frame(&mut stream).await? {
+///     let v = Telemetry::decode(&payload)?;
+/// }
+/// ```
+#[proc_macro]
+pub fn binary_frame(input: TokenStream) -> TokenStream {
+    binary_frame::expand_binary_frame(input)
+}
@@ -0,0 +1,100 @@
+use kr::codec::binary::Frame;
+use kr_macros::binary_frame;
+
+binary_frame! {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Telemetry {
+        pub device_id: u32,
+        pub voltage: f64 => fixed(u16, 2),
+        pub temperature: f32 => fixed(i16, 1),
+        pub meter: u64 => bcd(6),
+        pub status: u8,
+        pub imei: [u8; 4],
+    }
+}
+
+binary_frame! {
+    #[frame(little_endian)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TelemetryLE {
+        pub device_id: u32,
+        pub voltage: f64 => fixed(u16, 2),
+        pub temperature: f32 => fixed(i16, 1),
+        pub meter: u64 => bcd(6),
+        pub status: u8,
+        pub imei: [u8; 4],
+    }
+}
+
+#[test]
+fn test_binary_frame_big_endian() {
+    let v = Telemetry {
+        device_id: 0x01020304,
+        voltage: 220.57,
+        temperature: -12.5,
+        meter: 123456,
+        status: 7,
+        imei: [0xAA, 0xBB, 0xCC, 0xDD],
+    };
+    assert_eq!(Telemetry::SIZE, 4 + 2 + 2 + 6 + 1 + 4);
+
+    let bytes = v.to_bytes().unwrap();
+    assert_eq!(
+        bytes,
+        [
+            0x01, 0x02, 0x03, 0x04, // device_id
+            0x56, 0x29, // 22057
+            0xFF, 0x83, // -125
+            0x00, 0x00, 0x00, 0x12, 0x34, 0x56, // bcd
+            0x07, // status
+            0xAA, 0xBB, 0xCC, 0xDD, // imei
+        ]
+    );
+    assert_eq!(Telemetry::decode(&bytes).unwrap(), v);
+
+    // 长度不符
+    assert!(Telemetry::decode(&bytes[1..]).is_err());
+}
+
+#[test]
+fn test_binary_frame_little_endian() {
+    let v = TelemetryLE {
+        device_id: 0x01020304,
+        voltage: 220.57,
+        temperature: -12.5,
+        meter: 123456,
+        status: 7,
+        imei: [0xAA, 0xBB, 0xCC, 0xDD],
+    };
+
+    let bytes = v.to_bytes().unwrap();
+    assert_eq!(
+        bytes,
+        [
+            0x04, 0x03, 0x02, 0x01, // device_id
+            0x29, 0x56, // 22057
+            0x83, 0xFF, // -125
+            0x00, 0x00, 0x00, 0x12, 0x34, 0x56, // bcd 不受字节序影响
+            0x07, // status
+            0xAA, 0xBB, 0xCC, 0xDD, // imei
+        ]
+    );
+    assert_eq!(TelemetryLE::decode(&bytes).unwrap(), v);
+}
+
+#[test]
+fn test_binary_frame_out_of_range() {
+    let mut v = Telemetry {
+        device_id: 1,
+        voltage: 655.36, // 65536 超出 u16
+        temperature: 0.0,
+        meter: 1,
+        status: 0,
+        imei: [0; 4],
+    };
+    assert!(v.to_bytes().is_err());
+
+    v.voltage = 1.0;
+    v.meter = 1_000_000_000_000; // 超出 6 字节 BCD
+    assert!(v.to_bytes().is_err());
+}
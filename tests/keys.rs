@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use kr::helper::{redkit::key, CachedRepo, Entity};
+use kr_macros::{cache_key, redis_keys};
+
+redis_keys! {
+    #[derive(Debug, Clone)]
+    pub enum CacheKey {
+        UserProfile(user_id: i64) => "user:{}:profile", ttl = 10m,
+        OrderItems(uid: i64, id: String) => "order:{uid:tag}:items:{id}", ttl = 1h,
+        Session(token: String) => "session:{}", ttl = 30,
+        Config => "sys:config",
+    }
+}
+
+// 同一测试进程内前缀只能设置一次
+fn setup() {
+    key::set_prefix("app");
+}
+
+#[test]
+fn test_cache_key() {
+    setup();
+
+    assert_eq!(
+        cache_key!("order:{uid:tag}:items:{id}", uid = 10086, id = 1),
+        "app:order:{10086}:items:1"
+    );
+
+    let id = 1;
+    assert_eq!(cache_key!("user:{id}:profile"), "app:user:1:profile");
+
+    // 变量中的分隔符与 hash tag 字符会被替换
+    let id = "1:{admin}";
+    assert_eq!(cache_key!("user:{id}"), "app:user:1__admin_");
+    assert_eq!(
+        cache_key!("order:{uid:tag}", uid = "a{b}"),
+        "app:order:{a_b_}"
+    );
+}
+
+#[test]
+fn test_redis_keys() {
+    setup();
+
+    let k = CacheKey::UserProfile(10086);
+    assert_eq!(k.key(), "app:user:10086:profile");
+    assert_eq!(k.ttl(), Some(Duration::from_secs(600)));
+    assert_eq!(k.template(), "user:{}:profile");
+
+    let k = CacheKey::OrderItems(10086, "a:b".into());
+    assert_eq!(k.key(), "app:order:{10086}:items:a_b");
+    assert_eq!(k.ttl(), Some(Duration::from_secs(3600)));
+    assert_eq!(
+        key::slot(k.key()),
+        key::slot(CacheKey::OrderItems(10086, "c".into()).key())
+    );
+
+    assert_eq!(
+        CacheKey::Session("t".into()).ttl(),
+        Some(Duration::from_secs(30))
+    );
+
+    let k = CacheKey::Config;
+    assert_eq!(k.key(), "app:sys:config");
+    assert_eq!(k.ttl(), None);
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Demo {
+    id: i64,
+}
+
+impl Entity for Demo {
+    type Id = i64;
+    const NAME: &'static str = "demo";
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+#[test]
+fn test_builtin_key_prefix() {
+    setup();
+
+    // 内置模块的 key 同样使用全局前缀
+    assert_eq!(CachedRepo::<Demo>::key(&1), "app:repo:demo:1");
+}
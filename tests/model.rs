@@ -0,0 +1,201 @@
+use kr::{crypto::field, sql};
+use kr_macros::{Factory, Model};
+use sea_query::{Alias, Expr, Order, Query};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Model, Factory)]
+#[model(encrypt(phone, email))]
+#[model(UserLite !(name))]
+#[factory(table = "user")]
+pub struct User {
+    #[factory(skip)]
+    pub id: i64,
+    pub name: String,
+    #[sqlx(rename = "mobile")]
+    pub phone: String,
+    pub email: Option<String>,
+}
+
+#[tokio::test]
+async fn test_model_encrypt() {
+    field::set_key_provider(|| Ok(b"AES256Key-32Characters1234567890".to_vec()));
+
+    let pool = sql::test::memory_pool(Some(
+        "CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT NOT NULL, mobile TEXT NOT NULL, email TEXT)",
+    ))
+    .await
+    .unwrap();
+
+    UserFactory::new()
+        .name("kr")
+        .phone("13800138000")
+        .email(Some("kr@example.com".to_string()))
+        .create(&pool)
+        .await
+        .unwrap();
+    UserFactory::new()
+        .name("none")
+        .email(None)
+        .create(&pool)
+        .await
+        .unwrap();
+
+    // 落库为密文
+    let (mobile, email): (String, Option<String>) =
+        sqlx::query_as("SELECT mobile, email FROM user WHERE name = 'kr'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_ne!(mobile, "13800138000");
+    assert_eq!(field::decrypt(&mobile).unwrap(), "13800138000");
+    assert_eq!(field::decrypt(email.unwrap()).unwrap(), "kr@example.com");
+
+    // 读取时自动解密
+    let stmt = Query::select()
+        .from(Alias::new("user"))
+        .expr(Expr::cust("*"))
+        .and_where(Expr::col(Alias::new("name")).eq("kr"))
+        .to_owned();
+    let user = sql::sqlite::find_one::<_, User>(&pool, stmt.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.phone, "13800138000");
+    assert_eq!(user.email.as_deref(), Some("kr@example.com"));
+
+    let lite = sql::sqlite::find_one::<_, UserLite>(&pool, stmt)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(lite.phone, "13800138000");
+
+    let stmt = Query::select()
+        .from(Alias::new("user"))
+        .expr(Expr::cust("*"))
+        .and_where(Expr::col(Alias::new("name")).eq("none"))
+        .to_owned();
+    let user = sql::sqlite::find_one::<_, User>(&pool, stmt)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.email, None);
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub theme: String,
+}
+
+#[derive(Debug, Model)]
+#[model(ProfileLite !(settings))]
+pub struct Profile {
+    pub id: i64,
+    #[model(json)]
+    pub settings: Settings,
+    #[model(json)]
+    pub extra: Option<Settings>,
+    #[model(json)]
+    pub tags: Vec<String>,
+}
+
+// 与 Profile 共用 JSON 字段类型
+#[derive(Debug, Model, Factory)]
+#[factory(table = "account")]
+pub struct Account {
+    #[factory(skip)]
+    pub id: i64,
+    #[model(json)]
+    #[factory(default)]
+    pub settings: Settings,
+    #[model(json)]
+    #[factory(default)]
+    pub extra: Option<Settings>,
+}
+
+#[tokio::test]
+async fn test_model_json() {
+    let pool = sql::test::memory_pool(Some(
+        "CREATE TABLE profile (id INTEGER PRIMARY KEY, settings TEXT NOT NULL, extra TEXT, tags TEXT NOT NULL)",
+    ))
+    .await
+    .unwrap();
+
+    let settings = Settings {
+        theme: "dark".to_string(),
+    };
+    let tags = vec!["a".to_string(), "b".to_string()];
+    for (id, extra) in [(1, Some(&settings)), (2, None)] {
+        let stmt = Query::insert()
+            .into_table(Alias::new("profile"))
+            .columns(["id", "settings", "extra", "tags"].map(Alias::new))
+            .values_panic([
+                id.into(),
+                sql::json_value(Some(&settings)).unwrap().into(),
+                sql::json_value(extra).unwrap().into(),
+                sql::json_value(Some(&tags)).unwrap().into(),
+            ])
+            .to_owned();
+        sql::sqlite::create(&pool, stmt).await.unwrap();
+    }
+
+    // 落库为 JSON 文本
+    let (raw, raw_tags): (String, String) =
+        sqlx::query_as("SELECT settings, tags FROM profile WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(raw, r#"{"theme":"dark"}"#);
+    assert_eq!(raw_tags, r#"["a","b"]"#);
+
+    let stmt = Query::select()
+        .from(Alias::new("profile"))
+        .expr(Expr::cust("*"))
+        .order_by(Alias::new("id"), Order::Asc)
+        .to_owned();
+    let list = sql::sqlite::find_all::<_, Profile>(&pool, stmt.clone())
+        .await
+        .unwrap();
+    assert_eq!(list[0].settings, settings);
+    assert_eq!(list[0].extra.as_ref(), Some(&settings));
+    assert_eq!(list[0].tags, tags);
+    assert_eq!(list[1].extra, None);
+
+    let lite = sql::sqlite::find_all::<_, ProfileLite>(&pool, stmt)
+        .await
+        .unwrap();
+    assert_eq!(lite[0].extra.as_ref(), Some(&settings));
+    assert_eq!(lite[1].tags, tags);
+}
+
+#[tokio::test]
+async fn test_model_json_shared_type() {
+    let pool = sql::test::memory_pool(Some(
+        "CREATE TABLE account (id INTEGER PRIMARY KEY, settings TEXT NOT NULL, extra TEXT)",
+    ))
+    .await
+    .unwrap();
+
+    let settings = Settings {
+        theme: "light".to_string(),
+    };
+    AccountFactory::new()
+        .settings(settings.clone())
+        .extra(Some(settings.clone()))
+        .create(&pool)
+        .await
+        .unwrap();
+    AccountFactory::new().create(&pool).await.unwrap();
+
+    let stmt = Query::select()
+        .from(Alias::new("account"))
+        .expr(Expr::cust("*"))
+        .order_by(Alias::new("id"), Order::Asc)
+        .to_owned();
+    let list = sql::sqlite::find_all::<_, Account>(&pool, stmt)
+        .await
+        .unwrap();
+    assert_eq!(list[0].settings, settings);
+    assert_eq!(list[0].extra.as_ref(), Some(&settings));
+    assert_eq!(list[1].settings, Settings::default());
+    assert_eq!(list[1].extra, None);
+}